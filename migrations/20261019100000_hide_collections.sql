@@ -0,0 +1,2 @@
+ALTER TABLE actors
+    ADD COLUMN hide_collections BOOLEAN NOT NULL DEFAULT FALSE;
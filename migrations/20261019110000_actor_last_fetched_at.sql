@@ -0,0 +1,2 @@
+ALTER TABLE actors
+    ADD COLUMN last_fetched_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP;
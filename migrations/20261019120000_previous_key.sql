@@ -0,0 +1,3 @@
+ALTER TABLE actors
+    ADD COLUMN previous_public_key TEXT,
+    ADD COLUMN previous_key_expires_at TIMESTAMPTZ;
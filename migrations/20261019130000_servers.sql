@@ -0,0 +1,10 @@
+CREATE TABLE servers (
+    domain              TEXT    PRIMARY KEY,
+
+    signature_format    TEXT    NOT NULL,
+
+    created_at          TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP,
+    updated_at          TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP
+);
+
+SELECT add_updated_at_trigger('servers');
@@ -0,0 +1,13 @@
+CREATE TABLE relays (
+    id                      UUID    PRIMARY KEY,
+
+    inbox_url               TEXT    NOT NULL    UNIQUE,
+    follow_activity_url     TEXT    NOT NULL    UNIQUE,
+    actor_url               TEXT,
+    state                   TEXT    NOT NULL    DEFAULT 'pending',
+
+    created_at              TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP,
+    updated_at              TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP
+);
+
+SELECT add_updated_at_trigger('relays');
@@ -0,0 +1,7 @@
+CREATE TABLE received_activities (
+    activity_url    TEXT    PRIMARY KEY,
+
+    created_at      TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX received_activities_created_at ON received_activities (created_at);
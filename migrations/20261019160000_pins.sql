@@ -0,0 +1,19 @@
+CREATE TABLE pins (
+    id          UUID    PRIMARY KEY,
+
+    actor_id    UUID    NOT NULL    REFERENCES actors(id)   ON DELETE CASCADE,
+    object_id   UUID    NOT NULL    REFERENCES objects(id)  ON DELETE CASCADE,
+
+    created_at  TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP,
+    updated_at  TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE (actor_id, object_id)
+);
+
+SELECT add_updated_at_trigger('pins');
+
+-- Advertise the featured collection on the existing local users (the instance actor doesn't have one)
+UPDATE actors
+SET actor = jsonb_set(actor, '{featured}', to_jsonb((actor->>'id') || '/featured'))
+WHERE remote = FALSE
+AND actor->>'id' LIKE '%/users/%';
@@ -0,0 +1,14 @@
+CREATE TABLE poll_votes (
+    id          UUID    PRIMARY KEY,
+
+    object_id   UUID    NOT NULL    REFERENCES objects(id)  ON DELETE CASCADE,
+    actor_id    UUID    NOT NULL    REFERENCES actors(id)   ON DELETE CASCADE,
+    choice      INTEGER NOT NULL,
+
+    created_at  TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP,
+    updated_at  TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE (object_id, actor_id, choice)
+);
+
+SELECT add_updated_at_trigger('poll_votes');
@@ -0,0 +1,60 @@
+-- Hashtags of the objects (lowercase and without the leading `#`)
+CREATE TABLE object_hashtags (
+    object_id   UUID    NOT NULL    REFERENCES objects(id)  ON DELETE CASCADE,
+    name        TEXT    NOT NULL,
+
+    PRIMARY KEY (object_id, name)
+);
+
+CREATE INDEX object_hashtags_name_idx ON object_hashtags (name);
+
+-- Tags of the object as an array (the tag field can be a single tag as well)
+CREATE OR REPLACE FUNCTION object_tags(_data JSONB) RETURNS JSONB AS
+$$
+    SELECT CASE jsonb_typeof(_data->'tag')
+        WHEN 'array' THEN _data->'tag'
+        WHEN 'object' THEN jsonb_build_array(_data->'tag')
+        ELSE '[]'::JSONB
+    END;
+$$
+LANGUAGE sql IMMUTABLE;
+
+-- Keep the index in sync with the `Hashtag` tags of the objects, no matter where the objects come from
+CREATE OR REPLACE FUNCTION index_object_hashtags() RETURNS TRIGGER AS
+$$
+    BEGIN
+        DELETE FROM object_hashtags WHERE object_id = NEW.id;
+
+        INSERT INTO object_hashtags (object_id, name)
+        SELECT DISTINCT NEW.id, LOWER(LTRIM(tags->>'name', '#'))
+        FROM jsonb_array_elements(object_tags(NEW.data)) AS tags
+        WHERE tags->>'type' = 'Hashtag'
+        AND LTRIM(tags->>'name', '#') <> '';
+
+        RETURN NEW;
+    END;
+$$
+LANGUAGE plpgsql;
+
+CREATE TRIGGER index_object_hashtags AFTER INSERT OR UPDATE OF data ON objects FOR EACH ROW EXECUTE PROCEDURE index_object_hashtags();
+
+-- Index the hashtags of the existing objects
+INSERT INTO object_hashtags (object_id, name)
+SELECT DISTINCT objects.id, LOWER(LTRIM(tags->>'name', '#'))
+FROM objects, jsonb_array_elements(object_tags(objects.data)) AS tags
+WHERE tags->>'type' = 'Hashtag'
+AND LTRIM(tags->>'name', '#') <> '';
+
+CREATE TABLE followed_tags (
+    id          UUID    PRIMARY KEY,
+
+    actor_id    UUID    NOT NULL    REFERENCES actors(id)   ON DELETE CASCADE,
+    name        TEXT    NOT NULL,
+
+    created_at  TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP,
+    updated_at  TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE (actor_id, name)
+);
+
+SELECT add_updated_at_trigger('followed_tags');
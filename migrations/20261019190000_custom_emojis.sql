@@ -0,0 +1,35 @@
+CREATE TABLE custom_emojis (
+    id                  UUID    PRIMARY KEY,
+
+    shortcode           TEXT    NOT NULL,
+    -- Domain of the instance the emoji belongs to (NULL for local emojis)
+    domain              TEXT,
+    category            TEXT,
+    visible_in_picker   BOOLEAN NOT NULL    DEFAULT TRUE,
+
+    -- URL of the original image of remote emojis
+    image_url           TEXT,
+    -- `updated` value of the Emoji tag of remote emojis
+    remote_updated_at   TIMESTAMPTZ,
+
+    created_at  TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP,
+    updated_at  TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX custom_emojis_local_shortcode_idx ON custom_emojis (shortcode) WHERE domain IS NULL;
+CREATE UNIQUE INDEX custom_emojis_remote_shortcode_idx ON custom_emojis (shortcode, domain) WHERE domain IS NOT NULL;
+
+SELECT add_updated_at_trigger('custom_emojis');
+
+-- Uploaded images of local emojis and cached images of remote emojis
+CREATE TABLE custom_emoji_images (
+    emoji_id        UUID    PRIMARY KEY     REFERENCES custom_emojis(id)    ON DELETE CASCADE,
+
+    content_type    TEXT    NOT NULL,
+    data            BYTEA   NOT NULL,
+
+    created_at  TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP,
+    updated_at  TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP
+);
+
+SELECT add_updated_at_trigger('custom_emoji_images');
@@ -0,0 +1,20 @@
+CREATE TABLE reactions (
+    id          UUID    PRIMARY KEY,
+
+    object_id   UUID    NOT NULL    REFERENCES objects(id)  ON DELETE CASCADE,
+    actor_id    UUID    NOT NULL    REFERENCES actors(id)   ON DELETE CASCADE,
+
+    -- Unicode emoji, shortcode of a local custom emoji or `shortcode@domain` of a remote custom emoji
+    name        TEXT    NOT NULL,
+    -- Image of the custom emoji (NULL for Unicode emojis)
+    emoji_url   TEXT,
+    -- ID of the `EmojiReact` or `Like` activity that carried the reaction
+    activity_url    TEXT    NOT NULL    UNIQUE,
+
+    created_at  TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP,
+    updated_at  TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE (object_id, actor_id, name)
+);
+
+SELECT add_updated_at_trigger('reactions');
@@ -0,0 +1,17 @@
+-- Searchable text of the object (content warning and content without the HTML markup)
+CREATE OR REPLACE FUNCTION object_search_vector(_data JSONB) RETURNS TSVECTOR AS
+$$
+    SELECT to_tsvector(
+        'simple'::REGCONFIG,
+        regexp_replace(
+            COALESCE(_data->>'summary', '') || ' ' || COALESCE(_data->>'content', ''),
+            '<[^>]*>',
+            ' ',
+            'g'
+        )
+    );
+$$
+LANGUAGE sql IMMUTABLE;
+
+CREATE INDEX objects_search_idx ON objects USING GIN (object_search_vector(data))
+WHERE data->>'type' IN ('Note', 'Question');
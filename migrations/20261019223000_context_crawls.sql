@@ -0,0 +1,5 @@
+CREATE TABLE context_crawls (
+    object_id   UUID    PRIMARY KEY     REFERENCES objects(id)  ON DELETE CASCADE,
+
+    crawled_at  TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP
+);
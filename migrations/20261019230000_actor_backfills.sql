@@ -0,0 +1,5 @@
+CREATE TABLE actor_backfills (
+    actor_id        UUID    PRIMARY KEY     REFERENCES actors(id)   ON DELETE CASCADE,
+
+    backfilled_at   TIMESTAMPTZ     NOT NULL    DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,34 @@
+-- Whether the follow request of the follower was approved by the followed actor
+CREATE OR REPLACE FUNCTION is_approved_follower(_follower_url TEXT, _followed_url TEXT) RETURNS BOOLEAN AS
+$$
+    SELECT EXISTS (
+        SELECT 1 FROM objects
+        WHERE data->>'type' = 'Follow'
+        AND data->>'actor' = _follower_url
+        AND data->>'object' = _followed_url
+        AND (data->>'approved')::BOOLEAN
+    );
+$$
+LANGUAGE sql STABLE;
+
+-- Whether the viewer is allowed to see the object of the owner
+--
+-- Public and unlisted objects are visible to everyone (the viewer is NULL for logged-out requests).
+-- Followers-only objects are only visible to approved followers and direct objects only to the addressed actors.
+-- The owner can see all of their objects
+CREATE OR REPLACE FUNCTION is_visible_to(_data JSONB, _owner JSONB, _viewer_url TEXT) RETURNS BOOLEAN AS
+$$
+    SELECT COALESCE(
+        _data->'to' ? 'https://www.w3.org/ns/activitystreams#Public'
+        OR _data->'cc' ? 'https://www.w3.org/ns/activitystreams#Public'
+        OR _data->'to' ? _viewer_url
+        OR _data->'cc' ? _viewer_url
+        OR _owner->>'id' = _viewer_url
+        OR (
+            (_data->'to' ? (_owner->>'followers') OR _data->'cc' ? (_owner->>'followers'))
+            AND is_approved_follower(_viewer_url, _owner->>'id')
+        ),
+        FALSE
+    );
+$$
+LANGUAGE sql STABLE;
@@ -0,0 +1,62 @@
+-- Move everything of the duplicate actor over to the actor that is kept and delete the duplicate
+--
+-- Rows the kept actor already has an equivalent of are dropped instead of violating the unique constraints
+CREATE FUNCTION pg_temp.merge_actor(_duplicate_id UUID, _kept_id UUID) RETURNS VOID AS
+$$
+    BEGIN
+        UPDATE objects SET owner_id = _kept_id WHERE owner_id = _duplicate_id;
+        UPDATE oauth_authorizations SET actor_id = _kept_id WHERE actor_id = _duplicate_id;
+        UPDATE oauth_tokens SET actor_id = _kept_id WHERE actor_id = _duplicate_id;
+
+        DELETE FROM pins
+        WHERE actor_id = _duplicate_id
+        AND object_id IN (SELECT object_id FROM pins WHERE actor_id = _kept_id);
+        UPDATE pins SET actor_id = _kept_id WHERE actor_id = _duplicate_id;
+
+        DELETE FROM poll_votes
+        WHERE actor_id = _duplicate_id
+        AND (object_id, choice) IN (SELECT object_id, choice FROM poll_votes WHERE actor_id = _kept_id);
+        UPDATE poll_votes SET actor_id = _kept_id WHERE actor_id = _duplicate_id;
+
+        DELETE FROM reactions
+        WHERE actor_id = _duplicate_id
+        AND (object_id, name) IN (SELECT object_id, name FROM reactions WHERE actor_id = _kept_id);
+        UPDATE reactions SET actor_id = _kept_id WHERE actor_id = _duplicate_id;
+
+        DELETE FROM followed_tags
+        WHERE actor_id = _duplicate_id
+        AND name IN (SELECT name FROM followed_tags WHERE actor_id = _kept_id);
+        UPDATE followed_tags SET actor_id = _kept_id WHERE actor_id = _duplicate_id;
+
+        -- The backfill of the kept actor simply runs again
+        DELETE FROM actor_backfills WHERE actor_id IN (_duplicate_id, _kept_id);
+
+        DELETE FROM actors WHERE id = _duplicate_id;
+    END;
+$$
+LANGUAGE plpgsql;
+
+-- Merge the actors that were inserted more than once. Local actors are kept over remote copies of them,
+-- otherwise the most recently fetched copy is kept
+SELECT pg_temp.merge_actor(id, kept_id) FROM (
+    SELECT id, first_value(id) OVER (
+        PARTITION BY actor->>'id'
+        ORDER BY remote ASC, last_fetched_at DESC, created_at ASC, id ASC
+    ) AS kept_id
+    FROM actors
+) AS duplicates
+WHERE id <> kept_id;
+
+SELECT pg_temp.merge_actor(id, kept_id) FROM (
+    SELECT id, first_value(id) OVER (
+        PARTITION BY actor->'publicKey'->>'id'
+        ORDER BY remote ASC, last_fetched_at DESC, created_at ASC, id ASC
+    ) AS kept_id
+    FROM actors
+    WHERE actor->'publicKey'->>'id' IS NOT NULL
+) AS duplicates
+WHERE id <> kept_id;
+
+-- Lookups by the actor URL or the key ID must never be ambiguous
+CREATE UNIQUE INDEX actors_actor_id_idx ON actors ((actor->>'id'));
+CREATE UNIQUE INDEX actors_public_key_id_idx ON actors ((actor->'publicKey'->>'id'));
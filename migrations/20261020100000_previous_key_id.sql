@@ -0,0 +1,2 @@
+ALTER TABLE actors
+    ADD COLUMN previous_key_id TEXT;
@@ -1,30 +1,56 @@
-use crate::error::{Error, Result};
+use crate::{
+    error::{Error, Result},
+    pem::{DecodedKey, KeyType},
+};
 use ring::{
     rand,
-    signature::{RsaKeyPair, UnparsedPublicKey, VerificationAlgorithm},
+    signature::{
+        EcdsaKeyPair, EcdsaSigningAlgorithm, Ed25519KeyPair, RsaEncoding, RsaKeyPair,
+        RsaParameters, UnparsedPublicKey, VerificationAlgorithm, ECDSA_P256_SHA256_ASN1,
+        ECDSA_P256_SHA256_ASN1_SIGNING, ECDSA_P256_SHA256_FIXED, ECDSA_P256_SHA256_FIXED_SIGNING,
+        ED25519, RSA_PKCS1_SHA256, RSA_PKCS1_SHA384, RSA_PKCS1_SHA512, RSA_PSS_2048_8192_SHA512,
+        RSA_PSS_SHA512,
+    },
 };
 
 #[cfg(not(test))]
 use ring::signature::{
     RSA_PKCS1_2048_8192_SHA256, RSA_PKCS1_2048_8192_SHA384, RSA_PKCS1_2048_8192_SHA512,
-    RSA_PKCS1_SHA256, RSA_PKCS1_SHA384, RSA_PKCS1_SHA512,
 };
 
 #[cfg(test)]
 use ring::signature::{
     RSA_PKCS1_1024_8192_SHA256_FOR_LEGACY_USE_ONLY, RSA_PKCS1_1024_8192_SHA512_FOR_LEGACY_USE_ONLY,
-    RSA_PKCS1_SHA256, RSA_PKCS1_SHA384, RSA_PKCS1_SHA512,
+    RSA_PKCS1_2048_8192_SHA384,
 };
 
+#[cfg(not(test))]
+static RSA_SHA256: &RsaParameters = &RSA_PKCS1_2048_8192_SHA256;
+#[cfg(not(test))]
+static RSA_SHA512: &RsaParameters = &RSA_PKCS1_2048_8192_SHA512;
+
+// Enable unsecure key lengths for the tests because the official RFC examples are created using an RSA-1024 bit key
+#[cfg(test)]
+static RSA_SHA256: &RsaParameters = &RSA_PKCS1_1024_8192_SHA256_FOR_LEGACY_USE_ONLY;
+#[cfg(test)]
+static RSA_SHA512: &RsaParameters = &RSA_PKCS1_1024_8192_SHA512_FOR_LEGACY_USE_ONLY;
+
+static RSA_SHA384: &RsaParameters = &RSA_PKCS1_2048_8192_SHA384;
+
+/// Length of an ECDSA P-256 signature in the fixed-length (`r || s`) encoding
+const ECDSA_P256_FIXED_SIGNATURE_LEN: usize = 64;
+
 /// Struct holding information about the algorithms used to create the signature
 ///
 /// Defaults to `rsa-sha256`
 pub struct Algorithm<'a> {
-    /// Cryptographic algorithm being used (eg. `rsa`, `ecdsa`, `hmac` etc.)
+    /// Cryptographic algorithm being used (eg. `rsa`, `ecdsa`, `ed25519`, `hs2019` etc.)
     crypto: &'a str,
 
     /// Hash algorithm being used (eg. `sha256`, `sha384`, etc.)
-    hash: &'a str,
+    ///
+    /// Not present for algorithms that imply their hash algorithm (`ed25519` and `hs2019`)
+    hash: Option<&'a str>,
 }
 
 impl<'a> Default for Algorithm<'a> {
@@ -35,91 +61,271 @@ impl<'a> Default for Algorithm<'a> {
 
 impl<'a> From<(&'a str, &'a str)> for Algorithm<'a> {
     fn from((crypto, hash): (&'a str, &'a str)) -> Self {
-        Self { crypto, hash }
+        Self {
+            crypto,
+            hash: Some(hash),
+        }
     }
 }
 
 impl<'a> ToString for Algorithm<'a> {
     fn to_string(&self) -> String {
-        format!("{}-{}", self.crypto, self.hash)
+        match self.hash {
+            Some(hash) => format!("{}-{}", self.crypto, hash),
+            None => self.crypto.to_string(),
+        }
     }
 }
 
 impl<'a> Algorithm<'a> {
+    /// `hs2019` algorithm. The actual algorithm is determined by the type of the key
+    pub fn hs2019() -> Self {
+        Self {
+            crypto: "hs2019",
+            hash: None,
+        }
+    }
+
+    /// Algorithm used to sign requests with a key of the given type
+    ///
+    /// RSA keys keep using `rsa-sha256` because a lot of software doesn't understand `hs2019` yet
+    pub fn for_signing(key_type: KeyType) -> Self {
+        match key_type {
+            KeyType::Rsa => Self::default(),
+            KeyType::Ed25519 | KeyType::EcdsaP256 => Self::hs2019(),
+        }
+    }
+
     /// Parse the value of the algorithm field of the signature header
     ///
     /// `Option::None` will return the default algorithm
     pub fn parse(raw_str: Option<&'a str>) -> Result<Self> {
-        if let Some(raw_str) = raw_str {
-            let (crypto, hash) =
-                raw_str.split_at(raw_str.find('-').ok_or(Error::InvalidAlgorithm)?);
-
-            // Skip the first character of the hash specifier (it's the `-`)
-            let hash = &hash[1..];
-
-            Ok((crypto, hash).into())
-        } else {
+        let Some(raw_str) = raw_str else {
             // Assume that the default algorithm applies
-            Ok(Self::default())
+            return Ok(Self::default());
+        };
+
+        match raw_str.split_once('-') {
+            Some((crypto, hash)) if !crypto.is_empty() && !hash.is_empty() => {
+                Ok((crypto, hash).into())
+            }
+            None if raw_str == "hs2019" || raw_str == "ed25519" => Ok(Self {
+                crypto: raw_str,
+                hash: None,
+            }),
+            _ => Err(Error::InvalidAlgorithm),
         }
     }
 
-    /// Prepare the public key for verification usage
-    pub fn prepare_public_key<K>(&self, key_bytes: K) -> Result<UnparsedPublicKey<K>>
+    /// Verify the signature of the provided data with the public key
+    pub fn verify<D, S>(&self, public_key: &DecodedKey, data: D, signature: S) -> Result<bool>
     where
-        K: AsRef<[u8]>,
+        D: AsRef<[u8]>,
+        S: AsRef<[u8]>,
     {
-        let algorithm = get_algorithm(self.crypto, self.hash)?;
+        let signature = signature.as_ref();
+        let algorithm = get_algorithm(self.crypto, self.hash, public_key.key_type, signature)?;
+        let public_key = UnparsedPublicKey::new(algorithm, public_key.der.as_slice());
 
-        Ok(UnparsedPublicKey::new(algorithm, key_bytes))
+        Ok(public_key.verify(data.as_ref(), signature).is_ok())
     }
 
     /// Sign the provided data with the algorithm
-    pub fn sign<K, D>(&self, key_bytes: K, data: D) -> Result<Vec<u8>>
+    pub fn sign<D>(&self, private_key: &DecodedKey, data: D) -> Result<Vec<u8>>
     where
-        K: AsRef<[u8]>,
         D: AsRef<[u8]>,
     {
-        if self.crypto == "rsa" {
-            let algorithm = match self.hash {
-                "sha256" => &RSA_PKCS1_SHA256,
-                "sha384" => &RSA_PKCS1_SHA384,
-                "sha512" => &RSA_PKCS1_SHA512,
+        match (self.crypto, self.hash, private_key.key_type) {
+            ("rsa" | "hs2019", hash, KeyType::Rsa) => {
+                let algorithm = match (self.crypto, hash) {
+                    ("rsa", Some("sha256")) | ("hs2019", None) => &RSA_PKCS1_SHA256,
+                    ("rsa", Some("sha384")) => &RSA_PKCS1_SHA384,
+                    ("rsa", Some("sha512")) => &RSA_PKCS1_SHA512,
+
+                    _ => return Err(Error::UnknownAlgorithm),
+                };
+
+                sign_rsa(algorithm, private_key, data.as_ref())
+            }
+            ("ed25519" | "hs2019", None, KeyType::Ed25519) => {
+                sign_ed25519(private_key, data.as_ref())
+            }
+            ("ecdsa", Some("sha256"), KeyType::EcdsaP256)
+            | ("hs2019", None, KeyType::EcdsaP256) => {
+                sign_ecdsa(&ECDSA_P256_SHA256_ASN1_SIGNING, private_key, data.as_ref())
+            }
+            _ => Err(algorithm_error(self.crypto)),
+        }
+    }
+}
+
+/// Algorithms defined by RFC 9421 (HTTP message signatures)
+///
+/// HMAC isn't supported since only asymmetric keys are used for federation
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MessageAlgorithm {
+    /// RSASSA-PSS using SHA-512
+    RsaPssSha512,
+
+    /// RSASSA-PKCS1-v1_5 using SHA-256
+    RsaV15Sha256,
+
+    /// ECDSA using curve P-256 and SHA-256
+    EcdsaP256Sha256,
+
+    /// `EdDSA` using curve Ed25519
+    Ed25519,
+}
 
-                _ => return Err(Error::UnknownAlgorithm),
-            };
+impl MessageAlgorithm {
+    /// Parse the value of the `alg` parameter
+    pub fn parse(name: &str) -> Result<Self> {
+        let algorithm = match name {
+            "rsa-pss-sha512" => Self::RsaPssSha512,
+            "rsa-v1_5-sha256" => Self::RsaV15Sha256,
+            "ecdsa-p256-sha256" => Self::EcdsaP256Sha256,
+            "ed25519" => Self::Ed25519,
+            _ => return Err(Error::UnknownAlgorithm),
+        };
 
-            let key_pair = RsaKeyPair::from_der(key_bytes.as_ref())?;
-            let rng = rand::SystemRandom::new();
+        Ok(algorithm)
+    }
 
-            let mut signature = vec![0; key_pair.public_modulus_len()];
-            key_pair.sign(algorithm, &rng, data.as_ref(), &mut signature)?;
+    /// Algorithm used to sign messages with a key of the given type
+    #[must_use]
+    pub fn for_signing(key_type: KeyType) -> Self {
+        match key_type {
+            KeyType::Rsa => Self::RsaV15Sha256,
+            KeyType::Ed25519 => Self::Ed25519,
+            KeyType::EcdsaP256 => Self::EcdsaP256Sha256,
+        }
+    }
+
+    /// Algorithms that might have been used to create a signature with a key of the given type
+    ///
+    /// RSA keys don't tell whether they are used for PSS or PKCS#1 v1.5 signatures, so both are tried
+    #[must_use]
+    pub fn candidates(key_type: KeyType) -> &'static [Self] {
+        match key_type {
+            KeyType::Rsa => &[Self::RsaV15Sha256, Self::RsaPssSha512],
+            KeyType::Ed25519 => &[Self::Ed25519],
+            KeyType::EcdsaP256 => &[Self::EcdsaP256Sha256],
+        }
+    }
+
+    /// Type of the key this algorithm works with
+    fn key_type(self) -> KeyType {
+        match self {
+            Self::RsaPssSha512 | Self::RsaV15Sha256 => KeyType::Rsa,
+            Self::EcdsaP256Sha256 => KeyType::EcdsaP256,
+            Self::Ed25519 => KeyType::Ed25519,
+        }
+    }
+
+    /// Verify the signature of the provided data with the public key
+    pub fn verify(self, public_key: &DecodedKey, data: &[u8], signature: &[u8]) -> Result<bool> {
+        if self.key_type() != public_key.key_type {
+            return Err(Error::AlgorithmMismatch);
+        }
+
+        let algorithm: &'static dyn VerificationAlgorithm = match self {
+            Self::RsaPssSha512 => &RSA_PSS_2048_8192_SHA512,
+            Self::RsaV15Sha256 => RSA_SHA256,
+            Self::EcdsaP256Sha256 => &ECDSA_P256_SHA256_FIXED,
+            Self::Ed25519 => &ED25519,
+        };
+        let public_key = UnparsedPublicKey::new(algorithm, public_key.der.as_slice());
+
+        Ok(public_key.verify(data, signature).is_ok())
+    }
+
+    /// Sign the provided data with the private key
+    pub fn sign(self, private_key: &DecodedKey, data: &[u8]) -> Result<Vec<u8>> {
+        if self.key_type() != private_key.key_type {
+            return Err(Error::AlgorithmMismatch);
+        }
 
-            Ok(signature)
-        } else {
-            Err(Error::UnknownAlgorithm)
+        match self {
+            Self::RsaPssSha512 => sign_rsa(&RSA_PSS_SHA512, private_key, data),
+            Self::RsaV15Sha256 => sign_rsa(&RSA_PKCS1_SHA256, private_key, data),
+            Self::EcdsaP256Sha256 => {
+                sign_ecdsa(&ECDSA_P256_SHA256_FIXED_SIGNING, private_key, data)
+            }
+            Self::Ed25519 => sign_ed25519(private_key, data),
         }
     }
 }
 
-/// Get the ring algorithm from the HTTP signatures crypto and hash algorithm identifier
-fn get_algorithm(crypto: &str, hash: &str) -> Result<&'static dyn VerificationAlgorithm> {
-    #[cfg(not(test))]
-    let algorithm = match (crypto, hash) {
-        ("rsa", "sha256") => &RSA_PKCS1_2048_8192_SHA256,
-        ("rsa", "sha384") => &RSA_PKCS1_2048_8192_SHA384,
-        ("rsa", "sha512") => &RSA_PKCS1_2048_8192_SHA512,
+/// Sign the data with an RSA key
+fn sign_rsa(
+    algorithm: &'static dyn RsaEncoding,
+    private_key: &DecodedKey,
+    data: &[u8],
+) -> Result<Vec<u8>> {
+    let key_pair = RsaKeyPair::from_der(private_key.der.as_slice())?;
+    let rng = rand::SystemRandom::new();
 
-        _ => return Err(Error::UnknownKeyType),
-    };
+    let mut signature = vec![0; key_pair.public_modulus_len()];
+    key_pair.sign(algorithm, &rng, data, &mut signature)?;
+
+    Ok(signature)
+}
+
+/// Sign the data with an Ed25519 key
+fn sign_ed25519(private_key: &DecodedKey, data: &[u8]) -> Result<Vec<u8>> {
+    let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&private_key.der)?;
+
+    Ok(key_pair.sign(data).as_ref().to_vec())
+}
+
+/// Sign the data with an ECDSA key
+fn sign_ecdsa(
+    algorithm: &'static EcdsaSigningAlgorithm,
+    private_key: &DecodedKey,
+    data: &[u8],
+) -> Result<Vec<u8>> {
+    let key_pair = EcdsaKeyPair::from_pkcs8(algorithm, &private_key.der)?;
+    let rng = rand::SystemRandom::new();
+
+    Ok(key_pair.sign(&rng, data)?.as_ref().to_vec())
+}
 
-    // Enable unsecure key lengths for the tests because the official RFC examples are created using an RSA-1024 bit key
-    #[cfg(test)]
-    let algorithm = match (crypto, hash) {
-        ("rsa", "sha256") => &RSA_PKCS1_1024_8192_SHA256_FOR_LEGACY_USE_ONLY,
-        ("rsa", "sha512") => &RSA_PKCS1_1024_8192_SHA512_FOR_LEGACY_USE_ONLY,
+/// Error for an algorithm that can't be used with the key
+///
+/// Known algorithms that don't fit the key type are rejected as a mismatch, everything else is unknown
+fn algorithm_error(crypto: &str) -> Error {
+    match crypto {
+        "rsa" | "ecdsa" | "ed25519" | "hs2019" => Error::AlgorithmMismatch,
+        _ => Error::UnknownAlgorithm,
+    }
+}
+
+/// Get the ring algorithm from the HTTP signatures crypto and hash algorithm identifier and the type of the key
+///
+/// `hs2019` signatures are verified with RSA-SHA256 (PKCS#1 v1.5), Ed25519 or ECDSA P-256 with SHA-256 depending on the key.
+/// ECDSA signatures are accepted in their ASN.1 and their fixed-length encoding
+fn get_algorithm(
+    crypto: &str,
+    hash: Option<&str>,
+    key_type: KeyType,
+    signature: &[u8],
+) -> Result<&'static dyn VerificationAlgorithm> {
+    let algorithm: &'static dyn VerificationAlgorithm = match (crypto, hash, key_type) {
+        ("rsa", Some("sha256"), KeyType::Rsa) | ("hs2019", None, KeyType::Rsa) => RSA_SHA256,
+        ("rsa", Some("sha384"), KeyType::Rsa) => RSA_SHA384,
+        ("rsa", Some("sha512"), KeyType::Rsa) => RSA_SHA512,
+
+        ("ed25519" | "hs2019", None, KeyType::Ed25519) => &ED25519,
+
+        ("ecdsa", Some("sha256"), KeyType::EcdsaP256) | ("hs2019", None, KeyType::EcdsaP256) => {
+            if signature.len() == ECDSA_P256_FIXED_SIGNATURE_LEN {
+                &ECDSA_P256_SHA256_FIXED
+            } else {
+                &ECDSA_P256_SHA256_ASN1
+            }
+        }
 
-        _ => unreachable!(),
+        _ => return Err(algorithm_error(crypto)),
     };
 
     Ok(algorithm)
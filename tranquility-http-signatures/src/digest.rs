@@ -0,0 +1,114 @@
+//!
+//! Content digests as defined in [RFC 9530](https://www.rfc-editor.org/rfc/rfc9530)
+//! and the legacy `Digest` header ([RFC 3230](https://www.rfc-editor.org/rfc/rfc3230)) used alongside draft-cavage-12 signatures
+//!
+
+use crate::{
+    error::{Error, Result},
+    sfv::{self, BareItem, Item, Member},
+};
+use http::header::{HeaderName, HeaderValue};
+use ring::digest::{self, Algorithm, SHA256, SHA512};
+
+/// Name of the `Content-Digest` header
+pub static CONTENT_DIGEST: HeaderName = HeaderName::from_static("content-digest");
+
+/// Name of the legacy `Digest` header
+pub static DIGEST: HeaderName = HeaderName::from_static("digest");
+
+/// Get the digest algorithm from its name in the hash algorithms registry
+fn algorithm(name: &str) -> Option<&'static Algorithm> {
+    match name {
+        "sha-256" => Some(&SHA256),
+        "sha-512" => Some(&SHA512),
+        _ => None,
+    }
+}
+
+/// Calculate the value of the `Content-Digest` header for the body (using SHA-256)
+pub fn content_digest(body: &[u8]) -> Result<HeaderValue> {
+    let digest = digest::digest(&SHA256, body);
+    let dictionary = [(
+        "sha-256".to_string(),
+        Member::Item(Item {
+            bare_item: BareItem::ByteSequence(digest.as_ref().to_vec()),
+            parameters: Vec::new(),
+        }),
+    )];
+
+    Ok(HeaderValue::from_str(&sfv::serialize_dictionary(
+        &dictionary,
+    ))?)
+}
+
+/// Calculate the value of the legacy `Digest` header for the body (using SHA-256)
+pub fn sha256_digest(body: &[u8]) -> Result<HeaderValue> {
+    let digest = digest::digest(&SHA256, body);
+    let header_value = format!("SHA-256={}", base64::encode(digest.as_ref()));
+
+    Ok(HeaderValue::from_str(&header_value)?)
+}
+
+/// Verify the body against the value of the `Content-Digest` header
+///
+/// Every digest with a supported algorithm has to match.
+/// Headers that only contain unsupported algorithms are rejected
+pub fn verify_content_digest(header_value: &str, body: &[u8]) -> Result<bool> {
+    let dictionary = sfv::parse_dictionary(header_value)?;
+
+    let mut verified_any = false;
+    for (name, member) in dictionary {
+        let Some(algorithm) = algorithm(&name) else {
+            continue;
+        };
+
+        let Member::Item(Item {
+            bare_item: BareItem::ByteSequence(expected_digest),
+            ..
+        }) = member
+        else {
+            return Err(Error::InvalidStructuredField);
+        };
+
+        if digest::digest(algorithm, body).as_ref() != expected_digest.as_slice() {
+            return Ok(false);
+        }
+
+        verified_any = true;
+    }
+
+    if verified_any {
+        Ok(true)
+    } else {
+        Err(Error::UnknownAlgorithm)
+    }
+}
+
+/// Verify the body against the value of the legacy `Digest` header (for example, `SHA-256=<base64>`)
+///
+/// Same rules as for the `Content-Digest` header apply. The algorithm names are case-insensitive
+pub fn verify_digest(header_value: &str, body: &[u8]) -> Result<bool> {
+    let mut verified_any = false;
+    for instance in header_value.split(',') {
+        let (name, expected_digest) = instance
+            .trim()
+            .split_once('=')
+            .ok_or(Error::InvalidHeader)?;
+        let Some(algorithm) = algorithm(&name.to_lowercase()) else {
+            continue;
+        };
+
+        let expected_digest = base64::decode(expected_digest)?;
+        if digest::digest(algorithm, body).as_ref() != expected_digest.as_slice() {
+            return Ok(false);
+        }
+
+        verified_any = true;
+    }
+
+    if verified_any {
+        Ok(true)
+    } else {
+        Err(Error::UnknownAlgorithm)
+    }
+}
@@ -4,10 +4,22 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 #[non_exhaustive]
 /// Unified error enum
 pub enum Error {
+    #[error("Algorithm doesn't match the key type")]
+    /// The algorithm can't be used with the type of the key
+    AlgorithmMismatch,
+
     #[error("Base64 decode error: {0}")]
     /// Base64 decode error
     Base64Decode(#[from] base64::DecodeError),
 
+    #[error("Signature creation time is outside of the tolerated clock skew")]
+    /// The `Date` header or the `created` parameter differs too much from the current time
+    ClockSkew,
+
+    #[error("Signature has expired")]
+    /// The `expires` parameter lies in the past
+    ExpiredSignature,
+
     #[error("HTTP ToStrError: {0}")]
     /// HTTP ToStrError
     HttpToStr(#[from] http::header::ToStrError),
@@ -24,6 +36,10 @@ pub enum Error {
     /// Invalid HTTP header
     InvalidHeader,
 
+    #[error("Invalid structured field")]
+    /// Invalid structured field value
+    InvalidStructuredField,
+
     #[error("Missing header")]
     /// Missing HTTP header
     MissingHeader,
@@ -36,6 +52,10 @@ pub enum Error {
     /// Missing signature field
     MissingSignatureField,
 
+    #[error("Header isn't covered by the signature: {0}")]
+    /// A security-relevant header (or component) isn't covered by the signature
+    MissingSignedHeader(&'static str),
+
     #[error("PEM error: {0}")]
     /// PEM decoding error
     Pem(#[from] pem::PemError),
@@ -48,6 +68,10 @@ pub enum Error {
     /// Ring invalid key
     RingInvalidKey(#[from] ring::error::KeyRejected),
 
+    #[error("Unknown signature label")]
+    /// No signature with this label is present
+    UnknownSignatureLabel,
+
     #[error("Unknown algorithm")]
     /// Unknown algorithm
     UnknownAlgorithm,
@@ -55,4 +79,8 @@ pub enum Error {
     #[error("Unknown key type")]
     /// Unknown key type
     UnknownKeyType,
+
+    #[error("Unsupported component")]
+    /// Unsupported component of an HTTP message signature
+    UnsupportedComponent,
 }
@@ -8,9 +8,12 @@
 
 //!
 //! Implementation of the HTTP signatures [spec](https://datatracker.ietf.org/doc/html/draft-cavage-http-signatures-12#appendix-C.1)
+//! and the standardised HTTP message signatures ([RFC 9421](https://www.rfc-editor.org/rfc/rfc9421))
 //!
 
-use crate::{alg::Algorithm, error::Result, signature::Signature, sigstr::SignatureString};
+use crate::{
+    alg::Algorithm, error::Result, pem::KeyType, signature::Signature, sigstr::SignatureString,
+};
 use http::header::{HeaderName, HeaderValue};
 
 static SIGNATURE: HeaderName = HeaderName::from_static("signature");
@@ -32,20 +35,21 @@ where
     let encoded_signature_string = signature_string.to_string();
     let signature_string_bytes = encoded_signature_string.as_bytes();
 
-    // Use the default algorithm for signing
-    let algorithm = Algorithm::default();
-
-    // Decode the private key
+    // Decode the private key and choose the algorithm based on its type
     let decoded_priv_key = pem::decode(priv_key.data)?;
+    let algorithm = Algorithm::for_signing(decoded_priv_key.key_type);
 
     // Sign the signature string and base64-encode the signature
-    let signature = algorithm.sign(decoded_priv_key, signature_string_bytes)?;
+    let signature = algorithm.sign(&decoded_priv_key, signature_string_bytes)?;
     let encoded_signature = base64::encode(signature);
 
+    // The default algorithm doesn't have to be included in the header
+    let algorithm_name = (decoded_priv_key.key_type != KeyType::Rsa).then(|| algorithm.to_string());
+
     // Build the signature header and encode it into an `HeaderValue`
     let signature_header = Signature::new(
         priv_key.key_id,
-        None,
+        algorithm_name.as_deref(),
         headers.to_vec(),
         &encoded_signature,
         None,
@@ -56,7 +60,38 @@ where
     Ok((SIGNATURE.clone(), signature_header))
 }
 
+/// Get the key ID from the signature of an HTTP request
+///
+/// This doesn't verify the signature. It's intended to find out which public key is needed for the verification.
+/// For RFC 9421 signatures, the key ID of the first signature is returned
+pub fn key_id<'r, R>(req: R) -> Result<String>
+where
+    R: Into<Request<'r>>,
+{
+    __into!(req);
+
+    if is_message_signature(&req) {
+        return rfc9421::key_id(req, None);
+    }
+
+    let signature = req.signature()?;
+    let signature = Signature::parse(signature)?;
+
+    Ok(signature.key_id.to_string())
+}
+
+/// Check whether the request is signed with an RFC 9421 HTTP message signature
+#[must_use]
+pub fn is_message_signature(req: &Request<'_>) -> bool {
+    req.headers.contains_key(&rfc9421::SIGNATURE_INPUT)
+}
+
 /// Verify an HTTP request
+///
+/// Supports both, draft-cavage-12 signatures and RFC 9421 signatures (only the first signature is verified)
+///
+/// This only verifies the signature itself. Use [`verify_with_options`] to also enforce its freshness
+/// and the coverage of the security-relevant headers
 pub fn verify<'r, 'p, R, K>(req: R, pub_key: K) -> Result<bool>
 where
     R: Into<Request<'r>>,
@@ -64,44 +99,94 @@ where
 {
     __into!(req, pub_key);
 
+    if is_message_signature(&req) {
+        return rfc9421::verify(req, None, pub_key);
+    }
+
     // Parse the signature header
     let signature = req.signature()?;
     let signature = Signature::parse(signature)?;
 
+    verify_signature(&req, &signature, pub_key)
+}
+
+/// Verify an HTTP request and check the signature against the options
+///
+/// Signatures that violate the options are rejected with an error before their cryptographic verification
+pub fn verify_with_options<'r, 'p, R, K>(
+    req: R,
+    pub_key: K,
+    options: &VerifyOptions,
+) -> Result<bool>
+where
+    R: Into<Request<'r>>,
+    K: Into<PublicKey<'p>>,
+{
+    __into!(req, pub_key);
+
+    if is_message_signature(&req) {
+        let signature = rfc9421::select_signature(rfc9421::parse_signatures(&req)?, None)?;
+        policy::check_message_signature(&req, &signature, options)?;
+
+        return rfc9421::verify_signature(&req, &signature, pub_key);
+    }
+
+    let signature = req.signature()?;
+    let signature = Signature::parse(signature)?;
+    policy::check_signature(&req, &signature, options)?;
+
+    verify_signature(&req, &signature, pub_key)
+}
+
+/// Verify a parsed draft-cavage-12 signature of the request
+pub(crate) fn verify_signature(
+    req: &Request<'_>,
+    signature: &Signature<'_>,
+    pub_key: PublicKey<'_>,
+) -> Result<bool> {
     // Build a signature string
-    let signature_string = SignatureString::build(&req, &signature.headers)?;
+    let signature_string = SignatureString::build(req, &signature.headers)?;
     let encoded_signature_string = signature_string.to_string();
     let signature_string_bytes = encoded_signature_string.as_bytes();
 
     // Parse the algorithm and public key
     let algorithm = Algorithm::parse(signature.algorithm)?;
     let decoded_pub_key = pem::decode(pub_key)?;
-    let public_key = algorithm.prepare_public_key(decoded_pub_key)?;
 
     // Decode the base64-encoded signature
     let decoded_signature = base64::decode(signature.signature)?;
 
-    // Prepare the public key and verify the signature
-    let is_valid = public_key
-        .verify(signature_string_bytes, &decoded_signature)
-        .is_ok();
-
-    Ok(is_valid)
+    // Verify the signature
+    algorithm.verify(&decoded_pub_key, signature_string_bytes, decoded_signature)
 }
 
 mod alg;
+pub mod digest;
 mod error;
 mod key;
 mod macros;
 mod pem;
+mod policy;
 mod request;
+mod resolver;
+pub mod rfc9421;
+mod sfv;
 mod signature;
+mod signer;
 mod sigstr;
 mod util;
+mod verifier;
 
 #[cfg(test)]
 mod tests;
 
 pub use self::error::Error;
 pub use self::key::{PrivateKey, PublicKey};
+pub use self::policy::VerifyOptions;
 pub use self::request::Request;
+pub use self::resolver::{KeyResolver, ResolvedKey};
+pub use self::signer::{
+    Format, Signer, CAVAGE_BODY_HEADERS, CAVAGE_HEADERS, DEFAULT_LABEL, RFC9421_BODY_COMPONENTS,
+    RFC9421_COMPONENTS,
+};
+pub use self::verifier::{Failure, Verification, Verifier, DEFAULT_MAX_CLOCK_SKEW};
@@ -1,34 +1,106 @@
 use crate::error::{Error, Result};
-use pkcs8::{der::Decode, Document, PrivateKeyInfo, SubjectPublicKeyInfo};
+use pkcs8::{der::Decode, Document, ObjectIdentifier, PrivateKeyInfo, SubjectPublicKeyInfo};
+
+/// OID of RSA keys (`rsaEncryption`)
+const RSA_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+
+/// OID of Ed25519 keys (`id-Ed25519`)
+const ED25519_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.112");
+
+/// OID of elliptic curve keys (`id-ecPublicKey`)
+const EC_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+
+/// OID of the NIST P-256 curve (`prime256v1`)
+const P256_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.3.1.7");
+
+/// Type of a decoded key
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum KeyType {
+    /// RSA key
+    Rsa,
+
+    /// Ed25519 key
+    Ed25519,
+
+    /// ECDSA key on the NIST P-256 curve
+    EcdsaP256,
+}
+
+impl KeyType {
+    /// Determine the key type from the algorithm identifier of a PKCS#8/SPKI document
+    fn from_oids(oid: ObjectIdentifier, parameters_oid: Option<ObjectIdentifier>) -> Result<Self> {
+        match (oid, parameters_oid) {
+            (RSA_OID, _) => Ok(Self::Rsa),
+            (ED25519_OID, _) => Ok(Self::Ed25519),
+            (EC_OID, Some(P256_OID)) => Ok(Self::EcdsaP256),
+            _ => Err(Error::UnknownKeyType),
+        }
+    }
+}
+
+/// Decoded key in the format ring expects
+///
+/// - RSA keys are PKCS#1 DER encoded
+/// - Public Ed25519 and ECDSA keys are the raw public key bytes
+/// - Private Ed25519 and ECDSA keys are PKCS#8 DER encoded
+pub struct DecodedKey {
+    /// Type of the key
+    pub key_type: KeyType,
+
+    /// DER encoded key
+    pub der: Vec<u8>,
+}
+
+/// Decode an SPKI DER encoded public key
+fn decode_public(data: &[u8]) -> Result<DecodedKey> {
+    let pub_key = Document::from_der(data).map_err(|_| Error::UnknownKeyType)?;
+    let pub_key: SubjectPublicKeyInfo<'_> =
+        pub_key.decode_msg().map_err(|_| Error::UnknownKeyType)?;
+
+    let (oid, parameters_oid) = pub_key
+        .algorithm
+        .oids()
+        .map_err(|_| Error::UnknownKeyType)?;
+    let key_type = KeyType::from_oids(oid, parameters_oid)?;
+
+    Ok(DecodedKey {
+        key_type,
+        der: pub_key.subject_public_key.to_vec(),
+    })
+}
+
+/// Decode a PKCS#8 DER encoded private key
+fn decode_private(data: &[u8]) -> Result<DecodedKey> {
+    let priv_key = Document::from_der(data).map_err(|_| Error::UnknownKeyType)?;
+    let priv_key: PrivateKeyInfo<'_> = priv_key.decode_msg().map_err(|_| Error::UnknownKeyType)?;
+
+    let (oid, parameters_oid) = priv_key
+        .algorithm
+        .oids()
+        .map_err(|_| Error::UnknownKeyType)?;
+    let key_type = KeyType::from_oids(oid, parameters_oid)?;
 
-/// Convert/Decode PKCS#8 DER to PKCS#1 DER
-fn pkcs8_to_pkcs1(data: &[u8], is_public: bool) -> Result<Vec<u8>> {
     // PKCS#8 is nothing else than PKCS#1 with some additional metadata about the key
-    let der_key = if is_public {
-        let pub_key = Document::from_der(data).map_err(|_| Error::UnknownKeyType)?;
-        let pub_key: SubjectPublicKeyInfo<'_> =
-            pub_key.decode_msg().map_err(|_| Error::UnknownKeyType)?;
-
-        pub_key.subject_public_key.to_vec()
-    } else {
-        let priv_key = Document::from_der(data).map_err(|_| Error::UnknownKeyType)?;
-        let priv_key: PrivateKeyInfo<'_> =
-            priv_key.decode_msg().map_err(|_| Error::UnknownKeyType)?;
-
-        priv_key.private_key.to_vec()
+    // ring only parses the non-RSA keys in their PKCS#8 form, so these are passed through as-is
+    let der = match key_type {
+        KeyType::Rsa => priv_key.private_key.to_vec(),
+        KeyType::Ed25519 | KeyType::EcdsaP256 => data.to_vec(),
     };
 
-    Ok(der_key)
+    Ok(DecodedKey { key_type, der })
 }
 
-/// Decode a PEM-encoded key (PKCS#1 or PKCS#8) to PKCS#1 DER
-pub fn decode(data: &[u8]) -> Result<Vec<u8>> {
+/// Decode a PEM-encoded key (PKCS#1, PKCS#8 or SPKI)
+pub fn decode(data: &[u8]) -> Result<DecodedKey> {
     let pem = pem::parse(data)?;
 
     match pem.tag.as_str() {
-        "PRIVATE KEY" => pkcs8_to_pkcs1(pem.contents.as_slice(), false),
-        "PUBLIC KEY" => pkcs8_to_pkcs1(pem.contents.as_slice(), true),
-        "RSA PRIVATE KEY" | "RSA PUBLIC KEY" => Ok(pem.contents),
+        "PRIVATE KEY" => decode_private(pem.contents.as_slice()),
+        "PUBLIC KEY" => decode_public(pem.contents.as_slice()),
+        "RSA PRIVATE KEY" | "RSA PUBLIC KEY" => Ok(DecodedKey {
+            key_type: KeyType::Rsa,
+            der: pem.contents,
+        }),
         _ => Err(Error::UnknownKeyType),
     }
 }
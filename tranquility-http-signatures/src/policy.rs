@@ -0,0 +1,192 @@
+//!
+//! Checks that go beyond the cryptographic verification of a signature
+//!
+//! A valid signature alone doesn't mean much if it doesn't cover the security-relevant parts of the request
+//! or if it can be replayed indefinitely
+//!
+
+use crate::{
+    error::{Error, Result},
+    request::Request,
+    rfc9421::MessageSignature,
+    signature::Signature,
+    util::HeaderMapExt as _,
+};
+use http::header::DATE;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Additional requirements a signature has to fulfil
+#[derive(Clone, Copy, Debug)]
+pub struct VerifyOptions {
+    /// Maximum tolerated difference between the clocks of the sender and the receiver
+    ///
+    /// Applies to the signed `Date` header and the `created` parameter (the `expires` parameter is checked strictly)
+    pub max_clock_skew: Duration,
+
+    /// Whether the signature has to cover a digest of the body (`Digest` or `Content-Digest`)
+    pub require_digest: bool,
+
+    /// Time the timestamps are compared against
+    pub now: SystemTime,
+}
+
+impl VerifyOptions {
+    #[must_use]
+    /// Construct new options that compare the timestamps against the current time
+    pub fn new(max_clock_skew: Duration) -> Self {
+        Self {
+            max_clock_skew,
+            require_digest: false,
+            now: SystemTime::now(),
+        }
+    }
+
+    #[must_use]
+    /// Require the signature to cover a digest of the body
+    ///
+    /// Should be enabled for all requests that have a body
+    pub fn require_digest(mut self, require_digest: bool) -> Self {
+        self.require_digest = require_digest;
+        self
+    }
+
+    #[must_use]
+    /// Compare the timestamps against the given time instead of the current time
+    pub fn now(mut self, now: SystemTime) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// Check that the signature was created within the tolerated clock skew
+    fn check_created(&self, created: SystemTime) -> Result<()> {
+        let difference = match created.duration_since(self.now) {
+            Ok(difference) => difference,
+            Err(err) => err.duration(),
+        };
+
+        if difference > self.max_clock_skew {
+            return Err(Error::ClockSkew);
+        }
+
+        Ok(())
+    }
+
+    /// Check that the signature hasn't expired (without any leeway)
+    fn check_expires(&self, expires: SystemTime) -> Result<()> {
+        if self.now > expires {
+            return Err(Error::ExpiredSignature);
+        }
+
+        Ok(())
+    }
+}
+
+/// Convert a UNIX timestamp into a `SystemTime`
+fn from_unix_timestamp(timestamp: i64) -> Result<SystemTime> {
+    let seconds = u64::try_from(timestamp).map_err(|_| Error::InvalidHeader)?;
+
+    Ok(UNIX_EPOCH + Duration::from_secs(seconds))
+}
+
+/// Parse the value of the `Date` header
+fn date_header(req: &Request<'_>) -> Result<SystemTime> {
+    let date = req.headers.get_header(DATE)?.to_str()?;
+
+    httpdate::parse_http_date(date).map_err(|_| Error::InvalidHeader)
+}
+
+/// Check whether any of the names is contained in the list of covered headers/components
+fn covers_any(covered: &[&str], names: &[&str]) -> bool {
+    names.iter().any(|name| covered.contains(name))
+}
+
+/// Check the draft-cavage-12 signature against the options
+///
+/// The signature has to cover `(request-target)`, `host`, `date` (or `(created)`) and, if required, `digest`
+pub(crate) fn check_signature(
+    req: &Request<'_>,
+    signature: &Signature<'_>,
+    options: &VerifyOptions,
+) -> Result<()> {
+    let covered = signature.headers.as_slice();
+
+    if !covers_any(covered, &["(request-target)"]) {
+        return Err(Error::MissingSignedHeader("(request-target)"));
+    }
+
+    if !covers_any(covered, &["host"]) {
+        return Err(Error::MissingSignedHeader("host"));
+    }
+
+    if options.require_digest && !covers_any(covered, &["digest"]) {
+        return Err(Error::MissingSignedHeader("digest"));
+    }
+
+    let created = signature
+        .created
+        .map(|created| created.parse().map_err(|_| Error::InvalidHeader))
+        .transpose()?;
+    let expires = signature
+        .expires
+        .map(|expires| expires.parse().map_err(|_| Error::InvalidHeader))
+        .transpose()?;
+
+    if let Some(expires) = expires {
+        options.check_expires(from_unix_timestamp(expires)?)?;
+    }
+
+    match (covers_any(covered, &["date"]), created) {
+        (true, _) => options.check_created(date_header(req)?)?,
+        (false, Some(created)) if covers_any(covered, &["(created)"]) => {
+            options.check_created(from_unix_timestamp(created)?)?;
+        }
+        _ => return Err(Error::MissingSignedHeader("date")),
+    }
+
+    Ok(())
+}
+
+/// Check the RFC 9421 signature against the options
+///
+/// The equivalents of the draft-cavage-12 requirements are:
+///
+/// - `(request-target)`: `@method` and one of `@target-uri`, `@request-target` or `@path`
+/// - `host`: one of `@target-uri`, `@authority` or `host`
+/// - `date`: the `created` parameter or `date`
+/// - `digest`: `content-digest` or `digest`
+pub(crate) fn check_message_signature(
+    req: &Request<'_>,
+    signature: &MessageSignature,
+    options: &VerifyOptions,
+) -> Result<()> {
+    let components = signature.components()?;
+    let covered = components.as_slice();
+
+    if !covers_any(covered, &["@method"]) {
+        return Err(Error::MissingSignedHeader("@method"));
+    }
+
+    if !covers_any(covered, &["@target-uri", "@request-target", "@path"]) {
+        return Err(Error::MissingSignedHeader("@target-uri"));
+    }
+
+    if !covers_any(covered, &["@target-uri", "@authority", "host"]) {
+        return Err(Error::MissingSignedHeader("@authority"));
+    }
+
+    if options.require_digest && !covers_any(covered, &["content-digest", "digest"]) {
+        return Err(Error::MissingSignedHeader("content-digest"));
+    }
+
+    if let Some(expires) = signature.expires()? {
+        options.check_expires(from_unix_timestamp(expires)?)?;
+    }
+
+    match (signature.created()?, covers_any(covered, &["date"])) {
+        (Some(created), _) => options.check_created(from_unix_timestamp(created)?)?,
+        (None, true) => options.check_created(date_header(req)?)?,
+        (None, false) => return Err(Error::MissingSignedHeader("date")),
+    }
+
+    Ok(())
+}
@@ -17,6 +17,9 @@ pub struct Request<'a> {
 
     /// The headers of the HTTP request
     pub(crate) headers: &'a HeaderMap,
+
+    /// Scheme of the request URI (defaults to `https`)
+    pub(crate) scheme: &'a str,
 }
 
 impl<'a> Request<'a> {
@@ -33,11 +36,21 @@ impl<'a> Request<'a> {
             path,
             query,
             headers,
+            scheme: "https",
         }
     }
 
+    #[must_use]
+    /// Set the scheme of the request URI
+    ///
+    /// Only relevant for HTTP message signatures that cover the `@scheme` or `@target-uri` components
+    pub fn scheme(mut self, scheme: &'a str) -> Self {
+        self.scheme = scheme;
+        self
+    }
+
     /// Get the signature from the HTTP request
-    pub(crate) fn signature(&self) -> Result<&str> {
+    pub(crate) fn signature(&self) -> Result<&'a str> {
         // Try to get the signature from the signature header
         if let Some(header_value) = self.headers.get(&SIGNATURE) {
             return Ok(header_value.to_str()?);
@@ -67,7 +80,11 @@ impl<'a, T> From<&'a http::Request<T>> for Request<'a> {
         let path = uri.path();
         let query = uri.query();
 
-        Self::new(method, path, query, headers)
+        let request = Self::new(method, path, query, headers);
+        match uri.scheme_str() {
+            Some(scheme) => request.scheme(scheme),
+            None => request,
+        }
     }
 }
 
@@ -81,6 +98,6 @@ impl<'a> From<&'a reqwest::Request> for Request<'a> {
         let path = uri.path();
         let query = uri.query();
 
-        Self::new(method, path, query, headers)
+        Self::new(method, path, query, headers).scheme(uri.scheme())
     }
 }
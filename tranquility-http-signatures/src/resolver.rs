@@ -0,0 +1,35 @@
+use async_trait::async_trait;
+
+/// Public key that was resolved from a key ID
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResolvedKey {
+    /// ID of the owner of the key (for example, the ID of an actor)
+    pub owner: String,
+
+    /// Public key in PEM format
+    pub public_key_pem: String,
+}
+
+/// Resolves key IDs to public keys
+///
+/// Implementations usually look up the key in a cache or fetch it from the URL the key ID points to
+#[async_trait]
+pub trait KeyResolver {
+    /// Error that is returned if the key can't be resolved
+    type Error: Send;
+
+    /// Resolve the key ID to a public key
+    async fn resolve(&self, key_id: &str) -> Result<ResolvedKey, Self::Error>;
+
+    /// Get a different key after the verification with the resolved key failed
+    ///
+    /// This can be used to refetch keys that might have been rotated.
+    /// Returning `None` (the default) rejects the signature
+    async fn refresh(
+        &self,
+        _key_id: &str,
+        _key: &ResolvedKey,
+    ) -> Result<Option<ResolvedKey>, Self::Error> {
+        Ok(None)
+    }
+}
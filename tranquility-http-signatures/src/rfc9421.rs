@@ -0,0 +1,404 @@
+//!
+//! Implementation of HTTP message signatures as defined in [RFC 9421](https://www.rfc-editor.org/rfc/rfc9421)
+//!
+//! Only requests are supported. The supported derived components are `@method`, `@target-uri`, `@authority`,
+//! `@scheme`, `@request-target`, `@path` and `@query`. Header fields are only supported without parameters
+//!
+
+use crate::{
+    __into,
+    alg::MessageAlgorithm,
+    error::{Error, Result},
+    key::{PrivateKey, PublicKey},
+    pem,
+    request::Request,
+    sfv::{self, BareItem, InnerList, Item, Member},
+    util::HeaderMapExt as _,
+    SIGNATURE,
+};
+use http::header::{HeaderName, HeaderValue, HOST};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Name of the `Signature-Input` header
+pub static SIGNATURE_INPUT: HeaderName = HeaderName::from_static("signature-input");
+
+/// Component of an HTTP message that is covered by a signature
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Component<'a> {
+    Method,
+    TargetUri,
+    Authority,
+    Scheme,
+    RequestTarget,
+    Path,
+    Query,
+    Header(&'a str),
+}
+
+impl<'a> Component<'a> {
+    /// Parse a component identifier
+    fn parse(identifier: &'a str) -> Result<Self> {
+        let component = match identifier {
+            "@method" => Self::Method,
+            "@target-uri" => Self::TargetUri,
+            "@authority" => Self::Authority,
+            "@scheme" => Self::Scheme,
+            "@request-target" => Self::RequestTarget,
+            "@path" => Self::Path,
+            "@query" => Self::Query,
+            identifier if identifier.starts_with('@') => return Err(Error::UnsupportedComponent),
+            // Header names are always lowercase in the signature base
+            header_name if header_name.bytes().any(|c| c.is_ascii_uppercase()) => {
+                return Err(Error::UnsupportedComponent)
+            }
+            header_name => Self::Header(header_name),
+        };
+
+        Ok(component)
+    }
+
+    /// Get the value of the component from the request
+    fn value(self, request: &Request<'_>) -> Result<String> {
+        let value = match self {
+            Self::Method => request.method.to_string(),
+            Self::TargetUri => format!(
+                "{}://{}{}",
+                request.scheme,
+                authority(request)?,
+                request_target(request)
+            ),
+            Self::Authority => authority(request)?,
+            Self::Scheme => request.scheme.to_lowercase(),
+            Self::RequestTarget => request_target(request),
+            Self::Path => request.path.to_string(),
+            Self::Query => format!("?{}", request.query.unwrap_or_default()),
+            Self::Header(header_name) => request.headers.get_combined_header(header_name)?,
+        };
+
+        Ok(value)
+    }
+}
+
+/// Authority of the request (taken from the `Host` header)
+fn authority(request: &Request<'_>) -> Result<String> {
+    let host = request.headers.get_header(HOST)?.to_str()?;
+
+    Ok(host.to_lowercase())
+}
+
+/// Path and query of the request
+fn request_target(request: &Request<'_>) -> String {
+    match request.query {
+        Some(query) => format!("{}?{}", request.path, query),
+        None => request.path.to_string(),
+    }
+}
+
+/// Signature parameters of a parsed signature
+struct SignatureInput {
+    /// Covered components and parameters
+    inner_list: InnerList,
+}
+
+impl SignatureInput {
+    /// Identifiers of the covered components
+    fn components(&self) -> Result<Vec<&str>> {
+        self.inner_list
+            .items
+            .iter()
+            .map(|item| match item {
+                Item {
+                    bare_item: BareItem::String(identifier),
+                    parameters,
+                } if parameters.is_empty() => Ok(identifier.as_str()),
+                _ => Err(Error::UnsupportedComponent),
+            })
+            .collect()
+    }
+
+    /// Get the value of a string parameter
+    fn string_parameter(&self, key: &str) -> Result<Option<&str>> {
+        match self.inner_list.parameter(key) {
+            Some(BareItem::String(value)) => Ok(Some(value)),
+            Some(_) => Err(Error::InvalidStructuredField),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the value of an integer parameter
+    fn integer_parameter(&self, key: &str) -> Result<Option<i64>> {
+        match self.inner_list.parameter(key) {
+            Some(BareItem::Integer(value)) => Ok(Some(*value)),
+            Some(_) => Err(Error::InvalidStructuredField),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Parsed signature of a request
+pub struct MessageSignature {
+    /// Label of the signature
+    label: String,
+
+    /// Covered components and signature parameters
+    input: SignatureInput,
+
+    /// Raw signature
+    signature: Vec<u8>,
+}
+
+impl MessageSignature {
+    /// Label of the signature
+    #[must_use]
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Value of the `keyid` parameter
+    pub fn key_id(&self) -> Result<Option<&str>> {
+        self.input.string_parameter("keyid")
+    }
+
+    /// Value of the `alg` parameter
+    pub fn alg(&self) -> Result<Option<&str>> {
+        self.input.string_parameter("alg")
+    }
+
+    /// Value of the `nonce` parameter
+    pub fn nonce(&self) -> Result<Option<&str>> {
+        self.input.string_parameter("nonce")
+    }
+
+    /// Value of the `tag` parameter
+    pub fn tag(&self) -> Result<Option<&str>> {
+        self.input.string_parameter("tag")
+    }
+
+    /// Value of the `created` parameter (UNIX timestamp)
+    pub fn created(&self) -> Result<Option<i64>> {
+        self.input.integer_parameter("created")
+    }
+
+    /// Value of the `expires` parameter (UNIX timestamp)
+    pub fn expires(&self) -> Result<Option<i64>> {
+        self.input.integer_parameter("expires")
+    }
+
+    /// Identifiers of the covered components
+    pub fn components(&self) -> Result<Vec<&str>> {
+        self.input.components()
+    }
+}
+
+/// Build the signature base
+fn signature_base(request: &Request<'_>, input: &SignatureInput) -> Result<String> {
+    let components = input.components()?;
+
+    let mut signature_base = String::new();
+    for (index, identifier) in components.iter().enumerate() {
+        // Components must not be included more than once
+        if components[..index].contains(identifier) {
+            return Err(Error::InvalidStructuredField);
+        }
+
+        let value = Component::parse(identifier)?.value(request)?;
+
+        let mut serialized_identifier = String::new();
+        sfv::serialize_string(identifier, &mut serialized_identifier);
+
+        signature_base.push_str(&serialized_identifier);
+        signature_base.push_str(": ");
+        signature_base.push_str(&value);
+        signature_base.push('\n');
+    }
+
+    signature_base.push_str("\"@signature-params\": ");
+    signature_base.push_str(&input.inner_list.serialize());
+
+    Ok(signature_base)
+}
+
+/// Parse all signatures of the request
+pub fn signatures<'r, R>(req: R) -> Result<Vec<MessageSignature>>
+where
+    R: Into<Request<'r>>,
+{
+    __into!(req);
+
+    parse_signatures(&req)
+}
+
+/// Parse all signatures of the request
+pub(crate) fn parse_signatures(req: &Request<'_>) -> Result<Vec<MessageSignature>> {
+    let signature_inputs = req.headers.get_combined_header(&SIGNATURE_INPUT)?;
+    let signature_inputs = sfv::parse_dictionary(&signature_inputs)?;
+
+    let signatures = req.headers.get_combined_header(&SIGNATURE)?;
+    let mut signatures = sfv::parse_dictionary(&signatures)?;
+
+    signature_inputs
+        .into_iter()
+        .map(|(label, member)| {
+            let Member::InnerList(inner_list) = member else {
+                return Err(Error::InvalidStructuredField);
+            };
+
+            let position = signatures
+                .iter()
+                .position(|(signature_label, _)| *signature_label == label)
+                .ok_or(Error::MissingSignatureField)?;
+            let (_, signature) = signatures.swap_remove(position);
+
+            let Member::Item(Item {
+                bare_item: BareItem::ByteSequence(signature),
+                ..
+            }) = signature
+            else {
+                return Err(Error::InvalidStructuredField);
+            };
+
+            Ok(MessageSignature {
+                label,
+                input: SignatureInput { inner_list },
+                signature,
+            })
+        })
+        .collect()
+}
+
+/// Get the signature with the label (or the first signature if no label is provided)
+pub(crate) fn select_signature(
+    signatures: Vec<MessageSignature>,
+    label: Option<&str>,
+) -> Result<MessageSignature> {
+    let mut signatures = signatures.into_iter();
+
+    match label {
+        Some(label) => signatures
+            .find(|signature| signature.label == label)
+            .ok_or(Error::UnknownSignatureLabel),
+        None => signatures.next().ok_or(Error::MissingSignatureHeader),
+    }
+}
+
+/// Get the key ID of the signature with the label (or the first signature if no label is provided)
+pub fn key_id<'r, R>(req: R, label: Option<&str>) -> Result<String>
+where
+    R: Into<Request<'r>>,
+{
+    let signature = select_signature(signatures(req)?, label)?;
+
+    signature
+        .key_id()?
+        .map(ToString::to_string)
+        .ok_or(Error::MissingSignatureField)
+}
+
+/// Verify the signature with the label (or the first signature if no label is provided)
+///
+/// If the signature doesn't have an `alg` parameter, the algorithm is derived from the key
+pub fn verify<'r, 'p, R, K>(req: R, label: Option<&str>, pub_key: K) -> Result<bool>
+where
+    R: Into<Request<'r>>,
+    K: Into<PublicKey<'p>>,
+{
+    __into!(req, pub_key);
+
+    let signature = select_signature(parse_signatures(&req)?, label)?;
+    verify_signature(&req, &signature, pub_key)
+}
+
+/// Verify a parsed signature of the request
+pub fn verify_signature(
+    req: &Request<'_>,
+    signature: &MessageSignature,
+    pub_key: PublicKey<'_>,
+) -> Result<bool> {
+    let signature_base = signature_base(req, &signature.input)?;
+    let decoded_pub_key = pem::decode(pub_key)?;
+
+    if let Some(alg) = signature.alg()? {
+        return MessageAlgorithm::parse(alg)?.verify(
+            &decoded_pub_key,
+            signature_base.as_bytes(),
+            &signature.signature,
+        );
+    }
+
+    for algorithm in MessageAlgorithm::candidates(decoded_pub_key.key_type) {
+        if algorithm.verify(
+            &decoded_pub_key,
+            signature_base.as_bytes(),
+            &signature.signature,
+        )? {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Sign an HTTP request
+///
+/// Returns the `Signature-Input` and `Signature` headers.
+/// The signature contains the `created` and `keyid` parameters
+pub fn sign<'r, 'k, R, K>(
+    req: R,
+    label: &str,
+    components: &[&str],
+    priv_key: K,
+) -> Result<[(HeaderName, HeaderValue); 2]>
+where
+    R: Into<Request<'r>>,
+    K: Into<PrivateKey<'k>>,
+{
+    __into!(req, priv_key);
+
+    let created = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    let items = components
+        .iter()
+        .map(|identifier| Item {
+            bare_item: BareItem::String((*identifier).to_string()),
+            parameters: Vec::new(),
+        })
+        .collect();
+    let input = SignatureInput {
+        inner_list: InnerList {
+            items,
+            parameters: vec![
+                (
+                    "created".into(),
+                    BareItem::Integer(i64::try_from(created).unwrap_or(i64::MAX)),
+                ),
+                ("keyid".into(), BareItem::String(priv_key.key_id.into())),
+            ],
+        },
+    };
+
+    // Build the signature base and sign it with the algorithm matching the key
+    let signature_base = signature_base(&req, &input)?;
+    let decoded_priv_key = pem::decode(priv_key.data)?;
+    let algorithm = MessageAlgorithm::for_signing(decoded_priv_key.key_type);
+    let signature = algorithm.sign(&decoded_priv_key, signature_base.as_bytes())?;
+
+    let signature_input =
+        sfv::serialize_dictionary(&[(label.to_string(), Member::InnerList(input.inner_list))]);
+    let signature = sfv::serialize_dictionary(&[(
+        label.to_string(),
+        Member::Item(Item {
+            bare_item: BareItem::ByteSequence(signature),
+            parameters: Vec::new(),
+        }),
+    )]);
+
+    Ok([
+        (
+            SIGNATURE_INPUT.clone(),
+            HeaderValue::from_str(&signature_input)?,
+        ),
+        (SIGNATURE.clone(), HeaderValue::from_str(&signature)?),
+    ])
+}
@@ -0,0 +1,413 @@
+//!
+//! Minimal implementation of the structured field values for HTTP ([RFC 8941](https://www.rfc-editor.org/rfc/rfc8941))
+//!
+//! Only dictionaries are supported since those are the only top-level types used by HTTP message signatures
+//! (decimals aren't supported either)
+//!
+
+use crate::error::{Error, Result};
+
+/// Bare item of a structured field
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BareItem {
+    /// Integer
+    Integer(i64),
+
+    /// String
+    String(String),
+
+    /// Token
+    Token(String),
+
+    /// Byte sequence
+    ByteSequence(Vec<u8>),
+
+    /// Boolean
+    Boolean(bool),
+}
+
+impl BareItem {
+    /// Append the serialized form of the item to the string
+    fn serialize(&self, out: &mut String) {
+        match self {
+            Self::Integer(value) => out.push_str(&value.to_string()),
+            Self::String(value) => serialize_string(value, out),
+            Self::Token(value) => out.push_str(value),
+            Self::ByteSequence(value) => {
+                out.push(':');
+                out.push_str(&base64::encode(value));
+                out.push(':');
+            }
+            Self::Boolean(value) => out.push_str(if *value { "?1" } else { "?0" }),
+        }
+    }
+}
+
+/// Append the serialized form of a string to the string
+pub fn serialize_string(value: &str, out: &mut String) {
+    out.push('"');
+    for character in value.chars() {
+        if character == '"' || character == '\\' {
+            out.push('\\');
+        }
+        out.push(character);
+    }
+    out.push('"');
+}
+
+/// Ordered list of parameters
+pub type Parameters = Vec<(String, BareItem)>;
+
+/// Append the serialized form of the parameters to the string
+fn serialize_parameters(parameters: &Parameters, out: &mut String) {
+    for (key, value) in parameters {
+        out.push(';');
+        out.push_str(key);
+
+        // Parameters with the value `true` are serialized without the value
+        if *value != BareItem::Boolean(true) {
+            out.push('=');
+            value.serialize(out);
+        }
+    }
+}
+
+/// Item with parameters
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Item {
+    /// The value of the item
+    pub bare_item: BareItem,
+
+    /// Parameters attached to the item
+    pub parameters: Parameters,
+}
+
+impl Item {
+    /// Append the serialized form of the item to the string
+    fn serialize(&self, out: &mut String) {
+        self.bare_item.serialize(out);
+        serialize_parameters(&self.parameters, out);
+    }
+}
+
+/// Inner list with parameters
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InnerList {
+    /// Items of the inner list
+    pub items: Vec<Item>,
+
+    /// Parameters attached to the inner list
+    pub parameters: Parameters,
+}
+
+impl InnerList {
+    /// Serialize the inner list
+    pub fn serialize(&self) -> String {
+        let mut out = String::from("(");
+        for (index, item) in self.items.iter().enumerate() {
+            if index > 0 {
+                out.push(' ');
+            }
+            item.serialize(&mut out);
+        }
+        out.push(')');
+        serialize_parameters(&self.parameters, &mut out);
+
+        out
+    }
+
+    /// Get the value of a parameter
+    pub fn parameter(&self, key: &str) -> Option<&BareItem> {
+        self.parameters
+            .iter()
+            .find_map(|(param_key, value)| (param_key == key).then_some(value))
+    }
+}
+
+/// Member of a dictionary
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Member {
+    /// Single item
+    Item(Item),
+
+    /// Inner list
+    InnerList(InnerList),
+}
+
+impl Member {
+    /// Serialize the member value
+    pub fn serialize(&self) -> String {
+        match self {
+            Self::Item(item) => {
+                let mut out = String::new();
+                item.serialize(&mut out);
+                out
+            }
+            Self::InnerList(inner_list) => inner_list.serialize(),
+        }
+    }
+}
+
+/// Ordered dictionary
+pub type Dictionary = Vec<(String, Member)>;
+
+/// Serialize a dictionary
+pub fn serialize_dictionary(dictionary: &[(String, Member)]) -> String {
+    dictionary
+        .iter()
+        .map(|(key, member)| format!("{}={}", key, member.serialize()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parse a dictionary
+///
+/// Duplicate keys overwrite the previous value (as mandated by the RFC)
+pub fn parse_dictionary(input: &str) -> Result<Dictionary> {
+    let mut parser = Parser {
+        input: input.as_bytes(),
+        position: 0,
+    };
+    let mut dictionary = Dictionary::new();
+
+    parser.skip_spaces();
+    while !parser.is_empty() {
+        let key = parser.parse_key()?;
+
+        let member = if parser.consume(b'=') {
+            if parser.peek() == Some(b'(') {
+                Member::InnerList(parser.parse_inner_list()?)
+            } else {
+                Member::Item(parser.parse_item()?)
+            }
+        } else {
+            Member::Item(Item {
+                bare_item: BareItem::Boolean(true),
+                parameters: parser.parse_parameters()?,
+            })
+        };
+
+        dictionary.retain(|(existing_key, _)| *existing_key != key);
+        dictionary.push((key, member));
+
+        parser.skip_whitespace();
+        if parser.is_empty() {
+            break;
+        }
+
+        if !parser.consume(b',') {
+            return Err(Error::InvalidStructuredField);
+        }
+
+        parser.skip_whitespace();
+        if parser.is_empty() {
+            // Trailing comma
+            return Err(Error::InvalidStructuredField);
+        }
+    }
+
+    Ok(dictionary)
+}
+
+/// Parser state
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn is_empty(&self) -> bool {
+        self.position >= self.input.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let next = self.peek()?;
+        self.position += 1;
+
+        Some(next)
+    }
+
+    /// Consume the character if it's the next one
+    fn consume(&mut self, character: u8) -> bool {
+        if self.peek() == Some(character) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.consume(b' ') {}
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.consume(b' ') || self.consume(b'\t') {}
+    }
+
+    /// Consume characters as long as the predicate matches
+    fn take_while<P>(&mut self, predicate: P) -> &'a str
+    where
+        P: Fn(u8) -> bool,
+    {
+        let start = self.position;
+        while self.peek().is_some_and(&predicate) {
+            self.position += 1;
+        }
+
+        // The predicates only ever accept ASCII characters
+        std::str::from_utf8(&self.input[start..self.position]).unwrap_or_default()
+    }
+
+    fn parse_key(&mut self) -> Result<String> {
+        if !self
+            .peek()
+            .is_some_and(|c| c.is_ascii_lowercase() || c == b'*')
+        {
+            return Err(Error::InvalidStructuredField);
+        }
+
+        let key = self.take_while(|c| {
+            c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, b'_' | b'-' | b'.' | b'*')
+        });
+
+        Ok(key.to_string())
+    }
+
+    fn parse_parameters(&mut self) -> Result<Parameters> {
+        let mut parameters = Parameters::new();
+
+        while self.consume(b';') {
+            self.skip_spaces();
+
+            let key = self.parse_key()?;
+            let value = if self.consume(b'=') {
+                self.parse_bare_item()?
+            } else {
+                BareItem::Boolean(true)
+            };
+
+            parameters.retain(|(existing_key, _)| *existing_key != key);
+            parameters.push((key, value));
+        }
+
+        Ok(parameters)
+    }
+
+    fn parse_inner_list(&mut self) -> Result<InnerList> {
+        if !self.consume(b'(') {
+            return Err(Error::InvalidStructuredField);
+        }
+
+        let mut items = Vec::new();
+        loop {
+            self.skip_spaces();
+
+            if self.consume(b')') {
+                break;
+            }
+
+            items.push(self.parse_item()?);
+
+            // Items have to be separated by spaces
+            if !matches!(self.peek(), Some(b' ' | b')')) {
+                return Err(Error::InvalidStructuredField);
+            }
+        }
+
+        Ok(InnerList {
+            items,
+            parameters: self.parse_parameters()?,
+        })
+    }
+
+    fn parse_item(&mut self) -> Result<Item> {
+        Ok(Item {
+            bare_item: self.parse_bare_item()?,
+            parameters: self.parse_parameters()?,
+        })
+    }
+
+    fn parse_bare_item(&mut self) -> Result<BareItem> {
+        match self.peek().ok_or(Error::InvalidStructuredField)? {
+            b'-' | b'0'..=b'9' => self.parse_integer(),
+            b'"' => self.parse_string(),
+            b':' => self.parse_byte_sequence(),
+            b'?' => self.parse_boolean(),
+            c if c.is_ascii_alphabetic() || c == b'*' => Ok(self.parse_token()),
+            _ => Err(Error::InvalidStructuredField),
+        }
+    }
+
+    fn parse_integer(&mut self) -> Result<BareItem> {
+        let negative = self.consume(b'-');
+        let digits = self.take_while(|c| c.is_ascii_digit());
+
+        // Integers are limited to 15 digits
+        if digits.is_empty() || digits.len() > 15 || self.peek() == Some(b'.') {
+            return Err(Error::InvalidStructuredField);
+        }
+
+        let value: i64 = digits.parse().map_err(|_| Error::InvalidStructuredField)?;
+
+        Ok(BareItem::Integer(if negative { -value } else { value }))
+    }
+
+    fn parse_string(&mut self) -> Result<BareItem> {
+        if !self.consume(b'"') {
+            return Err(Error::InvalidStructuredField);
+        }
+
+        let mut value = String::new();
+        loop {
+            match self.next().ok_or(Error::InvalidStructuredField)? {
+                b'"' => break,
+                b'\\' => match self.next() {
+                    Some(c @ (b'"' | b'\\')) => value.push(char::from(c)),
+                    _ => return Err(Error::InvalidStructuredField),
+                },
+                c @ 0x20..=0x7e => value.push(char::from(c)),
+                _ => return Err(Error::InvalidStructuredField),
+            }
+        }
+
+        Ok(BareItem::String(value))
+    }
+
+    fn parse_token(&mut self) -> BareItem {
+        let token =
+            self.take_while(|c| c.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~:/".contains(&c));
+
+        BareItem::Token(token.to_string())
+    }
+
+    fn parse_byte_sequence(&mut self) -> Result<BareItem> {
+        if !self.consume(b':') {
+            return Err(Error::InvalidStructuredField);
+        }
+
+        let encoded =
+            self.take_while(|c| c.is_ascii_alphanumeric() || matches!(c, b'+' | b'/' | b'='));
+        if !self.consume(b':') {
+            return Err(Error::InvalidStructuredField);
+        }
+
+        Ok(BareItem::ByteSequence(base64::decode(encoded)?))
+    }
+
+    fn parse_boolean(&mut self) -> Result<BareItem> {
+        if !self.consume(b'?') {
+            return Err(Error::InvalidStructuredField);
+        }
+
+        match self.next() {
+            Some(b'1') => Ok(BareItem::Boolean(true)),
+            Some(b'0') => Ok(BareItem::Boolean(false)),
+            _ => Err(Error::InvalidStructuredField),
+        }
+    }
+}
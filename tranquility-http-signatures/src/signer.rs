@@ -0,0 +1,188 @@
+use crate::{
+    digest::{self, CONTENT_DIGEST, DIGEST},
+    error::Result,
+    key::PrivateKey,
+    request::Request,
+    rfc9421,
+};
+use http::header::{HeaderMap, HeaderName, HeaderValue, DATE, HOST};
+use std::time::SystemTime;
+
+/// Headers covered by draft-cavage-12 signatures of requests without a body
+pub const CAVAGE_HEADERS: &[&str] = &["(request-target)", "host", "date"];
+
+/// Headers covered by draft-cavage-12 signatures of requests with a body
+pub const CAVAGE_BODY_HEADERS: &[&str] = &["(request-target)", "host", "date", "digest"];
+
+/// Components covered by RFC 9421 signatures of requests without a body
+pub const RFC9421_COMPONENTS: &[&str] = &["@method", "@target-uri"];
+
+/// Components covered by RFC 9421 signatures of requests with a body
+pub const RFC9421_BODY_COMPONENTS: &[&str] = &["@method", "@target-uri", "content-digest"];
+
+/// Label of RFC 9421 signatures if no other label is set
+pub const DEFAULT_LABEL: &str = "sig1";
+
+/// Format of an HTTP signature
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// draft-cavage-http-signatures-12
+    Cavage,
+
+    /// RFC 9421 HTTP message signatures
+    Rfc9421,
+}
+
+/// Parts of the request target the signer needs
+struct Target {
+    method: String,
+    scheme: String,
+    authority: Option<String>,
+    path: String,
+    query: Option<String>,
+}
+
+/// Builder for signing HTTP requests
+///
+/// Takes care of the `Host`, `Date`, `Digest` and `Content-Digest` headers
+/// and selects the covered headers depending on the format and whether the request has a body
+pub struct Signer<'a> {
+    key: PrivateKey<'a>,
+    format: Format,
+    covered: Option<&'a [&'a str]>,
+    label: &'a str,
+}
+
+impl<'a> Signer<'a> {
+    /// Construct a new signer that creates draft-cavage-12 signatures
+    pub fn new<K>(key: K) -> Self
+    where
+        K: Into<PrivateKey<'a>>,
+    {
+        Self {
+            key: key.into(),
+            format: Format::Cavage,
+            covered: None,
+            label: DEFAULT_LABEL,
+        }
+    }
+
+    #[must_use]
+    /// Set the format of the signatures
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    #[must_use]
+    /// Cover the given headers (or components) instead of the defaults
+    pub fn covered(mut self, covered: &'a [&'a str]) -> Self {
+        self.covered = Some(covered);
+        self
+    }
+
+    #[must_use]
+    /// Set the label of RFC 9421 signatures
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// Sign an HTTP request
+    ///
+    /// Requests without a scheme in their URI are assumed to use `https`
+    pub fn sign<B>(&self, req: &mut http::Request<B>) -> Result<()>
+    where
+        B: AsRef<[u8]>,
+    {
+        let uri = req.uri();
+        let target = Target {
+            method: req.method().to_string(),
+            scheme: uri.scheme_str().unwrap_or("https").to_string(),
+            authority: uri.authority().map(ToString::to_string),
+            path: uri.path().to_string(),
+            query: uri.query().map(ToString::to_string),
+        };
+
+        // The body has to be copied since the headers are modified while the body is read
+        let body = req.body().as_ref().to_vec();
+
+        self.sign_parts(&target, req.headers_mut(), &body)
+    }
+
+    #[cfg(feature = "reqwest")]
+    /// Sign a reqwest HTTP request
+    ///
+    /// Streaming bodies aren't supported and are treated as if the request had no body
+    pub fn sign_reqwest(&self, req: &mut reqwest::Request) -> Result<()> {
+        let url = req.url();
+        let authority = url.host_str().map(|host| match url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        });
+        let target = Target {
+            method: req.method().to_string(),
+            scheme: url.scheme().to_string(),
+            authority,
+            path: url.path().to_string(),
+            query: url.query().map(ToString::to_string),
+        };
+
+        let body = req
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .map(<[u8]>::to_vec)
+            .unwrap_or_default();
+
+        self.sign_parts(&target, req.headers_mut(), &body)
+    }
+
+    /// Add the missing headers and the signature
+    fn sign_parts(&self, target: &Target, headers: &mut HeaderMap, body: &[u8]) -> Result<()> {
+        if let Some(ref authority) = target.authority {
+            if !headers.contains_key(HOST) {
+                headers.insert(HOST, HeaderValue::from_str(authority)?);
+            }
+        }
+
+        if !headers.contains_key(DATE) {
+            let date = httpdate::fmt_http_date(SystemTime::now());
+            headers.insert(DATE, HeaderValue::from_str(&date)?);
+        }
+
+        let has_body = !body.is_empty();
+        if has_body {
+            headers.insert(DIGEST.clone(), digest::sha256_digest(body)?);
+            headers.insert(CONTENT_DIGEST.clone(), digest::content_digest(body)?);
+        }
+
+        let covered = self.covered.unwrap_or(match (self.format, has_body) {
+            (Format::Cavage, false) => CAVAGE_HEADERS,
+            (Format::Cavage, true) => CAVAGE_BODY_HEADERS,
+            (Format::Rfc9421, false) => RFC9421_COMPONENTS,
+            (Format::Rfc9421, true) => RFC9421_BODY_COMPONENTS,
+        });
+
+        let signature_headers: Vec<(HeaderName, HeaderValue)> = {
+            let request = Request::new(
+                &target.method,
+                &target.path,
+                target.query.as_deref(),
+                headers,
+            )
+            .scheme(&target.scheme);
+            let key = PrivateKey::new(self.key.key_id, self.key.data);
+
+            match self.format {
+                Format::Cavage => vec![crate::sign(request, covered, key)?],
+                Format::Rfc9421 => Vec::from(rfc9421::sign(request, self.label, covered, key)?),
+            }
+        };
+
+        for (header_name, header_value) in signature_headers {
+            headers.insert(header_name, header_value);
+        }
+
+        Ok(())
+    }
+}
@@ -7,6 +7,11 @@ pub trait HeaderMapExt {
     fn get_header<K>(&self, key: K) -> Result<&HeaderValue>
     where
         K: AsHeaderName;
+
+    /// Get all values of the header (trimmed and joined by a comma)
+    fn get_combined_header<K>(&self, key: K) -> Result<String>
+    where
+        K: AsHeaderName;
 }
 
 impl<'a> HeaderMapExt for &'a HeaderMap {
@@ -16,6 +21,23 @@ impl<'a> HeaderMapExt for &'a HeaderMap {
     {
         self.get(key).ok_or(Error::MissingHeader)
     }
+
+    fn get_combined_header<K>(&self, key: K) -> Result<String>
+    where
+        K: AsHeaderName,
+    {
+        let values = self
+            .get_all(key)
+            .iter()
+            .map(|value| value.to_str().map(str::trim))
+            .try_collect_vec()?;
+
+        if values.is_empty() {
+            return Err(Error::MissingHeader);
+        }
+
+        Ok(values.join(", "))
+    }
 }
 
 pub trait IteratorExt: Iterator + Sized {
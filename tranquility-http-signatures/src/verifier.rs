@@ -0,0 +1,223 @@
+use crate::{
+    error::{Error, Result},
+    policy::{self, VerifyOptions},
+    request::Request,
+    resolver::{KeyResolver, ResolvedKey},
+    rfc9421::{self, MessageSignature},
+    signature::Signature,
+    signer::Format,
+};
+use std::time::Duration;
+
+/// Seconds in one hour
+const ONE_HOUR: u64 = 60 * 60;
+
+/// Clock skew tolerated by a verifier if no other value is set (one hour)
+pub const DEFAULT_MAX_CLOCK_SKEW: Duration = Duration::from_secs(ONE_HOUR);
+
+/// Reason why the verification of a signature failed
+#[derive(Debug)]
+pub enum Failure<E> {
+    /// The signature is missing, malformed or violates the verification options
+    Rejected(Error),
+
+    /// The key couldn't be resolved
+    KeyResolution(E),
+
+    /// The signature doesn't match the key
+    InvalidSignature,
+}
+
+/// Structured result of a verification
+#[derive(Debug)]
+pub struct Verification<E> {
+    /// Format of the signature (`None` if the request doesn't carry a parsable signature)
+    pub format: Option<Format>,
+
+    /// ID of the key the request was signed with
+    pub key_id: Option<String>,
+
+    /// Owner of the key (only present if the key was resolved)
+    pub owner: Option<String>,
+
+    /// Value of the `algorithm`/`alg` parameter
+    pub algorithm: Option<String>,
+
+    /// Headers (or components) covered by the signature
+    pub components: Vec<String>,
+
+    /// Reason why the verification failed (`None` if the signature is valid)
+    pub failure: Option<Failure<E>>,
+}
+
+impl<E> Verification<E> {
+    /// Check whether the signature is valid
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.failure.is_none()
+    }
+
+    /// Mark the verification as failed
+    fn fail(mut self, failure: Failure<E>) -> Self {
+        self.failure = Some(failure);
+        self
+    }
+}
+
+impl<E> Default for Verification<E> {
+    fn default() -> Self {
+        Self {
+            format: None,
+            key_id: None,
+            owner: None,
+            algorithm: None,
+            components: Vec::new(),
+            failure: None,
+        }
+    }
+}
+
+/// Parsed signature in one of the supported formats
+enum ParsedSignature<'a> {
+    Cavage(Signature<'a>),
+    Rfc9421(MessageSignature),
+}
+
+impl<'a> ParsedSignature<'a> {
+    /// Parse the signature of the request (only the first signature of RFC 9421 requests is used)
+    fn parse(req: &Request<'a>) -> Result<Self> {
+        if crate::is_message_signature(req) {
+            let signature = rfc9421::select_signature(rfc9421::parse_signatures(req)?, None)?;
+            Ok(Self::Rfc9421(signature))
+        } else {
+            Ok(Self::Cavage(Signature::parse(req.signature()?)?))
+        }
+    }
+
+    /// Fill in the metadata of the signature
+    fn describe<E>(&self, verification: &mut Verification<E>) -> Result<()> {
+        match self {
+            Self::Cavage(signature) => {
+                verification.format = Some(Format::Cavage);
+                verification.key_id = Some(signature.key_id.to_string());
+                verification.algorithm = signature.algorithm.map(ToString::to_string);
+                verification.components =
+                    signature.headers.iter().map(ToString::to_string).collect();
+            }
+            Self::Rfc9421(signature) => {
+                verification.format = Some(Format::Rfc9421);
+                verification.key_id = signature.key_id()?.map(ToString::to_string);
+                verification.algorithm = signature.alg()?.map(ToString::to_string);
+                verification.components = signature
+                    .components()?
+                    .into_iter()
+                    .map(ToString::to_string)
+                    .collect();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check the signature against the options
+    fn check(&self, req: &Request<'_>, options: &VerifyOptions) -> Result<()> {
+        match self {
+            Self::Cavage(signature) => policy::check_signature(req, signature, options),
+            Self::Rfc9421(signature) => policy::check_message_signature(req, signature, options),
+        }
+    }
+
+    /// Verify the signature with the key
+    fn verify(&self, req: &Request<'_>, key: &ResolvedKey) -> Result<bool> {
+        let public_key = key.public_key_pem.as_bytes();
+
+        match self {
+            Self::Cavage(signature) => crate::verify_signature(req, signature, public_key),
+            Self::Rfc9421(signature) => rfc9421::verify_signature(req, signature, public_key),
+        }
+    }
+}
+
+/// Verifies signed requests with keys obtained from a key resolver
+///
+/// The signatures are checked against [`VerifyOptions`].
+/// Requests with a method other than `GET` and `HEAD` have to cover a digest of their body
+pub struct Verifier<R> {
+    resolver: R,
+    max_clock_skew: Duration,
+}
+
+impl<R> Verifier<R>
+where
+    R: KeyResolver + Sync,
+{
+    /// Construct a new verifier
+    pub fn new(resolver: R) -> Self {
+        Self {
+            resolver,
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+        }
+    }
+
+    #[must_use]
+    /// Set the maximum tolerated clock skew
+    pub fn max_clock_skew(mut self, max_clock_skew: Duration) -> Self {
+        self.max_clock_skew = max_clock_skew;
+        self
+    }
+
+    /// Key resolver of the verifier
+    pub fn resolver(&self) -> &R {
+        &self.resolver
+    }
+
+    /// Verify the signature of the request
+    ///
+    /// If the verification with the resolved key fails, the key is refreshed once (see [`KeyResolver::refresh`])
+    pub async fn verify(&self, req: &Request<'_>) -> Verification<R::Error> {
+        let mut verification = Verification::default();
+
+        let signature = match ParsedSignature::parse(req) {
+            Ok(signature) => signature,
+            Err(err) => return verification.fail(Failure::Rejected(err)),
+        };
+        if let Err(err) = signature.describe(&mut verification) {
+            return verification.fail(Failure::Rejected(err));
+        }
+
+        let has_body =
+            !req.method.eq_ignore_ascii_case("GET") && !req.method.eq_ignore_ascii_case("HEAD");
+        let options = VerifyOptions::new(self.max_clock_skew).require_digest(has_body);
+        if let Err(err) = signature.check(req, &options) {
+            return verification.fail(Failure::Rejected(err));
+        }
+
+        let Some(key_id) = verification.key_id.clone() else {
+            return verification.fail(Failure::Rejected(Error::MissingSignatureField));
+        };
+
+        let mut key = match self.resolver.resolve(&key_id).await {
+            Ok(key) => key,
+            Err(err) => return verification.fail(Failure::KeyResolution(err)),
+        };
+
+        let mut refreshed = false;
+        loop {
+            verification.owner = Some(key.owner.clone());
+
+            match signature.verify(req, &key) {
+                Ok(true) => return verification,
+                Ok(false) if !refreshed => (),
+                Ok(false) => return verification.fail(Failure::InvalidSignature),
+                Err(err) => return verification.fail(Failure::Rejected(err)),
+            }
+
+            refreshed = true;
+            key = match self.resolver.refresh(&key_id, &key).await {
+                Ok(Some(refreshed_key)) => refreshed_key,
+                Ok(None) => return verification.fail(Failure::InvalidSignature),
+                Err(err) => return verification.fail(Failure::KeyResolution(err)),
+            };
+        }
+    }
+}
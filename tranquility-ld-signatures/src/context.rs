@@ -0,0 +1,427 @@
+use crate::error::{Error, Result};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Contexts bundled with the crate
+///
+/// Documents can only reference these contexts. Other remote contexts are rejected instead of being fetched
+const BUNDLED_CONTEXTS: &[(&str, &str)] = &[
+    (
+        "https://www.w3.org/ns/activitystreams",
+        include_str!("../contexts/activitystreams.jsonld"),
+    ),
+    (
+        "https://w3id.org/identity/v1",
+        include_str!("../contexts/identity-v1.jsonld"),
+    ),
+    (
+        "https://w3id.org/security/v1",
+        include_str!("../contexts/security-v1.jsonld"),
+    ),
+];
+
+/// Maximum nesting of remote contexts (the bundled contexts don't reference any other contexts)
+const MAX_REMOTE_CONTEXT_DEPTH: usize = 4;
+
+/// JSON-LD keywords
+const KEYWORDS: &[&str] = &[
+    "@base",
+    "@container",
+    "@context",
+    "@direction",
+    "@graph",
+    "@id",
+    "@import",
+    "@included",
+    "@index",
+    "@json",
+    "@language",
+    "@list",
+    "@nest",
+    "@none",
+    "@prefix",
+    "@propagate",
+    "@protected",
+    "@reverse",
+    "@set",
+    "@type",
+    "@value",
+    "@version",
+    "@vocab",
+];
+
+/// Check whether the string is a JSON-LD keyword
+pub fn is_keyword(value: &str) -> bool {
+    KEYWORDS.contains(&value)
+}
+
+/// Check whether the string has the form of a keyword (`@` followed by letters)
+///
+/// Such strings are reserved for future keywords and are ignored
+fn has_keyword_form(value: &str) -> bool {
+    matches!(
+        value.strip_prefix('@'),
+        Some(rest) if !rest.is_empty() && rest.chars().all(|c| c.is_ascii_alphabetic())
+    )
+}
+
+/// Look up a bundled context and return its `@context` entry
+fn bundled_context(url: &str) -> Result<Value> {
+    let (_, document) = BUNDLED_CONTEXTS
+        .iter()
+        .find(|(bundled_url, _)| *bundled_url == url)
+        .ok_or_else(|| Error::UnknownContext(url.to_string()))?;
+
+    let mut document: Value =
+        serde_json::from_str(document).expect("[Bug] Bundled context isn't valid JSON");
+    document
+        .get_mut("@context")
+        .map(Value::take)
+        .ok_or(Error::InvalidContext)
+}
+
+/// Definition of a term
+#[derive(Clone, Debug, Default)]
+pub struct TermDefinition {
+    /// IRI (or keyword) the term expands to
+    pub iri: String,
+
+    /// Type mapping (`@id`, `@vocab` or the IRI of a datatype)
+    pub type_mapping: Option<String>,
+
+    /// Container mapping (`@list`, `@set`, `@language` or `@index`)
+    pub container: Option<String>,
+
+    /// Language mapping (`Some(None)` if the language was explicitly reset)
+    #[allow(clippy::option_option)]
+    pub language: Option<Option<String>>,
+
+    /// Whether the term is a reverse property
+    pub reverse: bool,
+}
+
+/// Active context
+#[derive(Clone, Debug, Default)]
+pub struct Context {
+    /// Vocabulary mapping
+    vocab: Option<String>,
+
+    /// Default language
+    language: Option<String>,
+
+    /// Term definitions (`None` if the term was explicitly mapped to `null`)
+    terms: HashMap<String, Option<TermDefinition>>,
+}
+
+impl Context {
+    /// Default language of string values
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    /// Definition of the term
+    pub fn term(&self, term: &str) -> Option<&TermDefinition> {
+        self.terms.get(term).and_then(Option::as_ref)
+    }
+
+    /// Process a local context and return the resulting active context
+    pub fn process(&self, local_context: &Value) -> Result<Self> {
+        self.process_with_depth(local_context, 0)
+    }
+
+    fn process_with_depth(&self, local_context: &Value, depth: usize) -> Result<Self> {
+        let contexts = match local_context {
+            Value::Array(contexts) => contexts.iter().collect(),
+            context => vec![context],
+        };
+
+        let mut result = self.clone();
+        for context in contexts {
+            match context {
+                Value::Null => result = Self::default(),
+                Value::String(url) => {
+                    if depth >= MAX_REMOTE_CONTEXT_DEPTH {
+                        return Err(Error::InvalidContext);
+                    }
+
+                    result = result.process_with_depth(&bundled_context(url)?, depth + 1)?;
+                }
+                Value::Object(definitions) => result.process_definitions(definitions)?,
+                _ => return Err(Error::InvalidContext),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Process the entries of a context definition
+    fn process_definitions(&mut self, definitions: &Map<String, Value>) -> Result<()> {
+        match definitions.get("@base") {
+            None | Some(Value::Null) => (),
+            Some(..) => return Err(Error::UnsupportedFeature("@base")),
+        }
+        if definitions.contains_key("@import") {
+            return Err(Error::UnsupportedFeature("@import"));
+        }
+        if definitions.contains_key("@propagate") {
+            return Err(Error::UnsupportedFeature("@propagate"));
+        }
+
+        let mut defined = HashMap::new();
+
+        match definitions.get("@vocab") {
+            None => (),
+            Some(Value::Null) => self.vocab = None,
+            Some(Value::String(vocab)) => {
+                self.vocab = self.expand_iri_in_context(vocab, true, definitions, &mut defined)?;
+            }
+            Some(..) => return Err(Error::InvalidContext),
+        }
+
+        match definitions.get("@language") {
+            None => (),
+            Some(Value::Null) => self.language = None,
+            Some(Value::String(language)) => self.language = Some(language.to_lowercase()),
+            Some(..) => return Err(Error::InvalidContext),
+        }
+
+        for term in definitions.keys() {
+            // `@version` and `@protected` don't change the result of the expansion
+            if matches!(
+                term.as_str(),
+                "@base" | "@language" | "@protected" | "@version" | "@vocab"
+            ) {
+                continue;
+            }
+
+            self.create_term_definition(definitions, term, &mut defined)?;
+        }
+
+        Ok(())
+    }
+
+    /// Create the definition of a term of a local context
+    ///
+    /// `defined` tracks which terms were already processed (`false` while the term is being processed to detect cycles)
+    fn create_term_definition(
+        &mut self,
+        definitions: &Map<String, Value>,
+        term: &str,
+        defined: &mut HashMap<String, bool>,
+    ) -> Result<()> {
+        match defined.get(term) {
+            Some(true) => return Ok(()),
+            Some(false) => return Err(Error::InvalidContext),
+            None => (),
+        }
+        defined.insert(term.to_string(), false);
+
+        if is_keyword(term) {
+            return Err(Error::InvalidContext);
+        }
+        self.terms.remove(term);
+
+        let value = definitions.get(term).ok_or(Error::InvalidContext)?;
+        let definition = match value {
+            Value::Null => None,
+            Value::String(id) => {
+                let mut definition = Map::new();
+                definition.insert("@id".into(), Value::String(id.clone()));
+                self.term_definition(definitions, term, &definition, defined)?
+            }
+            Value::Object(definition) => {
+                self.term_definition(definitions, term, definition, defined)?
+            }
+            _ => return Err(Error::InvalidContext),
+        };
+
+        self.terms.insert(term.to_string(), definition);
+        defined.insert(term.to_string(), true);
+
+        Ok(())
+    }
+
+    /// Build the definition of a term from its expanded form
+    fn term_definition(
+        &mut self,
+        definitions: &Map<String, Value>,
+        term: &str,
+        value: &Map<String, Value>,
+        defined: &mut HashMap<String, bool>,
+    ) -> Result<Option<TermDefinition>> {
+        if value.contains_key("@context") {
+            return Err(Error::UnsupportedFeature("scoped contexts"));
+        }
+        if value.contains_key("@nest") {
+            return Err(Error::UnsupportedFeature("@nest"));
+        }
+
+        let mut definition = TermDefinition::default();
+
+        if let Some(reverse) = value.get("@reverse") {
+            let reverse = reverse.as_str().ok_or(Error::InvalidContext)?;
+            definition.iri = self
+                .expand_iri_in_context(reverse, true, definitions, defined)?
+                .ok_or(Error::InvalidContext)?;
+            definition.reverse = true;
+        } else if let Some(id) = value.get("@id").filter(|id| id.as_str() != Some(term)) {
+            let Some(id) = id.as_str() else {
+                // `@id: null` removes the term
+                return if id.is_null() {
+                    Ok(None)
+                } else {
+                    Err(Error::InvalidContext)
+                };
+            };
+
+            let Some(iri) = self.expand_iri_in_context(id, true, definitions, defined)? else {
+                return Ok(None);
+            };
+            if !is_keyword(&iri) && !iri.contains(':') {
+                return Err(Error::InvalidContext);
+            }
+
+            definition.iri = iri;
+        } else if let Some((prefix, suffix)) = term.split_once(':') {
+            if definitions.contains_key(prefix) {
+                self.create_term_definition(definitions, prefix, defined)?;
+            }
+
+            definition.iri = match self.term(prefix) {
+                Some(prefix_definition) => format!("{}{}", prefix_definition.iri, suffix),
+                None => term.to_string(),
+            };
+        } else if let Some(ref vocab) = self.vocab {
+            definition.iri = format!("{vocab}{term}");
+        } else {
+            return Err(Error::InvalidContext);
+        }
+
+        if let Some(r#type) = value.get("@type") {
+            let r#type = r#type.as_str().ok_or(Error::InvalidContext)?;
+            let r#type = self
+                .expand_iri_in_context(r#type, true, definitions, defined)?
+                .ok_or(Error::InvalidContext)?;
+
+            match r#type.as_str() {
+                "@id" | "@vocab" => (),
+                "@json" => return Err(Error::UnsupportedFeature("JSON literals")),
+                _ if r#type.contains(':') && !r#type.starts_with("_:") => (),
+                _ => return Err(Error::InvalidContext),
+            }
+
+            definition.type_mapping = Some(r#type);
+        }
+
+        if let Some(container) = value.get("@container") {
+            definition.container = container_mapping(container)?;
+        }
+
+        match value.get("@language") {
+            None => (),
+            Some(Value::Null) => definition.language = Some(None),
+            Some(Value::String(language)) => {
+                definition.language = Some(Some(language.to_lowercase()));
+            }
+            Some(..) => return Err(Error::InvalidContext),
+        }
+
+        Ok(Some(definition))
+    }
+
+    /// Expand an IRI while a local context is being processed
+    ///
+    /// Terms of the local context that weren't processed yet are defined on demand
+    fn expand_iri_in_context(
+        &mut self,
+        value: &str,
+        vocab: bool,
+        definitions: &Map<String, Value>,
+        defined: &mut HashMap<String, bool>,
+    ) -> Result<Option<String>> {
+        if definitions.contains_key(value) && !is_keyword(value) {
+            self.create_term_definition(definitions, value, defined)?;
+        }
+        if let Some((prefix, _suffix)) = value.split_once(':') {
+            if definitions.contains_key(prefix) {
+                self.create_term_definition(definitions, prefix, defined)?;
+            }
+        }
+
+        Ok(self.expand_iri(value, vocab))
+    }
+
+    /// Expand an IRI using the term definitions of the active context
+    ///
+    /// `vocab` decides whether terms and the vocabulary mapping are taken into account (property names and types)
+    /// or not (values of `@id`). Returns `None` if the value can't be expanded
+    pub fn expand_iri(&self, value: &str, vocab: bool) -> Option<String> {
+        if is_keyword(value) {
+            return Some(value.to_string());
+        }
+        if has_keyword_form(value) {
+            return None;
+        }
+
+        if vocab {
+            if let Some(definition) = self.terms.get(value) {
+                return definition.as_ref().map(|definition| definition.iri.clone());
+            }
+        }
+
+        if let Some((prefix, suffix)) = value.split_once(':') {
+            // Blank node identifiers and absolute IRIs
+            if prefix == "_" || suffix.starts_with("//") {
+                return Some(value.to_string());
+            }
+
+            // Compact IRIs
+            if let Some(definition) = self.term(prefix) {
+                return Some(format!("{}{}", definition.iri, suffix));
+            }
+
+            return Some(value.to_string());
+        }
+
+        if vocab {
+            if let Some(ref vocab) = self.vocab {
+                return Some(format!("{vocab}{value}"));
+            }
+        }
+
+        // Relative IRIs stay relative because documents don't have a base IRI
+        Some(value.to_string())
+    }
+}
+
+/// Parse the container mapping of a term definition
+fn container_mapping(container: &Value) -> Result<Option<String>> {
+    let containers = match container {
+        Value::Null => return Ok(None),
+        Value::String(container) => vec![container.as_str()],
+        Value::Array(containers) => containers
+            .iter()
+            .map(|container| container.as_str().ok_or(Error::InvalidContext))
+            .collect::<Result<_>>()?,
+        _ => return Err(Error::InvalidContext),
+    };
+
+    // `@set` can be combined with the other containers without changing their meaning
+    let mut mapping = None;
+    for container in containers {
+        match container {
+            "@set" => (),
+            "@list" | "@language" | "@index" if mapping.is_none() => {
+                mapping = Some(container.to_string());
+            }
+            "@graph" | "@id" | "@type" => {
+                return Err(Error::UnsupportedFeature(
+                    "@graph, @id and @type containers",
+                ))
+            }
+            _ => return Err(Error::InvalidContext),
+        }
+    }
+
+    Ok(mapping.or_else(|| Some("@set".into())))
+}
@@ -0,0 +1,66 @@
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+/// Unified error enum
+pub enum Error {
+    #[error("Base64 decode error: {0}")]
+    /// Base64 decode error
+    Base64Decode(#[from] base64::DecodeError),
+
+    #[error("Canonicalization is too complex")]
+    /// The dataset contains too many indistinguishable blank nodes to be canonicalized in reasonable time
+    CanonicalizationTooComplex,
+
+    #[error("Invalid context")]
+    /// The context is malformed
+    InvalidContext,
+
+    #[error("Invalid JSON-LD document")]
+    /// The document is malformed
+    InvalidDocument,
+
+    #[error("Invalid multibase value")]
+    /// The value isn't a base58btc encoded multibase value (or not a valid multikey)
+    InvalidMultibase,
+
+    #[error("Missing signature")]
+    /// The document doesn't carry a signature or proof
+    MissingSignature,
+
+    #[error("PEM error: {0}")]
+    /// PEM decoding error
+    Pem(#[from] pem::PemError),
+
+    #[error("Ring error")]
+    /// Ring crypto error
+    Ring(#[from] ring::error::Unspecified),
+
+    #[error("Invalid key: {0}")]
+    /// Ring invalid key
+    RingInvalidKey(#[from] ring::error::KeyRejected),
+
+    #[error("Time out of range: {0}")]
+    /// The creation time is out of range
+    TimeRange(#[from] time::error::ComponentRange),
+
+    #[error("Time formatting error: {0}")]
+    /// The creation time couldn't be formatted
+    TimeFormat(#[from] time::error::Format),
+
+    #[error("Unknown context: {0}")]
+    /// The context isn't bundled with this crate (remote contexts are never fetched)
+    UnknownContext(String),
+
+    #[error("Unknown key type")]
+    /// Unknown key type
+    UnknownKeyType,
+
+    #[error("Unknown signature type")]
+    /// The signature (or proof) has a type or cryptosuite this crate doesn't implement
+    UnknownSignatureType,
+
+    #[error("Unsupported JSON-LD feature: {0}")]
+    /// The document uses a JSON-LD feature the processor doesn't implement
+    UnsupportedFeature(&'static str),
+}
@@ -0,0 +1,419 @@
+use crate::{
+    context::{is_keyword, Context},
+    error::{Error, Result},
+};
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+
+/// Expand a JSON-LD document
+///
+/// Implements the subset of the [expansion algorithm](https://www.w3.org/TR/json-ld11-api/#expansion-algorithm)
+/// that is needed for ActivityPub documents. Unsupported features result in an error instead of a different expansion
+pub fn expand(document: &Value) -> Result<Vec<Value>> {
+    let expanded = expand_element(&Context::default(), None, document)?;
+
+    let expanded = match expanded {
+        Value::Object(mut object) if object.len() == 1 && object.contains_key("@graph") => {
+            object.remove("@graph").unwrap_or_default()
+        }
+        expanded => expanded,
+    };
+
+    Ok(match expanded {
+        Value::Null => Vec::new(),
+        Value::Array(items) => items,
+        expanded => vec![expanded],
+    })
+}
+
+/// Collect the properties of the document that don't end up in the RDF graph
+///
+/// These are terms that can't be expanded to an IRI (or only to a blank node identifier), for example terms that
+/// aren't defined by any of the bundled contexts. The returned names are the unexpanded property names
+pub fn dropped_properties(document: &Value) -> Result<Vec<String>> {
+    let mut dropped = Vec::new();
+    collect_dropped_properties(&Context::default(), document, &mut dropped)?;
+
+    Ok(dropped)
+}
+
+fn collect_dropped_properties(
+    context: &Context,
+    element: &Value,
+    dropped: &mut Vec<String>,
+) -> Result<()> {
+    match element {
+        Value::Array(items) => {
+            for item in items {
+                collect_dropped_properties(context, item, dropped)?;
+            }
+        }
+        Value::Object(object) => {
+            let context = match object.get("@context") {
+                Some(local_context) => Cow::Owned(context.process(local_context)?),
+                None => Cow::Borrowed(context),
+            };
+            let context = context.as_ref();
+
+            for (key, value) in object {
+                if key == "@context" {
+                    continue;
+                }
+
+                match context.expand_iri(key, true) {
+                    Some(property) if is_keyword(&property) => {
+                        if matches!(property.as_str(), "@graph" | "@list" | "@set") {
+                            collect_dropped_properties(context, value, dropped)?;
+                        }
+                    }
+                    Some(property) if property.contains(':') && !property.starts_with("_:") => {
+                        match container(context, Some(key)) {
+                            Some("@language") => (),
+                            Some("@index") => {
+                                for item in value.as_object().into_iter().flat_map(Map::values) {
+                                    collect_dropped_properties(context, item, dropped)?;
+                                }
+                            }
+                            _ => collect_dropped_properties(context, value, dropped)?,
+                        }
+                    }
+                    _ => dropped.push(key.clone()),
+                }
+            }
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Wrap the value into an array (if it isn't one already)
+fn into_array(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(items) => items,
+        Value::Null => Vec::new(),
+        value => vec![value],
+    }
+}
+
+/// Check whether the active property is a top-level position (no property or `@graph`)
+fn is_top_level(active_property: Option<&str>) -> bool {
+    matches!(active_property, None | Some("@graph"))
+}
+
+/// Container mapping of the active property
+fn container<'a>(context: &'a Context, active_property: Option<&str>) -> Option<&'a str> {
+    active_property
+        .and_then(|property| context.term(property))
+        .and_then(|definition| definition.container.as_deref())
+}
+
+fn expand_element(
+    context: &Context,
+    active_property: Option<&str>,
+    element: &Value,
+) -> Result<Value> {
+    match element {
+        Value::Null => Ok(Value::Null),
+        Value::Array(items) => {
+            let is_list = container(context, active_property) == Some("@list");
+
+            let mut result = Vec::with_capacity(items.len());
+            for item in items {
+                match expand_element(context, active_property, item)? {
+                    Value::Null => (),
+                    Value::Array(..) if is_list => {
+                        return Err(Error::UnsupportedFeature("lists of lists"))
+                    }
+                    Value::Array(expanded) => result.extend(expanded),
+                    expanded => result.push(expanded),
+                }
+            }
+
+            Ok(Value::Array(result))
+        }
+        Value::Object(object) => expand_object(context, active_property, object),
+        scalar => match active_property {
+            Some(active_property) if !is_top_level(Some(active_property)) => {
+                Ok(expand_value(context, active_property, scalar))
+            }
+            _ => Ok(Value::Null),
+        },
+    }
+}
+
+fn expand_object(
+    context: &Context,
+    active_property: Option<&str>,
+    object: &Map<String, Value>,
+) -> Result<Value> {
+    let context = match object.get("@context") {
+        Some(local_context) => Cow::Owned(context.process(local_context)?),
+        None => Cow::Borrowed(context),
+    };
+    let context = context.as_ref();
+
+    let mut result = Map::new();
+    for (key, value) in object {
+        if key == "@context" {
+            continue;
+        }
+
+        let Some(expanded_property) = context.expand_iri(key, true) else {
+            continue;
+        };
+
+        if is_keyword(&expanded_property) {
+            if result.contains_key(&expanded_property) {
+                return Err(Error::InvalidDocument);
+            }
+
+            let expanded_value = match expanded_property.as_str() {
+                "@id" => {
+                    let id = value.as_str().ok_or(Error::InvalidDocument)?;
+                    let id = context
+                        .expand_iri(id, false)
+                        .ok_or(Error::InvalidDocument)?;
+                    Value::String(id)
+                }
+                "@type" => expand_type(context, value)?,
+                "@graph" => {
+                    Value::Array(into_array(expand_element(context, Some("@graph"), value)?))
+                }
+                "@value" => match value {
+                    Value::Array(..) | Value::Object(..) => return Err(Error::InvalidDocument),
+                    value => value.clone(),
+                },
+                "@language" => {
+                    Value::String(value.as_str().ok_or(Error::InvalidDocument)?.to_lowercase())
+                }
+                "@index" => Value::String(value.as_str().ok_or(Error::InvalidDocument)?.into()),
+                "@list" => {
+                    // Free-floating lists are dropped
+                    if is_top_level(active_property) {
+                        continue;
+                    }
+
+                    Value::Array(into_array(expand_element(context, active_property, value)?))
+                }
+                "@set" => expand_element(context, active_property, value)?,
+                "@reverse" => return Err(Error::UnsupportedFeature("@reverse")),
+                "@included" => return Err(Error::UnsupportedFeature("@included")),
+                "@nest" => return Err(Error::UnsupportedFeature("@nest")),
+                "@direction" => return Err(Error::UnsupportedFeature("@direction")),
+                _ => continue,
+            };
+
+            result.insert(expanded_property, expanded_value);
+            continue;
+        }
+
+        // Properties that neither are IRIs nor blank node identifiers are dropped
+        if !expanded_property.contains(':') {
+            continue;
+        }
+
+        let definition = context.term(key);
+        if definition.is_some_and(|definition| definition.reverse) {
+            return Err(Error::UnsupportedFeature("reverse properties"));
+        }
+
+        let container = definition.and_then(|definition| definition.container.as_deref());
+        let expanded_value = match (container, value) {
+            (Some("@language"), Value::Object(language_map)) => expand_language_map(language_map)?,
+            (Some("@index"), Value::Object(index_map)) => {
+                expand_index_map(context, key, index_map)?
+            }
+            _ => expand_element(context, Some(key), value)?,
+        };
+        if expanded_value.is_null() {
+            continue;
+        }
+
+        let expanded_value = if container == Some("@list") && !is_list(&expanded_value) {
+            let mut list = Map::new();
+            list.insert("@list".into(), Value::Array(into_array(expanded_value)));
+            Value::Object(list)
+        } else {
+            expanded_value
+        };
+
+        let values = result
+            .entry(expanded_property)
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if let Value::Array(values) = values {
+            values.extend(into_array(expanded_value));
+        }
+    }
+
+    post_process(active_property, result)
+}
+
+/// Check whether the value is a list object
+fn is_list(value: &Value) -> bool {
+    value
+        .as_object()
+        .is_some_and(|object| object.contains_key("@list"))
+}
+
+/// Expand the value of `@type`
+fn expand_type(context: &Context, value: &Value) -> Result<Value> {
+    let expand = |r#type: &Value| -> Result<Value> {
+        let r#type = r#type.as_str().ok_or(Error::InvalidDocument)?;
+        let r#type = context
+            .expand_iri(r#type, true)
+            .ok_or(Error::InvalidDocument)?;
+
+        Ok(Value::String(r#type))
+    };
+
+    match value {
+        Value::Array(types) => Ok(Value::Array(
+            types.iter().map(expand).collect::<Result<_>>()?,
+        )),
+        r#type => expand(r#type),
+    }
+}
+
+/// Expand a language map into an array of language-tagged strings
+fn expand_language_map(language_map: &Map<String, Value>) -> Result<Value> {
+    let mut result = Vec::new();
+
+    for (language, values) in language_map {
+        for value in into_array(values.clone()) {
+            if value.is_null() {
+                continue;
+            }
+            if !value.is_string() {
+                return Err(Error::InvalidDocument);
+            }
+
+            let mut expanded = Map::new();
+            expanded.insert("@value".into(), value);
+            if language != "@none" {
+                expanded.insert("@language".into(), Value::String(language.to_lowercase()));
+            }
+
+            result.push(Value::Object(expanded));
+        }
+    }
+
+    Ok(Value::Array(result))
+}
+
+/// Expand an index map into an array of values with an `@index` entry
+fn expand_index_map(
+    context: &Context,
+    active_property: &str,
+    index_map: &Map<String, Value>,
+) -> Result<Value> {
+    let mut result = Vec::new();
+
+    for (index, values) in index_map {
+        let values = Value::Array(into_array(values.clone()));
+        for mut item in into_array(expand_element(context, Some(active_property), &values)?) {
+            if let Value::Object(ref mut item) = item {
+                item.entry("@index")
+                    .or_insert_with(|| Value::String(index.clone()));
+            }
+
+            result.push(item);
+        }
+    }
+
+    Ok(Value::Array(result))
+}
+
+/// Expand a scalar value of the property
+fn expand_value(context: &Context, active_property: &str, value: &Value) -> Value {
+    let definition = context.term(active_property);
+    let type_mapping = definition.and_then(|definition| definition.type_mapping.as_deref());
+
+    let mut result = Map::new();
+    match (type_mapping, value) {
+        (Some(type_mapping @ ("@id" | "@vocab")), Value::String(id)) => {
+            let Some(id) = context.expand_iri(id, type_mapping == "@vocab") else {
+                return Value::Null;
+            };
+            result.insert("@id".into(), Value::String(id));
+        }
+        (Some(type_mapping), value) if type_mapping != "@id" && type_mapping != "@vocab" => {
+            result.insert("@value".into(), value.clone());
+            result.insert("@type".into(), Value::String(type_mapping.into()));
+        }
+        (_, value) => {
+            result.insert("@value".into(), value.clone());
+
+            if value.is_string() {
+                let language = match definition.and_then(|definition| definition.language.as_ref())
+                {
+                    Some(language) => language.as_deref(),
+                    None => context.language(),
+                };
+
+                if let Some(language) = language {
+                    result.insert("@language".into(), Value::String(language.into()));
+                }
+            }
+        }
+    }
+
+    Value::Object(result)
+}
+
+/// Validate and simplify the expanded object
+fn post_process(active_property: Option<&str>, mut result: Map<String, Value>) -> Result<Value> {
+    if let Some(value) = result.get("@value") {
+        if result
+            .keys()
+            .any(|key| !matches!(key.as_str(), "@value" | "@language" | "@type" | "@index"))
+        {
+            return Err(Error::InvalidDocument);
+        }
+        if result.contains_key("@language") && result.contains_key("@type") {
+            return Err(Error::InvalidDocument);
+        }
+        if value.is_null() {
+            return Ok(Value::Null);
+        }
+        if result.contains_key("@language") && !value.is_string() {
+            return Err(Error::InvalidDocument);
+        }
+        if result
+            .get("@type")
+            .is_some_and(|r#type| !r#type.is_string())
+        {
+            return Err(Error::InvalidDocument);
+        }
+    } else if let Some(r#type) = result.get_mut("@type") {
+        if !r#type.is_array() {
+            *r#type = Value::Array(vec![r#type.take()]);
+        }
+    } else if result.contains_key("@set") || result.contains_key("@list") {
+        if result
+            .keys()
+            .any(|key| !matches!(key.as_str(), "@set" | "@list" | "@index"))
+        {
+            return Err(Error::InvalidDocument);
+        }
+
+        if let Some(set) = result.remove("@set") {
+            return Ok(set);
+        }
+    }
+
+    if result.len() == 1 && result.contains_key("@language") {
+        return Ok(Value::Null);
+    }
+
+    if is_top_level(active_property)
+        && (result.is_empty()
+            || result.contains_key("@value")
+            || result.contains_key("@list")
+            || (result.len() == 1 && result.contains_key("@id")))
+    {
+        return Ok(Value::Null);
+    }
+
+    Ok(Value::Object(result))
+}
@@ -0,0 +1,122 @@
+//!
+//! JSON canonicalization scheme ([RFC 8785](https://www.rfc-editor.org/rfc/rfc8785))
+//!
+
+use serde_json::{Map, Number, Value};
+use std::fmt::Write;
+
+/// Serialise the value into its canonical form
+#[must_use]
+pub fn canonicalize(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value);
+    out
+}
+
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+        Value::Number(number) => write_number(out, number),
+        Value::String(value) => write_string(out, value),
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_value(out, item);
+            }
+            out.push(']');
+        }
+        Value::Object(object) => write_object(out, object),
+    }
+}
+
+/// Write the object with its members sorted by the UTF-16 code units of their names
+fn write_object(out: &mut String, object: &Map<String, Value>) {
+    let mut members = object.iter().collect::<Vec<_>>();
+    members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+    out.push('{');
+    for (index, (name, value)) in members.into_iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        write_string(out, name);
+        out.push(':');
+        write_value(out, value);
+    }
+    out.push('}');
+}
+
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < '\u{20}' => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Write the number like ECMAScript's `Number.prototype.toString` does
+fn write_number(out: &mut String, number: &Number) {
+    if let Some(integer) = number.as_i64() {
+        let _ = write!(out, "{integer}");
+        return;
+    }
+
+    let value = number.as_f64().unwrap_or_default();
+    if value == 0.0 {
+        out.push('0');
+        return;
+    }
+    if value.is_sign_negative() {
+        out.push('-');
+    }
+
+    // Rust's `LowerExp` formatting yields the shortest digits that round-trip, just like ECMAScript
+    let formatted = format!("{:e}", value.abs());
+    let (mantissa, exponent) = formatted
+        .split_once('e')
+        .expect("[Bug] Exponent missing in formatted number");
+    let digits = mantissa.replace('.', "");
+    let exponent: i32 = exponent.parse().expect("[Bug] Invalid exponent");
+
+    // Position of the decimal point relative to the start of the digits
+    let point = exponent + 1;
+    let digit_count = i32::try_from(digits.len()).unwrap_or(i32::MAX);
+
+    if digit_count <= point && point <= 21 {
+        out.push_str(&digits);
+        out.extend((digit_count..point).map(|_| '0'));
+    } else if 0 < point && point <= 21 {
+        let (integer, fraction) = digits.split_at(point.unsigned_abs() as usize);
+        let _ = write!(out, "{integer}.{fraction}");
+    } else if -6 < point && point <= 0 {
+        out.push_str("0.");
+        out.extend((point..0).map(|_| '0'));
+        out.push_str(&digits);
+    } else {
+        let (first, rest) = digits.split_at(1);
+        out.push_str(first);
+        if !rest.is_empty() {
+            let _ = write!(out, ".{rest}");
+        }
+
+        let exponent = point - 1;
+        let sign = if exponent < 0 { '-' } else { '+' };
+        let _ = write!(out, "e{sign}{}", exponent.abs());
+    }
+}
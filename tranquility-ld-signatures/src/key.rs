@@ -0,0 +1,103 @@
+use crate::error::{Error, Result};
+use pkcs8::{der::Decode, Document, ObjectIdentifier, PrivateKeyInfo, SubjectPublicKeyInfo};
+use ring::{
+    rand::SystemRandom,
+    signature::{
+        Ed25519KeyPair, RsaKeyPair, UnparsedPublicKey, RSA_PKCS1_2048_8192_SHA256, RSA_PKCS1_SHA256,
+    },
+};
+
+/// OID of RSA keys (`rsaEncryption`)
+const RSA_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+
+/// OID of Ed25519 keys (`id-Ed25519`)
+const ED25519_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.112");
+
+/// Decode a PEM-encoded RSA public key (PKCS#1 or SPKI) into its PKCS#1 DER form
+pub fn decode_rsa_public_key(public_key: &[u8]) -> Result<Vec<u8>> {
+    let pem = pem::parse(public_key)?;
+
+    match pem.tag.as_str() {
+        "RSA PUBLIC KEY" => Ok(pem.contents),
+        "PUBLIC KEY" => {
+            let document = Document::from_der(&pem.contents).map_err(|_| Error::UnknownKeyType)?;
+            let public_key: SubjectPublicKeyInfo<'_> =
+                document.decode_msg().map_err(|_| Error::UnknownKeyType)?;
+
+            if public_key.algorithm.oid != RSA_OID {
+                return Err(Error::UnknownKeyType);
+            }
+
+            Ok(public_key.subject_public_key.to_vec())
+        }
+        _ => Err(Error::UnknownKeyType),
+    }
+}
+
+/// Decode a PEM-encoded private key (PKCS#1 or PKCS#8)
+///
+/// Returns the object identifier of the key type and the DER-encoded key in the format ring expects
+/// (PKCS#1 for RSA keys, PKCS#8 for Ed25519 keys)
+fn decode_private_key(private_key: &[u8]) -> Result<(ObjectIdentifier, Vec<u8>)> {
+    let pem = pem::parse(private_key)?;
+
+    match pem.tag.as_str() {
+        "RSA PRIVATE KEY" => Ok((RSA_OID, pem.contents)),
+        "PRIVATE KEY" => {
+            let document = Document::from_der(&pem.contents).map_err(|_| Error::UnknownKeyType)?;
+            let private_key: PrivateKeyInfo<'_> =
+                document.decode_msg().map_err(|_| Error::UnknownKeyType)?;
+
+            match private_key.algorithm.oid {
+                RSA_OID => Ok((RSA_OID, private_key.private_key.to_vec())),
+                ED25519_OID => Ok((ED25519_OID, pem.contents.clone())),
+                _ => Err(Error::UnknownKeyType),
+            }
+        }
+        _ => Err(Error::UnknownKeyType),
+    }
+}
+
+/// Sign the data with an RSA private key (`RSASSA-PKCS1-v1_5` with SHA-256)
+pub fn rsa_sign(private_key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    let (RSA_OID, der) = decode_private_key(private_key)? else {
+        return Err(Error::UnknownKeyType);
+    };
+
+    let key_pair = RsaKeyPair::from_der(&der)?;
+    let mut signature = vec![0; key_pair.public_modulus_len()];
+    key_pair.sign(
+        &RSA_PKCS1_SHA256,
+        &SystemRandom::new(),
+        data,
+        &mut signature,
+    )?;
+
+    Ok(signature)
+}
+
+/// Verify the signature with an RSA public key (`RSASSA-PKCS1-v1_5` with SHA-256)
+pub fn rsa_verify(public_key: &[u8], data: &[u8], signature: &[u8]) -> Result<bool> {
+    let der = decode_rsa_public_key(public_key)?;
+    let public_key = UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, der);
+
+    Ok(public_key.verify(data, signature).is_ok())
+}
+
+/// Sign the data with an Ed25519 private key
+pub fn ed25519_sign(private_key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    let (ED25519_OID, der) = decode_private_key(private_key)? else {
+        return Err(Error::UnknownKeyType);
+    };
+
+    let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&der)?;
+
+    Ok(key_pair.sign(data).as_ref().to_vec())
+}
+
+/// Verify the signature with a raw Ed25519 public key
+pub fn ed25519_verify(public_key: &[u8], data: &[u8], signature: &[u8]) -> bool {
+    let public_key = UnparsedPublicKey::new(&ring::signature::ED25519, public_key);
+
+    public_key.verify(data, signature).is_ok()
+}
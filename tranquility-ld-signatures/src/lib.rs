@@ -0,0 +1,46 @@
+#![forbid(rust_2018_idioms, unsafe_code)]
+#![warn(clippy::all, clippy::pedantic)]
+#![deny(missing_docs)]
+#![allow(
+    clippy::doc_markdown,
+    clippy::missing_errors_doc,
+    clippy::module_name_repetitions
+)]
+
+//!
+//! Implementation of `RsaSignature2017` [Linked Data Signatures](https://w3c-ccg.github.io/ld-signatures/)
+//! and of object integrity proofs ([FEP-8b32](https://codeberg.org/fediverse/fep/src/branch/main/fep/8b32/fep-8b32.md))
+//!
+//! Linked Data Signatures sign the URDNA2015 canonicalization of the document.
+//! The JSON-LD processor only knows the bundled contexts (ActivityStreams, security v1 and identity v1) and never fetches remote contexts
+//!
+
+use crate::{error::Result, expand::expand, rdf::ToRdf};
+use serde_json::Value;
+
+/// Canonicalize the JSON-LD document with URDNA2015
+///
+/// Returns the canonical N-Quads serialisation of the document
+pub fn canonicalize(document: &Value) -> Result<String> {
+    let expanded = expand(document)?;
+    let triples = ToRdf::convert(&expanded)?;
+
+    urdna2015::canonicalize(&triples)
+}
+
+mod context;
+mod error;
+mod expand;
+pub mod jcs;
+mod key;
+pub mod multibase;
+pub mod proof;
+mod rdf;
+pub mod signature;
+mod urdna2015;
+mod util;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::error::Error;
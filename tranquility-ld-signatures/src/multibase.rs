@@ -0,0 +1,152 @@
+//!
+//! Multibase (base58btc) and multikey encoding of Ed25519 and RSA public keys
+//!
+
+use crate::{
+    error::{Error, Result},
+    key,
+};
+use pem::{EncodeConfig, LineEnding, Pem};
+
+/// Bitcoin base58 alphabet
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Multibase prefix of base58btc encoded values
+const BASE58BTC_PREFIX: char = 'z';
+
+/// Multicodec prefix of Ed25519 public keys (`ed25519-pub`, varint encoded)
+const ED25519_PUB_PREFIX: [u8; 2] = [0xed, 0x01];
+
+/// Length of an Ed25519 public key
+const ED25519_PUBLIC_KEY_LEN: usize = 32;
+
+/// Multicodec prefix of RSA public keys (`rsa-pub`, varint encoded)
+const RSA_PUB_PREFIX: [u8; 2] = [0x85, 0x24];
+
+fn base58_encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|byte| **byte == 0).count();
+
+    // Base58 digits in little-endian order
+    let mut digits: Vec<u8> = Vec::new();
+    for byte in &data[zeros..] {
+        let mut carry = u32::from(*byte);
+        for digit in &mut digits {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut encoded = "1".repeat(zeros);
+    encoded.extend(
+        digits
+            .iter()
+            .rev()
+            .map(|digit| char::from(ALPHABET[usize::from(*digit)])),
+    );
+
+    encoded
+}
+
+fn base58_decode(encoded: &str) -> Result<Vec<u8>> {
+    let zeros = encoded.bytes().take_while(|c| *c == b'1').count();
+
+    // Bytes in little-endian order
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in encoded.bytes().skip(zeros) {
+        let (_digit, mut carry) = ALPHABET
+            .iter()
+            .zip(0_u32..)
+            .find(|(digit, _value)| **digit == c)
+            .ok_or(Error::InvalidMultibase)?;
+
+        for byte in &mut bytes {
+            carry += u32::from(*byte) * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut decoded = vec![0; zeros];
+    decoded.extend(bytes.iter().rev());
+
+    Ok(decoded)
+}
+
+/// Encode the data as a base58btc multibase value
+#[must_use]
+pub fn encode(data: &[u8]) -> String {
+    let mut encoded = String::from(BASE58BTC_PREFIX);
+    encoded.push_str(&base58_encode(data));
+    encoded
+}
+
+/// Decode a base58btc multibase value
+pub fn decode(encoded: &str) -> Result<Vec<u8>> {
+    let encoded = encoded
+        .strip_prefix(BASE58BTC_PREFIX)
+        .ok_or(Error::InvalidMultibase)?;
+
+    base58_decode(encoded)
+}
+
+/// Encode a raw Ed25519 public key as a multikey (the format of `publicKeyMultibase`)
+#[must_use]
+pub fn encode_multikey(public_key: &[u8]) -> String {
+    let mut data = ED25519_PUB_PREFIX.to_vec();
+    data.extend_from_slice(public_key);
+
+    encode(&data)
+}
+
+/// Decode a multikey into a raw Ed25519 public key
+///
+/// Only Ed25519 public keys are supported
+pub fn decode_multikey(encoded: &str) -> Result<Vec<u8>> {
+    let data = decode(encoded)?;
+
+    match data.strip_prefix(&ED25519_PUB_PREFIX) {
+        Some(public_key) if public_key.len() == ED25519_PUBLIC_KEY_LEN => Ok(public_key.to_vec()),
+        Some(..) => Err(Error::InvalidMultibase),
+        None => Err(Error::UnknownKeyType),
+    }
+}
+
+/// Encode a PEM-encoded RSA public key (PKCS#1 or SPKI) as a multikey
+pub fn encode_rsa_multikey(public_key: &[u8]) -> Result<String> {
+    let mut data = RSA_PUB_PREFIX.to_vec();
+    data.extend(key::decode_rsa_public_key(public_key)?);
+
+    Ok(encode(&data))
+}
+
+/// Decode a multikey into a PEM-encoded RSA public key (PKCS#1)
+pub fn decode_rsa_multikey(encoded: &str) -> Result<String> {
+    let data = decode(encoded)?;
+
+    match data.strip_prefix(&RSA_PUB_PREFIX) {
+        Some([]) => Err(Error::InvalidMultibase),
+        Some(public_key) => {
+            let pem = Pem {
+                tag: "RSA PUBLIC KEY".into(),
+                contents: public_key.to_vec(),
+            };
+
+            Ok(pem::encode_config(
+                &pem,
+                EncodeConfig {
+                    line_ending: LineEnding::LF,
+                },
+            ))
+        }
+        None => Err(Error::UnknownKeyType),
+    }
+}
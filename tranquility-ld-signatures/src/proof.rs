@@ -0,0 +1,118 @@
+//!
+//! Object integrity proofs as described in [FEP-8b32](https://codeberg.org/fediverse/fep/src/branch/main/fep/8b32/fep-8b32.md)
+//!
+//! Only the `eddsa-jcs-2022` cryptosuite is supported
+//!
+
+use crate::{
+    error::{Error, Result},
+    jcs, key, multibase,
+    util::sha256,
+};
+use serde_json::{Map, Value};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// Type of the proof
+pub const DATA_INTEGRITY_PROOF: &str = "DataIntegrityProof";
+
+/// Cryptosuite of the proof
+pub const EDDSA_JCS_2022: &str = "eddsa-jcs-2022";
+
+/// Purpose of the proof (the verification method has to be listed as an assertion method of the signer)
+const ASSERTION_METHOD: &str = "assertionMethod";
+
+/// Name of the entry that holds the proof
+const PROOF: &str = "proof";
+
+/// Data that is signed
+///
+/// The hash of the canonicalized proof configuration (the proof without its value, with the context of the document)
+/// concatenated with the hash of the canonicalized document (without its proof)
+fn hash_data(document: &Map<String, Value>, proof: &Map<String, Value>) -> Vec<u8> {
+    let mut proof_config = proof.clone();
+    proof_config.remove("proofValue");
+
+    let mut document = document.clone();
+    document.remove(PROOF);
+
+    if let Some(context) = document.get("@context") {
+        proof_config.insert("@context".into(), context.clone());
+    }
+
+    let mut hash_data = sha256(jcs::canonicalize(&Value::Object(proof_config)));
+    hash_data.extend(sha256(jcs::canonicalize(&Value::Object(document))));
+
+    hash_data
+}
+
+/// Sign the document with the private key and embed the proof
+///
+/// The private key has to be an Ed25519 key in the PKCS#8 PEM format.
+/// An existing proof is replaced
+pub fn sign(document: &mut Value, verification_method: &str, private_key: &[u8]) -> Result<()> {
+    let document = document.as_object_mut().ok_or(Error::InvalidDocument)?;
+
+    let created = OffsetDateTime::from_unix_timestamp(OffsetDateTime::now_utc().unix_timestamp())?
+        .format(&Rfc3339)?;
+
+    let mut proof = Map::new();
+    proof.insert("type".into(), Value::String(DATA_INTEGRITY_PROOF.into()));
+    proof.insert("cryptosuite".into(), Value::String(EDDSA_JCS_2022.into()));
+    proof.insert(
+        "verificationMethod".into(),
+        Value::String(verification_method.into()),
+    );
+    proof.insert(
+        "proofPurpose".into(),
+        Value::String(ASSERTION_METHOD.into()),
+    );
+    proof.insert("created".into(), Value::String(created));
+
+    let hash_data = hash_data(document, &proof);
+    let signature = key::ed25519_sign(private_key, &hash_data)?;
+    proof.insert(
+        "proofValue".into(),
+        Value::String(multibase::encode(&signature)),
+    );
+
+    document.insert(PROOF.into(), Value::Object(proof));
+
+    Ok(())
+}
+
+/// Get the verification method (the key ID) of the embedded proof
+///
+/// This doesn't verify the proof. It's intended to find out which public key is needed for the verification
+pub fn verification_method(document: &Value) -> Result<&str> {
+    document
+        .get(PROOF)
+        .ok_or(Error::MissingSignature)?
+        .get("verificationMethod")
+        .and_then(Value::as_str)
+        .ok_or(Error::MissingSignature)
+}
+
+/// Verify the embedded proof of the document with the public key
+///
+/// The public key has to be an Ed25519 key encoded as a multikey (the format of `publicKeyMultibase`)
+pub fn verify(document: &Value, public_key_multibase: &str) -> Result<bool> {
+    let document = document.as_object().ok_or(Error::InvalidDocument)?;
+    let proof = document
+        .get(PROOF)
+        .and_then(Value::as_object)
+        .ok_or(Error::MissingSignature)?;
+
+    let entry = |name: &str| proof.get(name).and_then(Value::as_str);
+    if entry("type") != Some(DATA_INTEGRITY_PROOF) || entry("cryptosuite") != Some(EDDSA_JCS_2022) {
+        return Err(Error::UnknownSignatureType);
+    }
+    if entry("proofPurpose") != Some(ASSERTION_METHOD) {
+        return Ok(false);
+    }
+
+    let signature = multibase::decode(entry("proofValue").ok_or(Error::MissingSignature)?)?;
+    let public_key = multibase::decode_multikey(public_key_multibase)?;
+    let hash_data = hash_data(document, proof);
+
+    Ok(key::ed25519_verify(&public_key, &hash_data, &signature))
+}
@@ -0,0 +1,345 @@
+use crate::error::{Error, Result};
+use serde_json::{Map, Number, Value};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write,
+};
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+
+const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+const XSD_DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
+const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+
+/// Numbers with an absolute value of at least 10^21 are converted to doubles
+const MAX_INTEGER: f64 = 1e21;
+
+/// RDF term
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Term {
+    /// IRI
+    Iri(String),
+
+    /// Blank node (the identifier is stored without the `_:` prefix)
+    Blank(String),
+
+    /// Literal with its datatype IRI and its language tag
+    Literal {
+        value: String,
+        datatype: String,
+        language: Option<String>,
+    },
+}
+
+impl Term {
+    /// Identifier of the blank node (`None` if the term isn't a blank node)
+    pub fn blank_node(&self) -> Option<&str> {
+        match self {
+            Self::Blank(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Write the term in its N-Quads form
+    ///
+    /// Blank node identifiers are passed through the closure
+    pub fn write<F>(&self, out: &mut String, blank_node_id: &F)
+    where
+        F: Fn(&str) -> String,
+    {
+        match self {
+            Self::Iri(iri) => {
+                let _ = write!(out, "<{iri}>");
+            }
+            Self::Blank(id) => {
+                let _ = write!(out, "_:{}", blank_node_id(id));
+            }
+            Self::Literal {
+                value,
+                datatype,
+                language,
+            } => {
+                out.push('"');
+                escape(out, value);
+                out.push('"');
+
+                if let Some(language) = language {
+                    let _ = write!(out, "@{language}");
+                } else if datatype != XSD_STRING {
+                    let _ = write!(out, "^^<{datatype}>");
+                }
+            }
+        }
+    }
+}
+
+/// Escape a literal value for N-Quads
+fn escape(out: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// RDF triple in the default graph
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Triple {
+    pub subject: Term,
+    pub predicate: Term,
+    pub object: Term,
+}
+
+impl Triple {
+    /// Serialise the triple into an N-Quads line (including the trailing newline)
+    pub fn to_nquad<F>(&self, blank_node_id: &F) -> String
+    where
+        F: Fn(&str) -> String,
+    {
+        let mut out = String::new();
+
+        self.subject.write(&mut out, blank_node_id);
+        out.push(' ');
+        self.predicate.write(&mut out, blank_node_id);
+        out.push(' ');
+        self.object.write(&mut out, blank_node_id);
+        out.push_str(" .\n");
+
+        out
+    }
+}
+
+/// Check whether the value is an absolute IRI (has a scheme)
+fn is_absolute_iri(value: &str) -> bool {
+    let Some((scheme, _rest)) = value.split_once(':') else {
+        return false;
+    };
+
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Format a number in the canonical lexical form of `xsd:double` (for example, `1.5E0`)
+fn canonical_double(value: f64) -> String {
+    let formatted = format!("{value:E}");
+    match formatted.split_once('E') {
+        Some((mantissa, exponent)) if !mantissa.contains('.') => {
+            format!("{mantissa}.0E{exponent}")
+        }
+        _ => formatted,
+    }
+}
+
+/// Convert a number into its lexical form and its datatype
+fn number_literal(number: &Number, datatype: Option<&str>) -> (String, String) {
+    if let Some(integer) = number.as_i64() {
+        if datatype != Some(XSD_DOUBLE) {
+            return (integer.to_string(), datatype.unwrap_or(XSD_INTEGER).into());
+        }
+    } else if let Some(integer) = number.as_u64() {
+        if datatype != Some(XSD_DOUBLE) {
+            return (integer.to_string(), datatype.unwrap_or(XSD_INTEGER).into());
+        }
+    }
+
+    let value = number.as_f64().unwrap_or_default();
+    if value.fract() == 0.0 && value.abs() < MAX_INTEGER && datatype != Some(XSD_DOUBLE) {
+        return (
+            format!("{value:.0}"),
+            datatype.unwrap_or(XSD_INTEGER).into(),
+        );
+    }
+
+    (
+        canonical_double(value),
+        datatype.unwrap_or(XSD_DOUBLE).into(),
+    )
+}
+
+/// Converts expanded JSON-LD documents into RDF triples
+///
+/// See the [deserialisation algorithm](https://www.w3.org/TR/json-ld11-api/#deserialize-json-ld-to-rdf-algorithm).
+/// Instead of building a node map first, the nodes are converted while they are visited. This results in the same dataset
+#[derive(Default)]
+pub struct ToRdf {
+    /// Relabelled blank nodes of the document
+    blank_nodes: HashMap<String, Term>,
+
+    /// Counter for new blank node identifiers
+    counter: usize,
+
+    /// Triples of the dataset
+    triples: BTreeSet<Triple>,
+}
+
+impl ToRdf {
+    /// Convert the expanded document into a set of triples
+    pub fn convert(expanded: &[Value]) -> Result<BTreeSet<Triple>> {
+        let mut to_rdf = Self::default();
+
+        for node in expanded {
+            let node = node.as_object().ok_or(Error::InvalidDocument)?;
+            to_rdf.node(node)?;
+        }
+
+        Ok(to_rdf.triples)
+    }
+
+    /// Generate a new blank node
+    fn new_blank_node(&mut self) -> Term {
+        let id = format!("b{}", self.counter);
+        self.counter += 1;
+
+        Term::Blank(id)
+    }
+
+    /// Relabel a blank node identifier of the document
+    fn blank_node(&mut self, id: &str) -> Term {
+        if let Some(node) = self.blank_nodes.get(id) {
+            return node.clone();
+        }
+
+        let node = self.new_blank_node();
+        self.blank_nodes.insert(id.to_string(), node.clone());
+
+        node
+    }
+
+    /// Convert an IRI (or blank node identifier) into a term
+    ///
+    /// Returns `None` for relative IRIs since they can't be represented in RDF
+    fn iri(&mut self, iri: &str) -> Option<Term> {
+        if let Some(id) = iri.strip_prefix("_:") {
+            Some(self.blank_node(id))
+        } else if is_absolute_iri(iri) {
+            Some(Term::Iri(iri.to_string()))
+        } else {
+            None
+        }
+    }
+
+    fn insert(&mut self, subject: Option<&Term>, predicate: Option<Term>, object: Option<Term>) {
+        if let (Some(subject), Some(predicate), Some(object)) = (subject, predicate, object) {
+            self.triples.insert(Triple {
+                subject: subject.clone(),
+                predicate,
+                object,
+            });
+        }
+    }
+
+    /// Convert a node object and return its subject
+    fn node(&mut self, node: &Map<String, Value>) -> Result<Option<Term>> {
+        if node.contains_key("@graph") {
+            return Err(Error::UnsupportedFeature("named graphs"));
+        }
+
+        let subject = match node.get("@id") {
+            Some(Value::String(id)) => self.iri(id),
+            Some(..) => return Err(Error::InvalidDocument),
+            None => Some(self.new_blank_node()),
+        };
+
+        if let Some(types) = node.get("@type") {
+            for r#type in types.as_array().ok_or(Error::InvalidDocument)? {
+                let r#type = r#type.as_str().ok_or(Error::InvalidDocument)?;
+                let object = self.iri(r#type);
+
+                self.insert(subject.as_ref(), Some(Term::Iri(RDF_TYPE.into())), object);
+            }
+        }
+
+        for (property, values) in node {
+            if property.starts_with('@') {
+                continue;
+            }
+
+            // Generalised RDF (blank nodes as predicates) isn't produced
+            let predicate = is_absolute_iri(property).then(|| Term::Iri(property.clone()));
+
+            for item in values.as_array().ok_or(Error::InvalidDocument)? {
+                let object = self.object(item)?;
+                self.insert(subject.as_ref(), predicate.clone(), object);
+            }
+        }
+
+        Ok(subject)
+    }
+
+    /// Convert a node, value or list object into a term
+    fn object(&mut self, item: &Value) -> Result<Option<Term>> {
+        let item = item.as_object().ok_or(Error::InvalidDocument)?;
+
+        if let Some(value) = item.get("@value") {
+            Ok(Self::literal(item, value))
+        } else if let Some(list) = item.get("@list") {
+            self.list(list.as_array().ok_or(Error::InvalidDocument)?)
+        } else {
+            self.node(item)
+        }
+    }
+
+    /// Convert a value object into a literal
+    fn literal(item: &Map<String, Value>, value: &Value) -> Option<Term> {
+        let datatype = item.get("@type").and_then(Value::as_str);
+        if datatype.is_some_and(|datatype| !is_absolute_iri(datatype)) {
+            return None;
+        }
+
+        let language = item
+            .get("@language")
+            .and_then(Value::as_str)
+            .map(ToString::to_string);
+
+        let (value, datatype) = match value {
+            Value::Bool(value) => (value.to_string(), datatype.unwrap_or(XSD_BOOLEAN).into()),
+            Value::Number(number) => number_literal(number, datatype),
+            Value::String(value) if language.is_some() => (value.clone(), RDF_LANG_STRING.into()),
+            Value::String(value) => (value.clone(), datatype.unwrap_or(XSD_STRING).into()),
+            Value::Null | Value::Array(..) | Value::Object(..) => return None,
+        };
+
+        Some(Term::Literal {
+            value,
+            datatype,
+            language,
+        })
+    }
+
+    /// Convert a list into a chain of `rdf:first`/`rdf:rest` triples and return its head
+    fn list(&mut self, items: &[Value]) -> Result<Option<Term>> {
+        if items.is_empty() {
+            return Ok(Some(Term::Iri(RDF_NIL.into())));
+        }
+
+        let heads = items
+            .iter()
+            .map(|_| self.new_blank_node())
+            .collect::<Vec<_>>();
+
+        for (index, item) in items.iter().enumerate() {
+            let head = Some(&heads[index]);
+
+            let object = self.object(item)?;
+            self.insert(head, Some(Term::Iri(RDF_FIRST.into())), object);
+
+            let rest = heads
+                .get(index + 1)
+                .cloned()
+                .unwrap_or_else(|| Term::Iri(RDF_NIL.into()));
+            self.insert(head, Some(Term::Iri(RDF_REST.into())), Some(rest));
+        }
+
+        Ok(heads.into_iter().next())
+    }
+}
@@ -0,0 +1,120 @@
+//!
+//! `RsaSignature2017` Linked Data Signatures (as used by Mastodon)
+//!
+
+use crate::{
+    error::{Error, Result},
+    expand, key,
+    util::sha256_hex,
+};
+use serde_json::{Map, Value};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// Type of the signature
+pub const RSA_SIGNATURE_2017: &str = "RsaSignature2017";
+
+/// Context the signature options are canonicalized with
+const IDENTITY_CONTEXT: &str = "https://w3id.org/identity/v1";
+
+/// Name of the entry that holds the signature
+const SIGNATURE: &str = "signature";
+
+/// Canonicalize the document and hash it
+fn hash(document: &Value) -> Result<String> {
+    Ok(sha256_hex(crate::canonicalize(document)?))
+}
+
+/// Data that is signed
+///
+/// The hash of the canonicalized options (the signature without its type, ID and value)
+/// concatenated with the hash of the canonicalized document (without its signature)
+fn signing_input(document: &Map<String, Value>, options: &Map<String, Value>) -> Result<String> {
+    let mut options = options.clone();
+    options.remove("type");
+    options.remove("id");
+    options.remove("signatureValue");
+    options.insert("@context".into(), Value::String(IDENTITY_CONTEXT.into()));
+
+    let mut document = document.clone();
+    document.remove(SIGNATURE);
+
+    let options_hash = hash(&Value::Object(options))?;
+    let document_hash = hash(&Value::Object(document))?;
+
+    Ok(options_hash + &document_hash)
+}
+
+/// Sign the document with the private key and embed the signature
+///
+/// The private key has to be an RSA key in the PKCS#1 or PKCS#8 PEM format.
+/// An existing signature is replaced
+pub fn sign(document: &mut Value, key_id: &str, private_key: &[u8]) -> Result<()> {
+    let document = document.as_object_mut().ok_or(Error::InvalidDocument)?;
+
+    let created = OffsetDateTime::from_unix_timestamp(OffsetDateTime::now_utc().unix_timestamp())?
+        .format(&Rfc3339)?;
+
+    let mut options = Map::new();
+    options.insert("type".into(), Value::String(RSA_SIGNATURE_2017.into()));
+    options.insert("creator".into(), Value::String(key_id.into()));
+    options.insert("created".into(), Value::String(created));
+
+    let signing_input = signing_input(document, &options)?;
+    let signature = key::rsa_sign(private_key, signing_input.as_bytes())?;
+    options.insert(
+        "signatureValue".into(),
+        Value::String(base64::encode(signature)),
+    );
+
+    document.insert(SIGNATURE.into(), Value::Object(options));
+
+    Ok(())
+}
+
+/// Get the key ID (`creator`) of the embedded signature
+///
+/// This doesn't verify the signature. It's intended to find out which public key is needed for the verification
+pub fn creator(document: &Value) -> Result<&str> {
+    document
+        .get(SIGNATURE)
+        .ok_or(Error::MissingSignature)?
+        .get("creator")
+        .and_then(Value::as_str)
+        .ok_or(Error::MissingSignature)
+}
+
+/// Get the properties of the document that aren't covered by the signature
+///
+/// The signature only covers the RDF graph of the document. Properties that aren't part of it
+/// (such as terms the bundled contexts don't define) can be changed without invalidating the signature
+pub fn unsigned_properties(document: &Value) -> Result<Vec<String>> {
+    let mut document = document.as_object().ok_or(Error::InvalidDocument)?.clone();
+    document.remove(SIGNATURE);
+
+    expand::dropped_properties(&Value::Object(document))
+}
+
+/// Verify the embedded signature of the document with the public key
+///
+/// The public key has to be an RSA key in the PKCS#1 or SPKI PEM format
+pub fn verify(document: &Value, public_key: &[u8]) -> Result<bool> {
+    let document = document.as_object().ok_or(Error::InvalidDocument)?;
+    let options = document
+        .get(SIGNATURE)
+        .and_then(Value::as_object)
+        .ok_or(Error::MissingSignature)?;
+
+    if options.get("type").and_then(Value::as_str) != Some(RSA_SIGNATURE_2017) {
+        return Err(Error::UnknownSignatureType);
+    }
+
+    let signature = options
+        .get("signatureValue")
+        .and_then(Value::as_str)
+        .ok_or(Error::MissingSignature)?;
+    let signature = base64::decode(signature)?;
+
+    let signing_input = signing_input(document, options)?;
+
+    key::rsa_verify(public_key, signing_input.as_bytes(), &signature)
+}
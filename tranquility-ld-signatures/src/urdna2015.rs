@@ -0,0 +1,305 @@
+use crate::{
+    error::{Error, Result},
+    rdf::{Term, Triple},
+    util::sha256_hex,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Maximum number of blank nodes that are permuted in one step
+///
+/// The number of permutations grows factorially. Datasets that exceed this limit are rejected
+const MAX_PERMUTATION_SIZE: usize = 6;
+
+/// Prefix of the canonical blank node identifiers
+const CANONICAL_PREFIX: &str = "c14n";
+
+/// Prefix of the temporary blank node identifiers
+const TEMPORARY_PREFIX: &str = "b";
+
+/// Issues new blank node identifiers and remembers the order in which they were issued
+#[derive(Clone)]
+struct IdentifierIssuer {
+    prefix: &'static str,
+    issued: HashMap<String, String>,
+    order: Vec<String>,
+}
+
+impl IdentifierIssuer {
+    fn new(prefix: &'static str) -> Self {
+        Self {
+            prefix,
+            issued: HashMap::new(),
+            order: Vec::new(),
+        }
+    }
+
+    /// Get the identifier that was issued for the blank node
+    fn get(&self, existing: &str) -> Option<&str> {
+        self.issued.get(existing).map(String::as_str)
+    }
+
+    /// Issue an identifier for the blank node (or return the previously issued one)
+    fn issue(&mut self, existing: &str) -> String {
+        if let Some(issued) = self.issued.get(existing) {
+            return issued.clone();
+        }
+
+        let issued = format!("{}{}", self.prefix, self.order.len());
+        self.issued.insert(existing.to_string(), issued.clone());
+        self.order.push(existing.to_string());
+
+        issued
+    }
+}
+
+/// All permutations of the blank nodes
+fn permutations(nodes: &[String]) -> Vec<Vec<String>> {
+    if nodes.len() <= 1 {
+        return vec![nodes.to_vec()];
+    }
+
+    let mut result = Vec::new();
+    for (index, node) in nodes.iter().enumerate() {
+        let mut rest = nodes.to_vec();
+        rest.remove(index);
+
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, node.clone());
+            result.push(permutation);
+        }
+    }
+
+    result
+}
+
+/// Implementation of the [URDNA2015](https://www.w3.org/TR/rdf-canon/) canonicalization algorithm
+struct Canonicalizer<'a> {
+    /// Triples that mention the blank node
+    blank_node_triples: BTreeMap<&'a str, Vec<&'a Triple>>,
+
+    /// Issuer of the canonical identifiers
+    canonical_issuer: IdentifierIssuer,
+}
+
+impl<'a> Canonicalizer<'a> {
+    fn new(triples: &'a BTreeSet<Triple>) -> Self {
+        let mut blank_node_triples: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for triple in triples {
+            let blank_nodes = [&triple.subject, &triple.object]
+                .into_iter()
+                .filter_map(|term| term.blank_node())
+                .collect::<BTreeSet<_>>();
+
+            for blank_node in blank_nodes {
+                blank_node_triples
+                    .entry(blank_node)
+                    .or_default()
+                    .push(triple);
+            }
+        }
+
+        Self {
+            blank_node_triples,
+            canonical_issuer: IdentifierIssuer::new(CANONICAL_PREFIX),
+        }
+    }
+
+    /// Hash the triples of the blank node without taking other blank nodes into account
+    fn hash_first_degree(&self, blank_node: &str) -> String {
+        let mut nquads = self.blank_node_triples[blank_node]
+            .iter()
+            .map(|triple| {
+                triple.to_nquad(&|id: &str| if id == blank_node { "a" } else { "z" }.into())
+            })
+            .collect::<Vec<_>>();
+        nquads.sort_unstable();
+
+        sha256_hex(nquads.concat())
+    }
+
+    /// Hash a blank node that is related to another blank node via the triple
+    fn hash_related_blank_node(
+        &self,
+        related: &str,
+        triple: &Triple,
+        issuer: &IdentifierIssuer,
+        position: char,
+    ) -> String {
+        let identifier = self
+            .canonical_issuer
+            .get(related)
+            .or_else(|| issuer.get(related))
+            .map_or_else(|| self.hash_first_degree(related), |id| format!("_:{id}"));
+
+        let mut input = String::from(position);
+        input.push('<');
+        if let Term::Iri(ref predicate) = triple.predicate {
+            input.push_str(predicate);
+        }
+        input.push('>');
+        input.push_str(&identifier);
+
+        sha256_hex(input)
+    }
+
+    /// Hash the blank node by taking the surrounding blank nodes into account
+    fn hash_n_degree(
+        &self,
+        blank_node: &str,
+        mut issuer: IdentifierIssuer,
+    ) -> Result<(String, IdentifierIssuer)> {
+        let mut related_hashes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for triple in &self.blank_node_triples[blank_node] {
+            let related_nodes = [('s', &triple.subject), ('o', &triple.object)]
+                .into_iter()
+                .filter_map(|(position, term)| Some((position, term.blank_node()?)))
+                .filter(|(_position, related)| *related != blank_node);
+
+            for (position, related) in related_nodes {
+                let hash = self.hash_related_blank_node(related, triple, &issuer, position);
+                related_hashes
+                    .entry(hash)
+                    .or_default()
+                    .push(related.to_string());
+            }
+        }
+
+        let mut data_to_hash = String::new();
+        for (related_hash, related_nodes) in related_hashes {
+            if related_nodes.len() > MAX_PERMUTATION_SIZE {
+                return Err(Error::CanonicalizationTooComplex);
+            }
+
+            data_to_hash.push_str(&related_hash);
+
+            let mut chosen: Option<(String, IdentifierIssuer)> = None;
+            'permutations: for permutation in permutations(&related_nodes) {
+                let mut issuer_copy = issuer.clone();
+                let mut path = String::new();
+                let mut recursion_list = Vec::new();
+
+                let exceeds_chosen = |path: &str, chosen: &Option<(String, IdentifierIssuer)>| {
+                    chosen.as_ref().is_some_and(|(chosen_path, _)| {
+                        path.len() >= chosen_path.len() && path > chosen_path.as_str()
+                    })
+                };
+
+                for related in &permutation {
+                    if let Some(canonical_id) = self.canonical_issuer.get(related) {
+                        path.push_str("_:");
+                        path.push_str(canonical_id);
+                    } else {
+                        if issuer_copy.get(related).is_none() {
+                            recursion_list.push(related.clone());
+                        }
+
+                        path.push_str("_:");
+                        path.push_str(&issuer_copy.issue(related));
+                    }
+
+                    if exceeds_chosen(&path, &chosen) {
+                        continue 'permutations;
+                    }
+                }
+
+                for related in recursion_list {
+                    let (hash, result_issuer) =
+                        self.hash_n_degree(&related, issuer_copy.clone())?;
+                    issuer_copy = result_issuer;
+
+                    path.push_str("_:");
+                    path.push_str(&issuer_copy.issue(&related));
+                    path.push('<');
+                    path.push_str(&hash);
+                    path.push('>');
+
+                    if exceeds_chosen(&path, &chosen) {
+                        continue 'permutations;
+                    }
+                }
+
+                let is_shorter = match chosen {
+                    Some((ref chosen_path, _)) => path < *chosen_path,
+                    None => true,
+                };
+                if is_shorter {
+                    chosen = Some((path, issuer_copy));
+                }
+            }
+
+            let (chosen_path, chosen_issuer) = chosen.expect("[Bug] No permutation was chosen");
+            data_to_hash.push_str(&chosen_path);
+            issuer = chosen_issuer;
+        }
+
+        Ok((sha256_hex(data_to_hash), issuer))
+    }
+
+    /// Issue canonical identifiers for all blank nodes
+    fn issue_identifiers(&mut self) -> Result<()> {
+        let mut hash_to_blank_nodes: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+        for &blank_node in self.blank_node_triples.keys() {
+            let hash = self.hash_first_degree(blank_node);
+            hash_to_blank_nodes
+                .entry(hash)
+                .or_default()
+                .push(blank_node);
+        }
+
+        // Blank nodes with a unique first degree hash get their identifiers right away
+        let mut shared_hashes = Vec::new();
+        for blank_nodes in hash_to_blank_nodes.into_values() {
+            match blank_nodes.as_slice() {
+                [blank_node] => {
+                    self.canonical_issuer.issue(blank_node);
+                }
+                _ => shared_hashes.push(blank_nodes),
+            }
+        }
+
+        for blank_nodes in shared_hashes {
+            let mut hash_path_list = Vec::new();
+            for blank_node in blank_nodes {
+                if self.canonical_issuer.get(blank_node).is_some() {
+                    continue;
+                }
+
+                let mut temporary_issuer = IdentifierIssuer::new(TEMPORARY_PREFIX);
+                temporary_issuer.issue(blank_node);
+
+                hash_path_list.push(self.hash_n_degree(blank_node, temporary_issuer)?);
+            }
+
+            hash_path_list.sort_by(|(hash_a, _), (hash_b, _)| hash_a.cmp(hash_b));
+            for (_hash, issuer) in hash_path_list {
+                for existing in issuer.order {
+                    self.canonical_issuer.issue(&existing);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Canonicalize the triples and serialise them into N-Quads
+pub fn canonicalize(triples: &BTreeSet<Triple>) -> Result<String> {
+    let mut canonicalizer = Canonicalizer::new(triples);
+    canonicalizer.issue_identifiers()?;
+
+    let canonical_issuer = &canonicalizer.canonical_issuer;
+    let canonical_id = |id: &str| {
+        canonical_issuer
+            .get(id)
+            .expect("[Bug] Blank node without canonical identifier")
+            .to_string()
+    };
+
+    let mut nquads = triples
+        .iter()
+        .map(|triple| triple.to_nquad(&canonical_id))
+        .collect::<Vec<_>>();
+    nquads.sort_unstable();
+
+    Ok(nquads.concat())
+}
@@ -0,0 +1,23 @@
+use ring::digest::{digest, SHA256};
+use std::fmt::Write;
+
+/// Hash the data with SHA-256
+pub fn sha256<D>(data: D) -> Vec<u8>
+where
+    D: AsRef<[u8]>,
+{
+    digest(&SHA256, data.as_ref()).as_ref().to_vec()
+}
+
+/// Hash the data with SHA-256 and encode the hash as lowercase hex
+pub fn sha256_hex<D>(data: D) -> String
+where
+    D: AsRef<[u8]>,
+{
+    sha256(data)
+        .into_iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
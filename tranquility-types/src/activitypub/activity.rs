@@ -13,15 +13,38 @@ pub struct Activity {
     /// Link to the actor this activity belongs to
     pub actor: String,
 
-    /// This can either be an "Actor", "Object" or an URL to either of those
+    /// This can either be an "Actor", "Object", "Activity" or an URL to either of those
     pub object: ObjectField,
+    /// Collection the object is added to or removed from (either its URL or the embedded collection)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<Value>,
 
-    #[serde(with = "time::serde::rfc3339")]
+    // Some software (for example, relays) omits the publishing date and the addressing of activities
+    #[serde(default = "OffsetDateTime::now_utc", with = "time::serde::rfc3339")]
     pub published: OffsetDateTime,
 
+    #[serde(default, deserialize_with = "super::one_or_many")]
     pub to: Vec<String>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "super::one_or_many")]
     pub cc: Vec<String>,
+
+    /// Emoji of an emoji reaction (either a Unicode emoji or the `:shortcode:` of a custom emoji)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Emoji of an emoji reaction sent as a `Like` activity by Misskey
+    #[serde(
+        default,
+        rename = "_misskey_reaction",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub misskey_reaction: Option<String>,
+    /// Custom emoji used in the emoji reaction
+    #[serde(
+        default,
+        deserialize_with = "super::one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub tag: Vec<super::Tag>,
 }
 
 impl Default for Activity {
@@ -34,19 +57,45 @@ impl Default for Activity {
             actor: String::default(),
 
             object: ObjectField::default(),
+            target: None,
             published: OffsetDateTime::now_utc(),
 
             to: Vec::default(),
             cc: Vec::default(),
+
+            content: None,
+            misskey_reaction: None,
+            tag: Vec::default(),
         }
     }
 }
 
+impl Activity {
+    /// Get the URL of the target collection
+    pub fn target_url(&self) -> Option<&str> {
+        let target = self.target.as_ref()?;
+
+        target
+            .as_str()
+            .or_else(|| target.get("id").and_then(Value::as_str))
+    }
+
+    /// Get the emoji of the reaction (`None` if the activity doesn't carry an emoji)
+    pub fn reaction(&self) -> Option<&str> {
+        self.content
+            .as_deref()
+            .or(self.misskey_reaction.as_deref())
+            .map(str::trim)
+            .filter(|reaction| !reaction.is_empty())
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum ObjectField {
     Actor(super::Actor),
     Object(super::Object),
+    Activity(Box<Activity>),
     Url(String),
 }
 
@@ -71,6 +120,13 @@ impl ObjectField {
         }
     }
 
+    pub fn as_activity(&self) -> Option<&Activity> {
+        match self {
+            Self::Activity(activity) => Some(activity),
+            _ => None,
+        }
+    }
+
     pub fn as_url(&self) -> Option<&String> {
         match self {
             Self::Url(url) => Some(url),
@@ -78,6 +134,24 @@ impl ObjectField {
         }
     }
 
+    /// Get the URL of the referenced activity (either the URL itself or the ID of the embedded activity)
+    pub fn activity_url(&self) -> Option<&str> {
+        match self {
+            Self::Activity(activity) => Some(activity.id.as_str()),
+            Self::Url(url) => Some(url.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Get the URL of the referenced object (either the URL itself or the ID of the embedded object)
+    pub fn object_url(&self) -> Option<&str> {
+        match self {
+            Self::Object(object) => Some(object.id.as_str()),
+            Self::Url(url) => Some(url.as_str()),
+            _ => None,
+        }
+    }
+
     pub fn as_mut_actor(&mut self) -> Option<&mut super::Actor> {
         match self {
             Self::Actor(actor) => Some(actor),
@@ -112,6 +186,12 @@ impl From<super::Object> for ObjectField {
     }
 }
 
+impl From<Activity> for ObjectField {
+    fn from(activity: Activity) -> Self {
+        Self::Activity(Box::new(activity))
+    }
+}
+
 impl From<String> for ObjectField {
     fn from(url: String) -> Self {
         Self::Url(url)
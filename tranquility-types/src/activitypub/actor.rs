@@ -11,6 +11,18 @@ pub struct PublicKey {
     pub public_key_pem: String,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// Struct representing a `Multikey` verification method (listed in the `assertionMethod` field of an actor)
+///
+/// These keys are used to verify [object integrity proofs](https://codeberg.org/fediverse/fep/src/branch/main/fep/8b32/fep-8b32.md)
+pub struct Multikey {
+    pub id: String,
+    pub r#type: String,
+    pub controller: String,
+    pub public_key_multibase: String,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Struct representing an [ActivityStreams actor](https://www.w3.org/TR/activitypub/#actor-objects) with ActivityPub specific extensions
@@ -19,18 +31,20 @@ pub struct Actor {
     pub context: Value,
 
     pub id: String,
-    // (Should) always equal "Person"
+    // One of the ActivityStreams actor types (see `ACTOR_TYPES`)
     pub r#type: String,
 
     // Display name
+    #[serde(default)]
     pub name: String,
     // Unique username
     #[serde(rename = "preferredUsername")]
     pub username: String,
 
+    #[serde(default)]
     pub summary: String,
     // In case you mention someone in your summary
-    #[serde(default)]
+    #[serde(default, deserialize_with = "super::one_or_many")]
     pub tag: Vec<Tag>,
     // Profile picture
     pub icon: Option<Attachment>,
@@ -42,9 +56,30 @@ pub struct Actor {
 
     pub inbox: String,
     pub outbox: String,
+    // Not every actor has those collections (for example, groups and relays)
+    #[serde(default)]
     pub followers: String,
+    #[serde(default)]
     pub following: String,
+    // Collection of the pinned posts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub featured: Option<String>,
     pub public_key: PublicKey,
+    // Keys the actor signs object integrity proofs with
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertion_method: Vec<Multikey>,
+}
+
+impl Actor {
+    /// Check whether the actor is an automated account (`Service` or `Application`)
+    pub fn is_bot(&self) -> bool {
+        self.r#type == "Service" || self.r#type == "Application"
+    }
+
+    /// Check whether the actor is a group
+    pub fn is_group(&self) -> bool {
+        self.r#type == "Group"
+    }
 }
 
 impl Default for Actor {
@@ -70,7 +105,9 @@ impl Default for Actor {
             outbox: String::default(),
             followers: String::default(),
             following: String::default(),
+            featured: None,
             public_key: PublicKey::default(),
+            assertion_method: Vec::default(),
         }
     }
 }
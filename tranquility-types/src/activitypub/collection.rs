@@ -11,13 +11,20 @@ pub struct Collection {
     pub id: String,
     pub r#type: String,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_items: Option<i64>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub first: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last: Option<String>,
 
     #[serde(default, skip_serializing_if = "String::is_empty")]
     pub part_of: String,
     #[serde(default, skip_serializing_if = "String::is_empty")]
     pub next: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub prev: String,
 
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub ordered_items: Vec<Item>,
@@ -27,6 +34,7 @@ pub struct Collection {
 #[serde(untagged)]
 pub enum Item {
     Activity(Box<super::Activity>),
+    Object(Box<super::Object>),
     Url(String),
 }
 
@@ -36,6 +44,12 @@ impl From<super::Activity> for Item {
     }
 }
 
+impl From<super::Object> for Item {
+    fn from(item: super::Object) -> Self {
+        Self::Object(Box::new(item))
+    }
+}
+
 impl From<String> for Item {
     fn from(item: String) -> Self {
         Self::Url(item)
@@ -50,10 +64,14 @@ impl Default for Collection {
             id: String::default(),
             r#type: String::default(),
 
+            total_items: None,
+
             first: None,
+            last: None,
 
             part_of: String::default(),
             next: String::default(),
+            prev: String::default(),
 
             ordered_items: Vec::default(),
         }
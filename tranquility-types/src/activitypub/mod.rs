@@ -1,7 +1,11 @@
+use serde::{Deserialize, Deserializer};
 use serde_json::{json, Value};
 
 pub const PUBLIC_IDENTIFIER: &str = "https://www.w3.org/ns/activitystreams#Public";
 
+/// All [actor types](https://www.w3.org/TR/activitystreams-vocabulary/#actor-types) defined by ActivityStreams
+pub const ACTOR_TYPES: &[&str] = &["Application", "Group", "Organization", "Person", "Service"];
+
 pub const OUTBOX_FOLLOW_COLLECTIONS_TYPE: &str = "OrderedCollection";
 pub const OUTBOX_FOLLOW_COLLECTIONS_PAGE_TYPE: &str = "OrderedCollectionPage";
 
@@ -10,6 +14,34 @@ pub fn context_field() -> Value {
     json!(["https://www.w3.org/ns/activitystreams"])
 }
 
+/// Deserialize `null` values into the default value of the type (for example, the `summary` of posts without content warning)
+pub fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Deserialize either a single value or an array of values into a vector (for example, the addressing of votes on polls)
+pub fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        Many(Vec<T>),
+        One(T),
+    }
+
+    match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::Many(values) => Ok(values),
+        OneOrMany::One(value) => Ok(vec![value]),
+    }
+}
+
 pub mod activity;
 pub mod actor;
 pub mod attachment;
@@ -19,9 +51,9 @@ pub mod tag;
 pub mod traits;
 
 pub use activity::Activity;
-pub use actor::{Actor, PublicKey};
+pub use actor::{Actor, Multikey, PublicKey};
 pub use attachment::Attachment;
 pub use collection::Collection;
-pub use object::Object;
+pub use object::{Object, QuestionOption, QuestionOptionReplies};
 pub use tag::Tag;
 pub use traits::{IsPrivate, IsPublic, IsUnlisted};
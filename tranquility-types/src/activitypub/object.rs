@@ -15,10 +15,16 @@ pub struct Object {
 
     pub attributed_to: String,
 
+    /// Only set on some objects (for example, votes on polls carry the name of the chosen option)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "super::null_as_default")]
     pub summary: String,
+    // Votes on polls don't have any content
+    #[serde(default)]
     pub content: String,
 
-    #[serde(with = "time::serde::rfc3339")]
+    #[serde(default = "OffsetDateTime::now_utc", with = "time::serde::rfc3339")]
     pub published: OffsetDateTime,
 
     #[serde(default)]
@@ -26,13 +32,91 @@ pub struct Object {
 
     #[serde(default)]
     pub attachment: Vec<Attachment>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "super::one_or_many")]
     pub tag: Vec<Tag>,
 
+    /// URL of the object this object is replying to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_reply_to: Option<String>,
+    /// Collection of the replies to this object (either its URL or the embedded collection)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replies: Option<Value>,
+    /// URL of the object this object is quoting
+    #[serde(
+        default,
+        alias = "quoteUri",
+        alias = "_misskey_quote",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub quote_url: Option<String>,
+
+    /// Options of a poll that allows only one choice
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub one_of: Option<Vec<QuestionOption>>,
+    /// Options of a poll that allows multiple choices
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub any_of: Option<Vec<QuestionOption>>,
+    /// Time the poll ends at
+    #[serde(
+        default,
+        with = "time::serde::rfc3339::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub end_time: Option<OffsetDateTime>,
+    /// Time the poll was closed at
+    #[serde(
+        default,
+        with = "time::serde::rfc3339::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub closed: Option<OffsetDateTime>,
+    /// Amount of actors that voted on the poll
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voters_count: Option<i64>,
+
+    #[serde(default, deserialize_with = "super::one_or_many")]
     pub to: Vec<String>,
+    #[serde(default, deserialize_with = "super::one_or_many")]
     pub cc: Vec<String>,
 }
 
+impl Object {
+    /// Get the options of the poll (`None` if the object isn't a poll)
+    pub fn poll_options(&self) -> Option<&[QuestionOption]> {
+        self.one_of.as_deref().or(self.any_of.as_deref())
+    }
+
+    /// Get mutable references to the options of the poll (`None` if the object isn't a poll)
+    pub fn poll_options_mut(&mut self) -> Option<&mut Vec<QuestionOption>> {
+        match self.one_of {
+            Some(ref mut one_of) => Some(one_of),
+            None => self.any_of.as_mut(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+/// Struct representing an option of a poll (ActivityStreams `Question` object)
+pub struct QuestionOption {
+    #[serde(default)]
+    pub r#type: String,
+    pub name: String,
+
+    /// Collection that only contains the amount of votes for this option
+    #[serde(default)]
+    pub replies: QuestionOptionReplies,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// Struct representing the vote count of a [QuestionOption]
+pub struct QuestionOptionReplies {
+    #[serde(default)]
+    pub r#type: String,
+    #[serde(default)]
+    pub total_items: i64,
+}
+
 impl Default for Object {
     fn default() -> Self {
         Self {
@@ -43,6 +127,7 @@ impl Default for Object {
 
             attributed_to: String::default(),
 
+            name: None,
             summary: String::default(),
             content: String::default(),
             published: OffsetDateTime::now_utc(),
@@ -51,6 +136,16 @@ impl Default for Object {
             attachment: Vec::default(),
             tag: Vec::default(),
 
+            in_reply_to: None,
+            replies: None,
+            quote_url: None,
+
+            one_of: None,
+            any_of: None,
+            end_time: None,
+            closed: None,
+            voters_count: None,
+
             to: Vec::default(),
             cc: Vec::default(),
         }
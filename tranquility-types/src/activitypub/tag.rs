@@ -1,10 +1,119 @@
+use super::Attachment;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use time::OffsetDateTime;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(from = "TagRepr", into = "TagRepr")]
+/// Enum representing an [ActivityStreams tag](https://www.w3.org/TR/activitystreams-vocabulary/#dfn-tag)
+pub enum Tag {
+    /// Mention of an actor
+    ///
+    /// Name format: @\<username\>@\<instance\>
+    Mention(Link),
+
+    /// Hashtag linking to the posts tagged with it
+    ///
+    /// Name format: #\<name\>
+    Hashtag(Link),
+
+    /// Custom emoji used in the text of the object or the profile of the actor
+    ///
+    /// Name format: :\<shortcode\>:
+    Emoji(Emoji),
+
+    /// Tags of any other type (or tags of the known types we can't interpret)
+    ///
+    /// They are kept as they are, so they aren't lost when the object is passed on
+    Other(Value),
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(tag = "type")]
+/// Tags of the types we know
+enum KnownTag {
+    Mention(Link),
+    Hashtag(Link),
+    Emoji(Emoji),
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+/// Serialisation format of [Tag]
+enum TagRepr {
+    Known(KnownTag),
+    Other(Value),
+}
+
+impl From<TagRepr> for Tag {
+    fn from(repr: TagRepr) -> Self {
+        match repr {
+            TagRepr::Known(KnownTag::Mention(link)) => Self::Mention(link),
+            TagRepr::Known(KnownTag::Hashtag(link)) => Self::Hashtag(link),
+            TagRepr::Known(KnownTag::Emoji(emoji)) => Self::Emoji(emoji),
+            TagRepr::Other(value) => Self::Other(value),
+        }
+    }
+}
+
+impl From<Tag> for TagRepr {
+    fn from(tag: Tag) -> Self {
+        match tag {
+            Tag::Mention(link) => Self::Known(KnownTag::Mention(link)),
+            Tag::Hashtag(link) => Self::Known(KnownTag::Hashtag(link)),
+            Tag::Emoji(emoji) => Self::Known(KnownTag::Emoji(emoji)),
+            Tag::Other(value) => Self::Other(value),
+        }
+    }
+}
+
+impl Tag {
+    /// Get the name of the hashtag without the leading `#` (`None` if the tag isn't a hashtag)
+    pub fn hashtag_name(&self) -> Option<&str> {
+        match self {
+            Self::Hashtag(link) => Some(link.name.trim_start_matches('#')),
+            _ => None,
+        }
+    }
+
+    /// Get the custom emoji (`None` if the tag isn't an emoji)
+    pub fn emoji(&self) -> Option<&Emoji> {
+        match self {
+            Self::Emoji(emoji) => Some(emoji),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
-/// Struct representing an [ActivityStreams tag](https://www.w3.org/TR/activitystreams-vocabulary/#dfn-tag)
-pub struct Tag {
-    pub r#type: String,
-    /// Format: @\<username\>@\<instance\>
+/// Name and target of a [Tag]
+pub struct Link {
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub href: String,
 }
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+/// Custom emoji referenced by an [Emoji tag](https://docs.joinmastodon.org/spec/activitypub/#emoji)
+pub struct Emoji {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+
+    #[serde(
+        default,
+        with = "time::serde::rfc3339::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub updated: Option<OffsetDateTime>,
+
+    pub icon: Attachment,
+}
+
+impl Emoji {
+    /// Get the shortcode of the emoji (the name without the surrounding colons)
+    pub fn shortcode(&self) -> &str {
+        self.name.trim_matches(':')
+    }
+}
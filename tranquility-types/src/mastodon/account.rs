@@ -11,6 +11,7 @@ pub struct Account {
 
     pub locked: bool,
     pub bot: bool,
+    pub group: bool,
 
     pub created_at: String,
     pub note: String,
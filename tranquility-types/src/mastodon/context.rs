@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Deserialize, Serialize)]
+/// Struct representing a [Mastodon context](https://docs.joinmastodon.org/entities/context/)
+pub struct Context {
+    pub ancestors: Vec<super::Status>,
+    pub descendants: Vec<super::Status>,
+}
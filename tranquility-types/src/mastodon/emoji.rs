@@ -9,4 +9,5 @@ pub struct Emoji {
     pub static_url: String,
 
     pub visible_in_picker: bool,
+    pub category: Option<String>,
 }
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Deserialize, Serialize)]
+/// Struct representing a [Pleroma emoji reaction](https://docs-develop.pleroma.social/backend/development/API/pleroma_api/#emoji-reactions)
+pub struct EmojiReaction {
+    /// Unicode emoji or shortcode of the custom emoji
+    pub name: String,
+    pub count: i64,
+    /// Whether the viewer reacted with the emoji
+    pub me: bool,
+
+    pub accounts: Vec<super::Account>,
+
+    /// Image of the custom emoji
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
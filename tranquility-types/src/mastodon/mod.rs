@@ -2,11 +2,14 @@ pub mod account;
 pub mod app;
 pub mod attachment;
 pub mod card;
+pub mod context;
 pub mod emoji;
+pub mod emoji_reaction;
 pub mod field;
 pub mod instance;
 pub mod mention;
 pub mod poll;
+pub mod search;
 pub mod source;
 pub mod status;
 pub mod tag;
@@ -15,11 +18,14 @@ pub use account::{Account, FollowResponse};
 pub use app::App;
 pub use attachment::Attachment;
 pub use card::Card;
+pub use context::Context;
 pub use emoji::Emoji;
+pub use emoji_reaction::EmojiReaction;
 pub use field::Field;
 pub use instance::Instance;
 pub use mention::Mention;
 pub use poll::Poll;
+pub use search::Search;
 pub use source::Source;
 pub use status::Status;
 pub use tag::{History, Tag};
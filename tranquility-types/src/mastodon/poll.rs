@@ -2,19 +2,22 @@
 #![allow(clippy::module_name_repetitions)]
 
 use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 
 #[derive(Default, Deserialize, Serialize)]
 /// Struct representing a [Mastodon poll](https://docs.joinmastodon.org/entities/poll/)
 pub struct Poll {
     pub id: String,
 
-    pub expires_at: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
     pub expired: bool,
 
     pub multiple: bool,
     pub votes_count: i64,
     pub voters_count: Option<i64>,
 
+    pub voted: Option<bool>,
     pub own_votes: Option<Vec<i64>>,
 
     pub options: Vec<PollOption>,
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Deserialize, Serialize)]
+/// Struct representing a [Mastodon search result](https://docs.joinmastodon.org/entities/search/)
+pub struct Search {
+    pub accounts: Vec<super::Account>,
+    pub statuses: Vec<super::Status>,
+    pub hashtags: Vec<super::Tag>,
+}
@@ -32,6 +32,8 @@ pub struct Status {
     pub muted: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bookmarked: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<bool>,
 
     pub content: String,
     pub reblog: Option<Box<Status>>,
@@ -42,6 +44,7 @@ pub struct Status {
     pub media_attachments: Vec<super::Attachment>,
     pub mentions: Vec<super::Mention>,
     pub tags: Vec<super::Tag>,
+    pub emojis: Vec<super::Emoji>,
 
     pub card: Option<super::Card>,
     pub poll: Option<super::Poll>,
@@ -67,6 +70,7 @@ impl Default for Status {
             reblogged: Option::default(),
             muted: Option::default(),
             bookmarked: Option::default(),
+            pinned: Option::default(),
             content: String::default(),
             reblog: Option::default(),
             application: super::App::default(),
@@ -74,6 +78,7 @@ impl Default for Status {
             media_attachments: Vec::default(),
             mentions: Vec::default(),
             tags: Vec::default(),
+            emojis: Vec::default(),
             card: Option::default(),
             poll: Option::default(),
         }
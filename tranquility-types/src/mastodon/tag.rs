@@ -6,7 +6,11 @@ pub struct Tag {
     pub name: String,
     pub url: String,
 
-    pub history: Option<History>,
+    #[serde(default)]
+    pub history: Vec<History>,
+    /// Whether the authorized user follows the tag (only present for authorized requests)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub following: Option<bool>,
 }
 
 #[derive(Default, Deserialize, Serialize)]
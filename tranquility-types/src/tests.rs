@@ -97,6 +97,17 @@ const OUTBOX_COLLECTION_WITH_ITEMS: &str = r#"
 }
 "#;
 
+const MASTODON_OUTBOX_COLLECTION: &str = r#"
+{
+    "@context": "https://www.w3.org/ns/activitystreams",
+    "id": "https://mastodon.social/users/Gargron/outbox",
+    "type": "OrderedCollection",
+    "totalItems": 72138,
+    "first": "https://mastodon.social/users/Gargron/outbox?page=true",
+    "last": "https://mastodon.social/users/Gargron/outbox?min_id=0&page=true"
+}
+"#;
+
 const MASTODON_WEBFINGER_RESOURCE: &str = r#"
 {
     "subject": "acct:Gargron@mastodon.social",
@@ -212,6 +223,226 @@ const RFC_JRD: &str = r#"
 }
 "#;
 
+const RELAY_ACCEPT_ACTIVITY: &str = r#"
+{
+    "@context": "https://www.w3.org/ns/activitystreams",
+    "type": "Accept",
+    "to": ["https://a.example.com/actor"],
+    "actor": "https://relay.example.com/actor",
+    "object": {
+        "type": "Follow",
+        "id": "https://a.example.com/objects/2b3c2d8f1f7a4d5e9c0b1a2d3e4f5a6b",
+        "object": "https://www.w3.org/ns/activitystreams#Public",
+        "actor": "https://a.example.com/actor"
+    },
+    "id": "https://relay.example.com/activities/6b1f0c2e-0d61-4e69-9d3c-4ea0e9e6a1f1"
+}
+"#;
+
+const LEMMY_GROUP: &str = r#"
+{
+    "@context": [
+        "https://www.w3.org/ns/activitystreams",
+        "https://w3id.org/security/v1"
+    ],
+    "type": "Group",
+    "id": "https://lemmy.example.com/c/test",
+    "preferredUsername": "test",
+    "name": "Test community",
+    "inbox": "https://lemmy.example.com/c/test/inbox",
+    "followers": "https://lemmy.example.com/c/test/followers",
+    "publicKey": {
+        "id": "https://lemmy.example.com/c/test#main-key",
+        "owner": "https://lemmy.example.com/c/test",
+        "publicKeyPem": "-----BEGIN PUBLIC KEY-----\nMIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEAuLs+YOuVhy+j80AH08Ok\n-----END PUBLIC KEY-----\n"
+    },
+    "outbox": "https://lemmy.example.com/c/test/outbox",
+    "endpoints": {
+        "sharedInbox": "https://lemmy.example.com/inbox"
+    },
+    "published": "2022-01-01T00:00:00.000000+00:00"
+}
+"#;
+
+const REPLY_OBJECT: &str = r#"
+{
+    "@context": ["https://www.w3.org/ns/activitystreams"],
+    "id": "https://a.example.com/users/test/statuses/3",
+    "type": "Note",
+    "attributedTo": "https://a.example.com/users/test",
+    "summary": "",
+    "content": "<p>RE: https://c.example.com/notes/2</p>",
+    "inReplyTo": "https://b.example.com/objects/1",
+    "quoteUri": "https://c.example.com/notes/2",
+    "published": "2022-01-01T00:00:00Z",
+    "to": ["https://www.w3.org/ns/activitystreams#Public"],
+    "cc": ["https://a.example.com/users/test/followers"],
+    "replies": {
+        "id": "https://a.example.com/users/test/statuses/3/replies",
+        "type": "Collection",
+        "first": {
+            "type": "CollectionPage",
+            "next": "https://a.example.com/users/test/statuses/3/replies?page=true",
+            "partOf": "https://a.example.com/users/test/statuses/3/replies",
+            "items": []
+        }
+    }
+}
+"#;
+
+const MASTODON_FEATURED_COLLECTION: &str = r#"
+{
+    "@context": ["https://www.w3.org/ns/activitystreams"],
+    "id": "https://mastodon.example.com/users/test/collections/featured",
+    "type": "OrderedCollection",
+    "totalItems": 2,
+    "orderedItems": [
+        {
+            "id": "https://mastodon.example.com/users/test/statuses/1",
+            "type": "Note",
+            "summary": null,
+            "inReplyTo": null,
+            "published": "2022-01-01T00:00:00Z",
+            "attributedTo": "https://mastodon.example.com/users/test",
+            "to": ["https://www.w3.org/ns/activitystreams#Public"],
+            "cc": ["https://mastodon.example.com/users/test/followers"],
+            "sensitive": false,
+            "content": "<p>Pinned</p>",
+            "attachment": [],
+            "tag": []
+        },
+        "https://mastodon.example.com/users/test/statuses/2"
+    ]
+}
+"#;
+
+const ADD_ACTIVITY: &str = r#"
+{
+    "@context": ["https://www.w3.org/ns/activitystreams"],
+    "type": "Add",
+    "id": "https://mastodon.example.com/users/test#add/1",
+    "actor": "https://mastodon.example.com/users/test",
+    "object": "https://mastodon.example.com/users/test/statuses/1",
+    "target": "https://mastodon.example.com/users/test/collections/featured"
+}
+"#;
+
+const MASTODON_QUESTION: &str = r#"
+{
+    "@context": ["https://www.w3.org/ns/activitystreams"],
+    "id": "https://mastodon.example.com/users/test/statuses/1",
+    "type": "Question",
+    "attributedTo": "https://mastodon.example.com/users/test",
+    "summary": null,
+    "content": "<p>Which one?</p>",
+    "published": "2022-11-20T12:00:00Z",
+    "endTime": "2022-11-21T12:00:00Z",
+    "votersCount": 3,
+    "to": ["https://www.w3.org/ns/activitystreams#Public"],
+    "cc": ["https://mastodon.example.com/users/test/followers"],
+    "anyOf": [
+        {
+            "type": "Note",
+            "name": "First",
+            "replies": { "type": "Collection", "totalItems": 2 }
+        },
+        {
+            "type": "Note",
+            "name": "Second",
+            "replies": { "type": "Collection", "totalItems": 1 }
+        }
+    ]
+}
+"#;
+
+const MASTODON_POLL_VOTE: &str = r#"
+{
+    "id": "https://mastodon.example.com/users/test#votes/1",
+    "type": "Note",
+    "name": "Second",
+    "attributedTo": "https://mastodon.example.com/users/test",
+    "inReplyTo": "https://b.example.com/objects/1",
+    "to": "https://b.example.com/users/test"
+}
+"#;
+
+const TAGGED_OBJECT: &str = r##"
+{
+    "id": "https://mastodon.example.com/users/test/statuses/2",
+    "type": "Note",
+    "attributedTo": "https://mastodon.example.com/users/test",
+    "content": "<p>Hello <a href=\"https://b.example.com/users/test\">@test</a> #Rust :blobcat:</p>",
+    "published": "2022-11-20T12:00:00Z",
+    "to": ["https://www.w3.org/ns/activitystreams#Public"],
+    "cc": [],
+    "tag": [
+        {
+            "type": "Mention",
+            "href": "https://b.example.com/users/test",
+            "name": "@test@b.example.com"
+        },
+        {
+            "type": "Hashtag",
+            "href": "https://mastodon.example.com/tags/rust",
+            "name": "#Rust"
+        },
+        {
+            "id": "https://mastodon.example.com/emojis/1",
+            "type": "Emoji",
+            "name": ":blobcat:",
+            "icon": { "type": "Image", "url": "https://mastodon.example.com/blobcat.png" }
+        },
+        {
+            "type": "PropertyValue",
+            "name": "Unknown"
+        }
+    ]
+}
+"##;
+
+const MISSKEY_REACTION: &str = r#"
+{
+    "@context": "https://www.w3.org/ns/activitystreams",
+    "id": "https://misskey.example.com/likes/1",
+    "type": "Like",
+    "actor": "https://misskey.example.com/users/1",
+    "object": "https://b.example.com/objects/1",
+    "content": ":blobcat:",
+    "_misskey_reaction": ":blobcat:",
+    "tag": {
+        "id": "https://misskey.example.com/emojis/blobcat",
+        "type": "Emoji",
+        "name": ":blobcat:",
+        "updated": "2022-11-20T12:00:00.000Z",
+        "icon": { "type": "Image", "url": "https://misskey.example.com/files/blobcat.png" }
+    }
+}
+"#;
+
+const PLEROMA_EMOJI_REACT: &str = r#"
+{
+    "@context": "https://www.w3.org/ns/activitystreams",
+    "id": "https://pleroma.example.com/activities/1",
+    "type": "EmojiReact",
+    "actor": "https://pleroma.example.com/users/test",
+    "object": "https://b.example.com/objects/1",
+    "content": "👍",
+    "to": ["https://b.example.com/users/test"],
+    "cc": []
+}
+"#;
+
+#[test]
+fn decode_group_actor() {
+    let actor: crate::activitypub::Actor = serde_json::from_str(LEMMY_GROUP).unwrap();
+
+    assert!(crate::activitypub::ACTOR_TYPES.contains(&actor.r#type.as_str()));
+    assert!(actor.is_group());
+    assert!(!actor.is_bot());
+    assert!(actor.summary.is_empty());
+    assert!(actor.following.is_empty());
+}
+
 #[test]
 fn decode_actor() {
     let _actor: crate::activitypub::Actor = serde_json::from_str(PLEROMA_ACTOR).unwrap();
@@ -232,6 +463,153 @@ fn decode_create_activity_object() {
     assert!(activity.object.as_object().is_some());
 }
 
+#[test]
+fn decode_relay_accept_activity() {
+    let activity: crate::activitypub::Activity =
+        serde_json::from_str(RELAY_ACCEPT_ACTIVITY).unwrap();
+
+    let follow_activity = activity.object.as_activity().unwrap();
+    assert_eq!(follow_activity.r#type, "Follow");
+    assert_eq!(
+        follow_activity.object.as_url().unwrap(),
+        crate::activitypub::PUBLIC_IDENTIFIER
+    );
+    assert!(follow_activity.to.is_empty());
+}
+
+#[test]
+fn decode_reply_object() {
+    let object: crate::activitypub::Object = serde_json::from_str(REPLY_OBJECT).unwrap();
+
+    assert_eq!(
+        object.in_reply_to.as_deref(),
+        Some("https://b.example.com/objects/1")
+    );
+    assert_eq!(
+        object.quote_url.as_deref(),
+        Some("https://c.example.com/notes/2")
+    );
+    assert_eq!(
+        object.replies.unwrap()["first"]["next"],
+        "https://a.example.com/users/test/statuses/3/replies?page=true"
+    );
+}
+
+#[test]
+fn decode_object_without_reply() {
+    let activity: crate::activitypub::Activity =
+        serde_json::from_str(CREATE_ACTIVTY_OBJECT).unwrap();
+    let object = activity.object.as_object().unwrap();
+
+    assert!(object.in_reply_to.is_none());
+    assert!(object.replies.is_none());
+    assert!(object.quote_url.is_none());
+}
+
+#[test]
+fn decode_featured_collection() {
+    let featured: crate::activitypub::Collection =
+        serde_json::from_str(MASTODON_FEATURED_COLLECTION).unwrap();
+
+    assert_eq!(featured.ordered_items.len(), 2);
+    assert!(matches!(
+        featured.ordered_items[0],
+        crate::activitypub::collection::Item::Object(ref object) if object.summary.is_empty()
+    ));
+    assert!(matches!(
+        featured.ordered_items[1],
+        crate::activitypub::collection::Item::Url(..)
+    ));
+}
+
+#[test]
+fn decode_add_activity() {
+    let activity: crate::activitypub::Activity = serde_json::from_str(ADD_ACTIVITY).unwrap();
+
+    assert_eq!(
+        activity.target_url(),
+        Some("https://mastodon.example.com/users/test/collections/featured")
+    );
+    assert_eq!(
+        activity.object.object_url(),
+        Some("https://mastodon.example.com/users/test/statuses/1")
+    );
+}
+
+#[test]
+fn decode_question() {
+    let object: crate::activitypub::Object = serde_json::from_str(MASTODON_QUESTION).unwrap();
+
+    assert!(object.one_of.is_none());
+    assert!(object.end_time.is_some());
+    assert!(object.closed.is_none());
+    assert_eq!(object.voters_count, Some(3));
+
+    let options = object.poll_options().unwrap();
+    assert_eq!(options.len(), 2);
+    assert_eq!(options[1].name, "Second");
+    assert_eq!(options[0].replies.total_items, 2);
+}
+
+#[test]
+fn decode_poll_vote() {
+    let object: crate::activitypub::Object = serde_json::from_str(MASTODON_POLL_VOTE).unwrap();
+
+    assert_eq!(object.name.as_deref(), Some("Second"));
+    assert!(object.content.is_empty());
+    assert_eq!(object.to, ["https://b.example.com/users/test"]);
+    assert!(object.poll_options().is_none());
+}
+
+#[test]
+fn decode_tags() {
+    use crate::activitypub::Tag;
+
+    let object: crate::activitypub::Object = serde_json::from_str(TAGGED_OBJECT).unwrap();
+
+    assert_eq!(object.tag.len(), 4);
+    assert!(matches!(object.tag[0], Tag::Mention(ref link) if link.name == "@test@b.example.com"));
+    assert_eq!(object.tag[1].hashtag_name(), Some("Rust"));
+    assert_eq!(
+        object.tag[2]
+            .emoji()
+            .map(crate::activitypub::tag::Emoji::shortcode),
+        Some("blobcat")
+    );
+    assert!(matches!(object.tag[3], Tag::Other(ref value) if value["type"] == "PropertyValue"));
+
+    // Unknown tags survive a round trip
+    let value = serde_json::to_value(&object.tag[3]).unwrap();
+    assert_eq!(
+        value,
+        serde_json::from_str::<serde_json::Value>(TAGGED_OBJECT).unwrap()["tag"][3]
+    );
+}
+
+#[test]
+fn decode_reactions() {
+    let like: crate::activitypub::Activity = serde_json::from_str(MISSKEY_REACTION).unwrap();
+
+    assert_eq!(like.reaction(), Some(":blobcat:"));
+    assert_eq!(like.tag.len(), 1);
+    assert_eq!(
+        like.tag[0]
+            .emoji()
+            .map(crate::activitypub::tag::Emoji::shortcode),
+        Some("blobcat")
+    );
+
+    let emoji_react: crate::activitypub::Activity =
+        serde_json::from_str(PLEROMA_EMOJI_REACT).unwrap();
+
+    assert_eq!(emoji_react.reaction(), Some("👍"));
+    assert!(emoji_react.misskey_reaction.is_none());
+    assert!(emoji_react.tag.is_empty());
+
+    let create: crate::activitypub::Activity = serde_json::from_str(CREATE_ACTIVTY).unwrap();
+    assert!(create.reaction().is_none());
+}
+
 #[test]
 fn decode_outbox_collection() {
     let _outbox: crate::activitypub::Collection = serde_json::from_str(OUTBOX_COLLECTION).unwrap();
@@ -243,6 +621,26 @@ fn decode_outbox_collection_with_items() {
         serde_json::from_str(OUTBOX_COLLECTION_WITH_ITEMS).unwrap();
 }
 
+#[test]
+fn decode_mastodon_outbox_collection() {
+    let outbox: crate::activitypub::Collection =
+        serde_json::from_str(MASTODON_OUTBOX_COLLECTION).unwrap();
+
+    assert_eq!(outbox.total_items, Some(72138));
+    assert!(outbox.first.is_some());
+    assert!(outbox.last.is_some());
+}
+
+#[test]
+fn decode_outbox_collection_page_links() {
+    let outbox: crate::activitypub::Collection =
+        serde_json::from_str(OUTBOX_COLLECTION_WITH_ITEMS).unwrap();
+
+    assert_eq!(outbox.part_of, "https://lain.com/users/lain/outbox");
+    assert!(!outbox.next.is_empty());
+    assert!(!outbox.prev.is_empty());
+}
+
 #[test]
 fn decode_rfc_jrd() {
     let _jrd: crate::webfinger::Resource = serde_json::from_str(RFC_JRD).unwrap();
@@ -0,0 +1,208 @@
+use crate::{
+    activitypub::{
+        emoji,
+        fetcher::{self, FetchContext},
+        Clean,
+    },
+    consts::activitypub::{
+        BACKFILL_COOLDOWN, BACKFILL_MAX_PAGES, BACKFILL_POSTS, MAX_CONCURRENT_BACKFILLS,
+    },
+    database::{
+        actor_backfill, pin::Pin, Actor as DbActor, InsertExt, InsertObject, Object as DbObject,
+    },
+    error::Error,
+    state::ArcState,
+};
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tranquility_types::activitypub::{
+    activity::ObjectField, collection::Item, Actor, IsPublic, Object,
+};
+use url::Url;
+use uuid::Uuid;
+
+static BACKFILL_PERMITS: Lazy<Semaphore> = Lazy::new(|| Semaphore::new(MAX_CONCURRENT_BACKFILLS));
+
+/// Check whether both URLs point to the same server
+fn is_same_origin(url: &str, other_url: &str) -> bool {
+    match (Url::parse(url), Url::parse(other_url)) {
+        (Ok(url), Ok(other_url)) => url.origin() == other_url.origin(),
+        _ => false,
+    }
+}
+
+/// Import the post of the actor unless it's already known
+///
+/// Embedded posts are only accepted if they are attributed to the actor and hosted on the server of the actor.
+/// Returns the ID of the post in the database (or `None` if the post wasn't accepted)
+async fn import_object(
+    state: &ArcState,
+    actor: &Actor,
+    db_actor: &DbActor,
+    mut object: Object,
+) -> Result<Option<Uuid>, Error> {
+    if object.attributed_to != actor.id || !is_same_origin(&object.id, &actor.id) {
+        return Ok(None);
+    }
+
+    if let Ok(db_object) = DbObject::by_url(&state.db_pool, &object.id).await {
+        return Ok(Some(db_object.id));
+    }
+
+    object.clean();
+
+    let db_object = InsertObject {
+        id: Uuid::new_v4(),
+        owner_id: db_actor.id,
+        data: serde_json::to_value(&object)?,
+    }
+    .insert(&state.db_pool)
+    .await?;
+
+    emoji::ingest(state, &object.attributed_to, &object.tag);
+
+    Ok(Some(db_object.id))
+}
+
+/// Import the most recent public posts from the outbox of the actor
+async fn import_outbox(state: &ArcState, actor: &Actor, db_actor: &DbActor) -> Result<(), Error> {
+    // Only the server of the actor can vouch for the posts in the outbox
+    if !is_same_origin(&actor.outbox, &actor.id) {
+        return Ok(());
+    }
+
+    let outbox = fetcher::fetch_collection(state, &actor.outbox).await?;
+    let mut page = match outbox.first {
+        Some(ref first_page) if outbox.ordered_items.is_empty() => {
+            fetcher::fetch_collection(state, first_page).await?
+        }
+        _ => outbox,
+    };
+
+    let mut imported_posts = 0;
+    for _ in 0..BACKFILL_MAX_PAGES {
+        for item in page.ordered_items {
+            let Item::Activity(activity) = item else {
+                continue;
+            };
+            if activity.r#type != "Create" || activity.actor != actor.id || !activity.is_public() {
+                continue;
+            }
+
+            let ObjectField::Object(object) = activity.object else {
+                continue;
+            };
+            if import_object(state, actor, db_actor, object)
+                .await?
+                .is_none()
+            {
+                continue;
+            }
+
+            imported_posts += 1;
+            if imported_posts >= BACKFILL_POSTS {
+                return Ok(());
+            }
+        }
+
+        if page.next.is_empty() || !is_same_origin(&page.next, &actor.id) {
+            break;
+        }
+        page = fetcher::fetch_collection(state, &page.next).await?;
+    }
+
+    Ok(())
+}
+
+/// Import the pinned posts from the featured collection of the actor and record them as pinned
+async fn import_featured(
+    state: &ArcState,
+    actor: &Actor,
+    db_actor: &DbActor,
+    featured_url: &str,
+) -> Result<(), Error> {
+    if !is_same_origin(featured_url, &actor.id) {
+        return Ok(());
+    }
+
+    let featured = fetcher::fetch_collection(state, featured_url).await?;
+
+    let mut pinned_object_ids = Vec::new();
+    for item in featured.ordered_items.into_iter().take(BACKFILL_POSTS) {
+        let object_id = match item {
+            Item::Object(object) => import_object(state, actor, db_actor, *object).await?,
+            // Referenced posts are fetched from their origin
+            Item::Url(url) => {
+                match fetcher::fetch_object(state, &FetchContext::new(0), &url).await {
+                    Ok(object) if object.attributed_to == actor.id => {
+                        Some(DbObject::by_url(&state.db_pool, &object.id).await?.id)
+                    }
+                    Ok(..) => None,
+                    Err(err) => {
+                        debug!(error = ?err, %url, "Couldn't fetch pinned post");
+                        None
+                    }
+                }
+            }
+            Item::Activity(..) => None,
+        };
+
+        pinned_object_ids.extend(object_id);
+    }
+
+    Pin::replace(&state.db_pool, db_actor.id, &pinned_object_ids).await
+}
+
+/// Import the recent public posts and the pinned posts of the remote actor
+///
+/// Posts we already know about are skipped
+pub async fn backfill(state: &ArcState, actor: &Actor, db_actor: &DbActor) -> Result<(), Error> {
+    // The semaphore is never closed
+    let Ok(_permit) = BACKFILL_PERMITS.acquire().await else {
+        return Ok(());
+    };
+
+    if let Err(err) = import_outbox(state, actor, db_actor).await {
+        debug!(error = ?err, "Couldn't import the outbox");
+    }
+    if let Some(ref featured_url) = actor.featured {
+        import_featured(state, actor, db_actor, featured_url).await?;
+    }
+
+    Ok(())
+}
+
+/// Backfill the posts of the remote actor unless they were backfilled recently
+async fn backfill_once(state: &ArcState, actor: &Actor, db_actor: &DbActor) -> Result<(), Error> {
+    let cooldown_secs = i64::try_from(BACKFILL_COOLDOWN.as_secs()).unwrap_or(i64::MAX);
+    if actor_backfill::claim(&state.db_pool, db_actor.id, cooldown_secs).await? {
+        backfill(state, actor, db_actor).await?;
+    }
+
+    Ok(())
+}
+
+/// Backfill the posts of the remote actor in the background
+pub fn spawn(state: &ArcState, actor: Actor, db_actor: DbActor) {
+    let state = Arc::clone(state);
+
+    tokio::spawn(async move {
+        if let Err(err) = backfill(&state, &actor, &db_actor).await {
+            debug!(error = ?err, actor = %actor.id, "Backfill failed");
+        }
+    });
+}
+
+/// Backfill the posts of the remote actor in the background unless they were backfilled recently
+///
+/// The posts of an actor are backfilled at most once per `BACKFILL_COOLDOWN`
+pub fn spawn_if_due(state: &ArcState, actor: Actor, db_actor: DbActor) {
+    let state = Arc::clone(state);
+
+    tokio::spawn(async move {
+        if let Err(err) = backfill_once(&state, &actor, &db_actor).await {
+            debug!(error = ?err, actor = %actor.id, "Backfill failed");
+        }
+    });
+}
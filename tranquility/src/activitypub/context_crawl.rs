@@ -0,0 +1,65 @@
+use crate::{
+    activitypub::fetcher::{self, FetchContext},
+    consts::activitypub::{CONTEXT_CRAWL_COOLDOWN, MAX_CONCURRENT_CONTEXT_CRAWLS},
+    database::context_crawl,
+    error::Error,
+    state::ArcState,
+};
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tranquility_types::activitypub::Object;
+use uuid::Uuid;
+
+static CRAWL_PERMITS: Lazy<Semaphore> = Lazy::new(|| Semaphore::new(MAX_CONCURRENT_CONTEXT_CRAWLS));
+
+/// Fetch the posts the object is replying to and crawl the replies collections
+///
+/// Both share one fetch context, so one crawl sends at most `MAX_FETCHED_ENTITIES` requests
+async fn crawl(state: &ArcState, object: &Object) {
+    let context = FetchContext::default();
+    let mut parent_context = context.clone();
+    let mut parent_url = object.in_reply_to.clone();
+
+    while let Some(url) = parent_url.take() {
+        let Some(next_context) = parent_context.enter(&url) else {
+            break;
+        };
+        parent_context = next_context;
+
+        match fetcher::fetch_object(state, &parent_context, &url).await {
+            Ok(parent) => parent_url = parent.in_reply_to,
+            Err(err) => debug!(error = ?err, %url, "Couldn't fetch ancestor"),
+        }
+    }
+
+    fetcher::fetch_replies(state, &context, object).await;
+}
+
+/// Crawl the context of the object unless it was crawled recently
+async fn crawl_once(state: &ArcState, object_id: Uuid, object: &Object) -> Result<(), Error> {
+    // The semaphore is never closed
+    let Ok(_permit) = CRAWL_PERMITS.acquire().await else {
+        return Ok(());
+    };
+
+    let cooldown_secs = i64::try_from(CONTEXT_CRAWL_COOLDOWN.as_secs()).unwrap_or(i64::MAX);
+    if context_crawl::claim(&state.db_pool, object_id, cooldown_secs).await? {
+        crawl(state, object).await;
+    }
+
+    Ok(())
+}
+
+/// Crawl the context of the object in the background
+///
+/// The context of an object is crawled at most once per `CONTEXT_CRAWL_COOLDOWN`
+pub fn spawn(state: &ArcState, object_id: Uuid, object: Object) {
+    let state = Arc::clone(state);
+
+    tokio::spawn(async move {
+        if let Err(err) = crawl_once(&state, object_id, &object).await {
+            debug!(error = ?err, object = %object.id, "Context crawl failed");
+        }
+    });
+}
@@ -1,18 +1,23 @@
-use crate::{crypto, database::Actor as DbActor, error::Error, state::ArcState, util::HTTP_CLIENT};
-use futures_util::stream::{FuturesUnordered, StreamExt};
-use itertools::Itertools;
-use reqwest::{
-    header::{HeaderName, HeaderValue, DATE},
-    Client, Request, Response,
+use crate::{
+    consts::activitypub::{MAX_CONCURRENT_DELIVERIES, RELAYED_ACTIVITY_TYPES},
+    crypto,
+    database::{relay::Relay, Actor as DbActor},
+    error::Error,
+    state::ArcState,
+    util::{network, HTTP_CLIENT},
 };
+use futures_util::stream::{self, StreamExt};
+use itertools::Itertools;
+use reqwest::{Client, Request, Response};
 use std::{future::Future, sync::Arc};
-use time::{format_description::well_known::Rfc3339, OffsetDateTime};
-use tranquility_types::activitypub::{Activity, Actor, PUBLIC_IDENTIFIER};
+use tranquility_types::activitypub::{Activity, Actor, IsPublic, IsUnlisted, PUBLIC_IDENTIFIER};
+use url::Url;
 /// Structure that holds data relevant to delivering an activity
 struct DeliveryData {
     author: Actor,
     author_db: DbActor,
     activity: Activity,
+    body: Vec<u8>,
     state: ArcState,
 }
 
@@ -21,10 +26,13 @@ impl DeliveryData {
         let (author, author_db) =
             crate::activitypub::fetcher::fetch_actor(&state, activity.actor.as_str()).await?;
 
+        let body = serialize_activity(&activity, &author, &author_db).await?;
+
         let delivery_data = DeliveryData {
             author,
             author_db,
             activity,
+            body,
             state,
         };
 
@@ -32,6 +40,30 @@ impl DeliveryData {
     }
 }
 
+/// Serialize the activity into the request body
+///
+/// Public and unlisted activities get a Linked Data Signature embedded so they can be forwarded by other servers.
+/// Other activities aren't signed since the signature would allow anyone who received them to replay them anywhere
+async fn serialize_activity(
+    activity: &Activity,
+    author: &Actor,
+    author_db: &DbActor,
+) -> Result<Vec<u8>, Error> {
+    let mut document = serde_json::to_value(activity)?;
+
+    if activity.is_public() || activity.is_unlisted() {
+        let key_id = author.public_key.id.clone();
+        let private_key = author_db
+            .private_key
+            .clone()
+            .ok_or(Error::MissingPrivateKey)?;
+
+        document = crypto::ld_signature::sign(document, key_id, private_key).await?;
+    }
+
+    Ok(serde_json::to_vec(&document)?)
+}
+
 /// Create a future that delivers the activity to the provided URL
 #[instrument(
     fields(activity_id = delivery_data.activity.id.as_str()),
@@ -54,8 +86,8 @@ fn construct_deliver_future<'a>(
 
 /// Prepare the HTTP request
 ///
+/// - Attach the serialized body
 /// - Sign the request
-/// - Serialize the body
 #[instrument(skip(client, delivery_data))]
 async fn prepare_request(
     client: &Client,
@@ -63,42 +95,31 @@ async fn prepare_request(
     delivery_data: Arc<DeliveryData>,
 ) -> Result<Request, Error> {
     let DeliveryData {
-        ref activity,
         ref author,
         ref author_db,
+        ref body,
+        ref state,
         ..
     } = *delivery_data;
 
-    let mut request = client
+    let url = Url::parse(url)?;
+    network::check_url(&url)?;
+
+    let signature_format = crate::activitypub::signature_format(state, &url).await?;
+
+    let request = client
         .post(url)
         .header("Content-Type", "application/activity+json")
-        .json(activity)
+        .body(body.clone())
         .build()?;
 
-    let rfc3339_timestamp = OffsetDateTime::now_utc()
-        .format(&Rfc3339)
-        .map_err(time::Error::from)?;
-    let date_header_value = HeaderValue::from_str(&rfc3339_timestamp)?;
-
-    let activity_bytes = serde_json::to_vec(activity)?;
-    let digest_header_value = crate::crypto::digest::http_header(activity_bytes).await?;
-
-    request.headers_mut().insert(DATE, date_header_value);
-    request
-        .headers_mut()
-        .insert(HeaderName::from_static("digest"), digest_header_value);
+    let key_id = author.public_key.id.clone();
+    let private_key = author_db
+        .private_key
+        .clone()
+        .ok_or(Error::MissingPrivateKey)?;
 
-    let (header_name, header_value) = {
-        let request = request.try_clone().unwrap();
-        let key_id = author.public_key.id.clone();
-        let private_key = author_db.private_key.as_ref().unwrap().clone();
-
-        crypto::request::sign(request, key_id, private_key).await?
-    };
-
-    request.headers_mut().insert(header_name, header_value);
-
-    Ok(request)
+    crypto::request::sign(request, signature_format, key_id, private_key).await
 }
 
 /// Resolve the follow collections and the actor URL to inbox URLs
@@ -147,12 +168,46 @@ async fn get_recipient_list(delivery_data: &DeliveryData) -> Result<Vec<String>,
         }
     }
 
+    let activity = &delivery_data.activity;
+    if activity.is_public() && RELAYED_ACTIVITY_TYPES.contains(&activity.r#type.as_str()) {
+        match Relay::accepted_inbox_urls(&delivery_data.state.db_pool).await {
+            Ok(relay_inbox_urls) => recipient_list.push(relay_inbox_urls),
+            Err(err) => warn!("Relays couldn't be resolved: {}", err),
+        }
+    }
+
     // Flatten the vector of vectors of strings to a vector of strings
-    let recipient_list = recipient_list.into_iter().flatten().collect();
+    // Different recipients might share an inbox
+    let recipient_list = recipient_list.into_iter().flatten().unique().collect();
 
     Ok(recipient_list)
 }
 
+/// Deliver the activity to the inbox URLs concurrently
+///
+/// At most `MAX_CONCURRENT_DELIVERIES` requests are sent at the same time
+async fn deliver_to_inboxes(delivery_data: &Arc<DeliveryData>, inbox_urls: Vec<String>) {
+    let mut deliver_futures = stream::iter(inbox_urls)
+        .map(|url| construct_deliver_future(delivery_data, url))
+        .buffer_unordered(MAX_CONCURRENT_DELIVERIES);
+
+    while let Some(delivery_result) = deliver_futures.next().await {
+        match delivery_result {
+            Ok(response) if response.status().is_success() => (),
+            Ok(response) => {
+                let response_status = response.status();
+                let response_body = response.text().await.unwrap_or_default();
+
+                warn!(
+                    "Delivery request wasn't successful\nStatus code: {}\nServer response: {}",
+                    response_status, response_body,
+                );
+            }
+            Err(err) => warn!("Delivery request failed: {}", err),
+        }
+    }
+}
+
 /// Deliver an activity to the specified user (groups)
 pub async fn deliver(activity: Activity, state: ArcState) -> Result<(), Error> {
     let delivery_data = DeliveryData::new(activity, state).await?;
@@ -167,27 +222,64 @@ pub async fn deliver(activity: Activity, state: ArcState) -> Result<(), Error> {
         };
 
         // Deliver the activity to the recipients concurrently
-        let mut deliver_futures = recipient_list
-            .into_iter()
-            .map(|url| construct_deliver_future(&delivery_data, url))
-            .collect::<FuturesUnordered<_>>();
-
-        while let Some(delivery_result) = deliver_futures.next().await {
-            match delivery_result {
-                Ok(response) if response.status().is_success() => (),
-                Ok(response) => {
-                    let response_status = response.status();
-                    let response_body = response.text().await.unwrap_or_default();
-
-                    warn!(
-                        "Delivery request wasn't successful\nStatus code: {}\nServer response: {}",
-                        response_status, response_body,
-                    );
-                }
-                Err(err) => warn!("Delivery request failed: {}", err),
+        deliver_to_inboxes(&delivery_data, recipient_list).await;
+    });
+
+    Ok(())
+}
+
+/// Forward an activity of someone else to the followers of the local actor
+///
+/// The original body is delivered unchanged, only the HTTP signature is made with the key of the local actor.
+/// Inboxes on the server of the activity's actor are skipped since that server already knows the activity
+pub async fn forward(
+    activity: Activity,
+    body: Vec<u8>,
+    forwarder: Actor,
+    forwarder_db: DbActor,
+    state: ArcState,
+) -> Result<(), Error> {
+    let origin = Url::parse(&activity.actor)?.origin();
+    let delivery_data = Arc::new(DeliveryData {
+        author: forwarder,
+        author_db: forwarder_db,
+        activity,
+        body,
+        state,
+    });
+
+    tokio::spawn(async move {
+        let followers_url = delivery_data.author.followers.clone();
+        let inbox_urls = match resolve_url(&delivery_data, followers_url).await {
+            Ok(inbox_urls) => inbox_urls,
+            Err(err) => {
+                warn!("Couldn't resolve the followers: {}", err);
+                return;
             }
-        }
+        };
+
+        let inbox_urls = inbox_urls
+            .into_iter()
+            .unique()
+            .filter(|url| !Url::parse(url).is_ok_and(|url| url.origin() == origin))
+            .collect();
+
+        deliver_to_inboxes(&delivery_data, inbox_urls).await;
     });
 
     Ok(())
 }
+
+/// Deliver an activity directly to the given inbox URLs instead of the addressed users
+///
+/// Unlike `deliver`, this function only returns once every delivery attempt has finished
+pub async fn deliver_to(
+    activity: Activity,
+    inbox_urls: Vec<String>,
+    state: ArcState,
+) -> Result<(), Error> {
+    let delivery_data = DeliveryData::new(activity, state).await?;
+    deliver_to_inboxes(&delivery_data, inbox_urls).await;
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use crate::{
+    activitypub::fetcher, config::Configuration, consts::activitypub::MAX_INGESTED_EMOJIS,
+    database::custom_emoji::CustomEmoji, error::Error, state::ArcState,
+    util::emoji::is_valid_shortcode,
+};
+use itertools::Itertools;
+use std::sync::Arc;
+use tranquility_types::activitypub::{tag::Emoji, Tag};
+use url::Url;
+
+/// Get the domain of the instance the emojis used by the owner belong to (`None` for local owners)
+///
+/// Emojis are attributed to the instance of the actor using them (and not the instance their tags claim they are from).
+/// This way remote instances can't overwrite the emojis of other instances
+pub fn domain(config: &Configuration, owner_url: &str) -> Result<Option<String>, Error> {
+    let owner_url = Url::parse(owner_url)?;
+    let domain = owner_url.host_str().ok_or(Error::MalformedUrl)?;
+
+    if domain == config.instance.domain {
+        Ok(None)
+    } else {
+        Ok(Some(domain.to_lowercase()))
+    }
+}
+
+/// Get the saved emoji referenced by the Emoji tag of an object or actor owned by the owner
+pub async fn lookup(
+    state: &ArcState,
+    owner_url: &str,
+    emoji: &Emoji,
+) -> Result<Option<CustomEmoji>, Error> {
+    let domain = domain(&state.config, owner_url)?;
+
+    CustomEmoji::by_shortcode(&state.db_pool, emoji.shortcode(), domain.as_deref()).await
+}
+
+/// Save the remote emoji and cache its image
+///
+/// The image is only fetched again if the emoji changed since it was cached
+async fn ingest_emoji(state: &ArcState, domain: &str, emoji: &Emoji) -> Result<(), Error> {
+    let shortcode = emoji.shortcode();
+    if !is_valid_shortcode(shortcode) {
+        return Err(Error::InvalidRequest);
+    }
+
+    if let Some(custom_emoji) =
+        CustomEmoji::by_shortcode(&state.db_pool, shortcode, Some(domain)).await?
+    {
+        let unchanged = custom_emoji.image_url.as_deref() == Some(emoji.icon.url.as_str())
+            && custom_emoji.remote_updated_at == emoji.updated;
+
+        if unchanged && CustomEmoji::has_image(&state.db_pool, custom_emoji.id).await? {
+            return Ok(());
+        }
+    }
+
+    let custom_emoji = CustomEmoji::upsert_remote(
+        &state.db_pool,
+        shortcode,
+        domain,
+        &emoji.icon.url,
+        emoji.updated,
+    )
+    .await?;
+
+    let (content_type, data) = fetcher::fetch_image(state, &emoji.icon.url).await?;
+    CustomEmoji::set_image(&state.db_pool, custom_emoji.id, &content_type, &data).await?;
+
+    Ok(())
+}
+
+/// Get the emojis of the Emoji tags that are going to be saved
+///
+/// Every shortcode is only saved once. Emojis beyond the maximum amount are skipped,
+/// otherwise a single object could make the server fetch and store an unlimited amount of images
+pub fn ingested_emojis(tags: &[Tag]) -> Vec<Emoji> {
+    tags.iter()
+        .filter_map(Tag::emoji)
+        .unique_by(|emoji| emoji.shortcode())
+        .take(MAX_INGESTED_EMOJIS)
+        .cloned()
+        .collect()
+}
+
+/// Save the emojis of the Emoji tags of a remote object or actor and cache their images in the background
+pub fn ingest(state: &ArcState, owner_url: &str, tags: &[Tag]) {
+    let emojis = ingested_emojis(tags);
+    if emojis.is_empty() {
+        return;
+    }
+
+    let domain = match domain(&state.config, owner_url) {
+        Ok(Some(domain)) => domain,
+        // Emojis of local objects and actors are managed by the admins
+        Ok(None) => return,
+        Err(err) => {
+            warn!(error = ?err, "Couldn't determine the domain of the emojis");
+            return;
+        }
+    };
+
+    let state = Arc::clone(state);
+    tokio::spawn(async move {
+        for emoji in emojis {
+            if let Err(err) = ingest_emoji(&state, &domain, &emoji).await {
+                warn!(error = ?err, shortcode = %emoji.shortcode(), "Couldn't ingest remote emoji");
+            }
+        }
+    });
+}
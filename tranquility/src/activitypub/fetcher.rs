@@ -1,16 +1,30 @@
 use crate::{
-    activitypub::Clean,
+    activitypub::{backfill, emoji, Clean},
     attempt_fetch,
+    consts::{
+        activitypub::{MAX_FETCHED_ENTITIES, MAX_FETCH_DEPTH},
+        http_client::{ACTIVITYPUB_CONTENT_TYPES, MAX_RESPONSE_SIZE},
+        KB_BYTES,
+    },
     database::{Actor as DbActor, InsertActor, InsertExt, InsertObject, Object as DbObject},
-    error::Error,
+    error::{Error, FetchError},
     impl_from, impl_into, impl_is_owned_by,
     state::ArcState,
-    util::HTTP_CLIENT,
+    util::{network, HTTP_CLIENT},
 };
-use reqwest::IntoUrl;
+use futures_util::future::{BoxFuture, FutureExt};
+use reqwest::{header::CONTENT_TYPE, Response};
 use serde_json::Value;
-use std::fmt::Debug;
-use tranquility_types::activitypub::{activity::ObjectField, Activity, Actor, Object};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+use time::OffsetDateTime;
+use tranquility_types::activitypub::{
+    activity::ObjectField, Activity, Actor, Collection, Object, ACTOR_TYPES,
+};
+use url::Url;
 use uuid::Uuid;
 
 pub enum Entity {
@@ -28,28 +42,114 @@ impl_is_owned_by!(
     (Object, attributed_to)
 );
 
+impl Entity {
+    /// ID of the entity
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Activity(activity) => &activity.id,
+            Self::Actor(actor) => &actor.id,
+            Self::Object(object) => &object.id,
+        }
+    }
+}
+
+/// Make sure both URLs point to the same server
+fn check_origin(url: &str, other_url: &str) -> Result<(), Error> {
+    if Url::parse(url)?.origin() == Url::parse(other_url)?.origin() {
+        Ok(())
+    } else {
+        Err(FetchError::OriginMismatch.into())
+    }
+}
+
+/// Budget of a recursive fetch
+///
+/// Every followed reference uses up one level of the depth budget and every entity is visited only once per context.
+/// At most `MAX_FETCHED_ENTITIES` entities are visited overall, so long or cyclic chains of references can't keep the fetcher busy forever
+#[derive(Clone)]
+pub struct FetchContext {
+    depth: usize,
+    visited: Arc<Mutex<HashSet<String>>>,
+}
+
+impl FetchContext {
+    /// Create a new context that follows references up to the given depth
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            visited: Arc::default(),
+        }
+    }
+
+    /// Mark the URL as visited
+    ///
+    /// Returns `false` if the URL was visited before or the context already visited too many entities
+    pub fn visit(&self, url: &str) -> bool {
+        let mut visited = self.visited.lock().unwrap_or_else(PoisonError::into_inner);
+        if visited.len() >= MAX_FETCHED_ENTITIES {
+            return false;
+        }
+
+        visited.insert(url.to_owned())
+    }
+
+    /// Follow a reference to the URL
+    ///
+    /// Returns the context the referenced entity should be fetched with or `None` if the reference shouldn't be followed
+    pub fn enter(&self, url: &str) -> Option<Self> {
+        if self.depth == 0 || !self.visit(url) {
+            return None;
+        }
+
+        Some(Self {
+            depth: self.depth - 1,
+            visited: Arc::clone(&self.visited),
+        })
+    }
+}
+
+impl Default for FetchContext {
+    fn default() -> Self {
+        Self::new(MAX_FETCH_DEPTH)
+    }
+}
+
 /// Takes any URL that points to an ActivityPub entity  
 ///
 /// It makes multiple attempts to fetch the entity and decode it into different normalized forms.
 /// If none of the attempts succeed, a `Fetch` error is returned
-#[instrument(skip(state))]
-pub async fn fetch_any(state: &ArcState, url: &str) -> Result<Entity, Error> {
-    // Create a custom closure around the `fetch_actor` function
+#[instrument(skip(state, context))]
+pub async fn fetch_any(
+    state: &ArcState,
+    context: &FetchContext,
+    url: &str,
+) -> Result<Entity, Error> {
+    // Create custom closures around the fetch functions
     // Otherwise the pattern in the macro won't match
+    let fetch_activity_fn = |state, url| async move { fetch_activity(state, context, url).await };
     let fetch_actor_fn = |state, url| async move {
         fetch_actor(state, url)
             .await
             .map(|(actor, _db_actor)| actor)
     };
+    let fetch_object_fn = |state, url| async move { fetch_object(state, context, url).await };
 
-    attempt_fetch!(state, url, [fetch_activity, fetch_actor_fn, fetch_object]);
+    attempt_fetch!(
+        state,
+        url,
+        [fetch_activity_fn, fetch_actor_fn, fetch_object_fn]
+    );
 
-    Err(Error::Fetch)
+    Err(FetchError::UnexpectedEntity.into())
 }
 
 /// Attempt to deserialize the data from the given URL as an ActivityPub activity
-#[instrument(skip(state))]
-pub async fn fetch_activity(state: &ArcState, url: &str) -> Result<Activity, Error> {
+#[instrument(skip(state, context))]
+pub async fn fetch_activity(
+    state: &ArcState,
+    context: &FetchContext,
+    url: &str,
+) -> Result<Activity, Error> {
     debug!("Fetching remote actor...");
 
     match DbObject::by_url(&state.db_pool, url).await {
@@ -60,10 +160,18 @@ pub async fn fetch_activity(state: &ArcState, url: &str) -> Result<Activity, Err
         ),
     }
 
-    if let Entity::Activity(mut activity) = fetch_entity(url).await? {
+    if let Entity::Activity(mut activity) = fetch_entity(state, url).await? {
+        // Only the server of the actor can vouch for the activity
+        check_origin(&activity.id, &activity.actor)?;
         let (_actor, actor_db) = fetch_actor(state, &activity.actor).await?;
         // Normalize the activity
         if let Some(object) = activity.object.as_mut_object() {
+            // The embedded object is saved as an object of the actor
+            if object.attributed_to != activity.actor {
+                return Err(FetchError::UnexpectedEntity.into());
+            }
+            check_origin(&object.id, &activity.actor)?;
+
             // If the object is embedded into the activity itself:
             // - Take the object
             // - Insert it into the database
@@ -80,6 +188,9 @@ pub async fn fetch_activity(state: &ArcState, url: &str) -> Result<Activity, Err
             .insert(&state.db_pool)
             .await?;
 
+            emoji::ingest(state, &object.attributed_to, &object.tag);
+            fetch_references(state, context, object).await;
+
             activity.object = ObjectField::Url(object.id.clone());
         } else if activity.object.as_actor().is_some() {
             return Err(Error::UnknownActivity); // You shouldn't be able to fetch an update activity?
@@ -99,7 +210,7 @@ pub async fn fetch_activity(state: &ArcState, url: &str) -> Result<Activity, Err
     } else {
         debug!("Remote server returned content we can't interpret");
 
-        Err(Error::Fetch)
+        Err(FetchError::UnexpectedEntity.into())
     }
 }
 
@@ -109,6 +220,19 @@ pub async fn fetch_actor(state: &ArcState, url: &str) -> Result<(Actor, DbActor)
     debug!("Fetching remote actor...");
 
     match DbActor::by_url(&state.db_pool, url).await {
+        Ok(actor) if is_stale(state, &actor) => {
+            debug!("Cached actor is stale. Attempting refetch...");
+
+            match refetch_actor(state, actor.clone()).await {
+                Ok(refetched_actor) => return Ok(refetched_actor),
+                Err(e) => debug!(
+                    error = ?e,
+                    "Refetch failed. Falling back to the cached actor"
+                ),
+            }
+
+            return Ok((serde_json::from_value(actor.actor.clone())?, actor));
+        }
         Ok(actor) => return Ok((serde_json::from_value(actor.actor.clone())?, actor)),
         Err(e) => debug!(
             error = ?e,
@@ -116,7 +240,14 @@ pub async fn fetch_actor(state: &ArcState, url: &str) -> Result<(Actor, DbActor)
         ),
     }
 
-    if let Entity::Actor(mut actor) = fetch_entity(url).await? {
+    if let Entity::Actor(mut actor) = fetch_entity(state, url).await? {
+        // The URL might have been an alias of an actor we already know
+        if actor.id != url {
+            if let Ok(db_actor) = DbActor::by_url(&state.db_pool, &actor.id).await {
+                return Ok((serde_json::from_value(db_actor.actor.clone())?, db_actor));
+            }
+        }
+
         actor.clean();
 
         let actor_value = serde_json::to_value(&actor)?;
@@ -134,17 +265,67 @@ pub async fn fetch_actor(state: &ArcState, url: &str) -> Result<(Actor, DbActor)
         .insert(&state.db_pool)
         .await?;
 
+        emoji::ingest(state, &actor.id, &actor.tag);
+
+        // Import some posts so the profile of the newly discovered actor isn't empty
+        backfill::spawn_if_due(state, actor.clone(), db_actor.clone());
+
         Ok((actor, db_actor))
     } else {
         debug!("Remote server returned content we can't interpret");
 
-        Err(Error::Fetch)
+        Err(FetchError::UnexpectedEntity.into())
     }
 }
 
+/// Check whether the cached copy of a remote actor is older than the configured TTL
+fn is_stale(state: &ArcState, db_actor: &DbActor) -> bool {
+    let actor_ttl = Duration::from_secs(state.config.federation.actor_ttl);
+
+    db_actor.remote && OffsetDateTime::now_utc() - db_actor.last_fetched_at > actor_ttl
+}
+
+/// Fetch the current version of a remote actor and update the cached copy
+///
+/// If the fetch fails, the actor is marked as fetched anyway. This way unreachable servers aren't contacted over and over again
+#[instrument(skip_all, fields(actor_id = %db_actor.id))]
+pub async fn refetch_actor(state: &ArcState, db_actor: DbActor) -> Result<(Actor, DbActor), Error> {
+    let cached_actor: Actor = serde_json::from_value(db_actor.actor)?;
+
+    let fetch_result = match fetch_entity(state, &cached_actor.id).await {
+        // Make sure the remote server doesn't swap the actor with a different one
+        Ok(Entity::Actor(actor)) if actor.id == cached_actor.id => Ok(actor),
+        Ok(..) => Err(FetchError::UnexpectedEntity.into()),
+        Err(err) => Err(err),
+    };
+
+    let mut actor = match fetch_result {
+        Ok(actor) => actor,
+        Err(err) => {
+            DbActor::mark_fetched(&state.db_pool, db_actor.id).await?;
+
+            return Err(err);
+        }
+    };
+    actor.clean();
+
+    let actor_value = serde_json::to_value(&actor)?;
+    let db_actor = DbActor::refresh(&state.db_pool, db_actor.id, actor_value).await?;
+
+    emoji::ingest(state, &actor.id, &actor.tag);
+
+    Ok((actor, db_actor))
+}
+
 /// Attempt to deserialize the data from the given URL as an ActivityPub object
-#[instrument(skip(state))]
-pub async fn fetch_object(state: &ArcState, url: &str) -> Result<Object, Error> {
+///
+/// Newly fetched objects get their references followed (see [`fetch_references`])
+#[instrument(skip(state, context))]
+pub async fn fetch_object(
+    state: &ArcState,
+    context: &FetchContext,
+    url: &str,
+) -> Result<Object, Error> {
     debug!("Fetching remote object...");
 
     match DbObject::by_url(&state.db_pool, url).await {
@@ -155,7 +336,9 @@ pub async fn fetch_object(state: &ArcState, url: &str) -> Result<Object, Error>
         ),
     }
 
-    if let Entity::Object(mut object) = fetch_entity(url).await? {
+    if let Entity::Object(mut object) = fetch_entity(state, url).await? {
+        // Only the server of the author can vouch for the object
+        check_origin(&object.id, &object.attributed_to)?;
         object.clean();
 
         let (_actor, actor_db) = fetch_actor(state, &object.attributed_to).await?;
@@ -169,18 +352,153 @@ pub async fn fetch_object(state: &ArcState, url: &str) -> Result<Object, Error>
         .insert(&state.db_pool)
         .await?;
 
+        emoji::ingest(state, &object.attributed_to, &object.tag);
+        fetch_references(state, context, &object).await;
+
         Ok(object)
     } else {
         debug!("Remote server returned content we can't interpret");
 
-        Err(Error::Fetch)
+        Err(FetchError::UnexpectedEntity.into())
     }
 }
 
-/// Fetch the contents from the URL and attempt to parse them as different ActivityPub types
-/// until either some type works or none of them work
-#[instrument]
-async fn fetch_entity<T: Debug + IntoUrl + Send>(url: T) -> Result<Entity, Error> {
+/// Fetch the objects the object is replying to or quoting (and, recursively, their references)
+///
+/// Only follows the references as far as the context allows. Failed fetches are logged and skipped
+pub fn fetch_references<'a>(
+    state: &'a ArcState,
+    context: &'a FetchContext,
+    object: &'a Object,
+) -> BoxFuture<'a, ()> {
+    async move {
+        context.visit(&object.id);
+
+        let references = object.in_reply_to.iter().chain(object.quote_url.iter());
+        for url in references {
+            let Some(context) = context.enter(url) else {
+                continue;
+            };
+
+            if let Err(err) = fetch_object(state, &context, url).await {
+                debug!(error = ?err, %url, "Couldn't fetch referenced object");
+            }
+        }
+    }
+    .boxed()
+}
+
+/// Crawl the replies collection of the object and fetch the replies (and, recursively, their replies)
+///
+/// Only crawls as far as the context allows. Failed fetches are logged and skipped
+pub fn fetch_replies<'a>(
+    state: &'a ArcState,
+    context: &'a FetchContext,
+    object: &'a Object,
+) -> BoxFuture<'a, ()> {
+    async move {
+        let Some(ref replies) = object.replies else {
+            return;
+        };
+
+        for url in collection_items(state, context, replies).await {
+            let Some(context) = context.enter(&url) else {
+                continue;
+            };
+
+            match fetch_object(state, &context, &url).await {
+                Ok(reply) if reply.in_reply_to.as_deref() == Some(object.id.as_str()) => {
+                    fetch_replies(state, &context, &reply).await;
+                }
+                Ok(..) => debug!(%url, "Collection item isn't a reply to the object"),
+                Err(err) => debug!(error = ?err, %url, "Couldn't fetch reply"),
+            }
+        }
+    }
+    .boxed()
+}
+
+/// Get the URLs of the items of the collection (either its URL or the embedded collection)
+///
+/// The pages of the collection are followed until the context doesn't allow any more fetches
+async fn collection_items(
+    state: &ArcState,
+    context: &FetchContext,
+    collection: &Value,
+) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut next_page = Some(collection.clone());
+
+    while let Some(page) = next_page.take() {
+        let page = match page {
+            Value::String(url) => {
+                if !context.visit(&url) {
+                    break;
+                }
+
+                match fetch_value(state, &url).await {
+                    Ok(page) => page,
+                    Err(err) => {
+                        debug!(error = ?err, %url, "Couldn't fetch collection page");
+                        break;
+                    }
+                }
+            }
+            page => page,
+        };
+
+        let page_items = ["items", "orderedItems"]
+            .into_iter()
+            .filter_map(|name| page.get(name).and_then(Value::as_array))
+            .flatten()
+            .filter_map(|item| {
+                item.as_str()
+                    .or_else(|| item.get("id").and_then(Value::as_str))
+            })
+            .map(ToOwned::to_owned);
+        items.extend(page_items);
+
+        // Collections link to their first page, pages link to the next one
+        next_page = page.get("first").or_else(|| page.get("next")).cloned();
+    }
+
+    items
+}
+
+/// Read the body of the response
+///
+/// Aborts as soon as the body exceeds the maximum size (in bytes)
+pub async fn read_body(mut response: Response, max_size: u64) -> Result<Vec<u8>, Error> {
+    if response
+        .content_length()
+        .is_some_and(|content_length| content_length > max_size)
+    {
+        return Err(FetchError::ResponseTooLarge.into());
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        body.extend_from_slice(&chunk);
+
+        if body.len() as u64 > max_size {
+            return Err(FetchError::ResponseTooLarge.into());
+        }
+    }
+
+    Ok(body)
+}
+
+/// Fetch the contents from the URL as JSON
+///
+/// The request is signed with the key of the instance actor so servers running in secure mode accept it
+#[instrument(skip(state))]
+async fn fetch_value(state: &ArcState, url: &str) -> Result<Value, Error> {
+    let url = Url::parse(url)?;
+    network::check_url(&url)?;
+    if state.config.federation.is_blocked(&url) {
+        return Err(Error::BlockedDomain);
+    }
+
     let request = HTTP_CLIENT
         .get(url)
         .header(
@@ -189,9 +507,108 @@ async fn fetch_entity<T: Debug + IntoUrl + Send>(url: T) -> Result<Entity, Error
         )
         .build()?;
 
-    let entity: Value = HTTP_CLIENT.execute(request).await?.json().await?;
+    let (instance_actor, instance_actor_db) =
+        crate::activitypub::instance_actor::get(state).await?;
+    let signature_format = crate::activitypub::signature_format(state, request.url()).await?;
+    let key_id = instance_actor.public_key.id;
+    let private_key = instance_actor_db
+        .private_key
+        .ok_or(Error::MissingPrivateKey)?;
+    let request =
+        crate::crypto::request::sign(request, signature_format, key_id, private_key).await?;
+
+    let response = HTTP_CLIENT.execute(request).await?;
+    let response_status = response.status();
+    if !response_status.is_success() {
+        return Err(FetchError::UnsuccessfulStatus(response_status).into());
+    }
+
+    network::check_content_type(&response, ACTIVITYPUB_CONTENT_TYPES)?;
+    let body = read_body(response, MAX_RESPONSE_SIZE).await?;
+
+    Ok(serde_json::from_slice(&body)?)
+}
+
+/// Fetch the image from the URL (for example, the image of a custom emoji)
+///
+/// Returns the content type and the contents of the image. Images larger than the upload limit are rejected
+#[instrument(skip(state))]
+pub async fn fetch_image(state: &ArcState, url: &str) -> Result<(String, Vec<u8>), Error> {
+    let url = Url::parse(url)?;
+    network::check_url(&url)?;
+    if state.config.federation.is_blocked(&url) {
+        return Err(Error::BlockedDomain);
+    }
+
+    let response = HTTP_CLIENT.get(url).send().await?;
+    let response_status = response.status();
+    if !response_status.is_success() {
+        return Err(FetchError::UnsuccessfulStatus(response_status).into());
+    }
+
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|header_value| header_value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    if !crate::util::is_image(&content_type) {
+        return Err(FetchError::UnexpectedContentType(content_type).into());
+    }
+
+    let max_size = state.config.instance.upload_limit * KB_BYTES;
+    let body = read_body(response, max_size).await?;
+
+    Ok((content_type, body))
+}
+
+/// Fetch the collection from the URL
+///
+/// Like [`fetch_entity`], this neither looks at nor inserts into the database
+pub async fn fetch_collection(state: &ArcState, url: &str) -> Result<Collection, Error> {
+    let collection = fetch_value(state, url).await?;
+
+    Ok(serde_json::from_value(collection)?)
+}
+
+/// Fetch the key document from the URL and return the URL of its owner
+///
+/// Key documents are either plain keys with an `owner` field or stub actors with the key embedded (like the ones of GoToSocial).
+/// Like [`fetch_entity`], this neither looks at nor inserts into the database
+pub async fn fetch_key_owner(state: &ArcState, key_id: &str) -> Result<String, Error> {
+    let document = fetch_value(state, key_id).await?;
+
+    let key = if document.get("publicKey").is_some() {
+        &document["publicKey"]
+    } else {
+        &document
+    };
+
+    if key.get("id").and_then(Value::as_str) != Some(key_id) {
+        return Err(FetchError::UnexpectedEntity.into());
+    }
+
+    key.get("owner")
+        .and_then(Value::as_str)
+        .map(ToString::to_string)
+        .ok_or_else(|| FetchError::UnexpectedEntity.into())
+}
+
+/// Fetch the contents from the URL and attempt to parse them as different ActivityPub types
+/// until either some type works or none of them work
+///
+/// Unlike the other fetch functions, this neither looks at nor inserts into the database.
+/// Entities whose ID points to a different server than the one they were fetched from are rejected
+#[instrument(skip(state))]
+pub async fn fetch_entity(state: &ArcState, url: &str) -> Result<Entity, Error> {
+    let entity = fetch_value(state, url).await?;
+
+    let entity_type = entity
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or(FetchError::MissingType)?;
 
-    let entity = if entity["type"].as_str().unwrap() == "Person" {
+    let entity = if ACTOR_TYPES.contains(&entity_type) {
         // This should be deserializable into an actor
         let actor = serde_json::from_value(entity)?;
 
@@ -207,6 +624,7 @@ async fn fetch_entity<T: Debug + IntoUrl + Send>(url: T) -> Result<Entity, Error
 
         Entity::Object(object)
     };
+    check_origin(entity.id(), url)?;
 
     Ok(entity)
 }
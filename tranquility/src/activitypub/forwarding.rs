@@ -0,0 +1,151 @@
+use crate::{
+    activitypub::deliverer,
+    database::{Actor as DbActor, Object as DbObject},
+    error::Error,
+    state::ArcState,
+};
+use itertools::Itertools;
+use serde_json::Value;
+use std::sync::Arc;
+use tranquility_types::activitypub::{Activity, Actor};
+use url::Url;
+use uuid::Uuid;
+
+/// Activity that is going to be forwarded to the followers of local actors ([ActivityPub §7.1.2](https://www.w3.org/TR/activitypub/#inbox-forwarding))
+pub struct Forwarding {
+    activity: Activity,
+    body: Vec<u8>,
+    forwarders: Vec<(Actor, DbActor)>,
+}
+
+/// Check whether the URL points to this server
+fn is_local(state: &ArcState, url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| url.host_str() == Some(state.config.instance.domain.as_str()))
+}
+
+/// Get the local actors whose followers collections are addressed by the activity
+async fn addressed_local_actors(
+    state: &ArcState,
+    activity: &Activity,
+) -> Result<Vec<(Actor, DbActor)>, Error> {
+    let local_collections = activity
+        .to
+        .iter()
+        .chain(activity.cc.iter())
+        .unique()
+        .filter(|url| is_local(state, url));
+
+    let mut local_actors = Vec::new();
+    for collection_url in local_collections {
+        let actor_url = match collection_url.strip_suffix("/followers") {
+            Some(actor_url) => actor_url,
+            None => continue,
+        };
+
+        let db_actor = match DbActor::by_url(&state.db_pool, actor_url).await {
+            Ok(db_actor) => db_actor,
+            Err(Error::Sqlx(sqlx::Error::RowNotFound)) => continue,
+            Err(err) => return Err(err),
+        };
+        let actor: Actor = serde_json::from_value(db_actor.actor.clone())?;
+
+        if !db_actor.remote && actor.followers == *collection_url {
+            local_actors.push((actor, db_actor));
+        }
+    }
+
+    Ok(local_actors)
+}
+
+/// Get the URLs of the entities the activity refers to
+///
+/// These are the `object`, `target` and `inReplyTo` values of the activity and of the embedded object
+fn referenced_urls(document: &Value) -> Vec<&str> {
+    let url = |value: &Value| {
+        value
+            .as_str()
+            .or_else(|| value.get("id").and_then(Value::as_str))
+    };
+
+    let mut urls = Vec::new();
+    let object = document.get("object");
+
+    for entity in [Some(document), object].into_iter().flatten() {
+        for name in ["object", "target", "inReplyTo"] {
+            urls.extend(entity.get(name).and_then(url));
+        }
+    }
+
+    urls
+}
+
+/// Get the IDs of the local actors who own the local entities the activity refers to
+async fn referenced_owner_ids(state: &ArcState, document: &Value) -> Result<Vec<Uuid>, Error> {
+    let local_urls = referenced_urls(document)
+        .into_iter()
+        .filter(|url| is_local(state, url))
+        .unique();
+
+    let mut owner_ids = Vec::new();
+    for url in local_urls {
+        match DbObject::by_url(&state.db_pool, url).await {
+            Ok(db_object) => owner_ids.push(db_object.owner_id),
+            Err(Error::Sqlx(sqlx::Error::RowNotFound)) => continue,
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(owner_ids)
+}
+
+/// Check whether the activity has to be forwarded
+///
+/// The activity is forwarded to the followers of a local actor if it addresses their followers collection
+/// and refers to an entity they own (for example, it replies to one of their posts).
+/// Has to be called only once per activity (the inbox makes sure every activity is handled only once)
+pub async fn prepare(
+    state: &ArcState,
+    activity: &Activity,
+    body: &[u8],
+) -> Result<Option<Forwarding>, Error> {
+    let mut forwarders = addressed_local_actors(state, activity).await?;
+    if forwarders.is_empty() {
+        return Ok(None);
+    }
+
+    // Otherwise anyone could make a local actor send anything to their followers by addressing them
+    let document: Value = serde_json::from_slice(body)?;
+    let owner_ids = referenced_owner_ids(state, &document).await?;
+    forwarders.retain(|(_forwarder, forwarder_db)| owner_ids.contains(&forwarder_db.id));
+    if forwarders.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(Forwarding {
+        activity: activity.clone(),
+        body: body.to_vec(),
+        forwarders,
+    }))
+}
+
+impl Forwarding {
+    /// Forward the activity to the followers of the addressed local actors
+    ///
+    /// The body is forwarded unchanged so the recipients can verify its embedded signature
+    pub async fn deliver(self, state: &ArcState) {
+        for (forwarder, forwarder_db) in self.forwarders {
+            let forward_result = deliverer::forward(
+                self.activity.clone(),
+                self.body.clone(),
+                forwarder,
+                forwarder_db,
+                Arc::clone(state),
+            )
+            .await;
+
+            if let Err(err) = forward_result {
+                warn!(error = ?err, "Activity couldn't be forwarded");
+            }
+        }
+    }
+}
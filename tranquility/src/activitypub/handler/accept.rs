@@ -1,10 +1,31 @@
-use crate::{activitypub::FollowActivity, database::Object, error::Error, state::ArcState};
+use crate::{
+    activitypub::{relay, FollowActivity},
+    database::{relay::RelayState, Object},
+    error::Error,
+    state::ArcState,
+};
 use http::StatusCode;
 use ormx::Table;
 use tranquility_types::activitypub::Activity;
 
 pub async fn handle(state: &ArcState, activity: Activity) -> Result<StatusCode, Error> {
-    let follow_activity_url = activity.object.as_url().ok_or(Error::UnknownActivity)?;
+    let follow_activity_url = activity
+        .object
+        .activity_url()
+        .ok_or(Error::UnknownActivity)?;
+
+    // Relays accept the follow of the public collection sent by the instance actor
+    if relay::answer(
+        state,
+        &activity.actor,
+        follow_activity_url,
+        RelayState::Accepted,
+    )
+    .await?
+    {
+        return Ok(StatusCode::OK);
+    }
+
     let mut follow_activity_db = Object::by_url(&state.db_pool, follow_activity_url).await?;
 
     let mut follow_activity: FollowActivity = serde_json::from_value(follow_activity_db.data)?;
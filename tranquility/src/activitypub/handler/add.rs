@@ -0,0 +1,37 @@
+use crate::{
+    activitypub::fetcher::{self, FetchContext},
+    database::{pin::Pin, Actor as DbActor, Object as DbObject},
+    error::Error,
+    state::ArcState,
+};
+use http::StatusCode;
+use tranquility_types::activitypub::Activity;
+
+/// Fetch the actor of an activity that has to target the featured collection of the actor
+///
+/// Other collections aren't supported
+pub async fn featured_actor(state: &ArcState, activity: &Activity) -> Result<DbActor, Error> {
+    let (actor, db_actor) = fetcher::fetch_actor(state, &activity.actor).await?;
+
+    match (activity.target_url(), actor.featured.as_deref()) {
+        (Some(target_url), Some(featured_url)) if target_url == featured_url => Ok(db_actor),
+        _ => Err(Error::UnknownActivity),
+    }
+}
+
+pub async fn handle(state: &ArcState, activity: Activity) -> Result<StatusCode, Error> {
+    let db_actor = featured_actor(state, &activity).await?;
+
+    let object_url = activity.object.object_url().ok_or(Error::UnknownActivity)?;
+    let object = fetcher::fetch_object(state, &FetchContext::default(), object_url).await?;
+
+    // Actors can only pin their own posts
+    let db_object = DbObject::by_url(&state.db_pool, &object.id).await?;
+    if db_object.owner_id != db_actor.id {
+        return Err(Error::Unauthorized);
+    }
+
+    Pin::pin(&state.db_pool, db_actor.id, db_object.id).await?;
+
+    Ok(StatusCode::CREATED)
+}
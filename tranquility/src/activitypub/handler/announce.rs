@@ -1,6 +1,6 @@
 use crate::{
-    activitypub::fetcher,
-    database::{Actor, InsertExt, InsertObject},
+    activitypub::fetcher::{self, FetchContext},
+    database::{relay::Relay, Actor, InsertExt, InsertObject},
     error::Error,
     state::ArcState,
 };
@@ -12,7 +12,12 @@ pub async fn handle(state: &ArcState, activity: Activity) -> Result<StatusCode,
     let object_url = activity.object.as_url().ok_or(Error::UnknownActivity)?;
 
     // Fetch the object (just in case)
-    fetcher::fetch_object(state, object_url).await?;
+    fetcher::fetch_object(state, &FetchContext::default(), object_url).await?;
+
+    // Relays announce public posts of other servers. These announces aren't boosts, only the object is of interest
+    if Relay::is_accepted_actor(&state.db_pool, &activity.actor).await? {
+        return Ok(StatusCode::CREATED);
+    }
     // Fetch the actor (just in case)
     fetcher::fetch_actor(state, &activity.actor).await?;
 
@@ -1,5 +1,9 @@
 use crate::{
-    activitypub::{fetcher, Clean},
+    activitypub::{
+        emoji,
+        fetcher::{self, FetchContext},
+        poll, Clean,
+    },
     database::{InsertExt, InsertObject},
     error::Error,
     state::ArcState,
@@ -24,21 +28,31 @@ async fn insert_object(state: &ArcState, activity: &Activity) -> Result<Object,
     .insert(&state.db_pool)
     .await?;
 
+    emoji::ingest(state, &object.attributed_to, &object.tag);
+
+    // Backfill the posts the object is replying to (or quoting)
+    fetcher::fetch_references(state, &FetchContext::default(), &object).await;
+
     Ok(object)
 }
 
 pub async fn handle(state: &ArcState, mut activity: Activity) -> Result<StatusCode, Error> {
     // Save the object in the database
     match activity.object {
-        ObjectField::Object(_) => {
+        ObjectField::Object(ref object) => {
+            // Votes on local polls are only counted, not saved as posts
+            if poll::receive_vote(state, &activity.actor, object).await? {
+                return Ok(StatusCode::CREATED);
+            }
+
             let object = insert_object(state, &activity).await?;
 
             activity.object = ObjectField::Url(object.id);
         }
         ObjectField::Url(ref url) => {
-            fetcher::fetch_object(state, url).await?;
+            fetcher::fetch_object(state, &FetchContext::default(), url).await?;
         }
-        ObjectField::Actor(_) => return Err(Error::UnknownActivity),
+        ObjectField::Actor(_) | ObjectField::Activity(_) => return Err(Error::UnknownActivity),
     }
 
     Ok(StatusCode::CREATED)
@@ -1,14 +1,20 @@
-use crate::{activitypub::fetcher, database::Object, error::Error, state::ArcState};
+use crate::{
+    activitypub::fetcher::{self, FetchContext},
+    database::Object,
+    error::Error,
+    state::ArcState,
+};
 use http::StatusCode;
 use tranquility_types::activitypub::{activity::ObjectField, Activity};
 
 pub async fn handle(state: &ArcState, mut activity: Activity) -> Result<StatusCode, Error> {
     // Normalize activity
     match activity.object {
-        ObjectField::Actor(..) => return Err(Error::UnknownActivity),
+        ObjectField::Actor(..) | ObjectField::Activity(..) => return Err(Error::UnknownActivity),
         ObjectField::Object(..) => (),
         ObjectField::Url(ref url) => {
-            let object = fetcher::fetch_object(state, url).await?;
+            // The object is about to be deleted. Its references aren't of interest
+            let object = fetcher::fetch_object(state, &FetchContext::new(0), url).await?;
 
             activity.object = ObjectField::Object(object);
         }
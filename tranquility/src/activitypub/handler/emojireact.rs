@@ -0,0 +1,71 @@
+use crate::{
+    activitypub::{
+        emoji,
+        fetcher::{self, FetchContext},
+    },
+    database::{reaction::Reaction, Actor, Object},
+    error::Error,
+    state::ArcState,
+    util::emoji::{is_unicode_emoji, reaction_shortcode},
+};
+use http::StatusCode;
+use tranquility_types::activitypub::{Activity, Tag};
+
+/// Get the name of the reaction and the image of its custom emoji
+///
+/// Custom emojis are named after their shortcode and the instance of the reacting actor (`shortcode@domain`)
+fn reaction_emoji(
+    state: &ArcState,
+    activity: &Activity,
+) -> Result<(String, Option<String>), Error> {
+    let reaction = activity.reaction().ok_or(Error::UnknownActivity)?;
+
+    let Some(shortcode) = reaction_shortcode(reaction) else {
+        if !is_unicode_emoji(reaction) {
+            return Err(Error::InvalidRequest);
+        }
+
+        return Ok((reaction.to_string(), None));
+    };
+
+    let emoji_tag = activity
+        .tag
+        .iter()
+        .filter_map(Tag::emoji)
+        .find(|emoji_tag| emoji_tag.shortcode() == shortcode)
+        .ok_or(Error::InvalidRequest)?;
+
+    let name = match emoji::domain(&state.config, &activity.actor)? {
+        Some(domain) => format!("{}@{}", shortcode, domain),
+        None => shortcode.to_string(),
+    };
+
+    Ok((name, Some(emoji_tag.icon.url.clone())))
+}
+
+pub async fn handle(state: &ArcState, activity: Activity) -> Result<StatusCode, Error> {
+    let object_url = activity.object.object_url().ok_or(Error::UnknownActivity)?;
+    let (name, emoji_url) = reaction_emoji(state, &activity)?;
+
+    // Fetch the object (just in case)
+    fetcher::fetch_object(state, &FetchContext::default(), object_url).await?;
+    // Fetch the actor (just in case)
+    fetcher::fetch_actor(state, &activity.actor).await?;
+
+    let object = Object::by_url(&state.db_pool, object_url).await?;
+    let actor = Actor::by_url(&state.db_pool, &activity.actor).await?;
+
+    emoji::ingest(state, &activity.actor, &activity.tag);
+
+    Reaction::react(
+        &state.db_pool,
+        object.id,
+        actor.id,
+        &name,
+        emoji_url.as_deref(),
+        &activity.id,
+    )
+    .await?;
+
+    Ok(StatusCode::CREATED)
+}
@@ -13,7 +13,7 @@ pub async fn handle(state: &ArcState, mut activity: Activity) -> Result<StatusCo
     let actor_url = match activity.object {
         ObjectField::Actor(ref actor) => actor.id.as_str(),
         ObjectField::Url(ref url) => url.as_str(),
-        ObjectField::Object(_) => return Err(Error::UnknownActivity),
+        ObjectField::Object(_) | ObjectField::Activity(_) => return Err(Error::UnknownActivity),
     };
 
     // Fetch the actor (just in case)
@@ -1,5 +1,5 @@
 use crate::{
-    activitypub::fetcher,
+    activitypub::fetcher::{self, FetchContext},
     database::{Actor, InsertExt, InsertObject},
     error::Error,
     state::ArcState,
@@ -9,10 +9,15 @@ use tranquility_types::activitypub::Activity;
 use uuid::Uuid;
 
 pub async fn handle(state: &ArcState, activity: Activity) -> Result<StatusCode, Error> {
+    // Misskey sends emoji reactions as likes carrying the emoji
+    if activity.reaction().is_some() {
+        return super::emojireact::handle(state, activity).await;
+    }
+
     let object_url = activity.object.as_url().ok_or(Error::UnknownActivity)?;
 
     // Fetch the object (just in case)
-    fetcher::fetch_object(state, object_url).await?;
+    fetcher::fetch_object(state, &FetchContext::default(), object_url).await?;
     // Fetch the actor (just in case)
     fetcher::fetch_actor(state, &activity.actor).await?;
     let actor = Actor::by_url(&state.db_pool, &activity.actor).await?;
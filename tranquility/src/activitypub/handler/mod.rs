@@ -1,9 +1,12 @@
 pub mod accept;
+pub mod add;
 pub mod announce;
 pub mod create;
 pub mod delete;
+pub mod emojireact;
 pub mod follow;
 pub mod like;
 pub mod reject;
+pub mod remove;
 pub mod undo;
 pub mod update;
@@ -0,0 +1,19 @@
+use super::add::featured_actor;
+use crate::{
+    database::{pin::Pin, Object as DbObject},
+    error::Error,
+    state::ArcState,
+};
+use http::StatusCode;
+use tranquility_types::activitypub::Activity;
+
+pub async fn handle(state: &ArcState, activity: Activity) -> Result<StatusCode, Error> {
+    let db_actor = featured_actor(state, &activity).await?;
+
+    let object_url = activity.object.object_url().ok_or(Error::UnknownActivity)?;
+    let db_object = DbObject::by_url(&state.db_pool, object_url).await?;
+
+    Pin::unpin(&state.db_pool, db_actor.id, db_object.id).await?;
+
+    Ok(StatusCode::OK)
+}
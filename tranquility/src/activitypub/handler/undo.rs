@@ -1,14 +1,37 @@
-use crate::{database::Object, error::Error, state::ArcState};
+use crate::{
+    database::{reaction::Reaction, Actor, Object},
+    error::Error,
+    state::ArcState,
+};
 use http::StatusCode;
+use ormx::Table;
 use tranquility_types::activitypub::Activity;
 
 pub async fn handle(state: &ArcState, delete_activity: Activity) -> Result<StatusCode, Error> {
     let activity_url = delete_activity
         .object
-        .as_url()
+        .activity_url()
         .ok_or(Error::UnknownActivity)?;
 
-    Object::delete_by_url(&state.db_pool, activity_url).await?;
+    // Emoji reactions of remote actors aren't saved as objects
+    if Reaction::delete_by_activity_url(&state.db_pool, activity_url, &delete_activity.actor)
+        .await?
+    {
+        return Ok(StatusCode::CREATED);
+    }
+
+    // Activities we don't know about are of no interest
+    let Ok(db_object) = Object::by_url(&state.db_pool, activity_url).await else {
+        return Ok(StatusCode::ACCEPTED);
+    };
+
+    // Only the owner of the saved activity can undo it
+    let owner = Actor::by_url(&state.db_pool, &delete_activity.actor).await?;
+    if db_object.owner_id != owner.id {
+        return Err(Error::Unauthorized);
+    }
+
+    db_object.delete(&state.db_pool).await?;
 
     Ok(StatusCode::CREATED)
 }
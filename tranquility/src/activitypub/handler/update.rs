@@ -1,20 +1,47 @@
 use crate::{
-    activitypub::{fetcher, Clean},
-    database::Actor,
+    activitypub::{emoji, fetcher, Clean},
+    database::{Actor, Object as DbObject},
     error::Error,
     state::ArcState,
 };
 use http::StatusCode;
 use ormx::Table;
-use tranquility_types::activitypub::Activity;
+use tranquility_types::activitypub::{activity::ObjectField, Activity, Object};
+
+/// Replace the saved copy of the object (for example, polls with new vote counts)
+async fn update_object(
+    state: &ArcState,
+    actor_url: &str,
+    mut object: Object,
+) -> Result<StatusCode, Error> {
+    // Objects we don't know about are of no interest
+    let Ok(mut db_object) = DbObject::by_url(&state.db_pool, object.id.as_str()).await else {
+        return Ok(StatusCode::ACCEPTED);
+    };
+
+    // Only the owner of the saved object can update it
+    let owner = Actor::by_url(&state.db_pool, actor_url).await?;
+    if db_object.owner_id != owner.id {
+        return Err(Error::Unauthorized);
+    }
+
+    object.clean();
+    db_object.data = serde_json::to_value(&object)?;
+    db_object.update(&state.db_pool).await?;
+
+    emoji::ingest(state, &object.attributed_to, &object.tag);
+
+    Ok(StatusCode::CREATED)
+}
 
 pub async fn handle(state: &ArcState, mut activity: Activity) -> Result<StatusCode, Error> {
     // Update activities are usually only used to update the actor
     // (For example, when the user changes their bio or display name)
-    let ap_actor = activity
-        .object
-        .as_mut_actor()
-        .ok_or(Error::UnknownActivity)?;
+    let ap_actor = match activity.object {
+        ObjectField::Actor(ref mut ap_actor) => ap_actor,
+        ObjectField::Object(object) => return update_object(state, &activity.actor, object).await,
+        ObjectField::Activity(_) | ObjectField::Url(_) => return Err(Error::UnknownActivity),
+    };
     ap_actor.clean();
 
     // Fetch the actor (just in case)
@@ -22,6 +49,8 @@ pub async fn handle(state: &ArcState, mut activity: Activity) -> Result<StatusCo
 
     let mut actor = Actor::by_url(&state.db_pool, ap_actor.id.as_str()).await?;
 
+    emoji::ingest(state, &ap_actor.id, &ap_actor.tag);
+
     // Update the actor value
     let ap_actor = serde_json::to_value(ap_actor)?;
     actor.actor = ap_actor;
@@ -0,0 +1,50 @@
+use crate::{config::ConfigurationFederation, error::Error};
+use std::{future::Future, sync::Arc};
+use tokio::sync::Semaphore;
+
+/// Queue of incoming activities that are processed in the background
+///
+/// At most `inbox-concurrency` activities are processed at the same time, the remaining ones wait in the queue.
+/// New activities are rejected once `inbox-queue-size` activities are waiting or being processed
+///
+/// The queue only lives in memory. Activities that are still queued when the server shuts down are lost
+/// (their deliveries were already acknowledged, so remote servers won't retry them)
+pub struct InboxQueue {
+    slots: Arc<Semaphore>,
+    workers: Arc<Semaphore>,
+}
+
+impl InboxQueue {
+    /// Create a new queue with the limits from the configuration
+    pub fn new(config: &ConfigurationFederation) -> Self {
+        Self {
+            slots: Arc::new(Semaphore::new(config.inbox_queue_size)),
+            workers: Arc::new(Semaphore::new(config.inbox_concurrency)),
+        }
+    }
+
+    /// Add the task to the queue
+    ///
+    /// Returns an error if the queue is full
+    pub fn push<F>(&self, task: F) -> Result<(), Error>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let slot = Arc::clone(&self.slots)
+            .try_acquire_owned()
+            .map_err(|_| Error::QueueFull)?;
+        let workers = Arc::clone(&self.workers);
+
+        tokio::spawn(async move {
+            // The semaphore is never closed
+            let Ok(_worker) = workers.acquire_owned().await else {
+                return;
+            };
+
+            task.await;
+            drop(slot);
+        });
+
+        Ok(())
+    }
+}
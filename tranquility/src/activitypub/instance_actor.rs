@@ -0,0 +1,43 @@
+use crate::{config::Configuration, database::Actor as DbActor, error::Error, state::ArcState};
+use tranquility_types::activitypub::Actor;
+
+/// Get the URL of the instance actor
+pub fn url(config: &Configuration) -> String {
+    format!("https://{}/actor", config.instance.domain)
+}
+
+/// Get the instance actor
+///
+/// If the instance actor doesn't exist yet, a new one is created and saved in the database.
+/// Concurrent calls all end up with the same instance actor
+#[instrument(skip(state))]
+pub async fn get(state: &ArcState) -> Result<(Actor, DbActor), Error> {
+    let url = url(&state.config);
+
+    match DbActor::by_url(&state.db_pool, &url).await {
+        Ok(db_actor) => return Ok((serde_json::from_value(db_actor.actor.clone())?, db_actor)),
+        Err(Error::Sqlx(sqlx::Error::RowNotFound)) => {
+            debug!("Instance actor not found in database. Creating a new one...");
+        }
+        Err(err) => return Err(err),
+    }
+
+    let rsa_private_key = crate::crypto::rsa::generate().await?;
+    let (public_key_pem, private_key_pem) = crate::crypto::rsa::to_pem(&rsa_private_key)?;
+
+    let actor = crate::activitypub::instantiate::instance_actor(&state.config, public_key_pem);
+    let actor_value = serde_json::to_value(&actor)?;
+
+    // Another task might have created the instance actor in the meantime.
+    // In that case its key pair is kept and the one generated here is discarded
+    DbActor::insert_instance_actor(
+        &state.db_pool,
+        &state.config.instance.domain,
+        &private_key_pem,
+        actor_value,
+    )
+    .await?;
+
+    let db_actor = DbActor::by_url(&state.db_pool, &url).await?;
+    Ok((serde_json::from_value(db_actor.actor.clone())?, db_actor))
+}
@@ -50,6 +50,7 @@ pub fn actor(
 
     let followers = format!("{}/followers", id);
     let following = format!("{}/following", id);
+    let featured = format!("{}/featured", id);
 
     let key_id = format!("{}#main-key", id);
 
@@ -68,6 +69,47 @@ pub fn actor(
         inbox,
         outbox,
 
+        followers,
+        following,
+        featured: Some(featured),
+
+        public_key,
+
+        ..Actor::default()
+    }
+}
+
+/// Instantiate the ActivityPub actor representing the instance itself
+///
+/// This actor is used to sign requests that aren't made on behalf of any specific user (for example, fetches of remote entities)
+pub fn instance_actor(config: &Configuration, public_key_pem: String) -> Actor {
+    let id = crate::activitypub::instance_actor::url(config);
+
+    let inbox = format!("{}/inbox", id);
+    let outbox = format!("{}/outbox", id);
+
+    let followers = format!("{}/followers", id);
+    let following = format!("{}/following", id);
+
+    let key_id = format!("{}#main-key", id);
+
+    let public_key = PublicKey {
+        id: key_id,
+        owner: id.clone(),
+        public_key_pem,
+    };
+
+    Actor {
+        id,
+        r#type: "Service".into(),
+
+        username: config.instance.domain.clone(),
+
+        manually_approves_followers: true,
+
+        inbox,
+        outbox,
+
         followers,
         following,
 
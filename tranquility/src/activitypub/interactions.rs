@@ -1,10 +1,19 @@
 use crate::{
-    database::{Actor as DbActor, InsertExt, InsertObject, Object as DbObject},
+    activitypub::poll,
+    consts::activitypub::MAX_PINNED_POSTS,
+    database::{
+        custom_emoji::CustomEmoji, pin::Pin, poll_vote::PollVote, reaction::Reaction,
+        Actor as DbActor, InsertExt, InsertObject, Object as DbObject,
+    },
     error::Error,
     state::ArcState,
+    util::emoji,
 };
+use itertools::Itertools;
+use ormx::Table;
+use serde_json::Value;
 use std::sync::Arc;
-use tranquility_types::activitypub::{Activity, Actor};
+use tranquility_types::activitypub::{Activity, Actor, IsPrivate, Object, PUBLIC_IDENTIFIER};
 
 /// Create an Follow activity for a follow, save it and send it out
 pub async fn follow(state: &ArcState, db_actor: DbActor, followed: &Actor) -> Result<(), Error> {
@@ -46,6 +55,282 @@ pub async fn follow(state: &ArcState, db_actor: DbActor, followed: &Actor) -> Re
     Ok(())
 }
 
+/// Create an Update activity for the actor, save it and send it out to the followers
+pub async fn update_actor(state: &ArcState, db_actor: DbActor) -> Result<(), Error> {
+    let actor: Actor = serde_json::from_value(db_actor.actor)?;
+
+    let (update_activity_id, update_activity) = crate::activitypub::instantiate::activity(
+        &state.config,
+        "Update",
+        actor.id.as_str(),
+        actor.clone(),
+        vec![PUBLIC_IDENTIFIER.into()],
+        vec![actor.followers],
+    );
+    let update_activity_value = serde_json::to_value(&update_activity)?;
+
+    InsertObject {
+        id: update_activity_id,
+        owner_id: db_actor.id,
+        data: update_activity_value,
+    }
+    .insert(&state.db_pool)
+    .await?;
+
+    crate::activitypub::deliverer::deliver(update_activity, Arc::clone(state)).await?;
+
+    Ok(())
+}
+
+/// Create an Add or Remove activity targeting the featured collection of the actor, save it and send it out to the followers
+async fn update_featured(
+    state: &ArcState,
+    db_actor: DbActor,
+    r#type: &str,
+    object_url: String,
+) -> Result<(), Error> {
+    let actor: Actor = serde_json::from_value(db_actor.actor)?;
+    let featured_url = actor.featured.ok_or(Error::InvalidRequest)?;
+
+    let (activity_id, mut activity) = crate::activitypub::instantiate::activity(
+        &state.config,
+        r#type,
+        actor.id.as_str(),
+        object_url,
+        vec![PUBLIC_IDENTIFIER.into()],
+        vec![actor.followers],
+    );
+    activity.target = Some(Value::String(featured_url));
+    let activity_value = serde_json::to_value(&activity)?;
+
+    InsertObject {
+        id: activity_id,
+        owner_id: db_actor.id,
+        data: activity_value,
+    }
+    .insert(&state.db_pool)
+    .await?;
+
+    crate::activitypub::deliverer::deliver(activity, Arc::clone(state)).await?;
+
+    Ok(())
+}
+
+/// Pin the post to the profile of the actor and send an Add activity out
+pub async fn pin(state: &ArcState, db_actor: DbActor, db_object: DbObject) -> Result<(), Error> {
+    // Tried to pin someone else's post
+    if db_object.owner_id != db_actor.id {
+        return Err(Error::Unauthorized);
+    }
+
+    // Only public and unlisted posts are listed in the featured collection
+    let object: Object = serde_json::from_value(db_object.data)?;
+    if object.is_private() {
+        return Err(Error::InvalidRequest);
+    }
+
+    if Pin::count(&state.db_pool, db_actor.id).await? >= MAX_PINNED_POSTS {
+        return Err(Error::InvalidRequest);
+    }
+
+    // The post is already pinned
+    if !Pin::pin(&state.db_pool, db_actor.id, db_object.id).await? {
+        return Ok(());
+    }
+
+    update_featured(state, db_actor, "Add", object.id).await
+}
+
+/// Unpin the post from the profile of the actor and send a Remove activity out
+pub async fn unpin(state: &ArcState, db_actor: DbActor, db_object: DbObject) -> Result<(), Error> {
+    let object: Object = serde_json::from_value(db_object.data)?;
+
+    // The post wasn't pinned
+    if !Pin::unpin(&state.db_pool, db_actor.id, db_object.id).await? {
+        return Ok(());
+    }
+
+    update_featured(state, db_actor, "Remove", object.id).await
+}
+
+/// Vote on the poll
+///
+/// Votes on local polls are counted right away.
+/// Votes on remote polls are sent to the author of the poll as `Note` objects carrying the name of the chosen option
+pub async fn vote(
+    state: &ArcState,
+    db_actor: DbActor,
+    db_object: DbObject,
+    choices: &[usize],
+) -> Result<(), Error> {
+    // Tried to vote on their own poll
+    if db_object.owner_id == db_actor.id {
+        return Err(Error::InvalidRequest);
+    }
+
+    // Changing the votes isn't supported
+    if !PollVote::choices(&state.db_pool, db_object.id, db_actor.id)
+        .await?
+        .is_empty()
+    {
+        return Err(Error::InvalidRequest);
+    }
+
+    let poll: Object = serde_json::from_value(db_object.data.clone())?;
+    let options = poll.poll_options().ok_or(Error::InvalidRequest)?;
+    let choices = choices.iter().copied().unique().collect_vec();
+    if choices.iter().any(|choice| *choice >= options.len()) {
+        return Err(Error::InvalidRequest);
+    }
+
+    let author_db = DbActor::by_id(&state.db_pool, db_object.owner_id).await?;
+    if !author_db.remote {
+        let mut transaction = state.db_pool.begin().await?;
+        poll::record_votes(&mut transaction, &db_object, &poll, db_actor.id, &choices).await?;
+        transaction.commit().await?;
+
+        poll::update_tally(state, db_object).await?;
+        return Ok(());
+    }
+
+    let actor: Actor = serde_json::from_value(db_actor.actor)?;
+    let create_activities = choices
+        .iter()
+        .map(|choice| {
+            let (_vote_id, mut vote) = crate::activitypub::instantiate::object(
+                &state.config,
+                "Note",
+                actor.id.as_str(),
+                "",
+                "",
+                false,
+                vec![poll.attributed_to.clone()],
+                vec![],
+            );
+            vote.name = Some(options[*choice].name.clone());
+            vote.in_reply_to = Some(poll.id.clone());
+
+            crate::activitypub::instantiate::activity(
+                &state.config,
+                "Create",
+                actor.id.as_str(),
+                vote,
+                vec![poll.attributed_to.clone()],
+                vec![],
+            )
+        })
+        .collect_vec();
+
+    // Save the votes and their Create activities together, so neither is kept without the other
+    let mut transaction = state.db_pool.begin().await?;
+    poll::record_votes(&mut transaction, &db_object, &poll, db_actor.id, &choices).await?;
+    for (create_activity_id, create_activity) in &create_activities {
+        let insert_object = InsertObject {
+            id: *create_activity_id,
+            owner_id: db_actor.id,
+            data: serde_json::to_value(create_activity)?,
+        };
+        ormx::Insert::insert(insert_object, &mut transaction).await?;
+    }
+    transaction.commit().await?;
+
+    for (_create_activity_id, create_activity) in create_activities {
+        crate::activitypub::deliverer::deliver(create_activity, Arc::clone(state)).await?;
+    }
+
+    Ok(())
+}
+
+/// React to the post with the emoji and send an EmojiReact activity to the author of the post
+///
+/// The reaction is either a Unicode emoji or the shortcode of a local custom emoji
+pub async fn react(
+    state: &ArcState,
+    db_actor: DbActor,
+    db_object: DbObject,
+    reaction: &str,
+) -> Result<(), Error> {
+    let object: Object = serde_json::from_value(db_object.data)?;
+    let actor: Actor = serde_json::from_value(db_actor.actor)?;
+
+    let (name, emoji_url, content, tag) = if emoji::is_unicode_emoji(reaction) {
+        (reaction.to_string(), None, reaction.to_string(), Vec::new())
+    } else {
+        let shortcode = reaction.trim_matches(':');
+        let custom_emoji = CustomEmoji::by_shortcode(&state.db_pool, shortcode, None)
+            .await?
+            .ok_or(Error::InvalidRequest)?;
+
+        (
+            custom_emoji.shortcode.clone(),
+            Some(emoji::image_url(&state.config, custom_emoji.id)),
+            format!(":{}:", custom_emoji.shortcode),
+            vec![emoji::tag(&state.config, &custom_emoji)],
+        )
+    };
+
+    let (react_activity_id, mut react_activity) = crate::activitypub::instantiate::activity(
+        &state.config,
+        "EmojiReact",
+        actor.id.as_str(),
+        object.id,
+        vec![object.attributed_to],
+        vec![],
+    );
+    // Misskey reads the emoji from its own field
+    react_activity.misskey_reaction = Some(content.clone());
+    react_activity.content = Some(content);
+    react_activity.tag = tag;
+
+    // The actor already reacted with the emoji
+    if !Reaction::react(
+        &state.db_pool,
+        db_object.id,
+        db_actor.id,
+        &name,
+        emoji_url.as_deref(),
+        &react_activity.id,
+    )
+    .await?
+    {
+        return Ok(());
+    }
+
+    let react_activity_value = serde_json::to_value(&react_activity)?;
+
+    InsertObject {
+        id: react_activity_id,
+        owner_id: db_actor.id,
+        data: react_activity_value,
+    }
+    .insert(&state.db_pool)
+    .await?;
+
+    crate::activitypub::deliverer::deliver(react_activity, Arc::clone(state)).await?;
+
+    Ok(())
+}
+
+/// Remove the reaction from the post and undo its EmojiReact activity
+pub async fn unreact(
+    state: &ArcState,
+    db_actor: DbActor,
+    db_object: DbObject,
+    reaction: &str,
+) -> Result<(), Error> {
+    let name = reaction.trim_matches(':');
+
+    // The actor didn't react with the emoji
+    let Some(removed_reaction) =
+        Reaction::unreact(&state.db_pool, db_object.id, db_actor.id, name).await?
+    else {
+        return Ok(());
+    };
+
+    let react_activity = DbObject::by_url(&state.db_pool, &removed_reaction.activity_url).await?;
+    undo(state, db_actor, react_activity).await
+}
+
 /// Create an Undo activity for the given activity, save it and send it out
 pub async fn undo(state: &ArcState, db_actor: DbActor, db_activity: DbObject) -> Result<(), Error> {
     // Tried to delete someone else's activity
@@ -0,0 +1,180 @@
+use crate::{
+    activitypub::fetcher, consts::activitypub::KEY_REFETCH_COOLDOWN, database::Actor as DbActor,
+    error::Error, state::ArcState,
+};
+use async_trait::async_trait;
+use time::OffsetDateTime;
+use tranquility_http_signatures::{KeyResolver, ResolvedKey};
+use tranquility_ld_signatures::multibase;
+use tranquility_types::activitypub::Actor;
+use url::Url;
+
+/// Resolves the key IDs of HTTP signatures (and embedded signatures) to the public keys of actors
+///
+/// Key IDs usually are the actor URL with a fragment attached (for example, `#main-key`)
+/// or the URL of a separate key document
+pub struct ActorKeyResolver {
+    state: ArcState,
+}
+
+impl ActorKeyResolver {
+    pub fn new(state: ArcState) -> Self {
+        Self { state }
+    }
+
+    /// Parse the key ID and check whether it points to a blocked domain
+    fn key_url(&self, key_id: &str) -> Result<Url, Error> {
+        let key_url = Url::parse(key_id)?;
+        if self.state.config.federation.is_blocked(&key_url) {
+            return Err(Error::BlockedDomain);
+        }
+
+        Ok(key_url)
+    }
+
+    /// Get the URL of the actor the key ID belongs to
+    ///
+    /// Key IDs with a fragment point into the actor document.
+    /// Other key IDs (for example, `/users/name/main-key`) point to a key document whose owner is the actor
+    async fn actor_url(&self, key_url: &Url) -> Result<String, Error> {
+        if key_url.fragment().is_some() {
+            let mut actor_url = key_url.clone();
+            actor_url.set_fragment(None);
+
+            return Ok(actor_url.into());
+        }
+
+        if let Some(db_actor) = DbActor::by_key_id(&self.state.db_pool, key_url.as_str()).await? {
+            let actor: Actor = serde_json::from_value(db_actor.actor)?;
+
+            return Ok(actor.id);
+        }
+
+        fetcher::fetch_key_owner(&self.state, key_url.as_str()).await
+    }
+
+    /// Fetch the actor the key ID points to
+    async fn fetch_actor(&self, key_url: &Url) -> Result<(Actor, DbActor), Error> {
+        let actor_url = self.actor_url(key_url).await?;
+
+        // Servers must not be able to claim keys for actors on other servers.
+        // This is checked before the fetch, so no actor gets saved for such a key
+        if Url::parse(&actor_url)?.origin() != key_url.origin() {
+            return Err(Error::Unauthorized);
+        }
+
+        fetcher::fetch_actor(&self.state, &actor_url).await
+    }
+
+    /// Resolve the verification method of an object integrity proof to the multikey of the actor
+    ///
+    /// Returns the owner of the key and the key in the `publicKeyMultibase` format
+    pub async fn resolve_multikey(
+        &self,
+        verification_method: &str,
+    ) -> Result<(String, String), Error> {
+        let key_url = self.key_url(verification_method)?;
+        let (actor, _db_actor) = self.fetch_actor(&key_url).await?;
+
+        actor
+            .assertion_method
+            .into_iter()
+            .find(|key| key.id == verification_method && key.controller == actor.id)
+            .map(|key| (actor.id, key.public_key_multibase))
+            .ok_or(Error::Unauthorized)
+    }
+}
+
+/// Current key of the actor (if the key ID matches)
+///
+/// Previous keys that are still valid after a key rotation are published as RSA multikeys in the `assertionMethod`
+/// of the actor, so these are considered as well
+fn current_key(actor: Actor, key_id: &str) -> Option<ResolvedKey> {
+    if actor.public_key.id == key_id {
+        return Some(ResolvedKey {
+            owner: actor.id,
+            public_key_pem: actor.public_key.public_key_pem,
+        });
+    }
+
+    let public_key_pem = actor
+        .assertion_method
+        .iter()
+        .filter(|key| key.id == key_id && key.controller == actor.id)
+        .find_map(|key| multibase::decode_rsa_multikey(&key.public_key_multibase).ok())?;
+
+    Some(ResolvedKey {
+        owner: actor.id,
+        public_key_pem,
+    })
+}
+
+/// Previous key of a local actor whose key was rotated recently (if the key ID matches)
+fn previous_key(actor: &Actor, db_actor: &DbActor, key_id: &str) -> Option<ResolvedKey> {
+    match db_actor.previous_key() {
+        Some((previous_key_id, public_key_pem)) if previous_key_id == key_id => Some(ResolvedKey {
+            owner: actor.id.clone(),
+            public_key_pem: public_key_pem.into(),
+        }),
+        _ => None,
+    }
+}
+
+/// Check whether the actor is remote and wasn't fetched just now
+fn can_refetch(db_actor: &DbActor) -> bool {
+    db_actor.remote && OffsetDateTime::now_utc() - db_actor.last_fetched_at >= KEY_REFETCH_COOLDOWN
+}
+
+#[async_trait]
+impl KeyResolver for ActorKeyResolver {
+    type Error = Error;
+
+    /// Resolve the key ID to the current key of the actor
+    ///
+    /// Remote actors whose cached key doesn't match the key ID are refetched once in case they rotated their key.
+    /// Local actors whose key was rotated recently also resolve to their previous key
+    async fn resolve(&self, key_id: &str) -> Result<ResolvedKey, Self::Error> {
+        let key_url = self.key_url(key_id)?;
+        let (actor, db_actor) = self.fetch_actor(&key_url).await?;
+
+        if let Some(key) = current_key(actor.clone(), key_id) {
+            return Ok(key);
+        }
+
+        if !db_actor.remote {
+            return previous_key(&actor, &db_actor, key_id).ok_or(Error::Unauthorized);
+        }
+
+        if !can_refetch(&db_actor) {
+            return Err(Error::Unauthorized);
+        }
+
+        debug!("Unknown key ID. Refetching the actor in case the key was rotated...");
+        let (actor, _db_actor) = fetcher::refetch_actor(&self.state, db_actor).await?;
+
+        current_key(actor, key_id).ok_or(Error::Unauthorized)
+    }
+
+    /// Refetch the actor after the verification failed (in case they rotated their key)
+    ///
+    /// Actors that were fetched just now aren't refetched. Local actors are never refetched
+    async fn refresh(
+        &self,
+        key_id: &str,
+        _key: &ResolvedKey,
+    ) -> Result<Option<ResolvedKey>, Self::Error> {
+        let key_url = self.key_url(key_id)?;
+        let (_actor, db_actor) = self.fetch_actor(&key_url).await?;
+
+        if !can_refetch(&db_actor) {
+            return Ok(None);
+        }
+
+        debug!(
+            "Signature verification failed. Refetching the actor in case the key was rotated..."
+        );
+        let (actor, _db_actor) = fetcher::refetch_actor(&self.state, db_actor).await?;
+
+        Ok(current_key(actor, key_id))
+    }
+}
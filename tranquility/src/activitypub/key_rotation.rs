@@ -0,0 +1,141 @@
+use crate::{
+    consts::crypto::KEY_ROTATION_GRACE_PERIOD,
+    database::{Actor as DbActor, InsertExt, InsertObject},
+    error::Error,
+    state::ArcState,
+};
+use ormx::Table;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tranquility_ld_signatures::multibase;
+use tranquility_types::activitypub::{Activity, Actor, Multikey, PublicKey, PUBLIC_IDENTIFIER};
+
+/// Replace the key pair of a local actor
+///
+/// The previous public key stays valid for verification until the grace period is over.
+/// The new public key gets a new key ID so remote servers notice the change.
+/// Returns the `Update` activity that announces the new public key.
+/// The instance actor isn't announced, remote servers refetch it once they see the new key ID
+pub async fn replace_key(
+    state: &ArcState,
+    mut db_actor: DbActor,
+) -> Result<Option<Activity>, Error> {
+    if db_actor.remote {
+        return Err(Error::InvalidRequest);
+    }
+
+    let rsa_private_key = crate::crypto::rsa::generate().await?;
+    let (public_key_pem, private_key_pem) = crate::crypto::rsa::to_pem(&rsa_private_key)?;
+
+    let mut actor: Actor = serde_json::from_value(db_actor.actor.clone())?;
+    let now = OffsetDateTime::now_utc();
+
+    db_actor.previous_public_key = Some(actor.public_key.public_key_pem.clone());
+    db_actor.previous_key_id = Some(actor.public_key.id.clone());
+    db_actor.previous_key_expires_at = Some(now + KEY_ROTATION_GRACE_PERIOD);
+
+    actor.public_key = PublicKey {
+        id: format!("{}#key-{}", actor.id, now.unix_timestamp()),
+        owner: actor.id.clone(),
+        public_key_pem,
+    };
+
+    db_actor.actor = serde_json::to_value(&actor)?;
+    db_actor.private_key = Some(private_key_pem);
+    db_actor.update(&state.db_pool).await?;
+
+    if actor.id == crate::activitypub::instance_actor::url(&state.config) {
+        info!("Rotated key of the instance actor");
+        return Ok(None);
+    }
+
+    info!("Rotated key. Sending out update activity...");
+
+    let (update_activity_id, update_activity) = crate::activitypub::instantiate::activity(
+        &state.config,
+        "Update",
+        actor.id.as_str(),
+        actor.clone(),
+        vec![PUBLIC_IDENTIFIER.into()],
+        vec![actor.followers],
+    );
+    let update_activity_value = serde_json::to_value(&update_activity)?;
+
+    InsertObject {
+        id: update_activity_id,
+        owner_id: db_actor.id,
+        data: update_activity_value,
+    }
+    .insert(&state.db_pool)
+    .await?;
+
+    Ok(Some(update_activity))
+}
+
+/// Publish the previous public key of a local actor until the grace period is over
+///
+/// The key is added to the `assertionMethod` of the actor as an RSA `Multikey` (FEP-521a).
+/// This way remote servers can still resolve its key ID and verify signatures that were made before the rotation
+pub fn publish_previous_key(actor: &mut Actor, db_actor: &DbActor) -> Result<(), Error> {
+    let Some((key_id, public_key_pem)) = db_actor.previous_key() else {
+        return Ok(());
+    };
+
+    actor.assertion_method.push(Multikey {
+        id: key_id.into(),
+        r#type: "Multikey".into(),
+        controller: actor.id.clone(),
+        public_key_multibase: multibase::encode_rsa_multikey(public_key_pem.as_bytes())?,
+    });
+
+    Ok(())
+}
+
+/// Replace the key pair of a local actor and announce the new public key
+///
+/// The `Update` activity is sent to the (shared) inbox of every known remote server.
+/// Signatures made with the previous key stay valid until the grace period is over.
+///
+/// Returns once every delivery attempt has finished
+#[instrument(skip_all, fields(actor_id = %db_actor.id))]
+pub async fn rotate(state: &ArcState, db_actor: DbActor) -> Result<(), Error> {
+    let Some(update_activity) = replace_key(state, db_actor).await? else {
+        return Ok(());
+    };
+
+    let inbox_urls = crate::database::inbox_urls::resolve_all_remote(&state.db_pool).await?;
+    crate::activitypub::deliverer::deliver_to(update_activity, inbox_urls, Arc::clone(state)).await
+}
+
+/// Rotate the keys of all local actors (one after another)
+///
+/// The inbox URLs are only resolved once for all actors. Failed rotations are logged and skipped,
+/// the last error is returned once every actor was handled
+pub async fn rotate_all(state: &ArcState) -> Result<(), Error> {
+    let db_actors = DbActor::local_with_keys(&state.db_pool).await?;
+    let inbox_urls = crate::database::inbox_urls::resolve_all_remote(&state.db_pool).await?;
+
+    let mut last_result = Ok(());
+    for db_actor in db_actors {
+        let actor_id = db_actor.id;
+        let result = match replace_key(state, db_actor).await {
+            Ok(Some(update_activity)) => {
+                crate::activitypub::deliverer::deliver_to(
+                    update_activity,
+                    inbox_urls.clone(),
+                    Arc::clone(state),
+                )
+                .await
+            }
+            Ok(None) => Ok(()),
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = result {
+            warn!(error = ?err, %actor_id, "Key rotation failed");
+            last_result = Err(err);
+        }
+    }
+
+    last_result
+}
@@ -1,5 +1,7 @@
+use crate::{config::SignatureFormat, error::Error, state::ArcState};
 use serde::{Deserialize, Serialize};
 use tranquility_types::activitypub::{Activity, Actor, IsPrivate, IsUnlisted, Object};
+use url::Url;
 
 #[derive(Clone, Deserialize)]
 #[serde(untagged)]
@@ -55,11 +57,33 @@ impl Clean for Object {
     }
 }
 
+/// Get the format of the HTTP signatures for requests to the URL
+///
+/// Uses the format recorded for the server and falls back to the configured default format
+pub async fn signature_format(state: &ArcState, url: &Url) -> Result<SignatureFormat, Error> {
+    let recorded_format = match url.host_str() {
+        Some(domain) => crate::database::server::signature_format(&state.db_pool, domain).await?,
+        None => None,
+    };
+
+    Ok(recorded_format.unwrap_or(state.config.federation.signature_format))
+}
+
+pub mod backfill;
+pub mod context_crawl;
 pub mod deliverer;
+pub mod emoji;
 pub mod fetcher;
+pub mod forwarding;
 pub mod handler;
+pub mod inbox_queue;
+pub mod instance_actor;
 pub mod instantiate;
 pub mod interactions;
+pub mod key_resolver;
+pub mod key_rotation;
+pub mod poll;
+pub mod relay;
 pub mod routes;
 
 pub use routes::routes;
@@ -0,0 +1,176 @@
+use crate::{
+    database::{
+        inbox_urls, poll_vote::PollVote, statuses, Actor as DbActor, InsertExt, InsertObject,
+        Object as DbObject,
+    },
+    error::Error,
+    state::ArcState,
+};
+use ormx::Table;
+use sqlx::PgConnection;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tranquility_types::activitypub::{Actor, Object};
+use uuid::Uuid;
+
+/// Check whether the poll still accepts votes
+pub fn is_open(object: &Object) -> bool {
+    object.closed.is_none()
+        && !object
+            .end_time
+            .is_some_and(|end_time| end_time <= OffsetDateTime::now_utc())
+}
+
+/// Record the votes of the actor on the poll
+///
+/// Polls that allow only one choice reject further votes of actors who already voted.
+/// Takes a connection so the votes can be saved in the same transaction as related rows.
+/// The connection has to be inside of a transaction, the poll stays locked until it ends
+pub async fn record_votes(
+    conn: &mut PgConnection,
+    db_object: &DbObject,
+    object: &Object,
+    voter_id: Uuid,
+    choices: &[usize],
+) -> Result<(), Error> {
+    let options = object.poll_options().ok_or(Error::InvalidRequest)?;
+    if !is_open(object) || choices.is_empty() {
+        return Err(Error::InvalidRequest);
+    }
+    if choices.iter().any(|choice| *choice >= options.len()) {
+        return Err(Error::InvalidRequest);
+    }
+
+    PollVote::lock_poll(&mut *conn, db_object.id).await?;
+
+    if object.one_of.is_some() {
+        let previous_choices = PollVote::choices(&mut *conn, db_object.id, voter_id).await?;
+        if choices.len() > 1 || !previous_choices.is_empty() {
+            return Err(Error::InvalidRequest);
+        }
+    }
+
+    for choice in choices {
+        let choice = i32::try_from(*choice).map_err(|_| Error::InvalidRequest)?;
+        PollVote::vote(&mut *conn, db_object.id, voter_id, choice).await?;
+    }
+
+    Ok(())
+}
+
+/// Write the current vote counts into the poll and save it
+pub async fn update_tally(state: &ArcState, mut db_object: DbObject) -> Result<Object, Error> {
+    let mut object: Object = serde_json::from_value(db_object.data)?;
+    let options = object.poll_options_mut().ok_or(Error::InvalidRequest)?;
+
+    let (vote_counts, voters_count) =
+        PollVote::tally(&state.db_pool, db_object.id, options.len()).await?;
+    for (option, vote_count) in options.iter_mut().zip(vote_counts) {
+        option.replies.total_items = vote_count;
+    }
+    object.voters_count = Some(voters_count);
+
+    db_object.data = serde_json::to_value(&object)?;
+    db_object.update(&state.db_pool).await?;
+
+    Ok(object)
+}
+
+/// Count the object as a vote if it's a vote on a local poll
+///
+/// Returns `false` if the object isn't a vote on a local poll
+pub async fn receive_vote(
+    state: &ArcState,
+    voter_url: &str,
+    object: &Object,
+) -> Result<bool, Error> {
+    let (Some(name), Some(in_reply_to)) = (object.name.as_deref(), object.in_reply_to.as_deref())
+    else {
+        return Ok(false);
+    };
+    if !object.content.is_empty() {
+        return Ok(false);
+    }
+
+    let Ok(db_object) = DbObject::by_url(&state.db_pool, in_reply_to).await else {
+        return Ok(false);
+    };
+    let poll: Object = serde_json::from_value(db_object.data.clone())?;
+    let Some(options) = poll.poll_options() else {
+        return Ok(false);
+    };
+    if DbActor::by_id(&state.db_pool, db_object.owner_id)
+        .await?
+        .remote
+    {
+        return Ok(false);
+    }
+
+    // Only actors who can see the poll are allowed to vote on it
+    if !statuses::is_visible(&state.db_pool, db_object.id, Some(voter_url)).await? {
+        return Err(Error::Unauthorized);
+    }
+
+    let choice = options
+        .iter()
+        .position(|option| option.name == name)
+        .ok_or(Error::InvalidRequest)?;
+    let (_voter, voter_db) = crate::activitypub::fetcher::fetch_actor(state, voter_url).await?;
+
+    let mut transaction = state.db_pool.begin().await?;
+    record_votes(&mut transaction, &db_object, &poll, voter_db.id, &[choice]).await?;
+    transaction.commit().await?;
+
+    update_tally(state, db_object).await?;
+
+    Ok(true)
+}
+
+/// Close the local poll and send an Update activity with the final results out
+///
+/// The Update activity is delivered to the remote voters as well, even if they aren't addressed by the poll
+pub async fn close(state: &ArcState, mut db_object: DbObject) -> Result<(), Error> {
+    let db_actor = DbActor::get(&state.db_pool, db_object.owner_id).await?;
+    let actor: Actor = serde_json::from_value(db_actor.actor)?;
+
+    let mut object: Object = serde_json::from_value(db_object.data)?;
+    object.closed = Some(OffsetDateTime::now_utc());
+    db_object.data = serde_json::to_value(&object)?;
+
+    let object_id = db_object.id;
+    let object = update_tally(state, db_object).await?;
+
+    let (update_activity_id, update_activity) = crate::activitypub::instantiate::activity(
+        &state.config,
+        "Update",
+        actor.id.as_str(),
+        object.clone(),
+        object.to.clone(),
+        object.cc.clone(),
+    );
+    let update_activity_value = serde_json::to_value(&update_activity)?;
+
+    InsertObject {
+        id: update_activity_id,
+        owner_id: db_actor.id,
+        data: update_activity_value,
+    }
+    .insert(&state.db_pool)
+    .await?;
+
+    let voter_inbox_urls = inbox_urls::resolve_remote_voters(&state.db_pool, object_id).await?;
+    let voter_delivery = crate::activitypub::deliverer::deliver_to(
+        update_activity.clone(),
+        voter_inbox_urls,
+        Arc::clone(state),
+    );
+    tokio::spawn(async move {
+        if let Err(err) = voter_delivery.await {
+            warn!(error = ?err, "Couldn't deliver the results of the poll to the voters");
+        }
+    });
+
+    crate::activitypub::deliverer::deliver(update_activity, Arc::clone(state)).await?;
+
+    Ok(())
+}
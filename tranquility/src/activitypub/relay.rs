@@ -0,0 +1,121 @@
+use crate::{
+    activitypub::{deliverer, fetcher, instance_actor, instantiate},
+    database::{
+        relay::{InsertRelay, Relay, RelayState},
+        InsertExt,
+    },
+    error::Error,
+    state::ArcState,
+    util::network,
+};
+use ormx::Delete;
+use std::sync::Arc;
+use tranquility_types::activitypub::{Activity, PUBLIC_IDENTIFIER};
+use url::Url;
+use uuid::Uuid;
+
+/// Send the activity to the inbox of the relay in the background
+fn deliver_to_relay(state: &ArcState, activity: Activity, inbox_url: String) {
+    let state = Arc::clone(state);
+
+    tokio::spawn(async move {
+        if let Err(err) = deliverer::deliver_to(activity, vec![inbox_url], state).await {
+            warn!(error = ?err, "Delivery to the relay failed");
+        }
+    });
+}
+
+/// Subscribe to the relay
+///
+/// The instance actor sends a `Follow` of the public collection to the inbox of the relay
+pub async fn subscribe(state: &ArcState, inbox_url: &str) -> Result<Relay, Error> {
+    let inbox_url = Url::parse(inbox_url)?;
+    network::check_url(&inbox_url)?;
+    if state.config.federation.is_blocked(&inbox_url) {
+        return Err(Error::BlockedDomain);
+    }
+
+    if Relay::by_inbox_url(&state.db_pool, inbox_url.as_str())
+        .await?
+        .is_some()
+    {
+        return Err(Error::InvalidRequest);
+    }
+
+    let (instance_actor, _instance_db_actor) = instance_actor::get(state).await?;
+    let (_follow_activity_id, follow_activity) = instantiate::activity(
+        &state.config,
+        "Follow",
+        instance_actor.id.as_str(),
+        PUBLIC_IDENTIFIER.to_string(),
+        Vec::new(),
+        Vec::new(),
+    );
+
+    let relay = InsertRelay {
+        id: Uuid::new_v4(),
+        inbox_url: inbox_url.to_string(),
+        follow_activity_url: follow_activity.id.clone(),
+    }
+    .insert(&state.db_pool)
+    .await?;
+
+    deliver_to_relay(state, follow_activity, relay.inbox_url.clone());
+
+    Ok(relay)
+}
+
+/// Unsubscribe from the relay
+///
+/// The instance actor sends an `Undo` of its `Follow` to the inbox of the relay
+pub async fn unsubscribe(state: &ArcState, relay: Relay) -> Result<(), Error> {
+    let (instance_actor, _instance_db_actor) = instance_actor::get(state).await?;
+
+    let follow_activity = Activity {
+        id: relay.follow_activity_url.clone(),
+        r#type: "Follow".into(),
+        actor: instance_actor.id.clone(),
+        object: PUBLIC_IDENTIFIER.to_string().into(),
+        ..Activity::default()
+    };
+    let (_undo_activity_id, undo_activity) = instantiate::activity(
+        &state.config,
+        "Undo",
+        instance_actor.id.as_str(),
+        follow_activity,
+        Vec::new(),
+        Vec::new(),
+    );
+
+    let inbox_url = relay.inbox_url.clone();
+    relay.delete(&state.db_pool).await?;
+
+    deliver_to_relay(state, undo_activity, inbox_url);
+
+    Ok(())
+}
+
+/// Record the answer of a relay to the subscription request
+///
+/// Returns `false` if the follow activity doesn't belong to a relay subscription
+pub async fn answer(
+    state: &ArcState,
+    actor_url: &str,
+    follow_activity_url: &str,
+    relay_state: RelayState,
+) -> Result<bool, Error> {
+    let relay = match Relay::by_follow_activity_url(&state.db_pool, follow_activity_url).await? {
+        Some(relay) => relay,
+        None => return Ok(false),
+    };
+
+    // Only the relay itself is allowed to answer
+    let (actor, _db_actor) = fetcher::fetch_actor(state, actor_url).await?;
+    if Url::parse(&actor.id)?.origin() != Url::parse(&relay.inbox_url)?.origin() {
+        return Err(Error::Unauthorized);
+    }
+
+    Relay::set_state(&state.db_pool, relay.id, relay_state, &actor.id).await?;
+
+    Ok(true)
+}
@@ -0,0 +1,54 @@
+use crate::{error::Error, state::ArcState};
+use axum::{response::IntoResponse, Extension, Json};
+use tranquility_types::activitypub::{Collection, OUTBOX_FOLLOW_COLLECTIONS_TYPE};
+
+/// Serve the instance actor
+///
+/// This route never requires a signature. Otherwise remote servers in secure mode couldn't verify our requests
+pub async fn actor(Extension(state): Extension<ArcState>) -> Result<impl IntoResponse, Error> {
+    let (mut instance_actor, instance_actor_db) =
+        crate::activitypub::instance_actor::get(&state).await?;
+    crate::activitypub::key_rotation::publish_previous_key(
+        &mut instance_actor,
+        &instance_actor_db,
+    )?;
+
+    Ok(Json(instance_actor))
+}
+
+/// Construct an empty collection
+///
+/// The instance actor never posts anything and nobody can follow it, so its collections are always empty
+fn empty_collection(collection_url: String) -> Collection {
+    Collection {
+        r#type: OUTBOX_FOLLOW_COLLECTIONS_TYPE.into(),
+
+        id: collection_url,
+        total_items: Some(0),
+        ..Collection::default()
+    }
+}
+
+/// Serve the (empty) outbox of the instance actor
+pub async fn outbox(Extension(state): Extension<ArcState>) -> Result<impl IntoResponse, Error> {
+    let (instance_actor, _instance_actor_db) =
+        crate::activitypub::instance_actor::get(&state).await?;
+
+    Ok(Json(empty_collection(instance_actor.outbox)))
+}
+
+/// Serve the (empty) followers collection of the instance actor
+pub async fn followers(Extension(state): Extension<ArcState>) -> Result<impl IntoResponse, Error> {
+    let (instance_actor, _instance_actor_db) =
+        crate::activitypub::instance_actor::get(&state).await?;
+
+    Ok(Json(empty_collection(instance_actor.followers)))
+}
+
+/// Serve the (empty) following collection of the instance actor
+pub async fn following(Extension(state): Extension<ArcState>) -> Result<impl IntoResponse, Error> {
+    let (instance_actor, _instance_actor_db) =
+        crate::activitypub::instance_actor::get(&state).await?;
+
+    Ok(Json(empty_collection(instance_actor.following)))
+}
@@ -0,0 +1,32 @@
+use super::signature;
+use crate::{error::Error, state::ArcState};
+use async_trait::async_trait;
+use axum::extract::{FromRequest, RequestParts};
+
+/// Extractor that enforces valid HTTP signatures on requests if the secure mode is enabled
+///
+/// Requests signed by actors of blocked domains are rejected as well
+pub struct AuthorizedFetch;
+
+#[async_trait]
+impl<B> FromRequest<B> for AuthorizedFetch
+where
+    B: Send,
+{
+    type Rejection = Error;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let state = req
+            .extensions()
+            .get::<ArcState>()
+            .expect("[Bug] State missing in request extensions");
+
+        if !state.config.federation.secure_mode {
+            return Ok(Self);
+        }
+
+        signature::verify(state, req.method(), req.uri(), req.headers()).await?;
+
+        Ok(Self)
+    }
+}
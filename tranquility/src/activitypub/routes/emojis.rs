@@ -0,0 +1,45 @@
+use crate::{database::custom_emoji::CustomEmoji, error::Error, state::ArcState, util::emoji};
+use axum::{extract::Path, response::IntoResponse, Extension, Json};
+use http::{
+    header::{CACHE_CONTROL, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS},
+    HeaderValue, StatusCode,
+};
+use ormx::Table;
+use uuid::Uuid;
+
+pub async fn emoji(
+    Path(id): Path<Uuid>,
+    Extension(state): Extension<ArcState>,
+) -> Result<impl IntoResponse, Error> {
+    let custom_emoji = CustomEmoji::get(&state.db_pool, id).await?;
+
+    // Only local emojis are served, remote emojis are served by their instances
+    if custom_emoji.domain.is_some() {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    Ok(Json(emoji::tag(&state.config, &custom_emoji)).into_response())
+}
+
+pub async fn image(
+    Path(id): Path<Uuid>,
+    Extension(state): Extension<ArcState>,
+) -> Result<impl IntoResponse, Error> {
+    // Images of remote emojis that couldn't be cached (yet) aren't served.
+    // Redirecting to their original URL would turn this route into an open redirect
+    let Some(image) = CustomEmoji::image(&state.db_pool, id).await? else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+
+    // The images of remote emojis are supplied by remote servers. Browsers must not interpret them as anything else
+    let headers = [
+        (CONTENT_TYPE, HeaderValue::from_str(&image.content_type)?),
+        (X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff")),
+        (
+            CACHE_CONTROL,
+            HeaderValue::from_static("public, max-age=86400"),
+        ),
+    ];
+
+    Ok((headers, image.data).into_response())
+}
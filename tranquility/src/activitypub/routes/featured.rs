@@ -0,0 +1,49 @@
+use crate::{
+    database::{pin::Pin, Actor as DbActor},
+    error::Error,
+    state::ArcState,
+};
+use axum::{extract::Path, response::IntoResponse, Extension, Json};
+use itertools::Itertools;
+use std::ops::Not;
+use tranquility_types::activitypub::{
+    collection::Item, Actor, Collection, IsPrivate, Object, OUTBOX_FOLLOW_COLLECTIONS_TYPE,
+};
+use uuid::Uuid;
+
+pub async fn featured(
+    Path(user_id): Path<Uuid>,
+    Extension(state): Extension<ArcState>,
+) -> Result<impl IntoResponse, Error> {
+    let user_db = DbActor::get(&state.db_pool, user_id).await?;
+    let user: Actor = serde_json::from_value(user_db.actor)?;
+    let featured_url = user
+        .featured
+        .unwrap_or_else(|| format!("{}/featured", user.id));
+
+    // The pinned posts are embedded, the collection is small enough to not need pages
+    let pinned_objects = Pin::objects(&state.db_pool, user_id).await?;
+    let ordered_items = pinned_objects
+        .into_iter()
+        .filter_map(|db_object| {
+            let object: Object = serde_json::from_value(db_object.data).ok()?;
+
+            object
+                .is_private()
+                .not()
+                .then(|| Item::Object(Box::new(object)))
+        })
+        .collect_vec();
+
+    let featured_collection = Collection {
+        id: featured_url,
+        r#type: OUTBOX_FOLLOW_COLLECTIONS_TYPE.into(),
+
+        total_items: i64::try_from(ordered_items.len()).ok(),
+        ordered_items,
+
+        ..Collection::default()
+    };
+
+    Ok(Json(featured_collection))
+}
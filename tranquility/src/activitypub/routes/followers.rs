@@ -1,17 +1,14 @@
-use super::CollectionQuery;
+use super::{collection_page, hidden_collection, root_collection, CollectionQuery};
 use crate::{
     activitypub::FollowActivity, consts::activitypub::ACTIVITIES_PER_PAGE,
-    database::Actor as DbActor, error::Error, format_uuid, state::ArcState,
+    database::Actor as DbActor, error::Error, state::ArcState,
 };
 use axum::{
     extract::{Path, Query},
     response::IntoResponse,
     Extension, Json,
 };
-use itertools::Itertools;
-use tranquility_types::activitypub::{
-    collection::Item, Actor, Collection, OUTBOX_FOLLOW_COLLECTIONS_PAGE_TYPE,
-};
+use tranquility_types::activitypub::{collection::Item, Actor};
 use uuid::Uuid;
 
 pub async fn followers(
@@ -19,44 +16,45 @@ pub async fn followers(
     Extension(state): Extension<ArcState>,
     Query(query): Query<CollectionQuery>,
 ) -> Result<impl IntoResponse, Error> {
-    let latest_follow_activities = crate::database::follow::followers(
-        &state.db_pool,
-        user_id,
-        query.last_id,
-        ACTIVITIES_PER_PAGE,
-    )
-    .await?;
-    let last_id = latest_follow_activities
-        .last()
-        .map(|activity| format_uuid!(activity.id))
-        .unwrap_or_default();
-
-    let latest_followers = latest_follow_activities
-        .into_iter()
-        .filter_map(|activity| {
-            let follow_activity: FollowActivity = serde_json::from_value(activity.data).ok()?;
-            let follower_id = follow_activity.activity.id;
-
-            Some(Item::Url(follower_id))
-        })
-        .collect_vec();
-
     let user_db = DbActor::get(&state.db_pool, user_id).await?;
     let user: Actor = serde_json::from_value(user_db.actor)?;
 
-    let next = format!("{}?last_id={}", user.followers, last_id);
-
-    let followers_collection = Collection {
-        r#type: OUTBOX_FOLLOW_COLLECTIONS_PAGE_TYPE.into(),
+    // Users can choose to only expose the size of their social graph
+    if user_db.hide_collections {
+        let total_items = crate::database::follow::followers_count(&state.db_pool, user_id).await?;
 
-        id: user.followers.clone(),
-        part_of: user.followers,
+        return Ok(Json(hidden_collection(user.followers, total_items)));
+    }
 
-        next,
+    let cursor = match query.cursor() {
+        Some(cursor) => cursor,
+        None => {
+            let total_items =
+                crate::database::follow::followers_count(&state.db_pool, user_id).await?;
 
-        ordered_items: latest_followers,
-        ..Collection::default()
+            return Ok(Json(root_collection(user.followers, total_items)));
+        }
     };
 
-    Ok(Json(followers_collection))
+    let latest_follow_activities = crate::database::follow::followers(
+        &state.db_pool,
+        user_id,
+        cursor,
+        ACTIVITIES_PER_PAGE + 1,
+    )
+    .await?;
+
+    let followers_page = collection_page(
+        user.followers,
+        cursor,
+        latest_follow_activities,
+        |activity| {
+            let follow_activity: FollowActivity = serde_json::from_value(activity.data).ok()?;
+            let follower_url = follow_activity.activity.actor;
+
+            Some(Item::Url(follower_url))
+        },
+    );
+
+    Ok(Json(followers_page))
 }
@@ -1,18 +1,14 @@
-use super::CollectionQuery;
-use crate::error::Error;
+use super::{collection_page, hidden_collection, root_collection, CollectionQuery};
 use crate::{
     activitypub::FollowActivity, consts::activitypub::ACTIVITIES_PER_PAGE,
-    database::Actor as DbActor, format_uuid, state::ArcState,
+    database::Actor as DbActor, error::Error, state::ArcState,
 };
 use axum::{
     extract::{Path, Query},
     response::IntoResponse,
     Extension, Json,
 };
-use itertools::Itertools;
-use tranquility_types::activitypub::{
-    collection::Item, Actor, Collection, OUTBOX_FOLLOW_COLLECTIONS_PAGE_TYPE,
-};
+use tranquility_types::activitypub::{collection::Item, Actor};
 use uuid::Uuid;
 
 pub async fn following(
@@ -20,44 +16,45 @@ pub async fn following(
     Extension(state): Extension<ArcState>,
     Query(query): Query<CollectionQuery>,
 ) -> Result<impl IntoResponse, Error> {
+    let user_db = DbActor::get(&state.db_pool, user_id).await?;
+    let user: Actor = serde_json::from_value(user_db.actor)?;
+
+    // Users can choose to only expose the size of their social graph
+    if user_db.hide_collections {
+        let total_items = crate::database::follow::following_count(&state.db_pool, user_id).await?;
+
+        return Ok(Json(hidden_collection(user.following, total_items)));
+    }
+
+    let cursor = match query.cursor() {
+        Some(cursor) => cursor,
+        None => {
+            let total_items =
+                crate::database::follow::following_count(&state.db_pool, user_id).await?;
+
+            return Ok(Json(root_collection(user.following, total_items)));
+        }
+    };
+
     let latest_follow_activities = crate::database::follow::following(
         &state.db_pool,
         user_id,
-        query.last_id,
-        ACTIVITIES_PER_PAGE,
+        cursor,
+        ACTIVITIES_PER_PAGE + 1,
     )
     .await?;
-    let last_id = latest_follow_activities
-        .last()
-        .map(|activity| format_uuid!(activity.id))
-        .unwrap_or_default();
-
-    let latest_followed = latest_follow_activities
-        .into_iter()
-        .filter_map(|activity| {
+
+    let following_page = collection_page(
+        user.following,
+        cursor,
+        latest_follow_activities,
+        |activity| {
             let follow_activity: FollowActivity = serde_json::from_value(activity.data).ok()?;
             let followed_url = follow_activity.activity.object.as_url()?.clone();
 
             Some(Item::Url(followed_url))
-        })
-        .collect_vec();
-
-    let user_db = DbActor::get(&state.db_pool, user_id).await?;
-    let user: Actor = serde_json::from_value(user_db.actor)?;
-
-    let next = format!("{}?last_id={}", user.following, last_id);
-
-    let following_collection = Collection {
-        r#type: OUTBOX_FOLLOW_COLLECTIONS_PAGE_TYPE.into(),
-
-        id: user.following.clone(),
-        part_of: user.following,
-
-        next,
-
-        ordered_items: latest_followed,
-        ..Collection::default()
-    };
+        },
+    );
 
-    Ok(Json(following_collection))
+    Ok(Json(following_page))
 }
@@ -1,14 +1,30 @@
-use crate::{activitypub::fetcher, crypto, error::Error, match_handler, state::ArcState};
+use super::signature::VerifiedSignature;
+use crate::{
+    activitypub::{
+        fetcher::{self, Entity, FetchContext},
+        forwarding,
+        key_resolver::ActorKeyResolver,
+    },
+    crypto,
+    database::received_activity,
+    error::Error,
+    match_handler,
+    state::ArcState,
+};
 use async_trait::async_trait;
 use axum::{
-    body::HttpBody,
+    body::{Bytes, HttpBody},
     extract::{FromRequest, RequestParts},
     response::{IntoResponse, Response},
-    Extension, Json,
+    Extension,
 };
 use http::StatusCode;
+use serde_json::Value;
 use std::{error::Error as StdError, sync::Arc};
+use tranquility_http_signatures::KeyResolver;
+use tranquility_ld_signatures::{proof, signature};
 use tranquility_types::activitypub::{activity::ObjectField, Activity};
+use url::Url;
 
 /// Checks if the activity/object contained/referenced in the activity actually belongs to the author of the activity
 async fn verify_ownership(state: ArcState, activity: Activity) -> Result<Activity, Error> {
@@ -17,11 +33,29 @@ async fn verify_ownership(state: ArcState, activity: Activity) -> Result<Activit
         return Ok(activity);
     }
 
+    // Reactions name the object they react to, which usually belongs to someone else.
+    // Their handlers only need the authenticated actor
+    if activity.r#type == "EmojiReact" || activity.r#type == "Like" {
+        return Ok(activity);
+    }
+
+    // These answer follow activities of someone else. Their handlers check whether the actor is the followed one
+    if activity.r#type == "Accept" || activity.r#type == "Reject" {
+        return Ok(activity);
+    }
+
+    // The undone activities might not be served anymore. The handler checks whether the saved activity belongs to the actor
+    if activity.r#type == "Undo" {
+        return Ok(activity);
+    }
+
     let identity_match = match activity.object {
         ObjectField::Actor(ref actor) => actor.id == activity.actor,
         ObjectField::Object(ref object) => object.attributed_to == activity.actor,
+        // The actor of an embedded activity is only a claim. None of the remaining handlers expect embedded activities
+        ObjectField::Activity(..) => false,
         ObjectField::Url(ref url) => {
-            let entity = fetcher::fetch_any(&state, url).await?;
+            let entity = fetcher::fetch_any(&state, &FetchContext::default(), url).await?;
             entity.is_owned_by(activity.actor.as_str())
         }
     };
@@ -31,10 +65,105 @@ async fn verify_ownership(state: ArcState, activity: Activity) -> Result<Activit
         .ok_or(Error::Unauthorized)
 }
 
+/// Verify the Linked Data Signature or the object integrity proof embedded into the activity
+///
+/// Returns the owner of the key if the activity carries a valid signature or proof.
+/// Linked Data Signatures only cover the terms of the bundled contexts. Activities with other properties aren't vouched for
+async fn verify_embedded_signature(
+    state: &ArcState,
+    document: Value,
+) -> Result<Option<String>, Error> {
+    let document = Arc::new(document);
+    let key_resolver = ActorKeyResolver::new(Arc::clone(state));
+
+    if let Ok(key_id) = signature::creator(&document) {
+        let unsigned_properties = signature::unsigned_properties(&document)?;
+        if !unsigned_properties.is_empty() {
+            debug!(
+                ?unsigned_properties,
+                "Linked Data signature doesn't cover the whole activity"
+            );
+
+            return Ok(None);
+        }
+
+        let key = key_resolver.resolve(key_id).await?;
+        let is_valid =
+            crypto::ld_signature::verify(Arc::clone(&document), key.public_key_pem).await?;
+
+        return Ok(is_valid.then_some(key.owner));
+    }
+
+    if let Ok(verification_method) = proof::verification_method(&document) {
+        let (owner, public_key_multibase) =
+            key_resolver.resolve_multikey(verification_method).await?;
+        let is_valid =
+            crypto::ld_signature::verify_proof(Arc::clone(&document), public_key_multibase).await?;
+
+        return Ok(is_valid.then_some(owner));
+    }
+
+    Ok(None)
+}
+
+/// Re-fetch an activity that wasn't signed by its actor from the server of its actor
+///
+/// Servers might not serve the activity itself. In that case, the object of `Create` activities is re-fetched instead.
+/// The re-fetched entities replace the delivered ones so nothing unverified gets processed
+async fn refetch_from_origin(state: &ArcState, mut activity: Activity) -> Result<Activity, Error> {
+    let actor_url = Url::parse(&activity.actor)?;
+    let is_same_origin = |url: &str| {
+        Url::parse(url)
+            .map(|url| url.origin() == actor_url.origin())
+            .unwrap_or(false)
+    };
+
+    if !is_same_origin(&activity.id) {
+        return Err(Error::Unauthorized);
+    }
+
+    match fetcher::fetch_entity(state, &activity.id).await {
+        Ok(Entity::Activity(fetched_activity))
+            if fetched_activity.id == activity.id && fetched_activity.actor == activity.actor =>
+        {
+            return Ok(fetched_activity);
+        }
+        Ok(..) => return Err(Error::Unauthorized),
+        Err(err) => debug!(error = ?err, "Couldn't re-fetch the activity"),
+    }
+
+    let object_id = match activity.object {
+        ObjectField::Object(ref object) if activity.r#type == "Create" => object.id.clone(),
+        _ => return Err(Error::Unauthorized),
+    };
+    if !is_same_origin(&object_id) {
+        return Err(Error::Unauthorized);
+    }
+
+    match fetcher::fetch_entity(state, &object_id).await? {
+        Entity::Object(fetched_object)
+            if fetched_object.id == object_id && fetched_object.attributed_to == activity.actor =>
+        {
+            activity.object = ObjectField::Object(fetched_object);
+            Ok(activity)
+        }
+        _ => Err(Error::Unauthorized),
+    }
+}
+
 /// Inbox payload extractor
 ///
-/// This extractor also runs additional checks about whether this request is actually valid
-pub struct InboxPayload(pub Activity);
+/// This extractor only runs the checks that don't require fetching remote content.
+/// The HTTP signature and the body digest have to be verified by the `verify_signature` middleware beforehand
+pub struct InboxPayload {
+    pub activity: Activity,
+
+    /// Original body of the request
+    pub body: Bytes,
+
+    /// Owner of the key the request was signed with
+    pub signer: String,
+}
 
 #[async_trait]
 impl<B> FromRequest<B> for InboxPayload
@@ -46,49 +175,154 @@ where
     type Rejection = Response;
 
     async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
-        let Json(activity) = Json::<Activity>::from_request(req)
+        let verified_signature = req
+            .extensions()
+            .get::<VerifiedSignature>()
+            .cloned()
+            .expect("[Bug] Signature verification middleware missing");
+
+        let body = Bytes::from_request(req)
             .await
             .map_err(IntoResponse::into_response)?;
+        let activity: Activity =
+            serde_json::from_slice(&body).map_err(|_| Error::InvalidRequest)?;
 
         let state = req
             .extensions()
             .get::<ArcState>()
             .expect("[Bug] State missing in request extensions");
 
-        let (remote_actor, _remote_actor_db) = fetcher::fetch_actor(state, &activity.actor).await?;
+        let actor_url = Url::parse(&activity.actor).map_err(Error::from)?;
+        if state.config.federation.is_blocked(&actor_url) {
+            return Err(Error::BlockedDomain.into());
+        }
 
-        crypto::request::verify(
-            req.method().as_str().to_string(),
-            req.uri().path().to_string(),
-            req.uri().query().map(ToString::to_string),
-            req.headers().clone(),
-            remote_actor.public_key.public_key_pem,
-        )
-        .await?
-        .then_some(())
-        .ok_or_else(|| StatusCode::UNAUTHORIZED.into_response())?;
+        // The ID is used to recognise repeated deliveries. Only the server of the actor is allowed to assign it
+        let activity_url = Url::parse(&activity.id).map_err(Error::from)?;
+        if activity_url.origin() != actor_url.origin() {
+            return Err(Error::Unauthorized.into());
+        }
 
-        let activity = verify_ownership(Arc::clone(state), activity).await?;
-        Ok(Self(activity))
+        Ok(Self {
+            activity,
+            body,
+            signer: verified_signature.owner,
+        })
     }
 }
 
-/// Inbox handler
-pub async fn inbox(
-    Extension(state): Extension<ArcState>,
-    InboxPayload(activity): InboxPayload,
-) -> Result<impl IntoResponse, Error> {
-    match_handler! {
+/// Make sure the activity was authored by its actor
+///
+/// Returns the activity that should be processed and the original body if it's known to be authored by the actor
+/// (and not replaced by a re-fetched version)
+pub async fn authenticate(
+    state: &ArcState,
+    InboxPayload {
+        activity,
+        body,
+        signer,
+    }: InboxPayload,
+) -> Result<(Activity, Option<Bytes>), Error> {
+    if signer == activity.actor {
+        return Ok((activity, Some(body)));
+    }
+
+    // Activities signed by someone other than their actor (for example, forwarded replies)
+    // are only accepted if they carry a signature of their actor or the server of their actor vouches for them
+    let document: Value = serde_json::from_slice(&body)?;
+    match verify_embedded_signature(state, document).await {
+        Ok(Some(owner)) if owner == activity.actor => Ok((activity, Some(body))),
+        result => {
+            debug!(
+                %signer,
+                actor = %activity.actor,
+                embedded_signature = ?result,
+                "Activity wasn't signed by its actor. Re-fetching it from its origin..."
+            );
+
+            Ok((refetch_from_origin(state, activity).await?, None))
+        }
+    }
+}
+
+/// Verify and handle the activity
+///
+/// If the activity couldn't be handled, the claim is released again.
+/// The sender already got a success response, so the activity is only processed again if the sender happens to deliver it again
+pub async fn process(state: ArcState, payload: InboxPayload) -> Result<StatusCode, Error> {
+    let (activity, body) = authenticate(&state, payload).await?;
+    let activity = verify_ownership(Arc::clone(&state), activity).await?;
+
+    // Deliveries of the same activity might be processed concurrently. Only the first one gets handled
+    if !received_activity::claim(&state.db_pool, &activity.id).await? {
+        return Ok(StatusCode::ACCEPTED);
+    }
+
+    let activity_url = activity.id.clone();
+    let result = handle(&state, activity, body).await;
+    if result.is_err() {
+        received_activity::release(&state.db_pool, &activity_url).await?;
+    }
+
+    result
+}
+
+/// Handle the claimed activity
+///
+/// Activities that concern the followers of local actors are forwarded to them after they were handled
+async fn handle(
+    state: &ArcState,
+    activity: Activity,
+    body: Option<Bytes>,
+) -> Result<StatusCode, Error> {
+    let forwarding = match body {
+        Some(body) => forwarding::prepare(state, &activity, &body).await?,
+        None => None,
+    };
+
+    let status = match_handler! {
         (state, activity);
 
         Accept,
+        Add,
         Announce,
         Create,
         Delete,
+        EmojiReact,
         Follow,
         Like,
         Reject,
+        Remove,
         Undo,
         Update
+    }?;
+
+    if let Some(forwarding) = forwarding {
+        forwarding.deliver(state).await;
     }
+
+    Ok(status)
+}
+
+/// Inbox handler
+///
+/// The activity is acknowledged right away and processed in the background by the inbox queue.
+/// Activities that were already received before are acknowledged without being processed again
+pub async fn inbox(
+    Extension(state): Extension<ArcState>,
+    payload: InboxPayload,
+) -> Result<StatusCode, Error> {
+    if received_activity::exists(&state.db_pool, &payload.activity.id).await? {
+        return Ok(StatusCode::ACCEPTED);
+    }
+
+    let activity_url = payload.activity.id.clone();
+    let queue_state = Arc::clone(&state);
+    state.inbox_queue.push(async move {
+        if let Err(err) = process(queue_state, payload).await {
+            warn!(error = ?err, activity = %activity_url, "Couldn't process the activity");
+        }
+    })?;
+
+    Ok(StatusCode::ACCEPTED)
 }
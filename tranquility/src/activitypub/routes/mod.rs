@@ -1,28 +1,177 @@
+use self::{authorized_fetch::AuthorizedFetch, signature::verify_signature};
+use crate::{
+    consts::activitypub::ACTIVITIES_PER_PAGE,
+    database::{Cursor, Object},
+    format_uuid,
+};
 use axum::{
+    middleware::{from_extractor, from_fn},
     routing::{get, post},
     Router,
 };
+use itertools::Itertools;
 use serde::Deserialize;
+use tranquility_types::activitypub::{
+    collection::Item, Collection, OUTBOX_FOLLOW_COLLECTIONS_PAGE_TYPE,
+    OUTBOX_FOLLOW_COLLECTIONS_TYPE,
+};
 use uuid::Uuid;
 
 #[derive(Deserialize)]
 pub struct CollectionQuery {
-    last_id: Option<Uuid>,
+    page: Option<String>,
+    max_id: Option<Uuid>,
+    min_id: Option<Uuid>,
+}
+
+impl CollectionQuery {
+    /// Get the cursor of the requested page
+    ///
+    /// Returns `None` if the root collection was requested
+    pub fn cursor(&self) -> Option<Cursor> {
+        match (self.max_id, self.min_id, self.page.as_deref()) {
+            (Some(max_id), _, _) => Some(Cursor::Before(Some(max_id))),
+            (None, Some(min_id), _) => Some(Cursor::After(Some(min_id))),
+            (None, None, Some("last")) => Some(Cursor::After(None)),
+            (None, None, Some(_)) => Some(Cursor::Before(None)),
+            (None, None, None) => None,
+        }
+    }
+}
+
+/// Construct the URL of the collection page the cursor points to
+fn page_url(collection_url: &str, cursor: Cursor) -> String {
+    match cursor {
+        Cursor::Before(Some(id)) => {
+            format!("{}?page=true&max_id={}", collection_url, format_uuid!(id))
+        }
+        Cursor::Before(None) => format!("{}?page=true", collection_url),
+        Cursor::After(Some(id)) => {
+            format!("{}?page=true&min_id={}", collection_url, format_uuid!(id))
+        }
+        Cursor::After(None) => format!("{}?page=last", collection_url),
+    }
+}
+
+/// Construct the root collection which only links to its first and last page
+pub fn root_collection(collection_url: String, total_items: i64) -> Collection {
+    Collection {
+        r#type: OUTBOX_FOLLOW_COLLECTIONS_TYPE.into(),
+
+        total_items: Some(total_items),
+
+        first: Some(page_url(&collection_url, Cursor::Before(None))),
+        last: Some(page_url(&collection_url, Cursor::After(None))),
+
+        id: collection_url,
+        ..Collection::default()
+    }
+}
+
+/// Construct a collection that only shows the total amount of items
+///
+/// Used for the follower and following collections of users who chose to hide them
+pub fn hidden_collection(collection_url: String, total_items: i64) -> Collection {
+    Collection {
+        r#type: OUTBOX_FOLLOW_COLLECTIONS_TYPE.into(),
+
+        id: collection_url,
+        total_items: Some(total_items),
+        ..Collection::default()
+    }
+}
+
+/// Construct a collection page out of objects (newest first)
+///
+/// The objects have to be fetched with a limit of `ACTIVITIES_PER_PAGE + 1`.
+/// The additional object is only used to determine whether there are more pages in the direction of the cursor
+pub fn collection_page<F>(
+    collection_url: String,
+    cursor: Cursor,
+    mut objects: Vec<Object>,
+    to_item: F,
+) -> Collection
+where
+    F: FnMut(Object) -> Option<Item>,
+{
+    let page_size = usize::try_from(ACTIVITIES_PER_PAGE).expect("[Bug] Negative page size");
+    let has_more = objects.len() > page_size;
+
+    // Drop the additional object. It's the oldest one when paginating backwards and the newest one otherwise
+    if has_more {
+        match cursor {
+            Cursor::Before(..) => objects.truncate(page_size),
+            Cursor::After(..) => {
+                objects.remove(0);
+            }
+        }
+    }
+
+    let newest_id = objects.first().map(|object| object.id);
+    let oldest_id = objects.last().map(|object| object.id);
+
+    let (prev_id, next_id) = match cursor {
+        Cursor::Before(before_id) => (before_id.and(newest_id), oldest_id.filter(|_| has_more)),
+        Cursor::After(after_id) => (newest_id.filter(|_| has_more), after_id.and(oldest_id)),
+    };
+
+    let prev = prev_id
+        .map(|id| page_url(&collection_url, Cursor::After(Some(id))))
+        .unwrap_or_default();
+    let next = next_id
+        .map(|id| page_url(&collection_url, Cursor::Before(Some(id))))
+        .unwrap_or_default();
+
+    let ordered_items = objects.into_iter().filter_map(to_item).collect_vec();
+
+    Collection {
+        r#type: OUTBOX_FOLLOW_COLLECTIONS_PAGE_TYPE.into(),
+
+        id: page_url(&collection_url, cursor),
+        part_of: collection_url,
+
+        next,
+        prev,
+
+        ordered_items,
+        ..Collection::default()
+    }
 }
 
 pub fn routes() -> Router {
-    Router::new()
+    let entity_routes = Router::new()
         .route("/users/:id", get(users::users))
+        .route("/users/:id/featured", get(featured::featured))
         .route("/users/:id/followers", get(followers::followers))
         .route("/users/:id/following", get(following::following))
-        .route("/users/:id/inbox", post(inbox::inbox))
         .route("/users/:id/outbox", get(outbox::outbox))
         .route("/objects/:id", get(objects::objects))
+        .route("/emojis/:id", get(emojis::emoji))
+        .route_layer(from_extractor::<AuthorizedFetch>());
+
+    let inbox_routes = Router::new()
+        .route("/actor/inbox", post(inbox::inbox))
+        .route("/users/:id/inbox", post(inbox::inbox))
+        .route_layer(from_fn(verify_signature));
+
+    Router::new()
+        .merge(entity_routes)
+        .merge(inbox_routes)
+        .route("/actor", get(actor::actor))
+        .route("/actor/followers", get(actor::followers))
+        .route("/actor/following", get(actor::following))
+        .route("/actor/outbox", get(actor::outbox))
+        .route("/media/emojis/:id", get(emojis::image))
 }
 
+pub mod actor;
+pub mod authorized_fetch;
+pub mod emojis;
+pub mod featured;
 pub mod followers;
 pub mod following;
 pub mod inbox;
 pub mod objects;
 pub mod outbox;
+pub mod signature;
 pub mod users;
@@ -1,18 +1,15 @@
-use super::CollectionQuery;
+use super::{collection_page, root_collection, CollectionQuery};
 use crate::{
     consts::activitypub::ACTIVITIES_PER_PAGE, database::Actor as DbActor, error::Error,
-    format_uuid, state::ArcState,
+    state::ArcState,
 };
 use axum::{
     extract::{Path, Query},
     response::IntoResponse,
     Extension, Json,
 };
-use itertools::Itertools;
 use std::ops::Not;
-use tranquility_types::activitypub::{
-    collection::Item, Activity, Actor, Collection, IsPrivate, OUTBOX_FOLLOW_COLLECTIONS_PAGE_TYPE,
-};
+use tranquility_types::activitypub::{collection::Item, Activity, Actor, IsPrivate};
 use uuid::Uuid;
 
 pub async fn outbox(
@@ -20,46 +17,34 @@ pub async fn outbox(
     Extension(state): Extension<ArcState>,
     Query(query): Query<CollectionQuery>,
 ) -> Result<impl IntoResponse, Error> {
-    let latest_activities = crate::database::outbox::activities(
-        &state.db_pool,
-        user_id,
-        query.last_id,
-        ACTIVITIES_PER_PAGE,
-    )
-    .await?;
-    let last_id = latest_activities
-        .last()
-        .map(|activity| format_uuid!(activity.id))
-        .unwrap_or_default();
-
-    let latest_activities = latest_activities
-        .into_iter()
-        .filter_map(|activity| {
-            let create_activity: Activity = serde_json::from_value(activity.data).ok()?;
-
-            create_activity
-                .is_private()
-                .not()
-                .then(|| Item::Activity(Box::new(create_activity)))
-        })
-        .collect_vec();
-
     let user_db = DbActor::get(&state.db_pool, user_id).await?;
     let user: Actor = serde_json::from_value(user_db.actor)?;
 
-    let next = format!("{}?last_id={}", user.outbox, last_id);
+    let cursor = match query.cursor() {
+        Some(cursor) => cursor,
+        None => {
+            let total_items = crate::database::outbox::count(&state.db_pool, user_id).await?;
 
-    let outbox_collection = Collection {
-        r#type: OUTBOX_FOLLOW_COLLECTIONS_PAGE_TYPE.into(),
+            return Ok(Json(root_collection(user.outbox, total_items)));
+        }
+    };
 
-        id: user.outbox.clone(),
-        part_of: user.outbox,
+    let latest_activities = crate::database::outbox::activities(
+        &state.db_pool,
+        user_id,
+        cursor,
+        ACTIVITIES_PER_PAGE + 1,
+    )
+    .await?;
 
-        next,
+    let outbox_page = collection_page(user.outbox, cursor, latest_activities, |activity| {
+        let create_activity: Activity = serde_json::from_value(activity.data).ok()?;
 
-        ordered_items: latest_activities,
-        ..Collection::default()
-    };
+        create_activity
+            .is_private()
+            .not()
+            .then(|| Item::Activity(Box::new(create_activity)))
+    });
 
-    Ok(Json(outbox_collection))
+    Ok(Json(outbox_page))
 }
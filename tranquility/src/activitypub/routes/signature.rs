@@ -0,0 +1,119 @@
+use crate::{
+    activitypub::key_resolver::ActorKeyResolver, config::SignatureFormat, consts::MAX_BODY_SIZE,
+    crypto, error::Error, state::ArcState,
+};
+use axum::{
+    body::{Body, HttpBody},
+    middleware::Next,
+    response::Response,
+};
+use http::{HeaderMap, Method, Request, Uri};
+use std::{sync::Arc, time::Duration};
+use tranquility_http_signatures::{Failure, Format, Verifier};
+use url::Url;
+
+/// Key ID and owner of a valid HTTP signature
+///
+/// Inserted into the request extensions by the `verify_signature` middleware
+#[derive(Clone)]
+pub struct VerifiedSignature {
+    pub key_id: String,
+    pub owner: String,
+}
+
+/// Verify the HTTP signature of a request
+///
+/// Servers that successfully sign their requests with RFC 9421 signatures are recorded to receive RFC 9421 signatures as well
+pub async fn verify(
+    state: &ArcState,
+    method: &Method,
+    uri: &Uri,
+    headers: &HeaderMap,
+) -> Result<VerifiedSignature, Error> {
+    let request = tranquility_http_signatures::Request::new(
+        method.as_str(),
+        uri.path(),
+        uri.query(),
+        headers,
+    );
+    let verifier = Verifier::new(ActorKeyResolver::new(Arc::clone(state)))
+        .max_clock_skew(Duration::from_secs(state.config.federation.max_clock_skew));
+    let verification = verifier.verify(&request).await;
+
+    match verification.failure {
+        None => (),
+        Some(Failure::KeyResolution(Error::BlockedDomain)) => return Err(Error::BlockedDomain),
+        Some(failure) => {
+            debug!(
+                key_id = ?verification.key_id,
+                ?failure,
+                "HTTP signature verification failed"
+            );
+
+            return Err(Error::Unauthorized);
+        }
+    }
+
+    let (Some(key_id), Some(owner)) = (verification.key_id, verification.owner) else {
+        return Err(Error::Unauthorized);
+    };
+
+    if verification.format == Some(Format::Rfc9421) {
+        let owner_url = Url::parse(&owner)?;
+        match owner_url.host_str() {
+            Some(domain) if domain != state.config.instance.domain => {
+                crate::database::server::record_signature_format(
+                    &state.db_pool,
+                    domain,
+                    SignatureFormat::Rfc9421,
+                )
+                .await?;
+            }
+            _ => (),
+        }
+    }
+
+    Ok(VerifiedSignature { key_id, owner })
+}
+
+/// Middleware that verifies the body digest and the HTTP signature of incoming requests
+///
+/// The digest is checked against the raw body before any handler deserialises it.
+/// Bodies larger than `MAX_BODY_SIZE` are rejected without reading them any further.
+/// On success, the `VerifiedSignature` is inserted into the request extensions
+pub async fn verify_signature(req: Request<Body>, next: Next<Body>) -> Result<Response, Error> {
+    let state = req
+        .extensions()
+        .get::<ArcState>()
+        .cloned()
+        .expect("[Bug] State missing in request extensions");
+
+    let (mut parts, mut body) = req.into_parts();
+    if body.size_hint().lower() > MAX_BODY_SIZE {
+        return Err(Error::PayloadTooLarge);
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|_| Error::InvalidRequest)?;
+        if (bytes.len() + chunk.len()) as u64 > MAX_BODY_SIZE {
+            return Err(Error::PayloadTooLarge);
+        }
+
+        bytes.extend_from_slice(&chunk);
+    }
+
+    if parts.method != Method::GET
+        && parts.method != Method::HEAD
+        && !crypto::digest::verify(&parts.headers, &bytes)?
+    {
+        return Err(Error::Unauthorized);
+    }
+
+    let verified_signature = verify(&state, &parts.method, &parts.uri, &parts.headers).await?;
+    parts.extensions.insert(verified_signature);
+
+    Ok(next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await)
+}
@@ -1,12 +1,16 @@
-use crate::{database::Actor, error::Error, state::ArcState};
+use crate::{database::Actor as DbActor, error::Error, state::ArcState};
 use axum::{extract::Path, response::IntoResponse, Extension, Json};
+use tranquility_types::activitypub::Actor;
 use uuid::Uuid;
 
 pub async fn users(
     Path(id): Path<Uuid>,
     Extension(state): Extension<ArcState>,
 ) -> Result<impl IntoResponse, Error> {
-    let actor = Actor::get(&state.db_pool, id).await?;
+    let db_actor = DbActor::get(&state.db_pool, id).await?;
 
-    Ok(Json(actor.actor))
+    let mut actor: Actor = serde_json::from_value(db_actor.actor.clone())?;
+    crate::activitypub::key_rotation::publish_previous_key(&mut actor, &db_actor)?;
+
+    Ok(Json(actor))
 }
@@ -0,0 +1,279 @@
+use super::Authorisation;
+use crate::{
+    activitypub::{backfill, key_rotation, relay},
+    config::SignatureFormat,
+    consts::{KB_BYTES, MAX_BODY_SIZE},
+    database::{custom_emoji::CustomEmoji, relay::Relay, Actor},
+    error::Error,
+    state::ArcState,
+    util::{emoji, Form},
+};
+use async_trait::async_trait;
+use axum::{
+    body::Bytes,
+    extract::{ContentLengthLimit, FromRequest, Path, Query, RequestParts},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{delete, get, post, put},
+    Extension, Json, Router,
+};
+use headers::{ContentType, HeaderMapExt};
+use http::HeaderMap;
+use ormx::Table;
+use serde::{Deserialize, Serialize};
+use std::ops::Deref;
+use uuid::Uuid;
+
+/// Admin extractor
+///
+/// Works like the [`Authorisation`] extractor but additionally requires the actor to be listed as a moderator
+pub struct Admin(pub Actor);
+
+impl Deref for Admin {
+    type Target = Actor;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<B> FromRequest<B> for Admin
+where
+    B: Send,
+{
+    type Rejection = Error;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let Authorisation(actor) = Authorisation::from_request(req).await?;
+
+        let state = req
+            .extensions()
+            .get::<ArcState>()
+            .expect("[Bug] Missing state in extensions");
+
+        if actor.remote || !state.config.instance.moderators.contains(&actor.username) {
+            return Err(Error::Unauthorized);
+        }
+
+        Ok(Self(actor))
+    }
+}
+
+async fn rotate_key(
+    Extension(state): Extension<ArcState>,
+    Admin(_admin): Admin,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, Error> {
+    let db_actor = Actor::get(&state.db_pool, id).await?;
+    if db_actor.remote {
+        return Err(Error::InvalidRequest);
+    }
+
+    tokio::spawn(async move {
+        if let Err(err) = key_rotation::rotate(&state, db_actor).await {
+            warn!(error = ?err, "Key rotation failed");
+        }
+    });
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn backfill_account(
+    Extension(state): Extension<ArcState>,
+    Admin(_admin): Admin,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, Error> {
+    let db_actor = Actor::by_id(&state.db_pool, id).await?;
+    if !db_actor.remote {
+        return Err(Error::InvalidRequest);
+    }
+
+    let actor = serde_json::from_value(db_actor.actor.clone())?;
+    backfill::spawn(&state, actor, db_actor);
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn rotate_keys(
+    Extension(state): Extension<ArcState>,
+    Admin(_admin): Admin,
+) -> impl IntoResponse {
+    tokio::spawn(async move {
+        if let Err(err) = key_rotation::rotate_all(&state).await {
+            warn!(error = ?err, "Key rotation failed");
+        }
+    });
+
+    StatusCode::ACCEPTED
+}
+
+#[derive(Deserialize)]
+pub struct ServerForm {
+    signature_format: SignatureFormat,
+}
+
+async fn update_server(
+    Extension(state): Extension<ArcState>,
+    Admin(_admin): Admin,
+    Path(domain): Path<String>,
+    ContentLengthLimit(Form(form)): ContentLengthLimit<Form<ServerForm>, MAX_BODY_SIZE>,
+) -> Result<impl IntoResponse, Error> {
+    crate::database::server::set_signature_format(
+        &state.db_pool,
+        &domain.to_lowercase(),
+        form.signature_format,
+    )
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Serialize)]
+pub struct RelayResponse {
+    id: Uuid,
+    inbox_url: String,
+    actor_url: Option<String>,
+    state: &'static str,
+}
+
+impl From<Relay> for RelayResponse {
+    fn from(relay: Relay) -> Self {
+        Self {
+            id: relay.id,
+            state: relay.state().as_str(),
+            inbox_url: relay.inbox_url,
+            actor_url: relay.actor_url,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RelayForm {
+    inbox_url: String,
+}
+
+async fn relays(
+    Extension(state): Extension<ArcState>,
+    Admin(_admin): Admin,
+) -> Result<impl IntoResponse, Error> {
+    let relays = Relay::all(&state.db_pool).await?;
+    let relays = relays
+        .into_iter()
+        .map(RelayResponse::from)
+        .collect::<Vec<_>>();
+
+    Ok(Json(relays))
+}
+
+async fn add_relay(
+    Extension(state): Extension<ArcState>,
+    Admin(_admin): Admin,
+    ContentLengthLimit(Form(form)): ContentLengthLimit<Form<RelayForm>, MAX_BODY_SIZE>,
+) -> Result<impl IntoResponse, Error> {
+    let relay = relay::subscribe(&state, &form.inbox_url).await?;
+
+    Ok((StatusCode::CREATED, Json(RelayResponse::from(relay))))
+}
+
+async fn remove_relay(
+    Extension(state): Extension<ArcState>,
+    Admin(_admin): Admin,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, Error> {
+    let relay = Relay::get(&state.db_pool, id).await?;
+    relay::unsubscribe(&state, relay).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+pub struct EmojiQuery {
+    category: Option<String>,
+    #[serde(default = "default_visible_in_picker")]
+    visible_in_picker: bool,
+}
+
+fn default_visible_in_picker() -> bool {
+    true
+}
+
+#[derive(Serialize)]
+pub struct EmojiResponse {
+    id: Uuid,
+    shortcode: String,
+    category: Option<String>,
+    visible_in_picker: bool,
+    url: String,
+}
+
+/// Upload the image of a local emoji (replaces the image of an existing emoji with the shortcode)
+///
+/// The image is sent as the request body, its content type in the `Content-Type` header
+async fn upload_emoji(
+    Extension(state): Extension<ArcState>,
+    Admin(_admin): Admin,
+    Path(shortcode): Path<String>,
+    Query(query): Query<EmojiQuery>,
+    headers: HeaderMap,
+    ContentLengthLimit(image): ContentLengthLimit<Bytes, MAX_BODY_SIZE>,
+) -> Result<impl IntoResponse, Error> {
+    let content_type = headers
+        .typed_get::<ContentType>()
+        .map(|content_type| content_type.to_string())
+        .unwrap_or_default();
+    if !emoji::is_valid_shortcode(&shortcode) || !crate::util::is_image(&content_type) {
+        return Err(Error::InvalidRequest);
+    }
+
+    let upload_limit = state.config.instance.upload_limit * KB_BYTES;
+    if !u64::try_from(image.len()).is_ok_and(|image_size| image_size <= upload_limit) {
+        return Ok(StatusCode::PAYLOAD_TOO_LARGE.into_response());
+    }
+
+    let custom_emoji = CustomEmoji::upsert_local(
+        &state.db_pool,
+        &shortcode,
+        query.category.as_deref(),
+        query.visible_in_picker,
+    )
+    .await?;
+    CustomEmoji::set_image(&state.db_pool, custom_emoji.id, &content_type, &image).await?;
+
+    let emoji_response = EmojiResponse {
+        id: custom_emoji.id,
+        url: emoji::image_url(&state.config, custom_emoji.id),
+        shortcode: custom_emoji.shortcode,
+        category: custom_emoji.category,
+        visible_in_picker: custom_emoji.visible_in_picker,
+    };
+
+    Ok((StatusCode::CREATED, Json(emoji_response)).into_response())
+}
+
+async fn remove_emoji(
+    Extension(state): Extension<ArcState>,
+    Admin(_admin): Admin,
+    Path(shortcode): Path<String>,
+) -> Result<impl IntoResponse, Error> {
+    let Some(custom_emoji) = CustomEmoji::by_shortcode(&state.db_pool, &shortcode, None).await?
+    else {
+        return Ok(StatusCode::NOT_FOUND);
+    };
+    custom_emoji.delete(&state.db_pool).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn routes() -> Router {
+    let admin_router = Router::new()
+        .route("/accounts/:id/backfill", post(backfill_account))
+        .route("/accounts/:id/rotate_key", post(rotate_key))
+        .route("/emojis/:shortcode", put(upload_emoji).delete(remove_emoji))
+        .route("/relays", get(relays).post(add_relay))
+        .route("/relays/:id", delete(remove_relay))
+        .route("/rotate_keys", post(rotate_keys))
+        .route("/servers/:domain", put(update_server));
+
+    Router::new().nest("/api/tranquility/v1/admin", admin_router)
+}
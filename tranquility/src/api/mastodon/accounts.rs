@@ -1,21 +1,36 @@
-use super::{convert::IntoMastodon, Authorisation};
+use super::convert::IntoMastodon;
 use crate::{
-    activitypub::interactions,
-    database::{Actor as DbActor, Object as DbObject},
+    activitypub::{backfill, interactions, Clean},
+    api::Authorisation,
+    consts::{
+        mastodon::{DEFAULT_STATUSES_LIMIT, MAX_DISPLAY_NAME_CHARS, MAX_STATUSES_LIMIT},
+        MAX_BODY_SIZE,
+    },
+    database::{pin::Pin, statuses::StatusFilter, Actor as DbActor, Cursor, Object as DbObject},
     error::Error,
     format_uuid,
     state::ArcState,
+    util::{emoji, Form},
 };
 use axum::{
-    extract::Path,
+    extract::{ContentLengthLimit, Path, Query},
+    http::StatusCode,
     response::IntoResponse,
-    routing::{get, post},
+    routing::{get, patch, post},
     Extension, Json, Router,
 };
+use http::{
+    header::{HeaderValue, LINK},
+    HeaderMap,
+};
+use itertools::Itertools;
+use ormx::Table;
+use serde::Deserialize;
 use tranquility_types::{
-    activitypub::Actor,
-    mastodon::{Account, FollowResponse, Source},
+    activitypub::{Actor, IsPrivate, Object, Tag},
+    mastodon::{Account, FollowResponse, Source, Status},
 };
+use url::Url;
 use uuid::Uuid;
 
 async fn accounts(
@@ -23,7 +38,7 @@ async fn accounts(
     Extension(state): Extension<ArcState>,
     authorized_db_actor: Option<Authorisation>,
 ) -> Result<impl IntoResponse, Error> {
-    let db_actor = DbActor::get(&state.db_pool, id).await?;
+    let db_actor = DbActor::by_id(&state.db_pool, id).await?;
     let mut mastodon_account: Account = db_actor.into_mastodon(&state).await?;
 
     // Add the source field to the returned account if the requested account
@@ -57,10 +72,23 @@ async fn follow(
     Ok(Json(follow_response))
 }
 
+/// Check whether the requester is allowed to see the social graph of the actor
+fn can_view_collections(db_actor: &DbActor, authorized_db_actor: Option<&Authorisation>) -> bool {
+    !db_actor.hide_collections
+        || authorized_db_actor
+            .is_some_and(|authorized_db_actor| authorized_db_actor.id == db_actor.id)
+}
+
 async fn following(
     Path(id): Path<Uuid>,
     Extension(state): Extension<ArcState>,
+    authorized_db_actor: Option<Authorisation>,
 ) -> Result<impl IntoResponse, Error> {
+    let db_actor = DbActor::get(&state.db_pool, id).await?;
+    if !can_view_collections(&db_actor, authorized_db_actor.as_ref()) {
+        return Ok(Json(Vec::new()));
+    }
+
     let follow_activities =
         DbObject::by_type_and_owner(&state.db_pool, "Follow", &id, 10, 0).await?;
     let followed_accounts: Vec<Account> = follow_activities.into_mastodon(&state).await?;
@@ -71,21 +99,171 @@ async fn following(
 async fn followers(
     Path(id): Path<Uuid>,
     Extension(state): Extension<ArcState>,
+    authorized_db_actor: Option<Authorisation>,
 ) -> Result<impl IntoResponse, Error> {
     let db_actor = DbActor::get(&state.db_pool, id).await?;
-    let actor: Actor = serde_json::from_value(db_actor.actor)?;
+    if !can_view_collections(&db_actor, authorized_db_actor.as_ref()) {
+        return Ok(Json(Vec::new()));
+    }
 
-    let followed_activities =
-        DbObject::by_type_and_object_url(&state.db_pool, "Follow", actor.id.as_str(), 10, 0)
-            .await?;
-    let follower_accounts: Vec<Account> = followed_activities.into_mastodon(&state).await?;
+    let follower_db_actors =
+        crate::database::follow::follower_actors(&state.db_pool, id, 10).await?;
+    let follower_accounts: Vec<Account> = follower_db_actors.into_mastodon(&state).await?;
 
     Ok(Json(follower_accounts))
 }
 
-// TODO: Implement `/api/v1/accounts/:id/statuses` endpoint
-/*async fn statuses(Path(id): Path<Uuid>, authorized_db_actor: Option<Auth>) -> Result<impl Reply, Rejection> {
-}*/
+#[derive(Deserialize)]
+struct StatusesQuery {
+    #[serde(default)]
+    only_media: bool,
+    #[serde(default)]
+    exclude_replies: bool,
+    #[serde(default)]
+    exclude_reblogs: bool,
+    #[serde(default)]
+    pinned: bool,
+    tagged: Option<String>,
+
+    max_id: Option<Uuid>,
+    min_id: Option<Uuid>,
+    limit: Option<i64>,
+}
+
+impl StatusesQuery {
+    /// Get the position the requested page starts at
+    fn cursor(&self) -> Cursor {
+        match (self.max_id, self.min_id) {
+            (None, Some(min_id)) => Cursor::After(Some(min_id)),
+            (max_id, _) => Cursor::Before(max_id),
+        }
+    }
+
+    /// Get the requested amount of statuses, clamped to the allowed range
+    fn limit(&self) -> i64 {
+        self.limit
+            .unwrap_or(DEFAULT_STATUSES_LIMIT)
+            .clamp(1, MAX_STATUSES_LIMIT)
+    }
+
+    /// Construct the URL of the page in the direction of the cursor, keeping the filters of the query
+    fn page_url(&self, state: &ArcState, account_id: Uuid, cursor: Cursor) -> String {
+        let mut url = Url::parse(&format!(
+            "https://{}/api/v1/accounts/{}/statuses",
+            state.config.instance.domain,
+            format_uuid!(account_id)
+        ))
+        .expect("[Bug] Invalid statuses URL");
+
+        {
+            let mut query_pairs = url.query_pairs_mut();
+            for (name, enabled) in [
+                ("only_media", self.only_media),
+                ("exclude_replies", self.exclude_replies),
+                ("exclude_reblogs", self.exclude_reblogs),
+            ] {
+                if enabled {
+                    query_pairs.append_pair(name, "true");
+                }
+            }
+            if let Some(ref tagged) = self.tagged {
+                query_pairs.append_pair("tagged", tagged);
+            }
+
+            match cursor {
+                Cursor::Before(Some(id)) => {
+                    query_pairs.append_pair("max_id", &format_uuid!(id));
+                }
+                Cursor::After(Some(id)) => {
+                    query_pairs.append_pair("min_id", &format_uuid!(id));
+                }
+                Cursor::Before(None) | Cursor::After(None) => (),
+            }
+            query_pairs.append_pair("limit", &self.limit().to_string());
+        }
+
+        url.into()
+    }
+}
+
+async fn statuses(
+    Path(id): Path<Uuid>,
+    Extension(state): Extension<ArcState>,
+    authorized_db_actor: Option<Authorisation>,
+    Query(query): Query<StatusesQuery>,
+) -> Result<impl IntoResponse, Error> {
+    // Remote accounts are served from the objects we stored
+    let db_actor = DbActor::by_id(&state.db_pool, id).await?;
+
+    // Import the recent posts of remote accounts logged-in users are looking at
+    if authorized_db_actor.is_some() && db_actor.remote {
+        let actor = serde_json::from_value(db_actor.actor.clone())?;
+        backfill::spawn_if_due(&state, actor, db_actor.clone());
+    }
+
+    // Pinned posts aren't paginated since there are only a few of them
+    if query.pinned {
+        let objects: Vec<Object> = Pin::objects(&state.db_pool, db_actor.id)
+            .await?
+            .into_iter()
+            .map(|db_object| serde_json::from_value(db_object.data))
+            .try_collect()?;
+
+        // Only public posts can be pinned but don't rely on that for remote accounts
+        let status_futures = objects
+            .into_iter()
+            .filter(|object| !object.is_private())
+            .map(|object| IntoMastodon::<Status>::into_mastodon(object, &state));
+        let mut statuses = futures_util::future::try_join_all(status_futures).await?;
+
+        for status in &mut statuses {
+            status.pinned = Some(true);
+        }
+
+        return Ok((HeaderMap::new(), Json(statuses)));
+    }
+
+    let viewer_url = authorized_db_actor
+        .as_ref()
+        .and_then(|authorized_db_actor| authorized_db_actor.actor["id"].as_str());
+    let filter = StatusFilter {
+        only_media: query.only_media,
+        exclude_replies: query.exclude_replies,
+        exclude_reblogs: query.exclude_reblogs,
+        tagged: query
+            .tagged
+            .as_deref()
+            .map(|tag| tag.trim_start_matches('#')),
+    };
+
+    let db_objects = crate::database::statuses::by_account(
+        &state.db_pool,
+        db_actor.id,
+        viewer_url,
+        filter,
+        query.cursor(),
+        query.limit(),
+    )
+    .await?;
+
+    // Link to the next (older) and previous (newer) page like Mastodon does
+    let mut headers = HeaderMap::new();
+    if let (Some(newest), Some(oldest)) = (db_objects.first(), db_objects.last()) {
+        let link = format!(
+            r#"<{}>; rel="next", <{}>; rel="prev""#,
+            query.page_url(&state, db_actor.id, Cursor::Before(Some(oldest.id))),
+            query.page_url(&state, db_actor.id, Cursor::After(Some(newest.id))),
+        );
+        headers.insert(LINK, HeaderValue::from_str(&link)?);
+    }
+
+    let status_futures = db_objects
+        .into_iter()
+        .map(|db_object| IntoMastodon::<Status>::into_mastodon(db_object, &state));
+    let statuses = futures_util::future::try_join_all(status_futures).await?;
+
+    Ok((headers, Json(statuses)))
+}
 
 async fn unfollow(
     Path(id): Path<Uuid>,
@@ -106,6 +284,94 @@ async fn unfollow(
     Ok(Json(unfollow_response))
 }
 
+/// Get the shortcodes of the Emoji tags
+fn emoji_shortcodes(tags: &[Tag]) -> Vec<String> {
+    tags.iter()
+        .filter_map(Tag::emoji)
+        .map(|emoji| emoji.shortcode().to_string())
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct UpdateCredentialsForm {
+    bot: Option<bool>,
+    hide_collections: Option<bool>,
+    display_name: Option<String>,
+    note: Option<String>,
+}
+
+async fn update_credentials(
+    Extension(state): Extension<ArcState>,
+    Authorisation(mut db_actor): Authorisation,
+    ContentLengthLimit(Form(form)): ContentLengthLimit<Form<UpdateCredentialsForm>, MAX_BODY_SIZE>,
+) -> Result<impl IntoResponse, Error> {
+    if form
+        .display_name
+        .as_ref()
+        .is_some_and(|display_name| display_name.chars().count() > MAX_DISPLAY_NAME_CHARS)
+    {
+        return Ok((StatusCode::BAD_REQUEST, "Display name too long").into_response());
+    }
+    if form
+        .note
+        .as_ref()
+        .is_some_and(|note| note.chars().count() > state.config.instance.character_limit)
+    {
+        return Ok((StatusCode::BAD_REQUEST, "Note too long").into_response());
+    }
+
+    if let Some(hide_collections) = form.hide_collections {
+        db_actor.hide_collections = hide_collections;
+    }
+
+    let mut actor: Actor = serde_json::from_value(db_actor.actor.clone())?;
+    let mut actor_changed = false;
+
+    if let Some(display_name) = form.display_name {
+        actor.name = display_name;
+        actor_changed = true;
+    }
+    if let Some(note) = form.note {
+        actor.summary = note;
+        actor_changed = true;
+    }
+    actor.clean();
+
+    // Bots are published as `Service` actors
+    if let Some(bot) = form.bot {
+        let actor_type = if bot { "Service" } else { "Person" };
+
+        if actor.r#type != actor_type {
+            actor.r#type = actor_type.into();
+            actor_changed = true;
+        }
+    }
+
+    // Keep the Emoji tags in sync with the emojis used in the display name and bio
+    let previous_emojis = emoji_shortcodes(&actor.tag);
+    emoji::replace_tags(&state, &mut actor.tag, &[&actor.name, &actor.summary]).await?;
+    if emoji_shortcodes(&actor.tag) != previous_emojis {
+        actor_changed = true;
+    }
+
+    if actor_changed {
+        db_actor.actor = serde_json::to_value(&actor)?;
+    }
+
+    db_actor.update(&state.db_pool).await?;
+
+    if actor_changed {
+        interactions::update_actor(&state, db_actor.clone()).await?;
+    }
+
+    let mut mastodon_account: Account = db_actor.clone().into_mastodon(&state).await?;
+    let mastodon_account_source: Source = db_actor.into_mastodon(&state).await?;
+
+    mastodon_account.source = Some(mastodon_account_source);
+
+    Ok(Json(mastodon_account).into_response())
+}
+
 async fn verify_credentials(
     Extension(state): Extension<ArcState>,
     Authorisation(db_actor): Authorisation,
@@ -124,7 +390,8 @@ pub fn routes() -> Router {
         .route("/accounts/:id/follow", post(follow))
         .route("/accounts/:id/following", get(following))
         .route("/accounts/:id/followers", get(followers))
-        //.route("/accounts/:id/statuses", get(statuses))
+        .route("/accounts/:id/statuses", get(statuses))
         .route("/accounts/:id/unfollow", post(unfollow))
+        .route("/accounts/update_credentials", patch(update_credentials))
         .route("/accounts/verify_credentials", get(verify_credentials))
 }
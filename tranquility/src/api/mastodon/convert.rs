@@ -1,16 +1,18 @@
 use crate::{
-    database::{Actor as DbActor, OAuthApplication, Object as DbObject},
+    database::{custom_emoji::CustomEmoji, Actor as DbActor, OAuthApplication, Object as DbObject},
     error::Error,
     format_uuid,
     state::ArcState,
+    util::{emoji, hashtag},
 };
 use async_trait::async_trait;
 use axum::response::IntoResponse;
 use itertools::Itertools;
+use ormx::Table;
 use serde::Serialize;
 use tranquility_types::{
-    activitypub::{Activity, Actor, Object},
-    mastodon::{Account, App, Source, Status},
+    activitypub::{Activity, Actor, Object, Tag as ApTag},
+    mastodon::{poll::PollOption, Account, App, Emoji, Poll, Source, Status, Tag},
 };
 use url::Url;
 
@@ -27,12 +29,40 @@ where
     async fn into_mastodon(self, state: &ArcState) -> Result<ApiEntity, Self::Error>;
 }
 
+/// Convert the Emoji tags of an object or actor owned by the owner into Mastodon emojis
+///
+/// Emojis that weren't saved (yet) link to the images given in their tags
+async fn tag_emojis(
+    state: &ArcState,
+    owner_url: &str,
+    tags: &[ApTag],
+) -> Result<Vec<Emoji>, Error> {
+    let mut emojis = Vec::new();
+    for tag_emoji in tags.iter().filter_map(ApTag::emoji) {
+        let emoji = match crate::activitypub::emoji::lookup(state, owner_url, tag_emoji).await? {
+            Some(custom_emoji) => custom_emoji.into_mastodon(state).await?,
+            None => Emoji {
+                shortcode: tag_emoji.shortcode().to_string(),
+                url: tag_emoji.icon.url.clone(),
+                static_url: tag_emoji.icon.url.clone(),
+                ..Emoji::default()
+            },
+        };
+
+        emojis.push(emoji);
+    }
+
+    Ok(emojis)
+}
+
 #[async_trait]
 impl IntoMastodon<Account> for DbActor {
     type Error = Error;
 
-    async fn into_mastodon(self, _state: &ArcState) -> Result<Account, Self::Error> {
+    async fn into_mastodon(self, state: &ArcState) -> Result<Account, Self::Error> {
         let actor: Actor = serde_json::from_value(self.actor)?;
+        let bot = actor.is_bot();
+        let group = actor.is_group();
 
         let id = format_uuid!(self.id);
         let username = actor.username;
@@ -61,17 +91,24 @@ impl IntoMastodon<Account> for DbActor {
             .map(|attachment| attachment.url)
             .unwrap_or_default();
 
+        let emojis = tag_emojis(state, &url, &actor.tag).await?;
+
         let account = Account {
             id,
             username,
             acct,
             display_name,
 
+            bot,
+            group,
+
             avatar_static: avatar.clone(),
             avatar,
 
             header_static: header.clone(),
             header,
+
+            emojis,
             ..Account::default()
         };
 
@@ -104,12 +141,80 @@ impl IntoMastodon<Status> for DbObject {
     type Error = Error;
 
     async fn into_mastodon(self, state: &ArcState) -> Result<Status, Self::Error> {
+        // Announces are represented as statuses wrapping the announced status
+        if self.data["type"] == "Announce" {
+            let activity: Activity = serde_json::from_value(self.data)?;
+            let object_url = activity.object.object_url().ok_or(Error::UnknownActivity)?;
+
+            let reblogged_db_object = DbObject::by_url(&state.db_pool, object_url).await?;
+            let reblog: Status = reblogged_db_object.into_mastodon(state).await?;
+
+            let db_actor = DbActor::by_id(&state.db_pool, self.owner_id).await?;
+            let account = db_actor.into_mastodon(state).await?;
+
+            let status = Status {
+                id: format_uuid!(self.id),
+                created_at: activity.published,
+
+                visibility: reblog.visibility.clone(),
+
+                uri: activity.id.clone(),
+                url: activity.id,
+
+                application: super::DEFAULT_APPLICATION.clone(),
+                account,
+
+                reblog: Some(Box::new(reblog)),
+                ..Status::default()
+            };
+
+            return Ok(status);
+        }
+
         let activity_or_object: Object = serde_json::from_value(self.data)?;
 
         activity_or_object.into_mastodon(state).await
     }
 }
 
+#[async_trait]
+impl IntoMastodon<Poll> for DbObject {
+    type Error = Error;
+
+    async fn into_mastodon(self, _state: &ArcState) -> Result<Poll, Self::Error> {
+        let object: Object = serde_json::from_value(self.data)?;
+        let options = object.poll_options().ok_or(Error::InvalidRequest)?;
+
+        let votes_count = options
+            .iter()
+            .map(|option| option.replies.total_items)
+            .sum();
+        let options = options
+            .iter()
+            .map(|option| PollOption {
+                title: option.name.clone(),
+                votes_count: option.replies.total_items,
+            })
+            .collect();
+
+        let poll = Poll {
+            id: format_uuid!(self.id),
+
+            expires_at: object.end_time,
+            expired: !crate::activitypub::poll::is_open(&object),
+
+            multiple: object.any_of.is_some(),
+            votes_count,
+            voters_count: object.voters_count,
+
+            options,
+            ..Poll::default()
+        };
+
+        Ok(poll)
+    }
+}
+
 #[async_trait]
 impl IntoMastodon<Vec<Account>> for Vec<DbObject> {
     type Error = Error;
@@ -146,6 +251,45 @@ impl IntoMastodon<Vec<Account>> for Vec<DbObject> {
     }
 }
 
+#[async_trait]
+impl IntoMastodon<Vec<Account>> for Vec<DbActor> {
+    type Error = Error;
+
+    async fn into_mastodon(self, state: &ArcState) -> Result<Vec<Account>, Self::Error> {
+        let account_futures = self
+            .into_iter()
+            .map(|db_actor| IntoMastodon::<Account>::into_mastodon(db_actor, state));
+
+        let accounts = futures_util::future::join_all(account_futures)
+            .await
+            .into_iter()
+            .try_collect()?;
+
+        Ok(accounts)
+    }
+}
+
+#[async_trait]
+impl IntoMastodon<Emoji> for CustomEmoji {
+    type Error = Error;
+
+    async fn into_mastodon(self, state: &ArcState) -> Result<Emoji, Self::Error> {
+        let url = emoji::image_url(&state.config, self.id);
+
+        let emoji = Emoji {
+            shortcode: self.shortcode,
+
+            static_url: url.clone(),
+            url,
+
+            visible_in_picker: self.visible_in_picker,
+            category: self.category,
+        };
+
+        Ok(emoji)
+    }
+}
+
 #[async_trait]
 impl IntoMastodon<App> for OAuthApplication {
     type Error = Error;
@@ -186,10 +330,39 @@ impl IntoMastodon<Status> for Object {
         let application = super::DEFAULT_APPLICATION.clone();
         let account = db_actor.into_mastodon(state).await?;
 
+        let parent = match self.in_reply_to {
+            Some(ref in_reply_to) => DbObject::by_url(&state.db_pool, in_reply_to).await.ok(),
+            None => None,
+        };
+        let in_reply_to_id = parent.as_ref().map(|parent| format_uuid!(parent.id));
+        let in_reply_to_account_id = parent.map(|parent| format_uuid!(parent.owner_id));
+
+        let poll = match self.poll_options() {
+            Some(_) => Some(db_object.into_mastodon(state).await?),
+            None => None,
+        };
+
+        let tags = self
+            .tag
+            .iter()
+            .filter_map(|tag| tag.hashtag_name())
+            .map(hashtag::normalise)
+            .unique()
+            .map(|name| Tag {
+                url: hashtag::url(&state.config, &name),
+                name,
+                ..Tag::default()
+            })
+            .collect();
+        let emojis = tag_emojis(state, &self.attributed_to, &self.tag).await?;
+
         let status = Status {
             id,
             created_at: self.published,
 
+            in_reply_to_id,
+            in_reply_to_account_id,
+
             sensitive: self.sensitive,
             spoiler_text: self.summary,
             visibility: "public".into(),
@@ -202,6 +375,9 @@ impl IntoMastodon<Status> for Object {
             application,
             account,
 
+            poll,
+            tags,
+            emojis,
             ..Status::default()
         };
 
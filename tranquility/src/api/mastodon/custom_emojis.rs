@@ -0,0 +1,19 @@
+use super::convert::IntoMastodon;
+use crate::{database::custom_emoji::CustomEmoji, error::Error, state::ArcState};
+use axum::{response::IntoResponse, routing::get, Extension, Json, Router};
+use tranquility_types::mastodon::Emoji;
+
+async fn custom_emojis(Extension(state): Extension<ArcState>) -> Result<impl IntoResponse, Error> {
+    let custom_emojis = CustomEmoji::local(&state.db_pool).await?;
+
+    let emoji_futures = custom_emojis
+        .into_iter()
+        .map(|custom_emoji| IntoMastodon::<Emoji>::into_mastodon(custom_emoji, &state));
+    let emojis = futures_util::future::try_join_all(emoji_futures).await?;
+
+    Ok(Json(emojis))
+}
+
+pub fn routes() -> Router {
+    Router::new().route("/custom_emojis", get(custom_emojis))
+}
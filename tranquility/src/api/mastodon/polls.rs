@@ -0,0 +1,128 @@
+use super::convert::IntoMastodon;
+use crate::{
+    activitypub::interactions,
+    api::Authorisation,
+    consts::MAX_BODY_SIZE,
+    database::{poll_vote::PollVote, statuses, Object as DbObject},
+    error::Error,
+    state::ArcState,
+    util::Form,
+};
+use axum::{
+    extract::{ContentLengthLimit, Path},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use ormx::Table;
+use serde::Deserialize;
+use tranquility_types::{activitypub::Object, mastodon::Poll};
+use uuid::Uuid;
+
+/// Convert the poll into its Mastodon representation, including the votes of the viewer
+async fn poll_with_votes(
+    state: &ArcState,
+    db_object: DbObject,
+    viewer_id: Option<Uuid>,
+) -> Result<Poll, Error> {
+    let object_id = db_object.id;
+    let owner_id = db_object.owner_id;
+    let mut poll: Poll = db_object.into_mastodon(state).await?;
+
+    if let Some(viewer_id) = viewer_id {
+        let own_votes = PollVote::choices(&state.db_pool, object_id, viewer_id)
+            .await?
+            .into_iter()
+            .map(i64::from)
+            .collect::<Vec<_>>();
+
+        // Authors can't vote on their own polls, so they are treated as if they already voted
+        poll.voted = Some(!own_votes.is_empty() || viewer_id == owner_id);
+        poll.own_votes = Some(own_votes);
+    }
+
+    Ok(poll)
+}
+
+async fn poll(
+    Path(id): Path<Uuid>,
+    Extension(state): Extension<ArcState>,
+    authorized_db_actor: Option<Authorisation>,
+) -> Result<impl IntoResponse, Error> {
+    let viewer_url = authorized_db_actor
+        .as_ref()
+        .and_then(|authorized_db_actor| authorized_db_actor.actor["id"].as_str());
+    if !statuses::is_visible(&state.db_pool, id, viewer_url).await? {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    let db_object = DbObject::get(&state.db_pool, id).await?;
+    let object: Object = serde_json::from_value(db_object.data.clone())?;
+    if object.poll_options().is_none() {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    let viewer_id = authorized_db_actor.map(|authorized_db_actor| authorized_db_actor.id);
+    let poll = poll_with_votes(&state, db_object, viewer_id).await?;
+
+    Ok(Json(poll).into_response())
+}
+
+/// Index of a chosen option. Some clients send the indices as strings
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Choice {
+    Index(usize),
+    Text(String),
+}
+
+impl TryFrom<Choice> for usize {
+    type Error = Error;
+
+    fn try_from(choice: Choice) -> Result<Self, Self::Error> {
+        match choice {
+            Choice::Index(index) => Ok(index),
+            Choice::Text(text) => text.parse().map_err(|_| Error::InvalidRequest),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct VoteForm {
+    choices: Vec<Choice>,
+}
+
+async fn vote(
+    Path(id): Path<Uuid>,
+    Extension(state): Extension<ArcState>,
+    Authorisation(voter_db): Authorisation,
+    ContentLengthLimit(Form(form)): ContentLengthLimit<Form<VoteForm>, MAX_BODY_SIZE>,
+) -> Result<impl IntoResponse, Error> {
+    let choices = form
+        .choices
+        .into_iter()
+        .map(usize::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let voter_url = voter_db.actor["id"].as_str();
+    if !statuses::is_visible(&state.db_pool, id, voter_url).await? {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    let db_object = DbObject::get(&state.db_pool, id).await?;
+    let voter_id = voter_db.id;
+    interactions::vote(&state, voter_db, db_object, &choices).await?;
+
+    // Get the poll again, the vote counts of local polls changed
+    let db_object = DbObject::get(&state.db_pool, id).await?;
+    let poll = poll_with_votes(&state, db_object, Some(voter_id)).await?;
+
+    Ok(Json(poll).into_response())
+}
+
+pub fn routes() -> Router {
+    Router::new()
+        .route("/polls/:id", get(poll))
+        .route("/polls/:id/votes", post(vote))
+}
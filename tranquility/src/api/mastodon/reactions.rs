@@ -0,0 +1,166 @@
+use super::convert::IntoMastodon;
+use crate::{
+    activitypub::interactions,
+    api::Authorisation,
+    database::{reaction::Reaction, statuses, Actor as DbActor, Object as DbObject},
+    error::Error,
+    state::ArcState,
+};
+use axum::{
+    extract::Path, http::StatusCode, response::IntoResponse, routing::get, Extension, Json, Router,
+};
+use itertools::Itertools;
+use ormx::Table;
+use std::collections::HashMap;
+use tranquility_types::{
+    activitypub::{Actor, IsPrivate, Object},
+    mastodon::{Account, EmojiReaction, Status},
+};
+use uuid::Uuid;
+
+/// Get the reactions to the object grouped by their emoji (optionally only the ones with the emoji)
+///
+/// The emojis are ordered by their first use
+async fn grouped_reactions(
+    state: &ArcState,
+    object_id: Uuid,
+    viewer_id: Option<Uuid>,
+    name: Option<&str>,
+) -> Result<Vec<EmojiReaction>, Error> {
+    let reactions = Reaction::by_object(&state.db_pool, object_id, name).await?;
+
+    let mut groups: Vec<Vec<Reaction>> = Vec::new();
+    for reaction in reactions {
+        match groups
+            .iter_mut()
+            .find(|group| group[0].name == reaction.name)
+        {
+            Some(group) => group.push(reaction),
+            None => groups.push(vec![reaction]),
+        }
+    }
+
+    // Load all the reacting actors at once instead of one query per reaction
+    let actor_ids = groups
+        .iter()
+        .flatten()
+        .map(|reaction| reaction.actor_id)
+        .unique()
+        .collect_vec();
+    let db_actors: HashMap<Uuid, DbActor> = DbActor::by_ids(&state.db_pool, &actor_ids)
+        .await?
+        .into_iter()
+        .map(|db_actor| (db_actor.id, db_actor))
+        .collect();
+
+    let mut emoji_reactions = Vec::with_capacity(groups.len());
+    for group in groups {
+        let me = viewer_id
+            .is_some_and(|viewer_id| group.iter().any(|reaction| reaction.actor_id == viewer_id));
+
+        let account_futures = group
+            .iter()
+            .filter_map(|reaction| db_actors.get(&reaction.actor_id).cloned())
+            .map(|db_actor| IntoMastodon::<Account>::into_mastodon(db_actor, state));
+        let accounts = futures_util::future::try_join_all(account_futures).await?;
+
+        emoji_reactions.push(EmojiReaction {
+            name: group[0].name.clone(),
+            count: i64::try_from(group.len()).unwrap_or(i64::MAX),
+            me,
+            accounts,
+            url: group[0].emoji_url.clone(),
+        });
+    }
+
+    Ok(emoji_reactions)
+}
+
+/// Get the object if it can be shown publicly
+async fn public_object(state: &ArcState, id: Uuid) -> Result<Option<DbObject>, Error> {
+    let db_object = DbObject::get(&state.db_pool, id).await?;
+    let object: Object = serde_json::from_value(db_object.data.clone())?;
+
+    // Do not expose private objects publicly
+    if object.is_private() {
+        return Ok(None);
+    }
+
+    Ok(Some(db_object))
+}
+
+async fn reactions(
+    Path(id): Path<Uuid>,
+    Extension(state): Extension<ArcState>,
+    authorized_db_actor: Option<Authorisation>,
+) -> Result<impl IntoResponse, Error> {
+    let Some(db_object) = public_object(&state, id).await? else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+
+    let viewer_id = authorized_db_actor.map(|authorized_db_actor| authorized_db_actor.id);
+    let emoji_reactions = grouped_reactions(&state, db_object.id, viewer_id, None).await?;
+
+    Ok(Json(emoji_reactions).into_response())
+}
+
+async fn reactions_by_emoji(
+    Path((id, emoji)): Path<(Uuid, String)>,
+    Extension(state): Extension<ArcState>,
+    authorized_db_actor: Option<Authorisation>,
+) -> Result<impl IntoResponse, Error> {
+    let Some(db_object) = public_object(&state, id).await? else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+
+    let viewer_id = authorized_db_actor.map(|authorized_db_actor| authorized_db_actor.id);
+    let name = emoji.trim_matches(':');
+    let emoji_reactions = grouped_reactions(&state, db_object.id, viewer_id, Some(name)).await?;
+
+    Ok(Json(emoji_reactions).into_response())
+}
+
+async fn react(
+    Path((id, emoji)): Path<(Uuid, String)>,
+    Extension(state): Extension<ArcState>,
+    Authorisation(reactor_db): Authorisation,
+) -> Result<impl IntoResponse, Error> {
+    let reactor: Actor = serde_json::from_value(reactor_db.actor.clone())?;
+    if !statuses::is_visible(&state.db_pool, id, Some(reactor.id.as_str())).await? {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    let db_object = DbObject::get(&state.db_pool, id).await?;
+    interactions::react(&state, reactor_db, db_object.clone(), &emoji).await?;
+
+    let mastodon_status: Status = db_object.into_mastodon(&state).await?;
+
+    Ok(Json(mastodon_status).into_response())
+}
+
+async fn unreact(
+    Path((id, emoji)): Path<(Uuid, String)>,
+    Extension(state): Extension<ArcState>,
+    Authorisation(reactor_db): Authorisation,
+) -> Result<impl IntoResponse, Error> {
+    let reactor: Actor = serde_json::from_value(reactor_db.actor.clone())?;
+    if !statuses::is_visible(&state.db_pool, id, Some(reactor.id.as_str())).await? {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    let db_object = DbObject::get(&state.db_pool, id).await?;
+    interactions::unreact(&state, reactor_db, db_object.clone(), &emoji).await?;
+
+    let mastodon_status: Status = db_object.into_mastodon(&state).await?;
+
+    Ok(Json(mastodon_status).into_response())
+}
+
+pub fn routes() -> Router {
+    Router::new()
+        .route("/pleroma/statuses/:id/reactions", get(reactions))
+        .route(
+            "/pleroma/statuses/:id/reactions/:emoji",
+            get(reactions_by_emoji).put(react).delete(unreact),
+        )
+}
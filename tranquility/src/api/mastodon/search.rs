@@ -0,0 +1,236 @@
+use super::convert::IntoMastodon;
+use crate::{
+    activitypub::fetcher::{self, Entity, FetchContext},
+    api::Authorisation,
+    consts::{
+        mastodon::{DEFAULT_SEARCH_LIMIT, MAX_SEARCH_LIMIT},
+        regex::ACCOUNT_HANDLE,
+    },
+    database::{Actor as DbActor, Object as DbObject},
+    error::Error,
+    regex,
+    state::ArcState,
+    util::hashtag,
+    well_known::webfinger,
+};
+use axum::{extract::Query, response::IntoResponse, routing::get, Extension, Json, Router};
+use serde::Deserialize;
+use tranquility_types::{
+    activitypub::{Actor, IsPrivate, Object},
+    mastodon::{Account, Search, Status, Tag},
+};
+
+regex!(ACCOUNT_HANDLE_REGEX = ACCOUNT_HANDLE);
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    /// Only search for this type of results (`accounts`, `statuses` or `hashtags`)
+    r#type: Option<String>,
+    /// Fetch remote accounts and posts that aren't known yet
+    #[serde(default)]
+    resolve: bool,
+    limit: Option<i64>,
+    #[serde(default)]
+    offset: i64,
+}
+
+impl SearchQuery {
+    /// Check whether the results of the type were requested
+    fn includes(&self, r#type: &str) -> bool {
+        match self.r#type.as_deref() {
+            Some(requested_type) => requested_type == r#type,
+            None => true,
+        }
+    }
+
+    /// Get the requested amount of results per type, clamped to the allowed range
+    fn limit(&self) -> i64 {
+        self.limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .clamp(1, MAX_SEARCH_LIMIT)
+    }
+
+    fn offset(&self) -> i64 {
+        self.offset.max(0)
+    }
+}
+
+/// Look up the account or post with the URL
+///
+/// If resolving is allowed, unknown accounts and posts are fetched from their instances
+async fn search_url(
+    state: &ArcState,
+    query: &SearchQuery,
+    url: &str,
+    resolve: bool,
+) -> Result<Search, Error> {
+    let mut search = Search::default();
+
+    let entity_url = if resolve {
+        match fetcher::fetch_any(state, &FetchContext::default(), url).await {
+            Ok(Entity::Actor(actor)) => actor.id,
+            Ok(Entity::Object(object)) => object.id,
+            Ok(Entity::Activity(_)) => return Ok(search),
+            Err(err) => {
+                debug!(error = ?err, "Couldn't resolve the URL");
+                return Ok(search);
+            }
+        }
+    } else {
+        url.to_string()
+    };
+
+    if let Ok(db_actor) = DbActor::by_url(&state.db_pool, &entity_url).await {
+        if query.includes("accounts") {
+            search.accounts.push(db_actor.into_mastodon(state).await?);
+        }
+    } else if let Ok(db_object) = DbObject::by_url(&state.db_pool, &entity_url).await {
+        let is_note = matches!(db_object.data["type"].as_str(), Some("Note" | "Question"));
+        let object: Object = serde_json::from_value(db_object.data.clone())?;
+
+        // Do not expose private objects publicly
+        if query.includes("statuses") && is_note && !object.is_private() {
+            search.statuses.push(db_object.into_mastodon(state).await?);
+        }
+    }
+
+    Ok(search)
+}
+
+/// Search the accounts by their username or display name
+///
+/// Handles of remote accounts (`user@domain`) are resolved via webfinger if resolving is allowed
+async fn search_accounts(
+    state: &ArcState,
+    query: &SearchQuery,
+    text: &str,
+    resolve: bool,
+) -> Result<Vec<Account>, Error> {
+    let db_actors = match ACCOUNT_HANDLE_REGEX.captures(text) {
+        Some(captures) => {
+            let username = captures.name("username").unwrap().as_str();
+            let domain = captures.name("domain").unwrap().as_str();
+
+            // Resolved accounts are saved and therefore found by the search
+            if resolve && !domain.eq_ignore_ascii_case(&state.config.instance.domain) {
+                if let Err(err) = webfinger::fetch_actor(state, username, domain).await {
+                    debug!(error = ?err, "Couldn't resolve the account");
+                }
+            }
+
+            crate::database::search::accounts(
+                &state.db_pool,
+                username,
+                Some(domain),
+                query.limit(),
+                query.offset(),
+            )
+            .await?
+        }
+        None => {
+            crate::database::search::accounts(
+                &state.db_pool,
+                text.trim_start_matches('@'),
+                None,
+                query.limit(),
+                query.offset(),
+            )
+            .await?
+        }
+    };
+
+    db_actors.into_mastodon(state).await
+}
+
+/// Search the posts the viewer interacted with by their content
+async fn search_statuses(
+    state: &ArcState,
+    query: &SearchQuery,
+    text: &str,
+    viewer_db: DbActor,
+) -> Result<Vec<Status>, Error> {
+    let viewer: Actor = serde_json::from_value(viewer_db.actor)?;
+
+    let db_objects = crate::database::search::statuses(
+        &state.db_pool,
+        viewer_db.id,
+        viewer.id.as_str(),
+        text,
+        query.limit(),
+        query.offset(),
+    )
+    .await?;
+
+    let status_futures = db_objects
+        .into_iter()
+        .map(|db_object| IntoMastodon::<Status>::into_mastodon(db_object, state));
+
+    futures_util::future::try_join_all(status_futures).await
+}
+
+/// Search the hashtags used by posts whose names start with the text
+async fn search_hashtags(
+    state: &ArcState,
+    query: &SearchQuery,
+    text: &str,
+) -> Result<Vec<Tag>, Error> {
+    let prefix = text.trim_start_matches('#');
+    if prefix.is_empty() || !prefix.chars().all(|ch| ch.is_alphanumeric() || ch == '_') {
+        return Ok(Vec::new());
+    }
+
+    let names =
+        crate::database::search::hashtags(&state.db_pool, prefix, query.limit(), query.offset())
+            .await?;
+    let tags = names
+        .into_iter()
+        .map(|name| Tag {
+            url: hashtag::url(&state.config, &name),
+            name,
+            ..Tag::default()
+        })
+        .collect();
+
+    Ok(tags)
+}
+
+async fn search(
+    Extension(state): Extension<ArcState>,
+    authorized_db_actor: Option<Authorisation>,
+    Query(query): Query<SearchQuery>,
+) -> Result<impl IntoResponse, Error> {
+    let text = query.q.trim();
+    if text.is_empty() {
+        return Ok(Json(Search::default()));
+    }
+
+    // Only logged-in users can make the server fetch remote accounts and posts
+    let resolve = query.resolve && authorized_db_actor.is_some();
+
+    if text.starts_with("https://") || text.starts_with("http://") {
+        let search = search_url(&state, &query, text, resolve).await?;
+
+        return Ok(Json(search));
+    }
+
+    let mut search = Search::default();
+    if query.includes("accounts") {
+        search.accounts = search_accounts(&state, &query, text, resolve).await?;
+    }
+    // Posts are only searched for logged-in users because only the posts they interacted with are searched
+    if let Some(Authorisation(viewer_db)) = authorized_db_actor {
+        if query.includes("statuses") {
+            search.statuses = search_statuses(&state, &query, text, viewer_db).await?;
+        }
+    }
+    if query.includes("hashtags") {
+        search.hashtags = search_hashtags(&state, &query, text).await?;
+    }
+
+    Ok(Json(search))
+}
+
+pub fn routes() -> Router {
+    Router::new().route("/search", get(search))
+}
@@ -1,23 +1,88 @@
-use super::{convert::IntoMastodon, Authorisation};
+use super::convert::IntoMastodon;
 use crate::{
-    activitypub::Clean,
-    consts::MAX_BODY_SIZE,
-    database::{InsertExt, InsertObject},
+    activitypub::{context_crawl, interactions, Clean},
+    api::Authorisation,
+    consts::{
+        activitypub::MAX_FETCH_DEPTH,
+        mastodon::{
+            MAX_POLL_EXPIRATION, MAX_POLL_OPTIONS, MAX_POLL_OPTION_CHARS, MIN_POLL_EXPIRATION,
+        },
+        MAX_BODY_SIZE,
+    },
+    database::{InsertExt, InsertObject, Object as DbObject},
     error::Error,
     state::ArcState,
-    util::{mention::FormatMention, Form},
+    util::{emoji, hashtag::FormatHashtag, mention::FormatMention, Form},
 };
 use axum::{
-    extract::ContentLengthLimit, http::StatusCode, response::IntoResponse, routing::post,
+    extract::{ContentLengthLimit, Path},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
     Extension, Json, Router,
 };
+use itertools::Itertools;
+use ormx::Table;
 use serde::Deserialize;
-use std::sync::Arc;
-use tranquility_types::activitypub::{Actor, PUBLIC_IDENTIFIER};
+use std::{collections::HashSet, sync::Arc};
+use time::Duration;
+use tranquility_types::{
+    activitypub::{
+        Actor, IsPrivate, Object, QuestionOption, QuestionOptionReplies, PUBLIC_IDENTIFIER,
+    },
+    mastodon::{Context, Status},
+};
+use uuid::Uuid;
 
 #[cfg(feature = "markdown")]
 use crate::api::ParseMarkdown;
 
+#[derive(Deserialize)]
+struct PollForm {
+    options: Vec<String>,
+    /// Duration of the poll in seconds
+    expires_in: i64,
+
+    #[serde(default)]
+    multiple: bool,
+}
+
+impl PollForm {
+    /// Check whether the poll is within the limits
+    fn is_valid(&self) -> bool {
+        (2..=MAX_POLL_OPTIONS).contains(&self.options.len())
+            && self.options.iter().all(|option| {
+                !option.trim().is_empty() && option.chars().count() <= MAX_POLL_OPTION_CHARS
+            })
+            && (MIN_POLL_EXPIRATION..=MAX_POLL_EXPIRATION).contains(&self.expires_in)
+    }
+
+    /// Attach the poll to the object, turning it into a `Question`
+    fn attach(self, object: &mut Object) {
+        let options = self
+            .options
+            .into_iter()
+            .map(|name| QuestionOption {
+                r#type: "Note".into(),
+                name,
+                replies: QuestionOptionReplies {
+                    r#type: "Collection".into(),
+                    total_items: 0,
+                },
+            })
+            .collect();
+
+        object.r#type = "Question".into();
+        if self.multiple {
+            object.any_of = Some(options);
+        } else {
+            object.one_of = Some(options);
+        }
+        object.end_time = Some(object.published + Duration::seconds(self.expires_in));
+        object.voters_count = Some(0);
+    }
+}
+
 #[derive(Deserialize)]
 struct CreateForm {
     status: String,
@@ -26,6 +91,8 @@ struct CreateForm {
     sensitive: bool,
     #[serde(default)]
     spoiler_text: String,
+
+    poll: Option<PollForm>,
 }
 
 async fn create(
@@ -36,6 +103,9 @@ async fn create(
     if state.config.instance.character_limit < form.status.chars().count() {
         return Ok((StatusCode::BAD_REQUEST, "Status too long").into_response());
     }
+    if form.poll.as_ref().is_some_and(|poll| !poll.is_valid()) {
+        return Ok((StatusCode::BAD_REQUEST, "Invalid poll").into_response());
+    }
 
     let author: Actor = serde_json::from_value(author_db.actor)?;
 
@@ -52,6 +122,9 @@ async fn create(
     );
 
     object.format_mentions(Arc::clone(&state)).await;
+    object.format_hashtags(&state.config);
+    let emoji_tags = emoji::local_tags(&state, &[&form.status, &form.spoiler_text]).await?;
+    object.tag.extend(emoji_tags);
 
     // Parse the markdown if the feature is enabled
     #[cfg(feature = "markdown")]
@@ -59,6 +132,10 @@ async fn create(
 
     object.clean();
 
+    if let Some(poll) = form.poll {
+        poll.attach(&mut object);
+    }
+
     let object_value = serde_json::to_value(&object)?;
 
     InsertObject {
@@ -69,7 +146,7 @@ async fn create(
     .insert(&state.db_pool)
     .await?;
 
-    let (_create_activity_id, create_activity) = crate::activitypub::instantiate::activity(
+    let (create_activity_id, create_activity) = crate::activitypub::instantiate::activity(
         &state.config,
         "Create",
         author.id.as_str(),
@@ -78,12 +155,160 @@ async fn create(
         object.cc.clone(),
     );
 
+    // Save the activity as well, otherwise the post won't show up in the outbox
+    let create_activity_value = serde_json::to_value(&create_activity)?;
+
+    InsertObject {
+        id: create_activity_id,
+        owner_id: author_db.id,
+        data: create_activity_value,
+    }
+    .insert(&state.db_pool)
+    .await?;
+
     crate::activitypub::deliverer::deliver(create_activity, Arc::clone(&state)).await?;
 
     let mastodon_status = object.into_mastodon(&state).await?;
     Ok(Json(&mastodon_status).into_response())
 }
 
+/// Get the stored posts the object is replying to (oldest post first)
+///
+/// Stops at the first post that isn't stored
+async fn ancestors(state: &ArcState, object: &Object) -> Result<Vec<Object>, Error> {
+    let mut ancestors = Vec::new();
+    let mut visited = HashSet::from([object.id.clone()]);
+    let mut parent_url = object.in_reply_to.clone();
+
+    while let Some(url) = parent_url.take() {
+        if ancestors.len() >= MAX_FETCH_DEPTH || !visited.insert(url.clone()) {
+            break;
+        }
+
+        let Ok(parent) = DbObject::by_url(&state.db_pool, &url).await else {
+            break;
+        };
+        let parent: Object = serde_json::from_value(parent.data)?;
+
+        parent_url = parent.in_reply_to.clone();
+        ancestors.push(parent);
+    }
+
+    ancestors.reverse();
+    Ok(ancestors)
+}
+
+/// Get the replies to the object that weren't visited yet (oldest reply first)
+async fn unvisited_replies(
+    state: &ArcState,
+    url: &str,
+    visited: &mut HashSet<String>,
+) -> Result<Vec<Object>, Error> {
+    let replies = DbObject::replies(&state.db_pool, url).await?;
+    let mut replies: Vec<Object> = replies
+        .into_iter()
+        .map(|reply| serde_json::from_value(reply.data))
+        .try_collect()?;
+    replies.retain(|reply| visited.insert(reply.id.clone()));
+
+    Ok(replies)
+}
+
+/// Get the replies to the object and their replies (depth-first, oldest reply first)
+async fn descendants(state: &ArcState, object: &Object) -> Result<Vec<Object>, Error> {
+    let mut descendants = Vec::new();
+    let mut visited = HashSet::from([object.id.clone()]);
+
+    // Push in reverse so the oldest reply gets popped first
+    let replies = unvisited_replies(state, &object.id, &mut visited).await?;
+    let mut stack: Vec<(Object, usize)> =
+        replies.into_iter().rev().map(|reply| (reply, 1)).collect();
+
+    while let Some((reply, depth)) = stack.pop() {
+        if depth < MAX_FETCH_DEPTH {
+            let replies = unvisited_replies(state, &reply.id, &mut visited).await?;
+            stack.extend(replies.into_iter().rev().map(|reply| (reply, depth + 1)));
+        }
+
+        descendants.push(reply);
+    }
+
+    Ok(descendants)
+}
+
+/// Get the stored ancestors and descendants of the status
+///
+/// Requests of logged-in users also crawl the missing posts in the background, so they show up on the next request
+async fn context(
+    Path(id): Path<Uuid>,
+    Extension(state): Extension<ArcState>,
+    authorized_db_actor: Option<Authorisation>,
+) -> Result<impl IntoResponse, Error> {
+    let db_object = DbObject::get(&state.db_pool, id).await?;
+    let object: Object = serde_json::from_value(db_object.data)?;
+
+    // Do not expose private objects publicly
+    if object.is_private() {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    if authorized_db_actor.is_some() {
+        context_crawl::spawn(&state, db_object.id, object.clone());
+    }
+
+    let ancestors = ancestors(&state, &object).await?;
+    let descendants = descendants(&state, &object).await?;
+
+    let state_ref = &state;
+    let into_statuses = move |objects: Vec<Object>| {
+        let status_futures = objects
+            .into_iter()
+            .filter(|object| !object.is_private())
+            .map(move |object| IntoMastodon::<Status>::into_mastodon(object, state_ref));
+
+        futures_util::future::try_join_all(status_futures)
+    };
+
+    let context = Context {
+        ancestors: into_statuses(ancestors).await?,
+        descendants: into_statuses(descendants).await?,
+    };
+
+    Ok(Json(&context).into_response())
+}
+
+async fn pin(
+    Path(id): Path<Uuid>,
+    Extension(state): Extension<ArcState>,
+    Authorisation(author_db): Authorisation,
+) -> Result<impl IntoResponse, Error> {
+    let db_object = DbObject::get(&state.db_pool, id).await?;
+    interactions::pin(&state, author_db, db_object.clone()).await?;
+
+    let mut mastodon_status: Status = db_object.into_mastodon(&state).await?;
+    mastodon_status.pinned = Some(true);
+
+    Ok(Json(mastodon_status))
+}
+
+async fn unpin(
+    Path(id): Path<Uuid>,
+    Extension(state): Extension<ArcState>,
+    Authorisation(author_db): Authorisation,
+) -> Result<impl IntoResponse, Error> {
+    let db_object = DbObject::get(&state.db_pool, id).await?;
+    interactions::unpin(&state, author_db, db_object.clone()).await?;
+
+    let mut mastodon_status: Status = db_object.into_mastodon(&state).await?;
+    mastodon_status.pinned = Some(false);
+
+    Ok(Json(mastodon_status))
+}
+
 pub fn routes() -> Router {
-    Router::new().route("/statuses", post(create))
+    Router::new()
+        .route("/statuses", post(create))
+        .route("/statuses/:id/context", get(context))
+        .route("/statuses/:id/pin", post(pin))
+        .route("/statuses/:id/unpin", post(unpin))
 }
@@ -0,0 +1,90 @@
+use crate::{
+    api::Authorisation, database::followed_tag::FollowedTag, error::Error, state::ArcState,
+    util::hashtag,
+};
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use tranquility_types::mastodon::Tag;
+
+/// Construct the Mastodon representation of the hashtag
+fn tag(state: &ArcState, name: &str, following: Option<bool>) -> Tag {
+    Tag {
+        name: hashtag::normalise(name),
+        url: hashtag::url(&state.config, name),
+        following,
+        ..Tag::default()
+    }
+}
+
+async fn get_tag(
+    Path(name): Path<String>,
+    Extension(state): Extension<ArcState>,
+    authorized_db_actor: Option<Authorisation>,
+) -> Result<impl IntoResponse, Error> {
+    if !hashtag::is_valid(&name) {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    let following = match authorized_db_actor {
+        Some(Authorisation(authorized_db_actor)) => {
+            Some(FollowedTag::is_following(&state.db_pool, authorized_db_actor.id, &name).await?)
+        }
+        None => None,
+    };
+
+    Ok(Json(tag(&state, &name, following)).into_response())
+}
+
+async fn follow(
+    Path(name): Path<String>,
+    Extension(state): Extension<ArcState>,
+    Authorisation(authorized_db_actor): Authorisation,
+) -> Result<impl IntoResponse, Error> {
+    if !hashtag::is_valid(&name) {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    FollowedTag::follow(&state.db_pool, authorized_db_actor.id, &name).await?;
+
+    Ok(Json(tag(&state, &name, Some(true))).into_response())
+}
+
+async fn unfollow(
+    Path(name): Path<String>,
+    Extension(state): Extension<ArcState>,
+    Authorisation(authorized_db_actor): Authorisation,
+) -> Result<impl IntoResponse, Error> {
+    if !hashtag::is_valid(&name) {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    FollowedTag::unfollow(&state.db_pool, authorized_db_actor.id, &name).await?;
+
+    Ok(Json(tag(&state, &name, Some(false))).into_response())
+}
+
+async fn followed_tags(
+    Extension(state): Extension<ArcState>,
+    Authorisation(authorized_db_actor): Authorisation,
+) -> Result<impl IntoResponse, Error> {
+    let tags = FollowedTag::by_actor(&state.db_pool, authorized_db_actor.id)
+        .await?
+        .into_iter()
+        .map(|followed_tag| tag(&state, &followed_tag.name, Some(true)))
+        .collect::<Vec<_>>();
+
+    Ok(Json(tags))
+}
+
+pub fn routes() -> Router {
+    Router::new()
+        .route("/followed_tags", get(followed_tags))
+        .route("/tags/:name", get(get_tag))
+        .route("/tags/:name/follow", post(follow))
+        .route("/tags/:name/unfollow", post(unfollow))
+}
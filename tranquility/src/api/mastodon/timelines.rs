@@ -0,0 +1,145 @@
+use super::convert::IntoMastodon;
+use crate::{
+    api::Authorisation,
+    consts::mastodon::{DEFAULT_STATUSES_LIMIT, MAX_STATUSES_LIMIT},
+    database::{Cursor, Object as DbObject},
+    error::Error,
+    format_uuid,
+    state::ArcState,
+    util::hashtag,
+};
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Extension, Json, Router,
+};
+use http::{
+    header::{HeaderValue, LINK},
+    HeaderMap,
+};
+use serde::Deserialize;
+use tranquility_types::{activitypub::Actor, mastodon::Status};
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+struct TimelineQuery {
+    max_id: Option<Uuid>,
+    min_id: Option<Uuid>,
+    limit: Option<i64>,
+}
+
+impl TimelineQuery {
+    /// Get the position the requested page starts at
+    fn cursor(&self) -> Cursor {
+        match (self.max_id, self.min_id) {
+            (None, Some(min_id)) => Cursor::After(Some(min_id)),
+            (max_id, _) => Cursor::Before(max_id),
+        }
+    }
+
+    /// Get the requested amount of statuses, clamped to the allowed range
+    fn limit(&self) -> i64 {
+        self.limit
+            .unwrap_or(DEFAULT_STATUSES_LIMIT)
+            .clamp(1, MAX_STATUSES_LIMIT)
+    }
+
+    /// Construct the URL of the page of the timeline in the direction of the cursor
+    fn page_url(&self, state: &ArcState, path: &str, cursor: Cursor) -> String {
+        let mut url = Url::parse(&format!("https://{}", state.config.instance.domain))
+            .expect("[Bug] Invalid instance URL");
+        url.path_segments_mut()
+            .expect("[Bug] Instance URL can't have a path")
+            .extend(path.split('/'));
+
+        {
+            let mut query_pairs = url.query_pairs_mut();
+            match cursor {
+                Cursor::Before(Some(id)) => {
+                    query_pairs.append_pair("max_id", &format_uuid!(id));
+                }
+                Cursor::After(Some(id)) => {
+                    query_pairs.append_pair("min_id", &format_uuid!(id));
+                }
+                Cursor::Before(None) | Cursor::After(None) => (),
+            }
+            query_pairs.append_pair("limit", &self.limit().to_string());
+        }
+
+        url.into()
+    }
+
+    /// Convert the page of the timeline into statuses and link to the next (older) and previous (newer) page
+    async fn respond(
+        &self,
+        state: &ArcState,
+        path: &str,
+        db_objects: Vec<DbObject>,
+    ) -> Result<(HeaderMap, Json<Vec<Status>>), Error> {
+        let mut headers = HeaderMap::new();
+        if let (Some(newest), Some(oldest)) = (db_objects.first(), db_objects.last()) {
+            let link = format!(
+                r#"<{}>; rel="next", <{}>; rel="prev""#,
+                self.page_url(state, path, Cursor::Before(Some(oldest.id))),
+                self.page_url(state, path, Cursor::After(Some(newest.id))),
+            );
+            headers.insert(LINK, HeaderValue::from_str(&link)?);
+        }
+
+        let status_futures = db_objects
+            .into_iter()
+            .map(|db_object| IntoMastodon::<Status>::into_mastodon(db_object, state));
+        let statuses = futures_util::future::try_join_all(status_futures).await?;
+
+        Ok((headers, Json(statuses)))
+    }
+}
+
+async fn home(
+    Extension(state): Extension<ArcState>,
+    Authorisation(authorized_db_actor): Authorisation,
+    Query(query): Query<TimelineQuery>,
+) -> Result<impl IntoResponse, Error> {
+    let actor: Actor = serde_json::from_value(authorized_db_actor.actor)?;
+
+    let db_objects = crate::database::statuses::home(
+        &state.db_pool,
+        authorized_db_actor.id,
+        actor.id.as_str(),
+        query.cursor(),
+        query.limit(),
+    )
+    .await?;
+
+    query
+        .respond(&state, "api/v1/timelines/home", db_objects)
+        .await
+}
+
+async fn tag(
+    Path(name): Path<String>,
+    Extension(state): Extension<ArcState>,
+    Query(query): Query<TimelineQuery>,
+) -> Result<impl IntoResponse, Error> {
+    if !hashtag::is_valid(&name) {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    let db_objects =
+        crate::database::statuses::by_hashtag(&state.db_pool, &name, query.cursor(), query.limit())
+            .await?;
+
+    let path = format!("api/v1/timelines/tag/{}", hashtag::normalise(&name));
+    let response = query.respond(&state, &path, db_objects).await?;
+
+    Ok(response.into_response())
+}
+
+pub fn routes() -> Router {
+    Router::new()
+        .route("/timelines/home", get(home))
+        .route("/timelines/tag/:hashtag", get(tag))
+}
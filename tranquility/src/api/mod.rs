@@ -1,10 +1,60 @@
-use axum::Router;
+use crate::{
+    database::{Actor, OAuthToken},
+    error::Error,
+    state::{ArcState, State},
+};
+use async_trait::async_trait;
+use axum::{
+    extract::{FromRequest, RequestParts},
+    Router,
+};
 use cfg_if::cfg_if;
+use headers::{authorization::Bearer, Authorization, HeaderMapExt};
+use std::ops::Deref;
 
-use crate::state::State;
+/// Authorisation extractor
+///
+/// It takes the `Authorization` header and tries to decodes it as an `Bearer` authorisation.  
+/// Then it fetches the actor associated with the token
+pub struct Authorisation(pub Actor);
+
+impl Deref for Authorisation {
+    type Target = Actor;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<B> FromRequest<B> for Authorisation
+where
+    B: Send,
+{
+    type Rejection = Error;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let credentials = req
+            .headers()
+            .typed_get::<Authorization<Bearer>>()
+            .ok_or(Error::Unauthorized)?;
+        let token = credentials.token();
+
+        let state = req
+            .extensions()
+            .get::<ArcState>()
+            .expect("[Bug] Missing state in extensions");
+
+        let access_token = OAuthToken::by_access_token(&state.db_pool, token).await?;
+        let actor = Actor::get(&state.db_pool, access_token.actor_id).await?;
+
+        Ok(Self(actor))
+    }
+}
 
 pub fn routes(state: &State) -> Router {
     let router = Router::new()
+        .merge(admin::routes())
         .merge(oauth::routes(state))
         .merge(register::routes(state));
 
@@ -39,5 +89,6 @@ cfg_if! {
 #[cfg(feature = "mastodon-api")]
 pub mod mastodon;
 
+pub mod admin;
 pub mod oauth;
 pub mod register;
@@ -1,6 +1,7 @@
 use crate::{
     config::Configuration,
     consts::PROPER_VERSION,
+    database::Actor,
     state::{ArcState, State},
 };
 use argh::FromArgs;
@@ -20,6 +21,46 @@ pub struct Opts {
     #[argh(switch, short = 'v')]
     /// print the version
     version: bool,
+
+    #[argh(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum Command {
+    RotateKeys(RotateKeys),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "rotate-keys")]
+/// rotate the keys of local actors and send the new public keys to all known inboxes
+struct RotateKeys {
+    #[argh(option)]
+    /// only rotate the key of the user with this username
+    username: Option<String>,
+}
+
+/// Run the key rotation and exit the process afterwards
+async fn rotate_keys(state: ArcState, options: RotateKeys) -> ! {
+    crate::database::migrate(&state.db_pool)
+        .await
+        .expect("Database migration failed");
+
+    let result = match options.username {
+        Some(username) => match Actor::by_username_local(&state.db_pool, &username).await {
+            Ok(db_actor) => crate::activitypub::key_rotation::rotate(&state, db_actor).await,
+            Err(err) => Err(err),
+        },
+        None => crate::activitypub::key_rotation::rotate_all(&state).await,
+    };
+
+    if let Err(err) = result {
+        error!(error = ?err, "Key rotation failed");
+        process::exit(1);
+    }
+
+    process::exit(0);
 }
 
 /// Initialise the tracing subscriber
@@ -68,5 +109,11 @@ pub async fn run() -> ArcState {
         .await
         .expect("Couldn't connect to database");
 
-    State::new(config, db_pool)
+    let state = State::new(config, db_pool);
+
+    if let Some(Command::RotateKeys(rotate_keys_options)) = options.command {
+        rotate_keys(state, rotate_keys_options).await;
+    }
+
+    state
 }
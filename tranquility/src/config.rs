@@ -1,9 +1,11 @@
+use crate::error::Error;
 use serde::Deserialize;
-use std::path::Path;
+use std::{path::Path, str::FromStr};
 use tokio::{
     fs::File,
     io::{AsyncReadExt, BufReader},
 };
+use url::Url;
 
 #[derive(Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -19,6 +21,102 @@ pub struct ConfigurationEmail {
     pub password: String,
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+/// Format of the HTTP signatures attached to outgoing requests
+pub enum SignatureFormat {
+    /// draft-cavage-http-signatures-12 (understood by most of the fediverse)
+    Cavage,
+
+    /// RFC 9421 HTTP message signatures
+    Rfc9421,
+}
+
+impl SignatureFormat {
+    /// Name of the format as used in the configuration and the database
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cavage => "cavage",
+            Self::Rfc9421 => "rfc9421",
+        }
+    }
+}
+
+impl From<SignatureFormat> for tranquility_http_signatures::Format {
+    fn from(format: SignatureFormat) -> Self {
+        match format {
+            SignatureFormat::Cavage => Self::Cavage,
+            SignatureFormat::Rfc9421 => Self::Rfc9421,
+        }
+    }
+}
+
+impl FromStr for SignatureFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cavage" => Ok(Self::Cavage),
+            "rfc9421" => Ok(Self::Rfc9421),
+            _ => Err(Error::InvalidRequest),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+/// Struct holding the federation specific configuration values
+pub struct ConfigurationFederation {
+    pub secure_mode: bool,
+    pub blocked_domains: Vec<String>,
+
+    pub actor_ttl: u64,
+    pub max_clock_skew: u64,
+
+    pub signature_format: SignatureFormat,
+
+    pub inbox_concurrency: usize,
+    pub inbox_queue_size: usize,
+    pub received_activity_retention: u64,
+}
+
+impl Default for ConfigurationFederation {
+    fn default() -> Self {
+        Self {
+            secure_mode: false,
+            blocked_domains: Vec::new(),
+
+            // Default to one day
+            actor_ttl: 24 * 60 * 60,
+            // Default to one hour
+            max_clock_skew: 60 * 60,
+
+            signature_format: SignatureFormat::Cavage,
+
+            inbox_concurrency: 8,
+            inbox_queue_size: 1024,
+            // Default to one week
+            received_activity_retention: 7 * 24 * 60 * 60,
+        }
+    }
+}
+
+impl ConfigurationFederation {
+    /// Check whether the URL points to a blocked domain (or one of its subdomains)
+    pub fn is_blocked(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+
+        self.blocked_domains.iter().any(|blocked_domain| {
+            host == blocked_domain
+                || host
+                    .strip_suffix(blocked_domain.as_str())
+                    .is_some_and(|subdomain| subdomain.ends_with('.'))
+        })
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "kebab-case")]
 /// Struct holding the instance specific configuration values
@@ -78,6 +176,8 @@ pub struct ConfigurationTls {
 /// Struct holding the configuration values
 pub struct Configuration {
     pub email: ConfigurationEmail,
+    #[serde(default)]
+    pub federation: ConfigurationFederation,
     pub instance: ConfigurationInstance,
     pub jaeger: ConfigurationJaeger,
     pub ratelimit: ConfigurationRatelimit,
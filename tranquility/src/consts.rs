@@ -1,5 +1,42 @@
 pub mod activitypub {
+    use std::time::Duration;
+
     pub const ACTIVITIES_PER_PAGE: i64 = 10;
+
+    // Public activities of these types are also delivered to the relays the instance is subscribed to
+    pub const RELAYED_ACTIVITY_TYPES: &[&str] = &["Create", "Update", "Delete"];
+
+    // Maximum length of the reply chains (and chains of quotes) that are followed when fetching objects
+    pub const MAX_FETCH_DEPTH: usize = 10;
+    // Maximum amount of entities that are fetched while following the references of one object
+    pub const MAX_FETCHED_ENTITIES: usize = 100;
+
+    // Maximum amount of posts a user can pin to their profile
+    pub const MAX_PINNED_POSTS: i64 = 5;
+
+    // Amount of recent public posts imported from the outbox of newly discovered actors
+    pub const BACKFILL_POSTS: usize = 20;
+    // Maximum amount of outbox pages requested per backfill
+    pub const BACKFILL_MAX_PAGES: usize = 5;
+    // Minimum time between two backfills of an actor that weren't requested by an admin
+    pub const BACKFILL_COOLDOWN: Duration = Duration::from_secs(24 * 60 * 60);
+    // Maximum amount of backfills running at the same time
+    pub const MAX_CONCURRENT_BACKFILLS: usize = 4;
+    // Maximum amount of requests sent at the same time while delivering one activity
+    pub const MAX_CONCURRENT_DELIVERIES: usize = 16;
+
+    // Maximum amount of characters of Unicode emojis used as reactions (emojis can be sequences of several characters)
+    pub const MAX_REACTION_CHARS: usize = 16;
+    // Maximum amount of custom emojis saved (and fetched) per remote object or actor
+    pub const MAX_INGESTED_EMOJIS: usize = 50;
+
+    // Minimum time between two crawls of the context (ancestors and replies) of a post
+    pub const CONTEXT_CRAWL_COOLDOWN: Duration = Duration::from_secs(15 * 60);
+    // Maximum amount of context crawls running at the same time
+    pub const MAX_CONCURRENT_CONTEXT_CRAWLS: usize = 4;
+
+    // Minimum time between the last fetch of an actor and a refetch caused by an invalid signature
+    pub const KEY_REFETCH_COOLDOWN: Duration = Duration::from_secs(60);
 }
 
 pub mod cors {
@@ -18,14 +55,66 @@ pub mod cors {
 }
 
 pub mod crypto {
+    use std::time::Duration;
+
     pub const KEY_SIZE: usize = 2048;
     pub const TOKEN_LENGTH: usize = 40;
+
+    // Signatures made with the previous key stay valid for one day after a key rotation
+    pub const KEY_ROTATION_GRACE_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
 }
 
 pub mod daemon {
     use std::time::Duration;
 
     pub const DELETE_INTERVAL: Duration = Duration::from_secs(60);
+
+    pub const POLL_CLOSE_INTERVAL: Duration = Duration::from_secs(60);
+
+    pub const ACTOR_REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+    pub const ACTOR_REFRESH_BATCH_SIZE: i64 = 50;
+    // Actors that created something in this time frame are refreshed by the daemon
+    pub const ACTOR_ACTIVITY_WINDOW: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+}
+
+pub mod http_client {
+    use std::time::Duration;
+
+    pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+    pub const TIMEOUT: Duration = Duration::from_secs(30);
+
+    pub const MAX_REDIRECTS: usize = 5;
+    // Default to 1MB
+    pub const MAX_RESPONSE_SIZE: u64 = super::MB_BYTES;
+
+    // Content types of responses that could contain ActivityPub entities or webfinger resources
+    pub const ACTIVITYPUB_CONTENT_TYPES: &[&str] = &[
+        "application/activity+json",
+        "application/ld+json",
+        "application/json",
+    ];
+    pub const WEBFINGER_CONTENT_TYPES: &[&str] = &["application/jrd+json", "application/json"];
+}
+
+pub mod mastodon {
+    // Amount of statuses returned by paginated endpoints if the client didn't specify a limit
+    pub const DEFAULT_STATUSES_LIMIT: i64 = 20;
+    // Maximum amount of statuses a client can request at once
+    pub const MAX_STATUSES_LIMIT: i64 = 40;
+
+    // Amount of results per type returned by searches if the client didn't specify a limit
+    pub const DEFAULT_SEARCH_LIMIT: i64 = 20;
+    // Maximum amount of results per type a client can request at once
+    pub const MAX_SEARCH_LIMIT: i64 = 40;
+
+    // Maximum length of the display names of local accounts (same as the one of Mastodon)
+    pub const MAX_DISPLAY_NAME_CHARS: usize = 30;
+
+    // Limits of polls attached to statuses (same as the ones of Mastodon)
+    pub const MAX_POLL_OPTIONS: usize = 4;
+    pub const MAX_POLL_OPTION_CHARS: usize = 50;
+    pub const MIN_POLL_EXPIRATION: i64 = 5 * 60;
+    pub const MAX_POLL_EXPIRATION: i64 = 31 * 24 * 60 * 60;
 }
 
 pub mod regex {
@@ -40,10 +129,32 @@ pub mod regex {
         USERNAME_BASE!(),
         r#")(?:@(?P<domain>[\w\.\-]+[[:alnum:]]+))?"#
     );
+    // Handle of an account as entered into the search (for example, "@test@example.com")
+    pub const ACCOUNT_HANDLE: &str = concat!(
+        r#"^@?(?P<username>"#,
+        USERNAME_BASE!(),
+        r#")@(?P<domain>[\w\.\-]+[[:alnum:]]+)$"#
+    );
+    // Hashtags have to contain at least one non-digit character and can't be part of a URL or HTML entity
+    pub const HASHTAG: &str = r#"(?P<prefix>^|[^\w&/])#(?P<name>\w*[^\W\d]\w*)"#;
+    // Shortcodes of custom emojis are surrounded by colons (for example, ":blobcat:")
+    pub const EMOJI_SHORTCODE: &str = r#"^[a-zA-Z0-9_]{2,}$"#;
+    pub const EMOJI: &str = r#":(?P<shortcode>[a-zA-Z0-9_]{2,}):"#;
 }
 
+// Content types of images that can be uploaded (and of remote images that are cached)
+pub const ALLOWED_IMAGE_TYPES: &[&str] = &[
+    "image/avif",
+    "image/gif",
+    "image/jpeg",
+    "image/png",
+    "image/webp",
+];
+// Maximum nesting depth of the fields of URL-encoded forms
+pub const FORM_MAX_DEPTH: usize = 5;
 // Default to 5MB
 pub const MAX_BODY_SIZE: u64 = 5 * MB_BYTES;
+pub const KB_BYTES: u64 = 1024;
 pub const MB_BYTES: u64 = 1024_u64.pow(2);
 
 pub const SOFTWARE_NAME: &str = env!("CARGO_PKG_NAME");
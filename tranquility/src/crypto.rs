@@ -14,22 +14,78 @@ where
 }
 
 pub mod digest {
+    use crate::error::Error;
+    use reqwest::header::{HeaderMap, HeaderName};
+    use tranquility_http_signatures::digest::{
+        verify_content_digest, verify_digest, CONTENT_DIGEST, DIGEST,
+    };
+
+    /// Verify the body against the `Digest` and `Content-Digest` headers
+    ///
+    /// Every present header has to match. Bodies without any digest header aren't considered valid
+    pub fn verify(headers: &HeaderMap, body: &[u8]) -> Result<bool, Error> {
+        let header_value = |header_name: &HeaderName| {
+            headers
+                .get(header_name)
+                .map(|value| value.to_str().map_err(|_| Error::InvalidRequest))
+                .transpose()
+        };
+
+        let digest = header_value(&DIGEST)?;
+        let content_digest = header_value(&CONTENT_DIGEST)?;
+        if digest.is_none() && content_digest.is_none() {
+            return Ok(false);
+        }
+
+        if let Some(digest) = digest {
+            if !verify_digest(digest, body)? {
+                return Ok(false);
+            }
+        }
+
+        if let Some(content_digest) = content_digest {
+            if !verify_content_digest(content_digest, body)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+pub mod ld_signature {
     use crate::{error::Error, util::cpu_intensive_task};
-    use reqwest::header::HeaderValue;
-    use sha2::{Digest, Sha256};
+    use serde_json::Value;
+    use std::{future::Future, sync::Arc};
+    use tranquility_ld_signatures::{proof, signature};
 
-    /// Calculate the digest HTTP header
-    pub async fn http_header(data: Vec<u8>) -> Result<HeaderValue, Error> {
+    /// Embed an `RsaSignature2017` Linked Data Signature into the document
+    pub fn sign(
+        mut document: Value,
+        key_id: String,
+        private_key: String,
+    ) -> impl Future<Output = Result<Value, Error>> + Send {
         cpu_intensive_task(move || {
-            let sha_hash = Sha256::digest(&data);
-            let base64_encoded_hash = base64::encode(&sha_hash);
+            signature::sign(&mut document, &key_id, private_key.as_bytes())?;
 
-            Ok(HeaderValue::from_str(&format!(
-                "SHA-256={}",
-                base64_encoded_hash
-            ))?)
+            Ok(document)
         })
-        .await
+    }
+
+    /// Verify the `RsaSignature2017` Linked Data Signature embedded into the document
+    pub fn verify(
+        document: Arc<Value>,
+        public_key_pem: String,
+    ) -> impl Future<Output = Result<bool, Error>> + Send {
+        cpu_intensive_task(move || Ok(signature::verify(&document, public_key_pem.as_bytes())?))
+    }
+
+    /// Verify the object integrity proof embedded into the document
+    pub fn verify_proof(
+        document: Arc<Value>,
+        public_key_multibase: String,
+    ) -> impl Future<Output = Result<bool, Error>> + Send {
+        cpu_intensive_task(move || Ok(proof::verify(&document, &public_key_multibase)?))
     }
 }
 
@@ -92,51 +148,28 @@ pub mod token {
 }
 
 pub mod request {
-    use crate::{error::Error, util::cpu_intensive_task};
-    use http::{
-        self,
-        header::{HeaderName, HeaderValue},
-        HeaderMap,
-    };
+    use crate::{config::SignatureFormat, error::Error, util::cpu_intensive_task};
     use std::future::Future;
-    use tranquility_http_signatures::Request;
+    use tranquility_http_signatures::Signer;
 
     /// Sign a reqwest HTTP request
+    ///
+    /// Missing `Host`, `Date` and digest headers are added before signing.
+    /// The covered headers depend on the format and on whether the request has a body
     pub fn sign(
-        request: reqwest::Request,
+        mut request: reqwest::Request,
+        format: SignatureFormat,
         key_id: String,
         // The public key is provided in the PEM format
         // That's why the function takes a `String`
         private_key: String,
-    ) -> impl Future<Output = Result<(HeaderName, HeaderValue), Error>> + Send {
-        cpu_intensive_task(move || {
-            let request = &request;
-            let key_id = key_id.as_str();
-            let private_key = private_key.as_bytes();
-
-            Ok(tranquility_http_signatures::sign(
-                request,
-                &["(request-target)", "date", "digest"],
-                (key_id, private_key),
-            )?)
-        })
-    }
-
-    /// Verify an HTTP request using parameters obtained from warp
-    pub fn verify(
-        method: String,
-        path: String,
-        query: Option<String>,
-        headers: HeaderMap,
-        // The public key is provided in the PEM format
-        // That's why the function takes a `String`
-        public_key: String,
-    ) -> impl Future<Output = Result<bool, Error>> + Send {
+    ) -> impl Future<Output = Result<reqwest::Request, Error>> + Send {
         cpu_intensive_task(move || {
-            let request = Request::new(&method, &path, query.as_deref(), &headers);
-            let public_key = public_key.as_bytes();
+            Signer::new((key_id.as_str(), private_key.as_bytes()))
+                .format(format.into())
+                .sign_reqwest(&mut request)?;
 
-            Ok(tranquility_http_signatures::verify(request, public_key)?)
+            Ok(request)
         })
     }
 }
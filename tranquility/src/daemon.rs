@@ -1,6 +1,15 @@
-use crate::{consts::daemon::DELETE_INTERVAL, database::OAuthAuthorization, state::ArcState};
+use crate::{
+    activitypub::{fetcher, poll},
+    consts::daemon::{
+        ACTOR_ACTIVITY_WINDOW, ACTOR_REFRESH_BATCH_SIZE, ACTOR_REFRESH_INTERVAL, DELETE_INTERVAL,
+        POLL_CLOSE_INTERVAL,
+    },
+    database::{received_activity, Actor, OAuthAuthorization, Object},
+    state::ArcState,
+};
 use std::{future::Future, sync::Arc};
 use tokio::time;
+use tranquility_types::activitypub::Object as ApObject;
 
 // Keeping this for future use
 #[allow(dead_code)]
@@ -24,8 +33,81 @@ async fn delete_expired_authorisation_codes(state: ArcState) {
     }
 }
 
-pub fn start(state: &ArcState) {
-    let state = Arc::clone(state);
+/// Forget the activities that were received longer ago than the retention period
+async fn delete_expired_received_activities(state: ArcState) {
+    let mut query_interval = time::interval(DELETE_INTERVAL);
+
+    let retention_secs =
+        i64::try_from(state.config.federation.received_activity_retention).unwrap_or(i64::MAX);
+
+    loop {
+        if let Err(err) = received_activity::delete_expired(&state.db_pool, retention_secs).await {
+            warn!(error = ?err, "Couldn't delete expired received activities");
+        }
+
+        query_interval.tick().await;
+    }
+}
+
+/// Close the local polls that ended and send their final results out
+async fn close_ended_polls(state: ArcState) {
+    let mut query_interval = time::interval(POLL_CLOSE_INTERVAL);
+
+    loop {
+        match Object::open_local_polls(&state.db_pool).await {
+            Ok(open_polls) => {
+                for db_object in open_polls {
+                    let has_ended = serde_json::from_value::<ApObject>(db_object.data.clone())
+                        .is_ok_and(|object| !poll::is_open(&object));
+                    if !has_ended {
+                        continue;
+                    }
+
+                    if let Err(err) = poll::close(&state, db_object).await {
+                        warn!(error = ?err, "Couldn't close poll");
+                    }
+                }
+            }
+            Err(err) => warn!(error = ?err, "Couldn't get open polls"),
+        }
 
-    tokio::spawn(delete_expired_authorisation_codes(state));
+        query_interval.tick().await;
+    }
+}
+
+/// Refetch remote actors that were active recently but whose cached copies are older than the TTL
+async fn refresh_stale_actors(state: ArcState) {
+    let mut query_interval = time::interval(ACTOR_REFRESH_INTERVAL);
+
+    let actor_ttl = i64::try_from(state.config.federation.actor_ttl).unwrap_or(i64::MAX);
+    let activity_window = i64::try_from(ACTOR_ACTIVITY_WINDOW.as_secs()).unwrap_or(i64::MAX);
+
+    loop {
+        match Actor::stale_active(
+            &state.db_pool,
+            actor_ttl,
+            activity_window,
+            ACTOR_REFRESH_BATCH_SIZE,
+        )
+        .await
+        {
+            Ok(stale_actors) => {
+                for stale_actor in stale_actors {
+                    if let Err(err) = fetcher::refetch_actor(&state, stale_actor).await {
+                        debug!(error = ?err, "Couldn't refresh actor");
+                    }
+                }
+            }
+            Err(err) => warn!(error = ?err, "Couldn't get stale actors"),
+        }
+
+        query_interval.tick().await;
+    }
+}
+
+pub fn start(state: &ArcState) {
+    tokio::spawn(delete_expired_authorisation_codes(Arc::clone(state)));
+    tokio::spawn(delete_expired_received_activities(Arc::clone(state)));
+    tokio::spawn(close_ended_polls(Arc::clone(state)));
+    tokio::spawn(refresh_stale_actors(Arc::clone(state)));
 }
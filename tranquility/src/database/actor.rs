@@ -23,6 +23,23 @@ pub struct Actor {
     pub actor: Value,
     pub remote: bool,
 
+    #[ormx(default)]
+    pub hide_collections: bool,
+
+    /// Time of the last attempt to fetch the remote actor
+    #[ormx(default)]
+    pub last_fetched_at: OffsetDateTime,
+
+    /// Public key that was replaced by a key rotation
+    ///
+    /// Signatures made with this key stay valid until the grace period is over
+    #[ormx(default)]
+    pub previous_public_key: Option<String>,
+    #[ormx(default)]
+    pub previous_key_id: Option<String>,
+    #[ormx(default)]
+    pub previous_key_expires_at: Option<OffsetDateTime>,
+
     #[ormx(default)]
     pub created_at: OffsetDateTime,
 
@@ -31,6 +48,24 @@ pub struct Actor {
 }
 
 impl Actor {
+    /// Get the ID and the public key (PEM) that were replaced by a key rotation
+    ///
+    /// Returns `None` if the key was never rotated or the grace period is over
+    pub fn previous_key(&self) -> Option<(&str, &str)> {
+        match (
+            &self.previous_key_id,
+            &self.previous_public_key,
+            self.previous_key_expires_at,
+        ) {
+            (Some(key_id), Some(public_key_pem), Some(expires_at))
+                if expires_at > OffsetDateTime::now_utc() =>
+            {
+                Some((key_id, public_key_pem))
+            }
+            _ => None,
+        }
+    }
+
     /// Get an confirmed actor by their ID
     pub async fn get(conn_pool: &PgPool, id: Uuid) -> Result<Self, Error> {
         let actor = sqlx::query_as!(
@@ -48,6 +83,40 @@ impl Actor {
         Ok(actor)
     }
 
+    /// Get an actor by their ID (confirmed or not)
+    ///
+    /// Remote actors are never confirmed, so lookups that should include them have to use this
+    pub async fn by_id(conn_pool: &PgPool, id: Uuid) -> Result<Self, Error> {
+        let actor = sqlx::query_as!(
+            Actor,
+            r#"
+                SELECT * FROM actors
+                WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_one(conn_pool)
+        .await?;
+
+        Ok(actor)
+    }
+
+    /// Get the actors by their IDs (confirmed or not) in no particular order
+    pub async fn by_ids(conn_pool: &PgPool, ids: &[Uuid]) -> Result<Vec<Self>, Error> {
+        let actors = sqlx::query_as!(
+            Actor,
+            r#"
+                SELECT * FROM actors
+                WHERE id = ANY($1)
+            "#,
+            ids
+        )
+        .fetch_all(conn_pool)
+        .await?;
+
+        Ok(actors)
+    }
+
     /// Get an actor by their URL
     pub async fn by_url(conn_pool: &PgPool, url: &str) -> Result<Self, Error> {
         let actor = sqlx::query_as!(
@@ -64,6 +133,22 @@ impl Actor {
         Ok(actor)
     }
 
+    /// Get an actor by the ID of their public key
+    pub async fn by_key_id(conn_pool: &PgPool, key_id: &str) -> Result<Option<Self>, Error> {
+        let actor = sqlx::query_as!(
+            Actor,
+            r#"
+                SELECT * FROM actors
+                WHERE actor->'publicKey'->>'id' = $1
+            "#,
+            key_id
+        )
+        .fetch_optional(conn_pool)
+        .await?;
+
+        Ok(actor)
+    }
+
     /// Get an confirmed local actor by their username
     pub async fn by_username_local(conn_pool: &PgPool, username: &str) -> Result<Self, Error> {
         let actor = sqlx::query_as!(
@@ -81,4 +166,118 @@ impl Actor {
 
         Ok(actor)
     }
+
+    /// Get all local actors that have a key pair
+    pub async fn local_with_keys(conn_pool: &PgPool) -> Result<Vec<Self>, Error> {
+        let actors = sqlx::query_as!(
+            Actor,
+            r#"
+                SELECT * FROM actors
+                WHERE remote = FALSE
+                AND private_key IS NOT NULL
+            "#
+        )
+        .fetch_all(conn_pool)
+        .await?;
+
+        Ok(actors)
+    }
+
+    /// Insert the instance actor unless it already exists
+    ///
+    /// Nothing is inserted if another actor with the same URL or username already exists.
+    /// The instance actor is never confirmed. This way nobody can log into it
+    pub async fn insert_instance_actor(
+        conn_pool: &PgPool,
+        username: &str,
+        private_key: &str,
+        actor: Value,
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+                INSERT INTO actors (id, username, private_key, is_confirmed, actor, remote)
+                VALUES ($1, $2, $3, FALSE, $4, FALSE)
+                ON CONFLICT DO NOTHING
+            "#,
+            Uuid::new_v4(),
+            username,
+            private_key,
+            actor
+        )
+        .execute(conn_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Replace the ActivityPub representation of the actor with a freshly fetched one
+    pub async fn refresh(conn_pool: &PgPool, id: Uuid, actor: Value) -> Result<Self, Error> {
+        let actor = sqlx::query_as!(
+            Actor,
+            r#"
+                UPDATE actors
+                SET actor = $2, last_fetched_at = NOW()
+                WHERE id = $1
+                RETURNING *
+            "#,
+            id,
+            actor
+        )
+        .fetch_one(conn_pool)
+        .await?;
+
+        Ok(actor)
+    }
+
+    /// Mark the actor as fetched without changing its ActivityPub representation
+    ///
+    /// Used when the fetch failed so unreachable servers aren't contacted over and over again
+    pub async fn mark_fetched(conn_pool: &PgPool, id: Uuid) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+                UPDATE actors
+                SET last_fetched_at = NOW()
+                WHERE id = $1
+            "#,
+            id
+        )
+        .execute(conn_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get remote actors that were active recently but haven't been fetched for longer than the TTL (stalest first)
+    ///
+    /// An actor counts as active if they created any activities or objects in the given time frame
+    pub async fn stale_active(
+        conn_pool: &PgPool,
+        ttl_secs: i64,
+        active_secs: i64,
+        limit: i64,
+    ) -> Result<Vec<Self>, Error> {
+        let actors = sqlx::query_as!(
+            Actor,
+            r#"
+                SELECT * FROM actors
+                WHERE remote = TRUE
+                AND last_fetched_at < NOW() - $1::BIGINT * INTERVAL '1 second'
+                AND EXISTS (
+                    SELECT 1 FROM objects
+                    WHERE owner_id = actors.id
+                    AND created_at > NOW() - $2::BIGINT * INTERVAL '1 second'
+                )
+
+                ORDER BY last_fetched_at ASC
+                LIMIT $3
+            "#,
+            ttl_secs,
+            active_secs,
+            limit
+        )
+        .fetch_all(conn_pool)
+        .await?;
+
+        Ok(actors)
+    }
 }
@@ -0,0 +1,24 @@
+use crate::error::Error;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Record that the posts of the actor are being backfilled
+///
+/// Returns `false` if the actor was already backfilled less than `cooldown_secs` seconds ago
+pub async fn claim(conn_pool: &PgPool, actor_id: Uuid, cooldown_secs: i64) -> Result<bool, Error> {
+    let result = sqlx::query!(
+        r#"
+            INSERT INTO actor_backfills (actor_id)
+            VALUES ($1)
+            ON CONFLICT (actor_id) DO UPDATE
+                SET backfilled_at = NOW()
+                WHERE actor_backfills.backfilled_at < NOW() - $2::BIGINT * INTERVAL '1 second'
+        "#,
+        actor_id,
+        cooldown_secs,
+    )
+    .execute(conn_pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
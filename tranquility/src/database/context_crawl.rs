@@ -0,0 +1,24 @@
+use crate::error::Error;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Record that the context of the object is being crawled
+///
+/// Returns `false` if the context was already crawled less than `cooldown_secs` seconds ago
+pub async fn claim(conn_pool: &PgPool, object_id: Uuid, cooldown_secs: i64) -> Result<bool, Error> {
+    let result = sqlx::query!(
+        r#"
+            INSERT INTO context_crawls (object_id)
+            VALUES ($1)
+            ON CONFLICT (object_id) DO UPDATE
+                SET crawled_at = NOW()
+                WHERE context_crawls.crawled_at < NOW() - $2::BIGINT * INTERVAL '1 second'
+        "#,
+        object_id,
+        cooldown_secs,
+    )
+    .execute(conn_pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
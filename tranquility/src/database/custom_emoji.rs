@@ -0,0 +1,218 @@
+use crate::{database::ObjectCount, error::Error};
+use ormx::Table;
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Clone, Table)]
+#[ormx(id = id, table = "custom_emojis", deletable)]
+/// Custom emoji uploaded by the admins of this instance or used by remote posts and profiles
+pub struct CustomEmoji {
+    pub id: Uuid,
+
+    pub shortcode: String,
+    /// Domain of the instance the emoji belongs to (`None` for local emojis)
+    pub domain: Option<String>,
+    pub category: Option<String>,
+    pub visible_in_picker: bool,
+
+    /// URL of the original image of remote emojis
+    pub image_url: Option<String>,
+    /// `updated` value of the Emoji tag of remote emojis
+    pub remote_updated_at: Option<OffsetDateTime>,
+
+    #[ormx(default)]
+    pub created_at: OffsetDateTime,
+
+    #[ormx(default)]
+    pub updated_at: OffsetDateTime,
+}
+
+/// Image of a custom emoji
+pub struct CustomEmojiImage {
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+impl CustomEmoji {
+    /// Get the emoji with the shortcode from the instance (`None` as the domain refers to this instance)
+    pub async fn by_shortcode(
+        conn_pool: &PgPool,
+        shortcode: &str,
+        domain: Option<&str>,
+    ) -> Result<Option<Self>, Error> {
+        let custom_emoji = sqlx::query_as!(
+            CustomEmoji,
+            r#"
+                SELECT * FROM custom_emojis
+                WHERE shortcode = $1
+                AND domain IS NOT DISTINCT FROM $2
+            "#,
+            shortcode,
+            domain,
+        )
+        .fetch_optional(conn_pool)
+        .await?;
+
+        Ok(custom_emoji)
+    }
+
+    /// Get the local emojis with the shortcodes
+    pub async fn local_by_shortcodes(
+        conn_pool: &PgPool,
+        shortcodes: &[String],
+    ) -> Result<Vec<Self>, Error> {
+        let custom_emojis = sqlx::query_as!(
+            CustomEmoji,
+            r#"
+                SELECT * FROM custom_emojis
+                WHERE domain IS NULL
+                AND shortcode = ANY($1)
+
+                ORDER BY shortcode ASC
+            "#,
+            shortcodes,
+        )
+        .fetch_all(conn_pool)
+        .await?;
+
+        Ok(custom_emojis)
+    }
+
+    /// Get all local emojis (sorted by category and shortcode)
+    pub async fn local(conn_pool: &PgPool) -> Result<Vec<Self>, Error> {
+        let custom_emojis = sqlx::query_as!(
+            CustomEmoji,
+            r#"
+                SELECT * FROM custom_emojis
+                WHERE domain IS NULL
+
+                ORDER BY category ASC NULLS FIRST, shortcode ASC
+            "#,
+        )
+        .fetch_all(conn_pool)
+        .await?;
+
+        Ok(custom_emojis)
+    }
+
+    /// Create the local emoji or update its category and visibility if it already exists
+    pub async fn upsert_local(
+        conn_pool: &PgPool,
+        shortcode: &str,
+        category: Option<&str>,
+        visible_in_picker: bool,
+    ) -> Result<Self, Error> {
+        let custom_emoji = sqlx::query_as!(
+            CustomEmoji,
+            r#"
+                INSERT INTO custom_emojis (id, shortcode, category, visible_in_picker)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (shortcode) WHERE domain IS NULL DO UPDATE SET
+                    category = EXCLUDED.category,
+                    visible_in_picker = EXCLUDED.visible_in_picker
+                RETURNING *
+            "#,
+            Uuid::new_v4(),
+            shortcode,
+            category,
+            visible_in_picker,
+        )
+        .fetch_one(conn_pool)
+        .await?;
+
+        Ok(custom_emoji)
+    }
+
+    /// Create the remote emoji or update the reference to its image if it already exists
+    ///
+    /// Remote emojis aren't offered in the emoji picker
+    pub async fn upsert_remote(
+        conn_pool: &PgPool,
+        shortcode: &str,
+        domain: &str,
+        image_url: &str,
+        remote_updated_at: Option<OffsetDateTime>,
+    ) -> Result<Self, Error> {
+        let custom_emoji = sqlx::query_as!(
+            CustomEmoji,
+            r#"
+                INSERT INTO custom_emojis (id, shortcode, domain, visible_in_picker, image_url, remote_updated_at)
+                VALUES ($1, $2, $3, FALSE, $4, $5)
+                ON CONFLICT (shortcode, domain) WHERE domain IS NOT NULL DO UPDATE SET
+                    image_url = EXCLUDED.image_url,
+                    remote_updated_at = EXCLUDED.remote_updated_at
+                RETURNING *
+            "#,
+            Uuid::new_v4(),
+            shortcode,
+            domain,
+            image_url,
+            remote_updated_at,
+        )
+        .fetch_one(conn_pool)
+        .await?;
+
+        Ok(custom_emoji)
+    }
+
+    /// Save the image of the emoji (replaces the previous image)
+    pub async fn set_image(
+        conn_pool: &PgPool,
+        emoji_id: Uuid,
+        content_type: &str,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+                INSERT INTO custom_emoji_images (emoji_id, content_type, data)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (emoji_id) DO UPDATE SET
+                    content_type = EXCLUDED.content_type,
+                    data = EXCLUDED.data
+            "#,
+            emoji_id,
+            content_type,
+            data,
+        )
+        .execute(conn_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get the image of the emoji (`None` if the image of the remote emoji isn't cached)
+    pub async fn image(
+        conn_pool: &PgPool,
+        emoji_id: Uuid,
+    ) -> Result<Option<CustomEmojiImage>, Error> {
+        let image = sqlx::query_as!(
+            CustomEmojiImage,
+            r#"
+                SELECT content_type, data FROM custom_emoji_images
+                WHERE emoji_id = $1
+            "#,
+            emoji_id,
+        )
+        .fetch_optional(conn_pool)
+        .await?;
+
+        Ok(image)
+    }
+
+    /// Check whether the image of the emoji is saved
+    pub async fn has_image(conn_pool: &PgPool, emoji_id: Uuid) -> Result<bool, Error> {
+        let count = sqlx::query_as!(
+            ObjectCount,
+            r#"
+                SELECT COUNT(*) as "count!" FROM custom_emoji_images
+                WHERE emoji_id = $1
+            "#,
+            emoji_id,
+        )
+        .fetch_one(conn_pool)
+        .await?;
+
+        Ok(i64::from(count) > 0)
+    }
+}
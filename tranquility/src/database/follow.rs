@@ -1,63 +1,202 @@
 use crate::{
-    database::{last_activity_timestamp, Object},
+    database::{Actor, Cursor, Object, ObjectCount},
     error::Error,
 };
 use sqlx::PgPool;
 use uuid::Uuid;
 
-/// Get follow activities addressed to the user
+/// Get approved follow activities addressed to the user (newest first)
 pub async fn followers(
     conn_pool: &PgPool,
     user_id: Uuid,
-    last_activity_id: Option<Uuid>,
+    cursor: Cursor,
     limit: i64,
 ) -> Result<Vec<Object>, Error> {
-    let last_activity_timestamp = last_activity_timestamp(conn_pool, last_activity_id).await?;
-    let follow_activities = sqlx::query_as!(
-        Object,
+    let follow_activities = match cursor {
+        Cursor::Before(before_id) => {
+            sqlx::query_as!(
+                Object,
+                r#"
+                    SELECT * FROM objects
+                    WHERE data->>'type' = 'Follow'
+                    AND data->>'object' = (
+                        SELECT actor->>'id' FROM actors
+                        WHERE id = $1
+                    )
+                    AND (data->>'approved')::BOOLEAN
+                    AND (
+                        $2::UUID IS NULL
+                        OR (created_at, id) < (SELECT created_at, id FROM objects WHERE id = $2)
+                    )
+
+                    ORDER BY created_at DESC, id DESC
+                    LIMIT $3
+                "#,
+                user_id,
+                before_id,
+                limit,
+            )
+            .fetch_all(conn_pool)
+            .await?
+        }
+        Cursor::After(after_id) => {
+            let mut follow_activities = sqlx::query_as!(
+                Object,
+                r#"
+                    SELECT * FROM objects
+                    WHERE data->>'type' = 'Follow'
+                    AND data->>'object' = (
+                        SELECT actor->>'id' FROM actors
+                        WHERE id = $1
+                    )
+                    AND (data->>'approved')::BOOLEAN
+                    AND (
+                        $2::UUID IS NULL
+                        OR (created_at, id) > (SELECT created_at, id FROM objects WHERE id = $2)
+                    )
+
+                    ORDER BY created_at ASC, id ASC
+                    LIMIT $3
+                "#,
+                user_id,
+                after_id,
+                limit,
+            )
+            .fetch_all(conn_pool)
+            .await?;
+            follow_activities.reverse();
+
+            follow_activities
+        }
+    };
+
+    Ok(follow_activities)
+}
+
+/// Count the approved follow activities addressed to the user
+pub async fn followers_count(conn_pool: &PgPool, user_id: Uuid) -> Result<i64, Error> {
+    let count = sqlx::query_as!(
+        ObjectCount,
         r#"
-            SELECT * FROM objects
+            SELECT COUNT(*) as "count!" FROM objects
             WHERE data->>'type' = 'Follow'
             AND data->>'object' = (
                 SELECT actor->>'id' FROM actors
                 WHERE id = $1
             )
-            AND created_at < $2
-            LIMIT $3
+            AND (data->>'approved')::BOOLEAN
+        "#,
+        user_id,
+    )
+    .fetch_one(conn_pool)
+    .await?;
+
+    Ok(count.into())
+}
+
+/// Get the actors whose follows of the user were approved (newest follow first)
+pub async fn follower_actors(
+    conn_pool: &PgPool,
+    user_id: Uuid,
+    limit: i64,
+) -> Result<Vec<Actor>, Error> {
+    let follower_actors = sqlx::query_as!(
+        Actor,
+        r#"
+            SELECT actors.* FROM actors, objects
+            WHERE objects.data->>'type' = 'Follow'
+            AND objects.data->>'object' = (
+                SELECT actor->>'id' FROM actors
+                WHERE id = $1
+            )
+            AND (objects.data->>'approved')::BOOLEAN
+            AND objects.data->>'actor' = actors.actor->>'id'
+
+            ORDER BY objects.created_at DESC, objects.id DESC
+            LIMIT $2
         "#,
         user_id,
-        last_activity_timestamp,
         limit,
     )
     .fetch_all(conn_pool)
     .await?;
 
-    Ok(follow_activities)
+    Ok(follower_actors)
 }
 
-/// Get follow activities created by the user
+/// Get follow activities created by the user (newest first)
 pub async fn following(
     conn_pool: &PgPool,
     user_id: Uuid,
-    last_activity_id: Option<Uuid>,
+    cursor: Cursor,
     limit: i64,
 ) -> Result<Vec<Object>, Error> {
-    let last_activity_timestamp = last_activity_timestamp(conn_pool, last_activity_id).await?;
-    let follow_activities = sqlx::query_as!(
-        Object,
+    let follow_activities = match cursor {
+        Cursor::Before(before_id) => {
+            sqlx::query_as!(
+                Object,
+                r#"
+                    SELECT * FROM objects
+                    WHERE data->>'type' = 'Follow'
+                    AND owner_id = $1
+                    AND (
+                        $2::UUID IS NULL
+                        OR (created_at, id) < (SELECT created_at, id FROM objects WHERE id = $2)
+                    )
+
+                    ORDER BY created_at DESC, id DESC
+                    LIMIT $3
+                "#,
+                user_id,
+                before_id,
+                limit,
+            )
+            .fetch_all(conn_pool)
+            .await?
+        }
+        Cursor::After(after_id) => {
+            let mut follow_activities = sqlx::query_as!(
+                Object,
+                r#"
+                    SELECT * FROM objects
+                    WHERE data->>'type' = 'Follow'
+                    AND owner_id = $1
+                    AND (
+                        $2::UUID IS NULL
+                        OR (created_at, id) > (SELECT created_at, id FROM objects WHERE id = $2)
+                    )
+
+                    ORDER BY created_at ASC, id ASC
+                    LIMIT $3
+                "#,
+                user_id,
+                after_id,
+                limit,
+            )
+            .fetch_all(conn_pool)
+            .await?;
+            follow_activities.reverse();
+
+            follow_activities
+        }
+    };
+
+    Ok(follow_activities)
+}
+
+/// Count the follow activities created by the user
+pub async fn following_count(conn_pool: &PgPool, user_id: Uuid) -> Result<i64, Error> {
+    let count = sqlx::query_as!(
+        ObjectCount,
         r#"
-            SELECT * FROM objects
+            SELECT COUNT(*) as "count!" FROM objects
             WHERE data->>'type' = 'Follow'
             AND owner_id = $1
-            AND created_at < $2
-            LIMIT $3
         "#,
         user_id,
-        last_activity_timestamp,
-        limit,
     )
-    .fetch_all(conn_pool)
+    .fetch_one(conn_pool)
     .await?;
 
-    Ok(follow_activities)
+    Ok(count.into())
 }
@@ -0,0 +1,103 @@
+use crate::{database::ObjectCount, error::Error};
+use ormx::Table;
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Clone, Table)]
+#[ormx(id = id, table = "followed_tags", deletable)]
+/// Hashtag followed by an actor (posts tagged with it show up in the home timeline of the actor)
+pub struct FollowedTag {
+    pub id: Uuid,
+
+    pub actor_id: Uuid,
+    /// Name of the hashtag (lowercase and without the leading `#`)
+    pub name: String,
+
+    #[ormx(default)]
+    pub created_at: OffsetDateTime,
+
+    #[ormx(default)]
+    pub updated_at: OffsetDateTime,
+}
+
+impl FollowedTag {
+    /// Follow the hashtag
+    ///
+    /// Returns `false` if the actor already follows the hashtag
+    pub async fn follow(conn_pool: &PgPool, actor_id: Uuid, name: &str) -> Result<bool, Error> {
+        let result = sqlx::query!(
+            r#"
+                INSERT INTO followed_tags (id, actor_id, name)
+                VALUES ($1, $2, LOWER($3))
+                ON CONFLICT DO NOTHING
+            "#,
+            Uuid::new_v4(),
+            actor_id,
+            name,
+        )
+        .execute(conn_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Unfollow the hashtag
+    ///
+    /// Returns `false` if the actor didn't follow the hashtag
+    pub async fn unfollow(conn_pool: &PgPool, actor_id: Uuid, name: &str) -> Result<bool, Error> {
+        let result = sqlx::query!(
+            r#"
+                DELETE FROM followed_tags
+                WHERE actor_id = $1
+                AND name = LOWER($2)
+            "#,
+            actor_id,
+            name,
+        )
+        .execute(conn_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Check whether the actor follows the hashtag
+    pub async fn is_following(
+        conn_pool: &PgPool,
+        actor_id: Uuid,
+        name: &str,
+    ) -> Result<bool, Error> {
+        let count = sqlx::query_as!(
+            ObjectCount,
+            r#"
+                SELECT COUNT(*) as "count!" FROM followed_tags
+                WHERE actor_id = $1
+                AND name = LOWER($2)
+            "#,
+            actor_id,
+            name,
+        )
+        .fetch_one(conn_pool)
+        .await?;
+
+        Ok(i64::from(count) > 0)
+    }
+
+    /// Get the hashtags followed by the actor (most recently followed hashtag first)
+    pub async fn by_actor(conn_pool: &PgPool, actor_id: Uuid) -> Result<Vec<Self>, Error> {
+        let followed_tags = sqlx::query_as!(
+            FollowedTag,
+            r#"
+                SELECT * FROM followed_tags
+                WHERE actor_id = $1
+
+                ORDER BY created_at DESC
+            "#,
+            actor_id,
+        )
+        .fetch_all(conn_pool)
+        .await?;
+
+        Ok(followed_tags)
+    }
+}
@@ -1,6 +1,7 @@
 use crate::error::Error;
 use futures_util::stream::{StreamExt, TryStreamExt};
 use sqlx::PgPool;
+use uuid::Uuid;
 
 // Required because of the "query_as" macro
 // Otherwise we couldn't use compile-time verified SQL queries
@@ -26,7 +27,8 @@ pub async fn resolve_followers(
             FROM actors, objects
             WHERE objects.data->>'type' = 'Follow'
             AND objects.data->>'object' = $1
-            AND objects.data->>'object' = actors.actor->>'id'
+            AND (objects.data->>'approved')::BOOLEAN
+            AND objects.data->>'actor' = actors.actor->>'id'
         "#,
         followed_url
     )
@@ -56,3 +58,47 @@ pub async fn resolve_one(conn_pool: &PgPool, url: &str) -> Result<String, Error>
 
     Ok(inbox_url.into())
 }
+
+/// Get the inbox URLs of all known remote actors
+///
+/// Actors with a shared inbox are reached via the shared inbox, so every server only receives the activity once
+pub async fn resolve_all_remote(conn_pool: &PgPool) -> Result<Vec<String>, Error> {
+    let inbox_urls = sqlx::query_as!(
+        InboxUrl,
+        r#"
+            SELECT DISTINCT COALESCE(actor->'endpoints'->>'sharedInbox', actor->>'inbox') as "inbox_url!"
+            FROM actors
+            WHERE remote = TRUE
+        "#
+    )
+    .fetch(conn_pool)
+    .map(|row_result| row_result.map(Into::into))
+    .try_collect()
+    .await?;
+
+    Ok(inbox_urls)
+}
+
+/// Get the inbox URLs of the remote actors who voted on the poll
+pub async fn resolve_remote_voters(
+    conn_pool: &PgPool,
+    object_id: Uuid,
+) -> Result<Vec<String>, Error> {
+    let inbox_urls = sqlx::query_as!(
+        InboxUrl,
+        r#"
+            SELECT DISTINCT actors.actor->>'inbox' as "inbox_url!"
+            FROM actors, poll_votes
+            WHERE poll_votes.object_id = $1
+            AND poll_votes.actor_id = actors.id
+            AND actors.remote = TRUE
+        "#,
+        object_id,
+    )
+    .fetch(conn_pool)
+    .map(|row_result| row_result.map(Into::into))
+    .try_collect()
+    .await?;
+
+    Ok(inbox_urls)
+}
@@ -1,7 +1,6 @@
 use crate::error::Error;
 use async_trait::async_trait;
 use sqlx::PgPool;
-use time::OffsetDateTime;
 use uuid::Uuid;
 
 pub mod connection {
@@ -31,39 +30,35 @@ pub trait InsertExt: ormx::Insert {
 #[async_trait]
 impl<T> InsertExt for T where T: ormx::Insert {}
 
-/// Wrapper struct for the query of the [last_activity_timestamp] function
-struct ObjectTimestamp {
-    timestamp: OffsetDateTime,
+/// Position inside of a paginated list of objects
+#[derive(Clone, Copy, Debug)]
+pub enum Cursor {
+    /// Objects older than the referenced object (or the newest objects if the ID is absent)
+    Before(Option<Uuid>),
+
+    /// Objects newer than the referenced object (or the oldest objects if the ID is absent)
+    After(Option<Uuid>),
 }
 
-impl From<ObjectTimestamp> for OffsetDateTime {
-    fn from(timestamp: ObjectTimestamp) -> Self {
-        timestamp.timestamp
+impl Cursor {
+    /// Split the cursor into the ID of the referenced object and whether the objects after it are requested
+    pub fn into_parts(self) -> (Option<Uuid>, bool) {
+        match self {
+            Cursor::Before(id) => (id, false),
+            Cursor::After(id) => (id, true),
+        }
     }
 }
 
-#[inline]
-/// Get the timestamp of the activity
-///
-/// If the activity doesn't exist the current time is returned
-async fn last_activity_timestamp(
-    conn_pool: &PgPool,
-    last_activity_id: Option<Uuid>,
-) -> Result<OffsetDateTime, Error> {
-    let last_timestamp = sqlx::query_as!(
-        ObjectTimestamp,
-        r#"
-            SELECT created_at as "timestamp!" FROM objects
-            WHERE id = $1
-        "#,
-        last_activity_id,
-    )
-    .fetch_one(conn_pool)
-    .await
-    // Either return the current time or convert it via the `Into` trait
-    .map_or_else(|_| OffsetDateTime::now_utc(), Into::into);
+/// Wrapper struct for queries that count rows
+struct ObjectCount {
+    count: i64,
+}
 
-    Ok(last_timestamp)
+impl From<ObjectCount> for i64 {
+    fn from(count: ObjectCount) -> Self {
+        count.count
+    }
 }
 
 /// Execute the embedded database migrations
@@ -74,11 +69,23 @@ pub async fn migrate(conn_pool: &PgPool) -> Result<(), Error> {
 }
 
 pub mod actor;
+pub mod actor_backfill;
+pub mod context_crawl;
+pub mod custom_emoji;
 pub mod follow;
+pub mod followed_tag;
 pub mod inbox_urls;
 pub mod oauth;
 pub mod object;
 pub mod outbox;
+pub mod pin;
+pub mod poll_vote;
+pub mod reaction;
+pub mod received_activity;
+pub mod relay;
+pub mod search;
+pub mod server;
+pub mod statuses;
 
 pub use actor::*;
 pub use oauth::*;
@@ -21,36 +21,6 @@ pub struct Object {
 }
 
 impl Object {
-    /// Get activities by its type and object URL
-    pub async fn by_type_and_object_url(
-        conn_pool: &PgPool,
-        r#type: &str,
-        object_url: &str,
-        limit: i64,
-        offset: i64,
-    ) -> Result<Vec<Self>, Error> {
-        let objects = sqlx::query_as!(
-            Object,
-            r#"
-                SELECT * FROM objects
-                WHERE data->>'type' = $1
-                AND data->>'object' = $2
-
-                ORDER BY created_at DESC
-                LIMIT $3
-                OFFSET $4
-            "#,
-            r#type,
-            object_url,
-            limit,
-            offset
-        )
-        .fetch_all(conn_pool)
-        .await?;
-
-        Ok(objects)
-    }
-
     /// Get objects by its type and owner
     pub async fn by_type_and_owner(
         conn_pool: &PgPool,
@@ -124,6 +94,42 @@ impl Object {
         Ok(object)
     }
 
+    /// Get the replies to the object identified by its URL (oldest reply first)
+    pub async fn replies(conn_pool: &PgPool, url: &str) -> Result<Vec<Self>, Error> {
+        let objects = sqlx::query_as!(
+            Object,
+            r#"
+                SELECT * FROM objects
+                WHERE data->>'inReplyTo' = $1
+
+                ORDER BY created_at ASC
+            "#,
+            url
+        )
+        .fetch_all(conn_pool)
+        .await?;
+
+        Ok(objects)
+    }
+
+    /// Get the polls of local actors that weren't closed yet
+    pub async fn open_local_polls(conn_pool: &PgPool) -> Result<Vec<Self>, Error> {
+        let objects = sqlx::query_as!(
+            Object,
+            r#"
+                SELECT objects.* FROM objects
+                INNER JOIN actors ON actors.id = objects.owner_id
+                WHERE actors.remote = FALSE
+                AND objects.data->>'type' = 'Question'
+                AND NOT objects.data ? 'closed'
+            "#,
+        )
+        .fetch_all(conn_pool)
+        .await?;
+
+        Ok(objects)
+    }
+
     /// Delete an object identified by its URL
     pub async fn delete_by_url(conn_pool: &PgPool, url: &str) -> Result<(), Error> {
         sqlx::query!(
@@ -1,33 +1,90 @@
 use crate::{
-    database::{last_activity_timestamp, Object},
+    database::{Cursor, Object, ObjectCount},
     error::Error,
 };
 use sqlx::PgPool;
+use tranquility_types::activitypub::PUBLIC_IDENTIFIER;
 use uuid::Uuid;
 
-/// Get activities for displaying on the outbox
+/// Get activities for displaying on the outbox (newest first)
 pub async fn activities(
     conn_pool: &PgPool,
     user_id: Uuid,
-    last_activity_id: Option<Uuid>,
+    cursor: Cursor,
     limit: i64,
 ) -> Result<Vec<Object>, Error> {
-    let last_activity_timestamp = last_activity_timestamp(conn_pool, last_activity_id).await?;
-    let create_activities = sqlx::query_as!(
-        Object,
+    let create_activities = match cursor {
+        Cursor::Before(before_id) => {
+            sqlx::query_as!(
+                Object,
+                r#"
+                    SELECT * FROM objects
+                    WHERE owner_id = $1
+                    AND data->>'type' = 'Create'
+                    AND (data->'to' ? $2 OR data->'cc' ? $2)
+                    AND (
+                        $3::UUID IS NULL
+                        OR (created_at, id) < (SELECT created_at, id FROM objects WHERE id = $3)
+                    )
+
+                    ORDER BY created_at DESC, id DESC
+                    LIMIT $4
+                "#,
+                user_id,
+                PUBLIC_IDENTIFIER,
+                before_id,
+                limit
+            )
+            .fetch_all(conn_pool)
+            .await?
+        }
+        Cursor::After(after_id) => {
+            let mut create_activities = sqlx::query_as!(
+                Object,
+                r#"
+                    SELECT * FROM objects
+                    WHERE owner_id = $1
+                    AND data->>'type' = 'Create'
+                    AND (data->'to' ? $2 OR data->'cc' ? $2)
+                    AND (
+                        $3::UUID IS NULL
+                        OR (created_at, id) > (SELECT created_at, id FROM objects WHERE id = $3)
+                    )
+
+                    ORDER BY created_at ASC, id ASC
+                    LIMIT $4
+                "#,
+                user_id,
+                PUBLIC_IDENTIFIER,
+                after_id,
+                limit
+            )
+            .fetch_all(conn_pool)
+            .await?;
+            create_activities.reverse();
+
+            create_activities
+        }
+    };
+
+    Ok(create_activities)
+}
+
+/// Count the activities displayed on the outbox
+pub async fn count(conn_pool: &PgPool, user_id: Uuid) -> Result<i64, Error> {
+    let count = sqlx::query_as!(
+        ObjectCount,
         r#"
-            SELECT * FROM objects
+            SELECT COUNT(*) as "count!" FROM objects
             WHERE owner_id = $1
             AND data->>'type' = 'Create'
-            AND created_at < $2
-            LIMIT $3
+            AND (data->'to' ? $2 OR data->'cc' ? $2)
         "#,
         user_id,
-        last_activity_timestamp,
-        limit
+        PUBLIC_IDENTIFIER,
     )
-    .fetch_all(conn_pool)
+    .fetch_one(conn_pool)
     .await?;
 
-    Ok(create_activities)
+    Ok(count.into())
 }
@@ -0,0 +1,149 @@
+use crate::{
+    database::{Object, ObjectCount},
+    error::Error,
+};
+use ormx::Table;
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Clone, Table)]
+#[ormx(id = id, table = "pins", deletable)]
+/// Post pinned to the profile of an actor (listed in the `featured` collection of the actor)
+pub struct Pin {
+    pub id: Uuid,
+
+    pub actor_id: Uuid,
+    pub object_id: Uuid,
+
+    #[ormx(default)]
+    pub created_at: OffsetDateTime,
+
+    #[ormx(default)]
+    pub updated_at: OffsetDateTime,
+}
+
+impl Pin {
+    /// Pin the object to the profile of the actor
+    ///
+    /// Returns `false` if the object was already pinned
+    pub async fn pin(conn_pool: &PgPool, actor_id: Uuid, object_id: Uuid) -> Result<bool, Error> {
+        let result = sqlx::query!(
+            r#"
+                INSERT INTO pins (id, actor_id, object_id)
+                VALUES ($1, $2, $3)
+                ON CONFLICT DO NOTHING
+            "#,
+            Uuid::new_v4(),
+            actor_id,
+            object_id,
+        )
+        .execute(conn_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Unpin the object from the profile of the actor
+    ///
+    /// Returns `false` if the object wasn't pinned
+    pub async fn unpin(conn_pool: &PgPool, actor_id: Uuid, object_id: Uuid) -> Result<bool, Error> {
+        let result = sqlx::query!(
+            r#"
+                DELETE FROM pins
+                WHERE actor_id = $1
+                AND object_id = $2
+            "#,
+            actor_id,
+            object_id,
+        )
+        .execute(conn_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Replace the pinned objects of the actor
+    ///
+    /// The objects are expected in the order of the featured collection (most recently pinned object first)
+    pub async fn replace(
+        conn_pool: &PgPool,
+        actor_id: Uuid,
+        object_ids: &[Uuid],
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+                DELETE FROM pins
+                WHERE actor_id = $1
+                AND object_id <> ALL($2)
+            "#,
+            actor_id,
+            object_ids,
+        )
+        .execute(conn_pool)
+        .await?;
+
+        for object_id in object_ids.iter().rev() {
+            Self::pin(conn_pool, actor_id, *object_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Check whether the object is pinned to the profile of the actor
+    pub async fn is_pinned(
+        conn_pool: &PgPool,
+        actor_id: Uuid,
+        object_id: Uuid,
+    ) -> Result<bool, Error> {
+        let count = sqlx::query_as!(
+            ObjectCount,
+            r#"
+                SELECT COUNT(*) as "count!" FROM pins
+                WHERE actor_id = $1
+                AND object_id = $2
+            "#,
+            actor_id,
+            object_id,
+        )
+        .fetch_one(conn_pool)
+        .await?;
+
+        Ok(i64::from(count) > 0)
+    }
+
+    /// Count the objects pinned to the profile of the actor
+    pub async fn count(conn_pool: &PgPool, actor_id: Uuid) -> Result<i64, Error> {
+        let count = sqlx::query_as!(
+            ObjectCount,
+            r#"
+                SELECT COUNT(*) as "count!" FROM pins
+                WHERE actor_id = $1
+            "#,
+            actor_id,
+        )
+        .fetch_one(conn_pool)
+        .await?;
+
+        Ok(count.into())
+    }
+
+    /// Get the objects pinned to the profile of the actor (most recently pinned object first)
+    pub async fn objects(conn_pool: &PgPool, actor_id: Uuid) -> Result<Vec<Object>, Error> {
+        let objects = sqlx::query_as!(
+            Object,
+            r#"
+                SELECT objects.* FROM pins
+                INNER JOIN objects ON objects.id = pins.object_id
+                WHERE pins.actor_id = $1
+
+                ORDER BY pins.created_at DESC
+            "#,
+            actor_id,
+        )
+        .fetch_all(conn_pool)
+        .await?;
+
+        Ok(objects)
+    }
+}
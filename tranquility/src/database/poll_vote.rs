@@ -0,0 +1,145 @@
+use crate::{database::ObjectCount, error::Error};
+use ormx::Table;
+use sqlx::{postgres::PgExecutor, PgPool};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Clone, Table)]
+#[ormx(id = id, table = "poll_votes", deletable)]
+/// Vote of an actor on one option of a poll
+pub struct PollVote {
+    pub id: Uuid,
+
+    pub object_id: Uuid,
+    pub actor_id: Uuid,
+    /// Index of the chosen option
+    pub choice: i32,
+
+    #[ormx(default)]
+    pub created_at: OffsetDateTime,
+
+    #[ormx(default)]
+    pub updated_at: OffsetDateTime,
+}
+
+/// Amount of votes for one option of a poll
+struct ChoiceCount {
+    choice: i32,
+    count: i64,
+}
+
+impl PollVote {
+    /// Lock the poll until the end of the transaction
+    ///
+    /// Votes on the same poll wait for each other this way, so the votes they check for can't change in the meantime
+    pub async fn lock_poll(conn: impl PgExecutor<'_>, object_id: Uuid) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+                SELECT id FROM objects
+                WHERE id = $1
+                FOR UPDATE
+            "#,
+            object_id,
+        )
+        .fetch_one(conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record the vote of the actor for the option
+    ///
+    /// Returns `false` if the actor already voted for the option
+    pub async fn vote(
+        conn: impl PgExecutor<'_>,
+        object_id: Uuid,
+        actor_id: Uuid,
+        choice: i32,
+    ) -> Result<bool, Error> {
+        let result = sqlx::query!(
+            r#"
+                INSERT INTO poll_votes (id, object_id, actor_id, choice)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT DO NOTHING
+            "#,
+            Uuid::new_v4(),
+            object_id,
+            actor_id,
+            choice,
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get the indices of the options the actor voted for (in ascending order)
+    pub async fn choices(
+        conn: impl PgExecutor<'_>,
+        object_id: Uuid,
+        actor_id: Uuid,
+    ) -> Result<Vec<i32>, Error> {
+        let votes = sqlx::query_as!(
+            PollVote,
+            r#"
+                SELECT * FROM poll_votes
+                WHERE object_id = $1
+                AND actor_id = $2
+
+                ORDER BY choice ASC
+            "#,
+            object_id,
+            actor_id,
+        )
+        .fetch_all(conn)
+        .await?;
+
+        Ok(votes.into_iter().map(|vote| vote.choice).collect())
+    }
+
+    /// Count the votes for each option of the poll
+    ///
+    /// Returns the vote counts indexed by option and the amount of actors that voted
+    pub async fn tally(
+        conn_pool: &PgPool,
+        object_id: Uuid,
+        option_count: usize,
+    ) -> Result<(Vec<i64>, i64), Error> {
+        let choice_counts = sqlx::query_as!(
+            ChoiceCount,
+            r#"
+                SELECT choice, COUNT(*) as "count!" FROM poll_votes
+                WHERE object_id = $1
+
+                GROUP BY choice
+            "#,
+            object_id,
+        )
+        .fetch_all(conn_pool)
+        .await?;
+
+        let voters_count = sqlx::query_as!(
+            ObjectCount,
+            r#"
+                SELECT COUNT(DISTINCT actor_id) as "count!" FROM poll_votes
+                WHERE object_id = $1
+            "#,
+            object_id,
+        )
+        .fetch_one(conn_pool)
+        .await?;
+
+        let mut vote_counts = vec![0; option_count];
+        for choice_count in choice_counts {
+            let vote_count = usize::try_from(choice_count.choice)
+                .ok()
+                .and_then(|choice| vote_counts.get_mut(choice));
+
+            if let Some(vote_count) = vote_count {
+                *vote_count = choice_count.count;
+            }
+        }
+
+        Ok((vote_counts, voters_count.into()))
+    }
+}
@@ -0,0 +1,136 @@
+use crate::error::Error;
+use ormx::Table;
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Clone, Table)]
+#[ormx(id = id, table = "reactions", deletable)]
+/// Emoji reaction of an actor to an object
+pub struct Reaction {
+    pub id: Uuid,
+
+    pub object_id: Uuid,
+    pub actor_id: Uuid,
+
+    /// Unicode emoji, shortcode of a local custom emoji or `shortcode@domain` of a remote custom emoji
+    pub name: String,
+    /// Image of the custom emoji (`None` for Unicode emojis)
+    pub emoji_url: Option<String>,
+    /// ID of the `EmojiReact` or `Like` activity that carried the reaction
+    pub activity_url: String,
+
+    #[ormx(default)]
+    pub created_at: OffsetDateTime,
+
+    #[ormx(default)]
+    pub updated_at: OffsetDateTime,
+}
+
+impl Reaction {
+    /// Record the reaction of the actor
+    ///
+    /// Returns `false` if the actor already reacted with the emoji
+    pub async fn react(
+        conn_pool: &PgPool,
+        object_id: Uuid,
+        actor_id: Uuid,
+        name: &str,
+        emoji_url: Option<&str>,
+        activity_url: &str,
+    ) -> Result<bool, Error> {
+        let result = sqlx::query!(
+            r#"
+                INSERT INTO reactions (id, object_id, actor_id, name, emoji_url, activity_url)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT DO NOTHING
+            "#,
+            Uuid::new_v4(),
+            object_id,
+            actor_id,
+            name,
+            emoji_url,
+            activity_url,
+        )
+        .execute(conn_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove the reaction of the actor, returning the removed reaction
+    pub async fn unreact(
+        conn_pool: &PgPool,
+        object_id: Uuid,
+        actor_id: Uuid,
+        name: &str,
+    ) -> Result<Option<Self>, Error> {
+        let reaction = sqlx::query_as!(
+            Reaction,
+            r#"
+                DELETE FROM reactions
+                WHERE object_id = $1
+                AND actor_id = $2
+                AND name = $3
+                RETURNING *
+            "#,
+            object_id,
+            actor_id,
+            name,
+        )
+        .fetch_optional(conn_pool)
+        .await?;
+
+        Ok(reaction)
+    }
+
+    /// Remove the reaction carried by the activity (only if the activity belongs to the actor)
+    ///
+    /// Returns `false` if the activity didn't carry a reaction
+    pub async fn delete_by_activity_url(
+        conn_pool: &PgPool,
+        activity_url: &str,
+        actor_url: &str,
+    ) -> Result<bool, Error> {
+        let result = sqlx::query!(
+            r#"
+                DELETE FROM reactions
+                WHERE activity_url = $1
+                AND actor_id IN (
+                    SELECT id FROM actors
+                    WHERE actor->>'id' = $2
+                )
+            "#,
+            activity_url,
+            actor_url,
+        )
+        .execute(conn_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get the reactions to the object (optionally only the ones with the emoji), oldest reaction first
+    pub async fn by_object(
+        conn_pool: &PgPool,
+        object_id: Uuid,
+        name: Option<&str>,
+    ) -> Result<Vec<Self>, Error> {
+        let reactions = sqlx::query_as!(
+            Reaction,
+            r#"
+                SELECT * FROM reactions
+                WHERE object_id = $1
+                AND ($2::TEXT IS NULL OR name = $2)
+
+                ORDER BY created_at ASC
+            "#,
+            object_id,
+            name,
+        )
+        .fetch_all(conn_pool)
+        .await?;
+
+        Ok(reactions)
+    }
+}
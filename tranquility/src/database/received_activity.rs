@@ -0,0 +1,66 @@
+use crate::{database::ObjectCount, error::Error};
+use sqlx::PgPool;
+
+/// Check whether the activity was already received
+pub async fn exists(conn_pool: &PgPool, activity_url: &str) -> Result<bool, Error> {
+    let count = sqlx::query_as!(
+        ObjectCount,
+        r#"
+            SELECT COUNT(*) as "count!" FROM received_activities
+            WHERE activity_url = $1
+        "#,
+        activity_url,
+    )
+    .fetch_one(conn_pool)
+    .await?;
+
+    Ok(i64::from(count) > 0)
+}
+
+/// Record that the activity was received
+///
+/// Returns `false` if the activity was already recorded before
+pub async fn claim(conn_pool: &PgPool, activity_url: &str) -> Result<bool, Error> {
+    let result = sqlx::query!(
+        r#"
+            INSERT INTO received_activities (activity_url)
+            VALUES ($1)
+            ON CONFLICT DO NOTHING
+        "#,
+        activity_url,
+    )
+    .execute(conn_pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Delete the record of the activity so it gets processed when it's delivered again
+pub async fn release(conn_pool: &PgPool, activity_url: &str) -> Result<(), Error> {
+    sqlx::query!(
+        r#"
+            DELETE FROM received_activities
+            WHERE activity_url = $1
+        "#,
+        activity_url,
+    )
+    .execute(conn_pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Delete the records of activities that were received more than `retention_secs` seconds ago
+pub async fn delete_expired(conn_pool: &PgPool, retention_secs: i64) -> Result<(), Error> {
+    sqlx::query!(
+        r#"
+            DELETE FROM received_activities
+            WHERE created_at < NOW() - $1::BIGINT * INTERVAL '1 second'
+        "#,
+        retention_secs,
+    )
+    .execute(conn_pool)
+    .await?;
+
+    Ok(())
+}
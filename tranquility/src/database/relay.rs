@@ -0,0 +1,162 @@
+use crate::{database::ObjectCount, error::Error};
+use futures_util::stream::{StreamExt, TryStreamExt};
+use ormx::Table;
+use sqlx::PgPool;
+use std::str::FromStr;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// State of the subscription to a relay
+pub enum RelayState {
+    /// The relay didn't answer the subscription request yet
+    Pending,
+
+    /// The relay accepted the subscription request
+    Accepted,
+
+    /// The relay rejected the subscription request
+    Rejected,
+}
+
+impl RelayState {
+    /// Name of the state as used in the database and the admin API
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Accepted => "accepted",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+impl FromStr for RelayState {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "accepted" => Ok(Self::Accepted),
+            "rejected" => Ok(Self::Rejected),
+            _ => Err(Error::InvalidRequest),
+        }
+    }
+}
+
+#[derive(Clone, Table)]
+#[ormx(id = id, table = "relays", deletable, insertable)]
+pub struct Relay {
+    pub id: Uuid,
+
+    #[ormx(get_optional(&str))]
+    pub inbox_url: String,
+
+    /// URL of the `Follow` activity the subscription was requested with
+    #[ormx(get_optional(&str))]
+    pub follow_activity_url: String,
+
+    /// URL of the relay actor (known once the relay answered the subscription request)
+    #[ormx(default)]
+    pub actor_url: Option<String>,
+
+    #[ormx(default)]
+    pub state: String,
+
+    #[ormx(default)]
+    pub created_at: OffsetDateTime,
+
+    #[ormx(default)]
+    pub updated_at: OffsetDateTime,
+}
+
+// Required because of the "query_as" macro
+struct RelayInboxUrl {
+    inbox_url: String,
+}
+
+impl From<RelayInboxUrl> for String {
+    fn from(relay_inbox_url: RelayInboxUrl) -> Self {
+        relay_inbox_url.inbox_url
+    }
+}
+
+impl Relay {
+    /// State of the subscription
+    ///
+    /// Unknown values are treated as pending subscriptions
+    pub fn state(&self) -> RelayState {
+        self.state.parse().unwrap_or(RelayState::Pending)
+    }
+
+    /// Get all relays (oldest subscription first)
+    pub async fn all(conn_pool: &PgPool) -> Result<Vec<Self>, Error> {
+        let relays = sqlx::query_as!(
+            Relay,
+            r#"
+                SELECT * FROM relays
+                ORDER BY created_at ASC
+            "#
+        )
+        .fetch_all(conn_pool)
+        .await?;
+
+        Ok(relays)
+    }
+
+    /// Record the answer of the relay to the subscription request
+    pub async fn set_state(
+        conn_pool: &PgPool,
+        id: Uuid,
+        state: RelayState,
+        actor_url: &str,
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+                UPDATE relays
+                SET state = $2, actor_url = $3
+                WHERE id = $1
+            "#,
+            id,
+            state.as_str(),
+            actor_url,
+        )
+        .execute(conn_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get the inbox URLs of the relays that accepted the subscription
+    pub async fn accepted_inbox_urls(conn_pool: &PgPool) -> Result<Vec<String>, Error> {
+        let inbox_urls = sqlx::query_as!(
+            RelayInboxUrl,
+            r#"
+                SELECT inbox_url FROM relays
+                WHERE state = 'accepted'
+            "#
+        )
+        .fetch(conn_pool)
+        .map(|row_result| row_result.map(Into::into))
+        .try_collect()
+        .await?;
+
+        Ok(inbox_urls)
+    }
+
+    /// Check whether the actor is a relay that accepted the subscription
+    pub async fn is_accepted_actor(conn_pool: &PgPool, actor_url: &str) -> Result<bool, Error> {
+        let count = sqlx::query_as!(
+            ObjectCount,
+            r#"
+                SELECT COUNT(*) as "count!" FROM relays
+                WHERE actor_url = $1
+                AND state = 'accepted'
+            "#,
+            actor_url,
+        )
+        .fetch_one(conn_pool)
+        .await?;
+
+        Ok(i64::from(count) > 0)
+    }
+}
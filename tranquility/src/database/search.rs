@@ -0,0 +1,155 @@
+use crate::{
+    database::{Actor, Object},
+    error::Error,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Name of a hashtag found by a search
+struct HashtagName {
+    name: String,
+}
+
+/// Search the local and the known remote accounts by their username or display name
+///
+/// Unconfirmed local accounts are left out (remote accounts are never confirmed).
+/// Exact username matches come first, followed by local accounts.
+/// If a domain is given, only accounts of this instance are returned
+pub async fn accounts(
+    conn_pool: &PgPool,
+    query: &str,
+    domain: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Actor>, Error> {
+    let actors = sqlx::query_as!(
+        Actor,
+        r#"
+            SELECT * FROM actors
+            WHERE (remote OR is_confirmed)
+            AND (
+                starts_with(LOWER(username), LOWER($1))
+                OR (
+                    $2::TEXT IS NULL
+                    AND position(LOWER($1) IN LOWER(COALESCE(actor->>'name', ''))) > 0
+                )
+            )
+            AND (
+                $2::TEXT IS NULL
+                OR LOWER(split_part(actor->>'id', '/', 3)) = LOWER($2)
+            )
+
+            ORDER BY LOWER(username) = LOWER($1) DESC, remote ASC, username ASC
+            LIMIT $3
+            OFFSET $4
+        "#,
+        query,
+        domain,
+        limit,
+        offset,
+    )
+    .fetch_all(conn_pool)
+    .await?;
+
+    Ok(actors)
+}
+
+/// Search the notes the viewer interacted with by their content (best match first)
+///
+/// These are the notes of the viewer, the notes mentioning the viewer
+/// and the notes the viewer replied to, announced, liked, reacted to or voted on.
+/// Notes the viewer isn't allowed to see (anymore) are left out
+pub async fn statuses(
+    conn_pool: &PgPool,
+    viewer_id: Uuid,
+    viewer_url: &str,
+    query: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Object>, Error> {
+    let objects = sqlx::query_as!(
+        Object,
+        r#"
+            SELECT objects.* FROM objects
+            INNER JOIN actors AS owners ON owners.id = objects.owner_id
+            WHERE objects.data->>'type' IN ('Note', 'Question')
+            AND object_search_vector(objects.data) @@ websearch_to_tsquery('simple', $3)
+            AND is_visible_to(objects.data, owners.actor, $2)
+            AND (
+                objects.owner_id = $1
+                OR object_tags(objects.data) @> jsonb_build_array(
+                    jsonb_build_object('type', 'Mention', 'href', $2::TEXT)
+                )
+                OR EXISTS (
+                    SELECT 1 FROM objects AS interactions
+                    WHERE interactions.owner_id = $1
+                    AND (
+                        interactions.data->>'inReplyTo' = objects.data->>'id'
+                        OR (
+                            interactions.data->>'type' IN ('Announce', 'Like')
+                            AND COALESCE(
+                                interactions.data->'object'->>'id',
+                                interactions.data->>'object'
+                            ) = objects.data->>'id'
+                        )
+                    )
+                )
+                OR EXISTS (
+                    SELECT 1 FROM reactions
+                    WHERE reactions.object_id = objects.id
+                    AND reactions.actor_id = $1
+                )
+                OR EXISTS (
+                    SELECT 1 FROM poll_votes
+                    WHERE poll_votes.object_id = objects.id
+                    AND poll_votes.actor_id = $1
+                )
+            )
+
+            ORDER BY
+                ts_rank(object_search_vector(objects.data), websearch_to_tsquery('simple', $3)) DESC,
+                objects.created_at DESC
+            LIMIT $4
+            OFFSET $5
+        "#,
+        viewer_id,
+        viewer_url,
+        query,
+        limit,
+        offset,
+    )
+    .fetch_all(conn_pool)
+    .await?;
+
+    Ok(objects)
+}
+
+/// Search the hashtags used by posts whose names start with the prefix (lowercase and without the leading `#`)
+///
+/// The most used hashtags come first
+pub async fn hashtags(
+    conn_pool: &PgPool,
+    prefix: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<String>, Error> {
+    let hashtags = sqlx::query_as!(
+        HashtagName,
+        r#"
+            SELECT name FROM object_hashtags
+            WHERE starts_with(name, LOWER($1))
+
+            GROUP BY name
+            ORDER BY COUNT(*) DESC, name ASC
+            LIMIT $2
+            OFFSET $3
+        "#,
+        prefix,
+        limit,
+        offset,
+    )
+    .fetch_all(conn_pool)
+    .await?;
+
+    Ok(hashtags.into_iter().map(|hashtag| hashtag.name).collect())
+}
@@ -0,0 +1,70 @@
+use crate::{config::SignatureFormat, error::Error};
+use sqlx::PgPool;
+
+// Required because of the "query_as" macro
+struct ServerSignatureFormat {
+    signature_format: String,
+}
+
+/// Get the signature format recorded for the server
+///
+/// Unknown values are treated as if nothing was recorded
+pub async fn signature_format(
+    conn_pool: &PgPool,
+    domain: &str,
+) -> Result<Option<SignatureFormat>, Error> {
+    let server = sqlx::query_as!(
+        ServerSignatureFormat,
+        r#"
+            SELECT signature_format FROM servers
+            WHERE domain = $1
+        "#,
+        domain
+    )
+    .fetch_optional(conn_pool)
+    .await?;
+
+    Ok(server.and_then(|server| server.signature_format.parse().ok()))
+}
+
+/// Set the signature format of the server (overwrites the previously recorded format)
+pub async fn set_signature_format(
+    conn_pool: &PgPool,
+    domain: &str,
+    signature_format: SignatureFormat,
+) -> Result<(), Error> {
+    sqlx::query!(
+        r#"
+            INSERT INTO servers (domain, signature_format)
+            VALUES ($1, $2)
+            ON CONFLICT (domain) DO UPDATE SET signature_format = EXCLUDED.signature_format
+        "#,
+        domain,
+        signature_format.as_str(),
+    )
+    .execute(conn_pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Record the signature format of the server unless a format is already recorded
+pub async fn record_signature_format(
+    conn_pool: &PgPool,
+    domain: &str,
+    signature_format: SignatureFormat,
+) -> Result<(), Error> {
+    sqlx::query!(
+        r#"
+            INSERT INTO servers (domain, signature_format)
+            VALUES ($1, $2)
+            ON CONFLICT (domain) DO NOTHING
+        "#,
+        domain,
+        signature_format.as_str(),
+    )
+    .execute(conn_pool)
+    .await?;
+
+    Ok(())
+}
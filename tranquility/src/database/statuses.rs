@@ -0,0 +1,241 @@
+use crate::{
+    database::{Cursor, Object},
+    error::Error,
+};
+use sqlx::PgPool;
+use tranquility_types::activitypub::PUBLIC_IDENTIFIER;
+use uuid::Uuid;
+
+/// Filters applied to the statuses of an account
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StatusFilter<'a> {
+    /// Only return notes with attachments
+    pub only_media: bool,
+
+    /// Skip replies (except replies to the account itself)
+    pub exclude_replies: bool,
+
+    /// Skip the announces of the account
+    pub exclude_reblogs: bool,
+
+    /// Only return notes tagged with this hashtag (without the leading `#`)
+    pub tagged: Option<&'a str>,
+}
+
+/// Get the notes and announces of an account the viewer is allowed to see (newest first)
+///
+/// Public and unlisted posts are visible to everyone.
+/// Followers-only posts are only visible to approved followers and direct posts only to the addressed actors.
+/// The account itself can see all of its posts
+pub async fn by_account(
+    conn_pool: &PgPool,
+    account_id: Uuid,
+    viewer_url: Option<&str>,
+    filter: StatusFilter<'_>,
+    cursor: Cursor,
+    limit: i64,
+) -> Result<Vec<Object>, Error> {
+    let (cursor_id, is_after) = cursor.into_parts();
+    let mut objects = sqlx::query_as!(
+        Object,
+        r#"
+            SELECT objects.* FROM objects
+            INNER JOIN actors AS owners ON owners.id = objects.owner_id
+            LEFT JOIN objects AS cursor_objects ON cursor_objects.id = $7
+            WHERE objects.owner_id = $1
+            AND (
+                objects.data->>'type' IN ('Note', 'Question')
+                OR (objects.data->>'type' = 'Announce' AND NOT $3)
+            )
+            AND (
+                NOT $4
+                OR CASE jsonb_typeof(objects.data->'attachment')
+                    WHEN 'array' THEN jsonb_array_length(objects.data->'attachment') > 0
+                    WHEN 'object' THEN TRUE
+                    ELSE FALSE
+                END
+            )
+            AND (
+                NOT $5
+                OR objects.data->>'inReplyTo' IS NULL
+                OR EXISTS (
+                    SELECT 1 FROM objects AS parents
+                    WHERE parents.data->>'id' = objects.data->>'inReplyTo'
+                    AND parents.owner_id = $1
+                )
+            )
+            AND (
+                $6::TEXT IS NULL
+                OR EXISTS (
+                    SELECT 1 FROM object_hashtags
+                    WHERE object_hashtags.object_id = objects.id
+                    AND object_hashtags.name = LOWER($6)
+                )
+            )
+            AND is_visible_to(objects.data, owners.actor, $2)
+            AND (
+                $7::UUID IS NULL
+                OR CASE WHEN $8
+                    THEN (objects.created_at, objects.id) > (cursor_objects.created_at, cursor_objects.id)
+                    ELSE (objects.created_at, objects.id) < (cursor_objects.created_at, cursor_objects.id)
+                END
+            )
+
+            ORDER BY
+                CASE WHEN $8 THEN objects.created_at END ASC,
+                CASE WHEN $8 THEN objects.id END ASC,
+                objects.created_at DESC,
+                objects.id DESC
+            LIMIT $9
+        "#,
+        account_id,
+        viewer_url,
+        filter.exclude_reblogs,
+        filter.only_media,
+        filter.exclude_replies,
+        filter.tagged,
+        cursor_id,
+        is_after,
+        limit,
+    )
+    .fetch_all(conn_pool)
+    .await?;
+
+    if is_after {
+        objects.reverse();
+    }
+
+    Ok(objects)
+}
+
+/// Check whether the viewer is allowed to see the object
+///
+/// Uses the same rules as the statuses of an account. Returns `false` if the object doesn't exist
+pub async fn is_visible(
+    conn_pool: &PgPool,
+    object_id: Uuid,
+    viewer_url: Option<&str>,
+) -> Result<bool, Error> {
+    let visibility = sqlx::query!(
+        r#"
+            SELECT is_visible_to(objects.data, owners.actor, $2) as "visible!" FROM objects
+            INNER JOIN actors AS owners ON owners.id = objects.owner_id
+            WHERE objects.id = $1
+        "#,
+        object_id,
+        viewer_url,
+    )
+    .fetch_optional(conn_pool)
+    .await?;
+
+    Ok(visibility.is_some_and(|visibility| visibility.visible))
+}
+
+/// Get the home timeline of the viewer (newest first)
+///
+/// Contains the posts of the viewer, the posts and announces of the actors the viewer follows
+/// and the posts tagged with hashtags the viewer follows, as far as the viewer is allowed to see them
+pub async fn home(
+    conn_pool: &PgPool,
+    viewer_id: Uuid,
+    viewer_url: &str,
+    cursor: Cursor,
+    limit: i64,
+) -> Result<Vec<Object>, Error> {
+    let (cursor_id, is_after) = cursor.into_parts();
+    let mut objects = sqlx::query_as!(
+        Object,
+        r#"
+            SELECT objects.* FROM objects
+            INNER JOIN actors AS owners ON owners.id = objects.owner_id
+            LEFT JOIN objects AS cursor_objects ON cursor_objects.id = $3
+            WHERE objects.data->>'type' IN ('Note', 'Question', 'Announce')
+            AND (
+                objects.owner_id = $1
+                OR is_approved_follower($2, owners.actor->>'id')
+                OR EXISTS (
+                    SELECT 1 FROM object_hashtags
+                    INNER JOIN followed_tags ON followed_tags.name = object_hashtags.name
+                    WHERE object_hashtags.object_id = objects.id
+                    AND followed_tags.actor_id = $1
+                )
+            )
+            AND is_visible_to(objects.data, owners.actor, $2)
+            AND (
+                $3::UUID IS NULL
+                OR CASE WHEN $4
+                    THEN (objects.created_at, objects.id) > (cursor_objects.created_at, cursor_objects.id)
+                    ELSE (objects.created_at, objects.id) < (cursor_objects.created_at, cursor_objects.id)
+                END
+            )
+
+            ORDER BY
+                CASE WHEN $4 THEN objects.created_at END ASC,
+                CASE WHEN $4 THEN objects.id END ASC,
+                objects.created_at DESC,
+                objects.id DESC
+            LIMIT $5
+        "#,
+        viewer_id,
+        viewer_url,
+        cursor_id,
+        is_after,
+        limit,
+    )
+    .fetch_all(conn_pool)
+    .await?;
+
+    if is_after {
+        objects.reverse();
+    }
+
+    Ok(objects)
+}
+
+/// Get the public posts tagged with the hashtag (newest first)
+pub async fn by_hashtag(
+    conn_pool: &PgPool,
+    name: &str,
+    cursor: Cursor,
+    limit: i64,
+) -> Result<Vec<Object>, Error> {
+    let (cursor_id, is_after) = cursor.into_parts();
+    let mut objects = sqlx::query_as!(
+        Object,
+        r#"
+            SELECT objects.* FROM object_hashtags
+            INNER JOIN objects ON objects.id = object_hashtags.object_id
+            LEFT JOIN objects AS cursor_objects ON cursor_objects.id = $3
+            WHERE object_hashtags.name = LOWER($1)
+            AND objects.data->>'type' IN ('Note', 'Question')
+            AND objects.data->'to' ? $2
+            AND (
+                $3::UUID IS NULL
+                OR CASE WHEN $4
+                    THEN (objects.created_at, objects.id) > (cursor_objects.created_at, cursor_objects.id)
+                    ELSE (objects.created_at, objects.id) < (cursor_objects.created_at, cursor_objects.id)
+                END
+            )
+
+            ORDER BY
+                CASE WHEN $4 THEN objects.created_at END ASC,
+                CASE WHEN $4 THEN objects.id END ASC,
+                objects.created_at DESC,
+                objects.id DESC
+            LIMIT $5
+        "#,
+        name,
+        PUBLIC_IDENTIFIER,
+        cursor_id,
+        is_after,
+        limit,
+    )
+    .fetch_all(conn_pool)
+    .await?;
+
+    if is_after {
+        objects.reverse();
+    }
+
+    Ok(objects)
+}
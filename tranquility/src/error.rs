@@ -11,13 +11,46 @@ use rsa::{
     pkcs8::{spki::Error as SpkiError, Error as Pkcs8Error},
 };
 use serde_json::Error as SerdeJsonError;
+use serde_qs::Error as SerdeQsError;
 use sqlx::{migrate::MigrateError as SqlxMigrationError, Error as SqlxError};
 use time::Error as TimeError;
 use tranquility_http_signatures::Error as HttpSignaturesError;
+use tranquility_ld_signatures::Error as LdSignaturesError;
 use url::ParseError as UrlParseError;
 use uuid::Error as UuidError;
 use validator::ValidationErrors;
 
+#[derive(Debug, thiserror::Error)]
+/// Reasons why fetching remote content can fail
+pub enum FetchError {
+    #[error("Address isn't publicly routable")]
+    ForbiddenAddress,
+
+    #[error("Entity has no type")]
+    MissingType,
+
+    #[error("Entity belongs to a different server")]
+    OriginMismatch,
+
+    #[error("Response is too large")]
+    ResponseTooLarge,
+
+    #[error("Too many redirects")]
+    TooManyRedirects,
+
+    #[error("Unexpected content type: {0}")]
+    UnexpectedContentType(String),
+
+    #[error("Remote server returned content we can't interpret")]
+    UnexpectedEntity,
+
+    #[error("Remote server responded with status code {0}")]
+    UnsuccessfulStatus(StatusCode),
+
+    #[error("Unsupported URL scheme")]
+    UnsupportedScheme,
+}
+
 #[derive(Debug, thiserror::Error)]
 /// Combined error enum for converting errors into rejections
 pub enum Error {
@@ -27,8 +60,11 @@ pub enum Error {
     #[error("Template formatting failed: {0}")]
     Askama(#[from] AskamaError),
 
-    #[error("Remote content fetch failed")]
-    Fetch,
+    #[error("Domain is blocked")]
+    BlockedDomain,
+
+    #[error("Remote content fetch failed: {0}")]
+    Fetch(#[from] FetchError),
 
     #[error("HTTP signature operation failed: {0}")]
     HttpSignatures(#[from] HttpSignaturesError),
@@ -36,12 +72,24 @@ pub enum Error {
     #[error("Invalid request")]
     InvalidRequest,
 
+    #[error("Linked Data signature operation failed: {0}")]
+    LdSignatures(#[from] LdSignaturesError),
+
     #[error("Malformed URL")]
     MalformedUrl,
 
+    #[error("Actor has no private key")]
+    MissingPrivateKey,
+
+    #[error("Payload is too large")]
+    PayloadTooLarge,
+
     #[error("Unauthorized")]
     Unauthorized,
 
+    #[error("Queue is full")]
+    QueueFull,
+
     #[error("PKCS#8 operation failed: {0}")]
     Pkcs8(#[from] Pkcs8Error),
 
@@ -66,6 +114,9 @@ pub enum Error {
     #[error("serde-json operation failed: {0}")]
     SerdeJson(#[from] SerdeJsonError),
 
+    #[error("Form couldn't be parsed: {0}")]
+    SerdeQs(#[from] SerdeQsError),
+
     #[error("time operation failed: {0}")]
     Time(#[from] TimeError),
 
@@ -99,12 +150,31 @@ impl IntoResponse for Error {
             Error::InvalidRequest
             | Error::UnknownActivity
             | Error::MalformedUrl
-            | Error::Uuid(..) => (StatusCode::BAD_REQUEST, error_text).into_response(),
+            | Error::SerdeQs(..)
+            | Error::Uuid(..)
+            | Error::HttpSignatures(
+                HttpSignaturesError::Base64Decode(..)
+                | HttpSignaturesError::HttpToStr(..)
+                | HttpSignaturesError::InvalidHeader
+                | HttpSignaturesError::InvalidStructuredField
+                | HttpSignaturesError::UnknownAlgorithm,
+            ) => (StatusCode::BAD_REQUEST, error_text).into_response(),
 
             // Add special case to send the previously defined error messages
             Error::Validation(err) => (StatusCode::BAD_REQUEST, Json(err)).into_response(),
 
-            Error::Unauthorized => (StatusCode::UNAUTHORIZED, error_text).into_response(),
+            Error::Unauthorized
+            | Error::HttpSignatures(
+                HttpSignaturesError::ClockSkew
+                | HttpSignaturesError::ExpiredSignature
+                | HttpSignaturesError::MissingSignedHeader(..),
+            ) => (StatusCode::UNAUTHORIZED, error_text).into_response(),
+
+            Error::BlockedDomain => (StatusCode::FORBIDDEN, error_text).into_response(),
+
+            Error::PayloadTooLarge => (StatusCode::PAYLOAD_TOO_LARGE, error_text).into_response(),
+
+            Error::QueueFull => (StatusCode::SERVICE_UNAVAILABLE, error_text).into_response(),
 
             Error::Argon2(..)
             | Error::Pkcs8(..)
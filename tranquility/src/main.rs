@@ -27,6 +27,9 @@ async fn main() -> io::Result<()> {
     database::migrate(&state.db_pool)
         .await
         .expect("Database migration failed");
+    activitypub::instance_actor::get(&state)
+        .await
+        .expect("Couldn't initialise the instance actor");
     daemon::start(&state);
 
     server::run(state).await?;
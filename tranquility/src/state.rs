@@ -1,4 +1,4 @@
-use crate::config::Configuration;
+use crate::{activitypub::inbox_queue::InboxQueue, config::Configuration};
 use sqlx::PgPool;
 use std::sync::Arc;
 
@@ -10,6 +10,7 @@ pub type ArcState = Arc<State>;
 pub struct State {
     pub config: Configuration,
     pub db_pool: PgPool,
+    pub inbox_queue: InboxQueue,
 }
 
 impl State {
@@ -20,6 +21,12 @@ impl State {
 
     /// Create a new state instance
     pub fn new_arcless(config: Configuration, db_pool: PgPool) -> Self {
-        Self { config, db_pool }
+        let inbox_queue = InboxQueue::new(&config.federation);
+
+        Self {
+            config,
+            db_pool,
+            inbox_queue,
+        }
     }
 }
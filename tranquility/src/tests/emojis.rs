@@ -0,0 +1,208 @@
+use crate::{
+    activitypub::emoji,
+    consts::activitypub::MAX_INGESTED_EMOJIS,
+    database::custom_emoji::CustomEmoji,
+    format_uuid,
+    state::ArcState,
+    tests::{random_domain, start_test_server, test_state},
+};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tranquility_types::activitypub::Tag;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn custom_emojis() {
+    let state = test_state().await;
+    let domain = random_domain();
+    let shortcode = format!("emoji{}", Uuid::new_v4().as_simple());
+
+    let local_emoji = CustomEmoji::upsert_local(&state.db_pool, &shortcode, None, false)
+        .await
+        .unwrap();
+    let updated_local_emoji =
+        CustomEmoji::upsert_local(&state.db_pool, &shortcode, Some("cats"), true)
+            .await
+            .unwrap();
+    assert_eq!(local_emoji.id, updated_local_emoji.id);
+    assert_eq!(updated_local_emoji.category.as_deref(), Some("cats"));
+    assert!(updated_local_emoji.visible_in_picker);
+
+    // Remote emojis with the same shortcode don't replace the local emoji
+    let remote_emoji = CustomEmoji::upsert_remote(
+        &state.db_pool,
+        &shortcode,
+        &domain,
+        "https://example.com/emoji.png",
+        None,
+    )
+    .await
+    .unwrap();
+    assert_ne!(local_emoji.id, remote_emoji.id);
+    assert!(!remote_emoji.visible_in_picker);
+
+    let found_emoji = CustomEmoji::by_shortcode(&state.db_pool, &shortcode, Some(&domain))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found_emoji.id, remote_emoji.id);
+    assert_eq!(
+        found_emoji.image_url.as_deref(),
+        Some("https://example.com/emoji.png")
+    );
+
+    let local_emojis =
+        CustomEmoji::local_by_shortcodes(&state.db_pool, &[shortcode.clone(), "missing".into()])
+            .await
+            .unwrap();
+    assert_eq!(local_emojis.len(), 1);
+    assert_eq!(local_emojis[0].id, local_emoji.id);
+
+    assert!(!CustomEmoji::has_image(&state.db_pool, remote_emoji.id)
+        .await
+        .unwrap());
+    CustomEmoji::set_image(&state.db_pool, remote_emoji.id, "image/png", &[1, 2, 3])
+        .await
+        .unwrap();
+    CustomEmoji::set_image(&state.db_pool, remote_emoji.id, "image/gif", &[4, 5])
+        .await
+        .unwrap();
+    assert!(CustomEmoji::has_image(&state.db_pool, remote_emoji.id)
+        .await
+        .unwrap());
+
+    let image = CustomEmoji::image(&state.db_pool, remote_emoji.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(image.content_type, "image/gif");
+    assert_eq!(image.data, [4, 5]);
+
+    // Emojis are attributed to the instance of the actor using them
+    let owner_url = format!("https://{}/users/test", domain);
+    assert_eq!(
+        emoji::domain(&state.config, &owner_url).unwrap(),
+        Some(domain)
+    );
+    let local_owner_url = format!("https://{}/users/test", state.config.instance.domain);
+    assert_eq!(
+        emoji::domain(&state.config, &local_owner_url).unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn emoji_routes() {
+    let state: ArcState = test_state().await.into();
+    let domain = random_domain();
+    let shortcode = format!("emoji{}", Uuid::new_v4().as_simple());
+
+    let local_emoji = CustomEmoji::upsert_local(&state.db_pool, &shortcode, None, true)
+        .await
+        .unwrap();
+    CustomEmoji::set_image(&state.db_pool, local_emoji.id, "image/png", &[1, 2, 3])
+        .await
+        .unwrap();
+    let remote_emoji = CustomEmoji::upsert_remote(
+        &state.db_pool,
+        &shortcode,
+        &domain,
+        "https://example.com/emoji.png",
+        None,
+    )
+    .await
+    .unwrap();
+
+    let test_client = start_test_server(Arc::clone(&state));
+
+    // Only local emojis are listed
+    let custom_emojis: Vec<Value> = test_client
+        .get("/api/v1/custom_emojis")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let listed_emojis = custom_emojis
+        .iter()
+        .filter(|custom_emoji| custom_emoji["shortcode"] == shortcode.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(listed_emojis.len(), 1);
+    assert_eq!(
+        listed_emojis[0]["url"],
+        format!(
+            "https://{}/media/emojis/{}",
+            state.config.instance.domain,
+            format_uuid!(local_emoji.id)
+        )
+    );
+
+    let emoji_tag: Value = test_client
+        .get(&format!("/emojis/{}", local_emoji.id))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(emoji_tag["type"], "Emoji");
+    assert_eq!(emoji_tag["name"], format!(":{}:", shortcode));
+    assert_eq!(emoji_tag["icon"]["url"], listed_emojis[0]["url"]);
+
+    // Remote emojis are served by their instances
+    let response = test_client
+        .get(&format!("/emojis/{}", remote_emoji.id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = test_client
+        .get(&format!("/media/emojis/{}", local_emoji.id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "image/png");
+    assert_eq!(response.headers()["x-content-type-options"], "nosniff");
+    assert_eq!(response.bytes().await.unwrap().as_ref(), [1, 2, 3]);
+
+    // Uncached images of remote emojis don't redirect to their original URL
+    let response = test_client
+        .get(&format!("/media/emojis/{}", remote_emoji.id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.headers().get("location").is_none());
+}
+
+#[test]
+fn ingested_emojis_limit() {
+    let emoji_tag = |shortcode: String| {
+        json!({
+            "type": "Emoji",
+            "name": format!(":{}:", shortcode),
+            "icon": { "type": "Image", "url": format!("https://example.com/{}.png", shortcode) },
+        })
+    };
+
+    // Repeated shortcodes only count once
+    let tags = (0..MAX_INGESTED_EMOJIS * 2)
+        .map(|index| emoji_tag(format!("emoji{}", index)))
+        .chain([emoji_tag("emoji0".into())])
+        .collect::<Vec<_>>();
+    let tags: Vec<Tag> = serde_json::from_value(json!(tags)).unwrap();
+
+    let emojis = emoji::ingested_emojis(&tags);
+    assert_eq!(emojis.len(), MAX_INGESTED_EMOJIS);
+    assert_eq!(emojis[0].shortcode(), "emoji0");
+    assert_eq!(
+        emojis.last().unwrap().shortcode(),
+        format!("emoji{}", MAX_INGESTED_EMOJIS - 1)
+    );
+
+    let tags: Vec<Tag> = serde_json::from_value(json!([
+        emoji_tag("blobcat".into()),
+        emoji_tag("blobcat".into())
+    ]))
+    .unwrap();
+    assert_eq!(emoji::ingested_emojis(&tags).len(), 1);
+}
@@ -0,0 +1,267 @@
+use crate::{
+    config::{ConfigurationFederation, SignatureFormat},
+    database::{context_crawl, received_activity, Object as DbObject},
+    state::ArcState,
+    tests::{actor_url, insert_actor, insert_object, random_domain, start_test_server, test_state},
+};
+use http::StatusCode;
+use serde_json::{json, Value};
+use tranquility_types::activitypub::Actor;
+use url::Url;
+
+#[test]
+fn blocked_domains() {
+    let federation_config = ConfigurationFederation {
+        blocked_domains: vec!["blocked.example.com".into()],
+        ..ConfigurationFederation::default()
+    };
+
+    let is_blocked = |url: &str| federation_config.is_blocked(&Url::parse(url).unwrap());
+
+    assert!(is_blocked("https://blocked.example.com/users/test"));
+    assert!(is_blocked("https://sub.blocked.example.com/users/test"));
+    assert!(!is_blocked("https://notblocked.example.com/users/test"));
+    assert!(!is_blocked("https://example.com/users/test"));
+}
+
+#[tokio::test]
+async fn secure_mode_rejects_unsigned_fetch() {
+    let mut state = test_state().await;
+    state.config.federation.secure_mode = true;
+    let test_client = start_test_server(state);
+
+    let test_response = test_client
+        .get("/users/00000000000000000000000000000000")
+        .await
+        .expect("Failed to send fetch request");
+    assert_eq!(test_response.status(), StatusCode::UNAUTHORIZED);
+
+    let test_response = test_client
+        .get("/objects/00000000000000000000000000000000")
+        .await
+        .expect("Failed to send fetch request");
+    assert_eq!(test_response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn instance_actor() {
+    let mut state = test_state().await;
+    state.config.federation.secure_mode = true;
+    let test_client = start_test_server(state);
+
+    let test_response = test_client
+        .get("/actor")
+        .await
+        .expect("Failed to send fetch request");
+    assert_eq!(test_response.status(), StatusCode::OK);
+
+    let instance_actor: Actor = test_response
+        .json()
+        .await
+        .expect("Failed to decode instance actor");
+    assert_eq!(instance_actor.r#type, "Service");
+    assert_eq!(instance_actor.id, "https://tranquility.example.com/actor");
+    assert_eq!(instance_actor.username, "tranquility.example.com");
+
+    // The collections the instance actor links to exist, they're just empty
+    for collection_url in [
+        instance_actor.outbox,
+        instance_actor.followers,
+        instance_actor.following,
+    ] {
+        let path = Url::parse(&collection_url).unwrap().path().to_string();
+        let test_response = test_client
+            .get(&path)
+            .await
+            .expect("Failed to send fetch request");
+        assert_eq!(test_response.status(), StatusCode::OK);
+
+        let collection: Value = test_response
+            .json()
+            .await
+            .expect("Failed to decode collection");
+        assert_eq!(collection["id"], collection_url.as_str());
+        assert_eq!(collection["totalItems"], 0);
+    }
+}
+
+#[tokio::test]
+async fn concurrent_instance_actor_creation() {
+    let mut state = test_state().await;
+    state.config.instance.domain = random_domain();
+    let state: ArcState = state.into();
+
+    // Whoever creates the instance actor first wins, everyone else gets the same actor
+    let (first, second) = tokio::join!(
+        crate::activitypub::instance_actor::get(&state),
+        crate::activitypub::instance_actor::get(&state),
+    );
+    let (first_actor, first_db_actor) = first.unwrap();
+    let (second_actor, second_db_actor) = second.unwrap();
+
+    assert_eq!(first_db_actor.id, second_db_actor.id);
+    assert_eq!(
+        first_actor.public_key.public_key_pem,
+        second_actor.public_key.public_key_pem
+    );
+}
+
+#[tokio::test]
+async fn server_signature_format() {
+    let state = test_state().await;
+    let domain = random_domain();
+    let domain = domain.as_str();
+
+    assert_eq!(
+        crate::database::server::signature_format(&state.db_pool, domain)
+            .await
+            .unwrap(),
+        None
+    );
+
+    // Recording a format doesn't overwrite an already recorded one
+    crate::database::server::record_signature_format(
+        &state.db_pool,
+        domain,
+        SignatureFormat::Rfc9421,
+    )
+    .await
+    .unwrap();
+    crate::database::server::record_signature_format(
+        &state.db_pool,
+        domain,
+        SignatureFormat::Cavage,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        crate::database::server::signature_format(&state.db_pool, domain)
+            .await
+            .unwrap(),
+        Some(SignatureFormat::Rfc9421)
+    );
+
+    // Setting the format does
+    crate::database::server::set_signature_format(&state.db_pool, domain, SignatureFormat::Cavage)
+        .await
+        .unwrap();
+    assert_eq!(
+        crate::database::server::signature_format(&state.db_pool, domain)
+            .await
+            .unwrap(),
+        Some(SignatureFormat::Cavage)
+    );
+}
+
+#[tokio::test]
+async fn received_activities() {
+    let state = test_state().await;
+    let activity_url = format!("https://{}/activities/1", random_domain());
+
+    assert!(!received_activity::exists(&state.db_pool, &activity_url)
+        .await
+        .unwrap());
+
+    assert!(received_activity::claim(&state.db_pool, &activity_url)
+        .await
+        .unwrap());
+    assert!(!received_activity::claim(&state.db_pool, &activity_url)
+        .await
+        .unwrap());
+
+    assert!(received_activity::exists(&state.db_pool, &activity_url)
+        .await
+        .unwrap());
+
+    received_activity::delete_expired(&state.db_pool, 60)
+        .await
+        .unwrap();
+    assert!(received_activity::exists(&state.db_pool, &activity_url)
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+async fn resolve_follower_inboxes() {
+    let state = test_state().await;
+    let domain = random_domain();
+    let followed_url = actor_url(&domain, "followed");
+    let follower_url = actor_url(&domain, "follower");
+    let follower_inbox_url = format!("{}/inbox", follower_url);
+
+    insert_actor(&state, &domain, "followed", true).await;
+    let follower = insert_actor(&state, &domain, "follower", true).await;
+
+    for (index, approved) in [true, false].into_iter().enumerate() {
+        let data = json!({
+            "id": format!("https://{}/follows/{}", domain, index),
+            "type": "Follow",
+            "actor": follower_url,
+            "object": followed_url,
+            "approved": approved,
+        });
+        insert_object(&state, follower.id, data).await;
+    }
+
+    // Only the inbox of the follower is returned (once, since the second follow wasn't approved)
+    let inbox_urls = crate::database::inbox_urls::resolve_followers(&state.db_pool, &followed_url)
+        .await
+        .unwrap();
+    assert_eq!(inbox_urls, vec![follower_inbox_url]);
+}
+
+#[tokio::test]
+async fn object_replies() {
+    let state = test_state().await;
+    let domain = random_domain();
+    let db_actor = insert_actor(&state, &domain, "test", true).await;
+
+    let object_url = |name: &str| format!("https://{}/notes/{}", domain, name);
+    for (name, in_reply_to) in [
+        ("parent", None),
+        ("first", Some(object_url("parent"))),
+        ("second", Some(object_url("parent"))),
+        ("nested", Some(object_url("first"))),
+    ] {
+        let data = json!({
+            "id": object_url(name),
+            "type": "Note",
+            "attributedTo": actor_url(&domain, "test"),
+            "inReplyTo": in_reply_to,
+        });
+        insert_object(&state, db_actor.id, data).await;
+    }
+
+    // Only the direct replies are returned (oldest reply first)
+    let reply_urls: Vec<_> = DbObject::replies(&state.db_pool, &object_url("parent"))
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|reply| reply.data["id"].as_str().unwrap().to_owned())
+        .collect();
+    assert_eq!(reply_urls, vec![object_url("first"), object_url("second")]);
+}
+
+#[tokio::test]
+async fn context_crawl_cooldown() {
+    let state = test_state().await;
+    let domain = random_domain();
+    let db_actor = insert_actor(&state, &domain, "test", true).await;
+
+    let data = json!({
+        "id": format!("https://{}/notes/1", domain),
+        "type": "Note",
+        "attributedTo": actor_url(&domain, "test"),
+    });
+    let db_object = insert_object(&state, db_actor.id, data).await;
+
+    assert!(context_crawl::claim(&state.db_pool, db_object.id, 60)
+        .await
+        .unwrap());
+    assert!(!context_crawl::claim(&state.db_pool, db_object.id, 60)
+        .await
+        .unwrap());
+    assert!(context_crawl::claim(&state.db_pool, db_object.id, -1)
+        .await
+        .unwrap());
+}
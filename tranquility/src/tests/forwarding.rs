@@ -0,0 +1,88 @@
+use crate::{
+    activitypub::forwarding,
+    state::ArcState,
+    tests::{
+        actor_url, insert_actor, insert_local_actor, insert_object, random_domain, test_state,
+    },
+};
+use serde_json::{json, Value};
+use tranquility_types::activitypub::{Activity, PUBLIC_IDENTIFIER};
+use uuid::Uuid;
+
+/// Construct a reply to the note that addresses the followers collection
+fn reply(author_url: &str, in_reply_to: &str, followers_url: &str) -> Value {
+    let note_url = format!("{}/notes/{}", author_url, Uuid::new_v4().as_simple());
+
+    json!({
+        "id": format!("{}/activity", note_url),
+        "type": "Create",
+        "actor": author_url,
+        "object": {
+            "id": note_url,
+            "type": "Note",
+            "attributedTo": author_url,
+            "inReplyTo": in_reply_to,
+            "content": "Hello",
+            "to": [PUBLIC_IDENTIFIER],
+            "cc": [followers_url],
+        },
+        "to": [PUBLIC_IDENTIFIER],
+        "cc": [followers_url],
+    })
+}
+
+/// Check whether the activity would be forwarded
+async fn is_forwarded(state: &ArcState, document: &Value) -> bool {
+    let activity: Activity = serde_json::from_value(document.clone()).unwrap();
+    let body = serde_json::to_vec(document).unwrap();
+
+    forwarding::prepare(state, &activity, &body)
+        .await
+        .unwrap()
+        .is_some()
+}
+
+#[tokio::test]
+async fn forward_replies() {
+    let state: ArcState = test_state().await.into();
+    let local_domain = state.config.instance.domain.clone();
+    let remote_domain = random_domain();
+
+    let author = insert_local_actor(&state, &local_domain, "author").await;
+    let bystander = insert_local_actor(&state, &local_domain, "bystander").await;
+    insert_actor(&state, &remote_domain, "replier", true).await;
+    let replier_url = actor_url(&remote_domain, "replier");
+
+    let author_url = author.actor["id"].as_str().unwrap();
+    let note_url = format!("{}/notes/{}", author_url, Uuid::new_v4().as_simple());
+    let note = json!({
+        "id": note_url,
+        "type": "Note",
+        "attributedTo": author_url,
+        "to": [PUBLIC_IDENTIFIER],
+    });
+    insert_object(&state, author.id, note).await;
+
+    // Replies to a post are forwarded to the followers of its author
+    let author_followers = author.actor["followers"].as_str().unwrap();
+    let document = reply(&replier_url, &note_url, author_followers);
+    assert!(is_forwarded(&state, &document).await);
+
+    // Addressing the followers of someone else doesn't make them forward the reply
+    let bystander_followers = bystander.actor["followers"].as_str().unwrap();
+    let document = reply(&replier_url, &note_url, bystander_followers);
+    assert!(!is_forwarded(&state, &document).await);
+
+    // Neither does mentioning them
+    let mut document = reply(&replier_url, &note_url, bystander_followers);
+    document["object"]["tag"] = json!([{
+        "type": "Mention",
+        "href": bystander.actor["id"],
+    }]);
+    assert!(!is_forwarded(&state, &document).await);
+
+    // Unknown local URLs don't belong to anyone
+    let unknown_url = format!("{}/notes/unknown", author_url);
+    let document = reply(&replier_url, &unknown_url, author_followers);
+    assert!(!is_forwarded(&state, &document).await);
+}
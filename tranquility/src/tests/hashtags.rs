@@ -0,0 +1,147 @@
+use crate::{
+    database::{followed_tag::FollowedTag, statuses, Cursor, Object as DbObject},
+    tests::{actor_url, insert_actor, insert_object, random_domain, test_state},
+};
+use itertools::Itertools;
+use ormx::Table;
+use serde_json::json;
+use std::collections::HashMap;
+use tranquility_types::activitypub::PUBLIC_IDENTIFIER;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn hashtags() {
+    let state = test_state().await;
+    let domain = random_domain();
+    // The tag timeline isn't limited to a domain
+    let tag_name = format!("Tag{}", Uuid::new_v4().as_simple());
+
+    let viewer = insert_actor(&state, &domain, "viewer", true).await;
+    let author = insert_actor(&state, &domain, "author", true).await;
+    let stranger = insert_actor(&state, &domain, "stranger", true).await;
+    let viewer_url = actor_url(&domain, "viewer");
+    let author_url = actor_url(&domain, "author");
+    let stranger_followers_url = format!("{}/followers", actor_url(&domain, "stranger"));
+
+    let follow = json!({
+        "id": format!("https://{}/follows/1", domain),
+        "type": "Follow",
+        "actor": viewer_url,
+        "object": author_url,
+        "approved": true,
+    });
+    insert_object(&state, viewer.id, follow).await;
+
+    let hashtag = json!({ "type": "Hashtag", "name": format!("#{}", tag_name) });
+    let posts = [
+        ("own", viewer.id, json!({ "type": "Note", "to": [] })),
+        (
+            "followed_public",
+            author.id,
+            json!({ "type": "Note", "to": [PUBLIC_IDENTIFIER] }),
+        ),
+        (
+            "followed_followers",
+            author.id,
+            json!({ "type": "Note", "to": [format!("{}/followers", author_url)] }),
+        ),
+        (
+            "tagged",
+            stranger.id,
+            json!({ "type": "Note", "to": [PUBLIC_IDENTIFIER], "tag": hashtag }),
+        ),
+        (
+            "tagged_followers",
+            stranger.id,
+            json!({
+                "type": "Note",
+                "to": [stranger_followers_url],
+                "tag": [hashtag],
+            }),
+        ),
+        (
+            "untagged",
+            stranger.id,
+            json!({ "type": "Note", "to": [PUBLIC_IDENTIFIER] }),
+        ),
+    ];
+
+    let mut post_ids = HashMap::new();
+    let mut db_objects = HashMap::new();
+    for (name, owner_id, mut data) in posts {
+        data["id"] = json!(format!("https://{}/objects/{}", domain, name));
+
+        let db_object = insert_object(&state, owner_id, data).await;
+        post_ids.insert(db_object.id, name);
+        db_objects.insert(name, db_object);
+    }
+
+    let names = |db_objects: Vec<DbObject>| {
+        db_objects
+            .into_iter()
+            .map(|db_object| post_ids[&db_object.id])
+            .sorted_unstable()
+            .collect::<Vec<_>>()
+    };
+
+    let home = statuses::home(
+        &state.db_pool,
+        viewer.id,
+        &viewer_url,
+        Cursor::Before(None),
+        20,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        names(home),
+        ["followed_followers", "followed_public", "own"]
+    );
+
+    assert!(FollowedTag::follow(&state.db_pool, viewer.id, &tag_name)
+        .await
+        .unwrap());
+    assert!(
+        !FollowedTag::follow(&state.db_pool, viewer.id, &tag_name.to_lowercase())
+            .await
+            .unwrap()
+    );
+    assert!(
+        FollowedTag::is_following(&state.db_pool, viewer.id, &tag_name)
+            .await
+            .unwrap()
+    );
+
+    let home = statuses::home(
+        &state.db_pool,
+        viewer.id,
+        &viewer_url,
+        Cursor::Before(None),
+        20,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        names(home),
+        ["followed_followers", "followed_public", "own", "tagged"]
+    );
+
+    // Hashtags added by updates are indexed as well
+    let mut db_object = db_objects.remove("untagged").unwrap();
+    db_object.data["tag"] = json!([hashtag]);
+    db_object.update(&state.db_pool).await.unwrap();
+
+    let tagged = statuses::by_hashtag(&state.db_pool, &tag_name, Cursor::Before(None), 20)
+        .await
+        .unwrap();
+    assert_eq!(names(tagged), ["tagged", "untagged"]);
+
+    assert!(FollowedTag::unfollow(&state.db_pool, viewer.id, &tag_name)
+        .await
+        .unwrap());
+    assert!(
+        !FollowedTag::is_following(&state.db_pool, viewer.id, &tag_name)
+            .await
+            .unwrap()
+    );
+}
@@ -0,0 +1,153 @@
+use crate::{
+    activitypub::routes::inbox::authenticate,
+    error::Error,
+    state::ArcState,
+    tests::{inbox_payload, random_domain, test_state, RemoteServer},
+};
+use serde_json::{json, Value};
+use tranquility_types::activitypub::{activity::ObjectField, Activity, PUBLIC_IDENTIFIER};
+
+/// Construct a `Create` activity of the note
+fn create_activity(id: &str, actor_url: &str, note_url: &str, content: &str) -> Value {
+    json!({
+        "id": id,
+        "type": "Create",
+        "actor": actor_url,
+        "object": {
+            "id": note_url,
+            "type": "Note",
+            "attributedTo": actor_url,
+            "content": content,
+            "to": [PUBLIC_IDENTIFIER],
+        },
+        "to": [PUBLIC_IDENTIFIER],
+    })
+}
+
+/// Get the content of the note created by the activity
+fn note_content(activity: &Activity) -> &str {
+    match activity.object {
+        ObjectField::Object(ref object) => object.content.as_str(),
+        _ => panic!("Activity doesn't embed an object"),
+    }
+}
+
+#[tokio::test]
+async fn signed_by_actor() {
+    let state: ArcState = test_state().await.into();
+    let actor_url = format!("https://{}/users/alice", random_domain());
+
+    let activity_url = format!("{}/activities/create", actor_url);
+    let note_url = format!("{}/notes/1", actor_url);
+    let document = create_activity(&activity_url, &actor_url, &note_url, "Hello");
+
+    let (activity, body) = authenticate(&state, inbox_payload(&document, &actor_url))
+        .await
+        .unwrap();
+    assert_eq!(activity.id, activity_url);
+    assert!(body.is_some());
+}
+
+#[tokio::test]
+async fn refetch_from_origin() {
+    let state: ArcState = test_state().await.into();
+    let remote_server = RemoteServer::start(&random_domain());
+    let actor_url = remote_server.url("/users/alice");
+    let signer = format!("https://{}/users/mallory", random_domain());
+
+    let activity_url = remote_server.url("/activities/create");
+    let note_url = remote_server.url("/notes/1");
+    remote_server.serve(create_activity(
+        &activity_url,
+        &actor_url,
+        &note_url,
+        "Original",
+    ));
+
+    // The version served by the origin replaces the delivered one
+    let document = create_activity(&activity_url, &actor_url, &note_url, "Tampered");
+    let (activity, body) = authenticate(&state, inbox_payload(&document, &signer))
+        .await
+        .unwrap();
+    assert_eq!(note_content(&activity), "Original");
+    assert!(body.is_none());
+
+    // The origin doesn't serve the activity, only its object
+    let activity_url = remote_server.url("/activities/create-unserved");
+    let note_url = remote_server.url("/notes/2");
+    remote_server.serve(json!({
+        "id": note_url,
+        "type": "Note",
+        "attributedTo": actor_url,
+        "content": "Original",
+    }));
+
+    let document = create_activity(&activity_url, &actor_url, &note_url, "Tampered");
+    let (activity, body) = authenticate(&state, inbox_payload(&document, &signer))
+        .await
+        .unwrap();
+    assert_eq!(activity.id, activity_url);
+    assert_eq!(note_content(&activity), "Original");
+    assert!(body.is_none());
+}
+
+#[tokio::test]
+async fn reject_unverifiable_activities() {
+    let state: ArcState = test_state().await.into();
+    let remote_server = RemoteServer::start(&random_domain());
+    let actor_url = remote_server.url("/users/alice");
+    let signer = format!("https://{}/users/mallory", random_domain());
+
+    // The object lives on a different server than the actor, so the origin can't vouch for it
+    let activity_url = remote_server.url("/activities/cross-origin");
+    let note_url = format!("https://{}/notes/1", random_domain());
+    let document = create_activity(&activity_url, &actor_url, &note_url, "Forged");
+    assert!(matches!(
+        authenticate(&state, inbox_payload(&document, &signer)).await,
+        Err(Error::Unauthorized)
+    ));
+
+    // The origin serves the activity under the ID, but with a different actor
+    let activity_url = remote_server.url("/activities/other-actor");
+    let note_url = remote_server.url("/notes/2");
+    let other_actor_url = remote_server.url("/users/bob");
+    remote_server.serve(create_activity(
+        &activity_url,
+        &other_actor_url,
+        &note_url,
+        "Original",
+    ));
+
+    let document = create_activity(&activity_url, &actor_url, &note_url, "Forged");
+    assert!(matches!(
+        authenticate(&state, inbox_payload(&document, &signer)).await,
+        Err(Error::Unauthorized)
+    ));
+
+    // Someone else delivers an update of the actor that swaps the key of the actor for their own
+    let document = json!({
+        "id": remote_server.url("/activities/update"),
+        "type": "Update",
+        "actor": actor_url,
+        "object": {
+            "id": actor_url,
+            "type": "Person",
+            "preferredUsername": "alice",
+            "inbox": format!("{}/inbox", actor_url),
+            "outbox": format!("{}/outbox", actor_url),
+            "publicKey": {
+                "id": format!("{}#main-key", actor_url),
+                "owner": actor_url,
+                "publicKeyPem": "-----BEGIN PUBLIC KEY-----\nforged\n-----END PUBLIC KEY-----",
+            },
+        },
+    });
+    assert!(matches!(
+        inbox_payload(&document, &signer).activity.object,
+        ObjectField::Actor(..)
+    ));
+    assert!(matches!(
+        authenticate(&state, inbox_payload(&document, &signer)).await,
+        Err(Error::Unauthorized)
+    ));
+}
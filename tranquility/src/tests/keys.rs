@@ -0,0 +1,141 @@
+use crate::{
+    activitypub::{key_resolver::ActorKeyResolver, key_rotation},
+    database::{Actor as DbActor, InsertActor, InsertExt},
+    format_uuid,
+    state::ArcState,
+    tests::{start_test_server, test_state, TestClient},
+};
+use ormx::Table;
+use std::sync::Arc;
+use time::{Duration, OffsetDateTime};
+use tranquility_http_signatures::KeyResolver;
+use tranquility_ld_signatures::multibase;
+use tranquility_types::activitypub::Actor;
+use uuid::Uuid;
+
+/// Insert a local actor with a freshly generated key pair
+async fn insert_actor_with_key(state: &ArcState) -> DbActor {
+    let id = Uuid::new_v4();
+    let rsa_private_key = crate::crypto::rsa::generate().await.unwrap();
+    let (public_key_pem, private_key_pem) = crate::crypto::rsa::to_pem(&rsa_private_key).unwrap();
+
+    let username = format!("keys{}", id.as_simple());
+    let actor = crate::activitypub::instantiate::actor(
+        &state.config,
+        &format_uuid!(id),
+        &username,
+        public_key_pem,
+    );
+
+    InsertActor {
+        id,
+        username,
+        email: None,
+        password_hash: None,
+        private_key: Some(private_key_pem),
+        is_confirmed: true,
+        confirmation_code: None,
+        actor: serde_json::to_value(&actor).unwrap(),
+        remote: false,
+    }
+    .insert(&state.db_pool)
+    .await
+    .unwrap()
+}
+
+/// Fetch the actor document like a remote server would
+async fn published_actor(test_client: &TestClient, db_actor: &DbActor) -> Actor {
+    test_client
+        .get(&format!("/users/{}", format_uuid!(db_actor.id)))
+        .await
+        .expect("Failed to send fetch request")
+        .json()
+        .await
+        .expect("Failed to decode actor")
+}
+
+#[tokio::test]
+async fn rotate_key() {
+    let state: ArcState = test_state().await.into();
+    let db_actor = insert_actor_with_key(&state).await;
+    let previous_actor: Actor = serde_json::from_value(db_actor.actor.clone()).unwrap();
+
+    let update_activity = key_rotation::replace_key(&state, db_actor.clone())
+        .await
+        .unwrap()
+        .expect("Rotation of a user wasn't announced");
+    assert_eq!(update_activity.r#type, "Update");
+
+    let db_actor = DbActor::get(&state.db_pool, db_actor.id).await.unwrap();
+    let actor: Actor = serde_json::from_value(db_actor.actor.clone()).unwrap();
+    assert_ne!(actor.public_key.id, previous_actor.public_key.id);
+    assert_ne!(
+        actor.public_key.public_key_pem,
+        previous_actor.public_key.public_key_pem
+    );
+    assert_eq!(
+        db_actor.previous_key(),
+        Some((
+            previous_actor.public_key.id.as_str(),
+            previous_actor.public_key.public_key_pem.as_str()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn key_rotation_grace_period() {
+    let state: ArcState = test_state().await.into();
+    let db_actor = insert_actor_with_key(&state).await;
+    let previous_actor: Actor = serde_json::from_value(db_actor.actor.clone()).unwrap();
+    let previous_key_id = previous_actor.public_key.id.as_str();
+
+    key_rotation::replace_key(&state, db_actor.clone())
+        .await
+        .unwrap();
+    let mut db_actor = DbActor::get(&state.db_pool, db_actor.id).await.unwrap();
+    let actor: Actor = serde_json::from_value(db_actor.actor.clone()).unwrap();
+
+    // Both key IDs resolve to their own key. Other key IDs of the actor don't resolve to the previous key
+    let key_resolver = ActorKeyResolver::new(Arc::clone(&state));
+    let key = key_resolver.resolve(&actor.public_key.id).await.unwrap();
+    assert_eq!(key.public_key_pem, actor.public_key.public_key_pem);
+
+    let previous_key = key_resolver.resolve(previous_key_id).await.unwrap();
+    assert_eq!(previous_key.owner, actor.id);
+    assert_eq!(
+        previous_key.public_key_pem,
+        previous_actor.public_key.public_key_pem
+    );
+
+    assert!(key_resolver
+        .resolve(&format!("{}#unknown-key", actor.id))
+        .await
+        .is_err());
+
+    // Remote servers find the previous key in the actor document
+    let test_client = start_test_server(Arc::clone(&state));
+    let actor_document = published_actor(&test_client, &db_actor).await;
+    assert_eq!(actor_document.public_key.id, actor.public_key.id);
+
+    let multikey = actor_document
+        .assertion_method
+        .iter()
+        .find(|key| key.id == previous_key_id)
+        .expect("Previous key isn't published");
+    assert_eq!(multikey.controller, actor.id);
+    assert_eq!(
+        multikey.public_key_multibase,
+        multibase::encode_rsa_multikey(previous_actor.public_key.public_key_pem.as_bytes())
+            .unwrap()
+    );
+
+    // Once the grace period is over, the previous key is neither resolvable nor published
+    db_actor.previous_key_expires_at = Some(OffsetDateTime::now_utc() - Duration::SECOND);
+    db_actor.update(&state.db_pool).await.unwrap();
+
+    assert!(key_resolver.resolve(previous_key_id).await.is_err());
+    assert!(published_actor(&test_client, &db_actor)
+        .await
+        .assertion_method
+        .is_empty());
+}
@@ -1,16 +1,40 @@
 use crate::{
-    activitypub::FollowActivity,
+    activitypub::{routes::inbox::InboxPayload, FollowActivity},
     config::{
-        Configuration, ConfigurationEmail, ConfigurationInstance, ConfigurationJaeger,
-        ConfigurationRatelimit, ConfigurationServer, ConfigurationTls,
+        Configuration, ConfigurationEmail, ConfigurationFederation, ConfigurationInstance,
+        ConfigurationJaeger, ConfigurationRatelimit, ConfigurationServer, ConfigurationTls,
+        SignatureFormat,
+    },
+    database::{
+        Actor as DbActor, InsertActor, InsertExt, InsertOAuthToken, InsertObject,
+        Object as DbObject,
     },
     server::create_router_make_service,
     state::{ArcState, State},
+    util::network::PublicResolver,
+};
+use axum::{
+    body::Bytes,
+    http::{StatusCode, Uri},
+    response::IntoResponse,
+    routing::get,
+    Json, Router, Server,
 };
-use axum::Server;
+use futures_util::FutureExt;
 use mime::Mime;
+use once_cell::sync::Lazy;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde_json::{json, Value};
 use sqlx::PgPool;
-use std::{env, net::SocketAddr};
+use std::{
+    collections::HashMap,
+    env, iter,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+};
+use time::{Duration, OffsetDateTime};
+use url::Url;
+use uuid::Uuid;
 
 const FOLLOW_ACTIVITY: &str = r#"
 {
@@ -37,6 +61,16 @@ fn test_config() -> Configuration {
             username: "tranquility".into(),
             password: "tranquility-acct-password".into(),
         },
+        federation: ConfigurationFederation {
+            secure_mode: false,
+            blocked_domains: Vec::new(),
+            actor_ttl: 24 * 60 * 60,
+            max_clock_skew: 60 * 60,
+            signature_format: SignatureFormat::Cavage,
+            inbox_concurrency: 8,
+            inbox_queue_size: 1024,
+            received_activity_retention: 7 * 24 * 60 * 60,
+        },
         instance: ConfigurationInstance {
             closed_registrations: false,
             domain: "tranquility.example.com".into(),
@@ -84,9 +118,99 @@ async fn test_state() -> State {
     State::new_arcless(config, db_pool)
 }
 
+/// Generate a random domain for the actors and objects of a test
+///
+/// The test database isn't reset between runs, so every test uses its own domain
+fn random_domain() -> String {
+    format!("{}.example.com", Uuid::new_v4().as_simple())
+}
+
+/// Get the URL of the actor with the username on the domain
+fn actor_url(domain: &str, username: &str) -> String {
+    format!("https://{}/users/{}", domain, username)
+}
+
+/// Insert an actor with an inbox, a followers collection and a (placeholder) public key
+///
+/// Like in production, only local actors are confirmed (remote actors are inserted unconfirmed by the fetcher).
+/// The username of local actors has to be unique across test runs
+async fn insert_actor(state: &State, domain: &str, username: &str, remote: bool) -> DbActor {
+    let actor_url = actor_url(domain, username);
+
+    InsertActor {
+        id: Uuid::new_v4(),
+        username: username.into(),
+        email: None,
+        password_hash: None,
+        private_key: None,
+        is_confirmed: !remote,
+        confirmation_code: None,
+        actor: json!({
+            "id": actor_url,
+            "type": "Person",
+            "preferredUsername": username,
+            "inbox": format!("{}/inbox", actor_url),
+            "outbox": format!("{}/outbox", actor_url),
+            "followers": format!("{}/followers", actor_url),
+            "following": format!("{}/following", actor_url),
+            "publicKey": {
+                "id": format!("{}#main-key", actor_url),
+                "owner": actor_url,
+                "publicKeyPem": "",
+            },
+        }),
+        remote,
+    }
+    .insert(&state.db_pool)
+    .await
+    .unwrap()
+}
+
+/// Insert a local actor with a unique username starting with the name
+async fn insert_local_actor(state: &State, domain: &str, name: &str) -> DbActor {
+    let username = format!("{}{}", name, Uuid::new_v4().as_simple());
+    insert_actor(state, domain, &username, false).await
+}
+
+/// Insert an object owned by the actor
+async fn insert_object(state: &State, owner_id: Uuid, data: Value) -> DbObject {
+    InsertObject {
+        id: Uuid::new_v4(),
+        owner_id,
+        data,
+    }
+    .insert(&state.db_pool)
+    .await
+    .unwrap()
+}
+
+/// Insert an OAuth access token for the actor
+///
+/// # Returns
+///
+/// Returns the access token (to be sent as a bearer token)
+async fn insert_access_token(state: &State, actor_id: Uuid) -> String {
+    let access_token = crate::crypto::token::generate();
+
+    InsertOAuthToken {
+        application_id: None,
+        actor_id,
+        access_token: access_token.clone(),
+        refresh_token: None,
+        valid_until: OffsetDateTime::now_utc() + Duration::HOUR,
+    }
+    .insert(&state.db_pool)
+    .await
+    .unwrap();
+
+    access_token
+}
+
+#[derive(Clone)]
 struct TestClient {
     address: SocketAddr,
     client: reqwest::Client,
+    access_token: Option<String>,
 }
 
 impl TestClient {
@@ -95,6 +219,21 @@ impl TestClient {
         Self {
             address,
             client: reqwest::Client::new(),
+            access_token: None,
+        }
+    }
+
+    /// Send all requests of this client with the access token as a bearer token
+    fn with_access_token(mut self, access_token: String) -> Self {
+        self.access_token = Some(access_token);
+        self
+    }
+
+    /// Add the access token (if any) to the request
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self.access_token {
+            Some(ref access_token) => request.bearer_auth(access_token),
+            None => request,
         }
     }
 
@@ -104,7 +243,9 @@ impl TestClient {
 
     /// Send a GET request
     async fn get(&self, uri: &str) -> reqwest::Result<reqwest::Response> {
-        self.client.get(self.format_url(uri)).send().await
+        self.authorize(self.client.get(self.format_url(uri)))
+            .send()
+            .await
     }
 
     /// Send a POST request
@@ -121,8 +262,7 @@ impl TestClient {
     {
         let content_type = content_type.unwrap_or(mime::APPLICATION_WWW_FORM_URLENCODED);
 
-        self.client
-            .post(self.format_url(uri))
+        self.authorize(self.client.post(self.format_url(uri)))
             .header("Content-Type", content_type.as_ref())
             .body(body)
             .send()
@@ -150,6 +290,94 @@ where
     TestClient::new(bound_address)
 }
 
+/// Construct the inbox payload of an activity delivered by the signer
+fn inbox_payload(document: &Value, signer: &str) -> InboxPayload {
+    InboxPayload {
+        activity: serde_json::from_value(document.clone()).unwrap(),
+        body: Bytes::from(serde_json::to_vec(document).unwrap()),
+        signer: signer.into(),
+    }
+}
+
+/// Domains the tests serve from local servers
+static TEST_HOSTS: Lazy<Mutex<HashMap<String, IpAddr>>> = Lazy::new(Default::default);
+
+/// DNS resolver the HTTP client uses in the tests
+///
+/// Routes the domains of the [`RemoteServer`]s to their local addresses. Every other domain is resolved by the [`PublicResolver`]
+pub struct TestResolver;
+
+impl Resolve for TestResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        if let Some(addr) = TEST_HOSTS.lock().unwrap().get(name.as_str()).copied() {
+            let addrs = iter::once(SocketAddr::new(addr, 0));
+            return futures_util::future::ready(Ok(Box::new(addrs) as Addrs)).boxed();
+        }
+
+        PublicResolver.resolve(name)
+    }
+}
+
+/// Server standing in for a remote server
+///
+/// It serves the documents it was given under the path of their ID. Every other path responds with a 404
+struct RemoteServer {
+    base_url: String,
+    documents: Arc<Mutex<HashMap<String, Value>>>,
+}
+
+impl RemoteServer {
+    /// Start a remote server for the domain
+    ///
+    /// Requests to the domain are routed to this server instead of being resolved via DNS
+    fn start(domain: &str) -> Self {
+        let documents: Arc<Mutex<HashMap<String, Value>>> = Arc::default();
+
+        let served_documents = Arc::clone(&documents);
+        let router = Router::new().route(
+            "/*path",
+            get(move |uri: Uri| {
+                let document = served_documents.lock().unwrap().get(uri.path()).cloned();
+
+                async move {
+                    match document {
+                        Some(document) => Json(document).into_response(),
+                        None => StatusCode::NOT_FOUND.into_response(),
+                    }
+                }
+            }),
+        );
+
+        let server = Server::bind(&SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+            .serve(router.into_make_service());
+        let bound_address = server.local_addr();
+
+        tokio::spawn(server);
+        TEST_HOSTS
+            .lock()
+            .unwrap()
+            .insert(domain.into(), Ipv4Addr::LOCALHOST.into());
+
+        Self {
+            base_url: format!("http://{}:{}", domain, bound_address.port()),
+            documents,
+        }
+    }
+
+    /// Get the URL of the path on this server
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Serve the document under the path of its ID
+    fn serve(&self, document: Value) {
+        let id = document["id"].as_str().expect("Document without an ID");
+        let path = Url::parse(id).unwrap().path().to_string();
+
+        self.documents.lock().unwrap().insert(path, document);
+    }
+}
+
 #[test]
 fn decode_follow_activity() {
     let follow_activity: FollowActivity = serde_json::from_str(FOLLOW_ACTIVITY).unwrap();
@@ -158,5 +386,18 @@ fn decode_follow_activity() {
     assert!(!follow_activity.approved);
 }
 
+mod emojis;
+mod federation;
+mod forwarding;
+mod hashtags;
+mod inbox;
+mod keys;
 mod nodeinfo;
+mod pins;
+mod polls;
+mod reactions;
+mod refetch;
 mod register;
+mod relays;
+mod search;
+mod statuses;
@@ -0,0 +1,168 @@
+use crate::{
+    activitypub::handler,
+    database::pin::Pin,
+    error::Error,
+    state::ArcState,
+    tests::{
+        actor_url, insert_actor, insert_local_actor, insert_object, random_domain,
+        start_test_server, test_state,
+    },
+};
+use ormx::Table;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tranquility_types::activitypub::{Activity, PUBLIC_IDENTIFIER};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn pins() {
+    let state = test_state().await;
+    let domain = random_domain();
+    let db_actor = insert_actor(&state, &domain, "test", true).await;
+
+    let mut object_ids = Vec::new();
+    for index in 0..3 {
+        let data = json!({
+            "id": format!("https://{}/notes/{}", domain, index),
+            "type": "Note",
+            "attributedTo": actor_url(&domain, "test"),
+        });
+        let db_object = insert_object(&state, db_actor.id, data).await;
+
+        object_ids.push(db_object.id);
+    }
+
+    assert!(Pin::pin(&state.db_pool, db_actor.id, object_ids[0])
+        .await
+        .unwrap());
+    assert!(!Pin::pin(&state.db_pool, db_actor.id, object_ids[0])
+        .await
+        .unwrap());
+    assert_eq!(Pin::count(&state.db_pool, db_actor.id).await.unwrap(), 1);
+
+    // The featured collection lists the most recently pinned object first
+    Pin::replace(&state.db_pool, db_actor.id, &[object_ids[2], object_ids[1]])
+        .await
+        .unwrap();
+    let pinned_ids: Vec<_> = Pin::objects(&state.db_pool, db_actor.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|db_object| db_object.id)
+        .collect();
+    assert_eq!(pinned_ids, vec![object_ids[2], object_ids[1]]);
+
+    assert!(Pin::unpin(&state.db_pool, db_actor.id, object_ids[2])
+        .await
+        .unwrap());
+    assert!(!Pin::unpin(&state.db_pool, db_actor.id, object_ids[0])
+        .await
+        .unwrap());
+    assert!(Pin::is_pinned(&state.db_pool, db_actor.id, object_ids[1])
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+async fn featured_collection() {
+    let state: ArcState = test_state().await.into();
+    let domain = random_domain();
+    let db_actor = insert_local_actor(&state, &domain, "pinner").await;
+    let actor_url = db_actor.actor["id"].as_str().unwrap();
+    let followers_url = db_actor.actor["followers"].as_str().unwrap();
+
+    let mut object_urls = Vec::new();
+    for to in [PUBLIC_IDENTIFIER, followers_url] {
+        let object_url = format!("{}/notes/{}", actor_url, Uuid::new_v4().as_simple());
+        let data = json!({
+            "id": object_url,
+            "type": "Note",
+            "attributedTo": actor_url,
+            "to": [to],
+        });
+        let db_object = insert_object(&state, db_actor.id, data).await;
+        Pin::pin(&state.db_pool, db_actor.id, db_object.id)
+            .await
+            .unwrap();
+
+        object_urls.push(object_url);
+    }
+
+    let test_client = start_test_server(Arc::clone(&state));
+    let featured: Value = test_client
+        .get(&format!("/users/{}/featured", db_actor.id))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // The followers-only post isn't exposed, even though it's pinned
+    assert_eq!(featured["id"], format!("{}/featured", actor_url));
+    assert_eq!(featured["totalItems"], 1);
+    assert_eq!(featured["orderedItems"][0]["id"], object_urls[0]);
+}
+
+#[tokio::test]
+async fn add_to_featured() {
+    let state: ArcState = test_state().await.into();
+    let domain = random_domain();
+
+    let mut db_actor = insert_actor(&state, &domain, "pinner", true).await;
+    let other_db_actor = insert_actor(&state, &domain, "other", true).await;
+    let pinner_url = actor_url(&domain, "pinner");
+    let featured_url = format!("{}/featured", pinner_url);
+    db_actor.actor["featured"] = json!(featured_url);
+    db_actor.update(&state.db_pool).await.unwrap();
+
+    let mut note_urls = Vec::new();
+    for (owner, owner_url) in [
+        (&db_actor, pinner_url.clone()),
+        (&other_db_actor, actor_url(&domain, "other")),
+    ] {
+        let note_url = format!("{}/notes/{}", owner_url, Uuid::new_v4().as_simple());
+        let data = json!({
+            "id": note_url,
+            "type": "Note",
+            "attributedTo": owner_url,
+            "to": [PUBLIC_IDENTIFIER],
+        });
+        let db_object = insert_object(&state, owner.id, data).await;
+
+        note_urls.push((db_object.id, note_url));
+    }
+
+    let add_activity = |object_url: &str, target_url: &str| -> Activity {
+        serde_json::from_value(json!({
+            "id": format!("{}/activities/{}", pinner_url, Uuid::new_v4().as_simple()),
+            "type": "Add",
+            "actor": pinner_url,
+            "object": object_url,
+            "target": target_url,
+        }))
+        .unwrap()
+    };
+
+    // Only the featured collection is supported
+    let activity = add_activity(&note_urls[0].1, &format!("{}/collections/tags", pinner_url));
+    assert!(matches!(
+        handler::add::handle(&state, activity).await,
+        Err(Error::UnknownActivity)
+    ));
+
+    // Actors can't pin the posts of others
+    let activity = add_activity(&note_urls[1].1, &featured_url);
+    assert!(matches!(
+        handler::add::handle(&state, activity).await,
+        Err(Error::Unauthorized)
+    ));
+    assert!(!Pin::is_pinned(&state.db_pool, db_actor.id, note_urls[1].0)
+        .await
+        .unwrap());
+
+    let activity = add_activity(&note_urls[0].1, &featured_url);
+    handler::add::handle(&state, activity).await.unwrap();
+    assert!(Pin::is_pinned(&state.db_pool, db_actor.id, note_urls[0].0)
+        .await
+        .unwrap());
+}
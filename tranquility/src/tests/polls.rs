@@ -0,0 +1,343 @@
+use crate::{
+    activitypub::poll,
+    database::{poll_vote::PollVote, Actor as DbActor, Object as DbObject},
+    error::Error,
+    state::{ArcState, State},
+    tests::{
+        actor_url, insert_access_token, insert_actor, insert_local_actor, insert_object,
+        random_domain, start_test_server, test_state,
+    },
+};
+use ormx::Table;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tranquility_types::{activitypub::Object, mastodon::Poll};
+use uuid::Uuid;
+
+/// Insert a poll of the author that allows one choice
+async fn insert_poll(state: &State, author: &DbActor, to: Vec<String>) -> DbObject {
+    let author_url = author.actor["id"].as_str().unwrap();
+    let poll = json!({
+        "id": format!("{}/polls/{}", author_url, Uuid::new_v4().as_simple()),
+        "type": "Question",
+        "attributedTo": author_url,
+        "content": "Which one?",
+        "oneOf": [{ "name": "First" }, { "name": "Second" }],
+        "to": to,
+    });
+
+    insert_object(state, author.id, poll).await
+}
+
+/// Construct a vote on the poll like remote servers send them
+fn vote_object(voter_url: &str, poll_url: &str, name: &str) -> Object {
+    serde_json::from_value(json!({
+        "id": format!("{}/votes/{}", voter_url, Uuid::new_v4().as_simple()),
+        "type": "Note",
+        "attributedTo": voter_url,
+        "name": name,
+        "inReplyTo": poll_url,
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn poll_votes() {
+    let state = test_state().await;
+    let domain = random_domain();
+
+    // Polls of local actors are closed by the server (and local usernames have to be unique)
+    let author_username = format!("author{}", Uuid::new_v4().as_simple());
+    let author = insert_actor(&state, &domain, &author_username, false).await;
+    let voter = insert_actor(&state, &domain, "voter", true).await;
+
+    let poll = json!({
+        "id": format!("https://{}/objects/poll", domain),
+        "type": "Question",
+        "anyOf": [{ "name": "First" }, { "name": "Second" }, { "name": "Third" }],
+    });
+    let db_object = insert_object(&state, author.id, poll).await;
+
+    for choice in [2, 0] {
+        assert!(
+            PollVote::vote(&state.db_pool, db_object.id, voter.id, choice)
+                .await
+                .unwrap()
+        );
+    }
+    assert!(!PollVote::vote(&state.db_pool, db_object.id, voter.id, 0)
+        .await
+        .unwrap());
+
+    let choices = PollVote::choices(&state.db_pool, db_object.id, voter.id)
+        .await
+        .unwrap();
+    assert_eq!(choices, [0, 2]);
+
+    let (vote_counts, voters_count) = PollVote::tally(&state.db_pool, db_object.id, 3)
+        .await
+        .unwrap();
+    assert_eq!(vote_counts, [1, 0, 1]);
+    assert_eq!(voters_count, 1);
+
+    let voter_inboxes =
+        crate::database::inbox_urls::resolve_remote_voters(&state.db_pool, db_object.id)
+            .await
+            .unwrap();
+    assert_eq!(
+        voter_inboxes,
+        [format!("https://{}/users/voter/inbox", domain)]
+    );
+
+    let open_polls = DbObject::open_local_polls(&state.db_pool).await.unwrap();
+    assert!(open_polls
+        .iter()
+        .any(|open_poll| open_poll.id == db_object.id));
+}
+
+#[tokio::test]
+async fn followers_only_poll() {
+    let state: ArcState = test_state().await.into();
+    let domain = random_domain();
+
+    let author = insert_local_actor(&state, &domain, "author").await;
+    let follower = insert_local_actor(&state, &domain, "follower").await;
+    let stranger = insert_local_actor(&state, &domain, "stranger").await;
+    let author_url = author.actor["id"].as_str().unwrap();
+    let follower_url = follower.actor["id"].as_str().unwrap();
+
+    let follow = json!({
+        "id": format!("{}/follows/{}", follower_url, Uuid::new_v4().as_simple()),
+        "type": "Follow",
+        "actor": follower_url,
+        "object": author_url,
+        "approved": true,
+    });
+    insert_object(&state, follower.id, follow).await;
+
+    let followers_url = author.actor["followers"].as_str().unwrap();
+    let db_object = insert_poll(&state, &author, vec![followers_url.into()]).await;
+    let poll_uri = format!("/api/v1/polls/{}", db_object.id);
+    let votes_uri = format!("{}/votes", poll_uri);
+
+    let test_client = start_test_server(Arc::clone(&state));
+    let follower_client = test_client
+        .clone()
+        .with_access_token(insert_access_token(&state, follower.id).await);
+    let stranger_client = test_client
+        .clone()
+        .with_access_token(insert_access_token(&state, stranger.id).await);
+
+    // Only approved followers can see the poll
+    let response = test_client.get(&poll_uri).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = stranger_client.get(&poll_uri).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let poll: Poll = follower_client
+        .get(&poll_uri)
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(poll.voted, Some(false));
+    assert_eq!(poll.votes_count, 0);
+
+    // Actors who can't see the poll can't vote on it either
+    let response = stranger_client
+        .post(
+            &votes_uri,
+            Some(mime::APPLICATION_JSON),
+            r#"{"choices":[0]}"#,
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(PollVote::choices(&state.db_pool, db_object.id, stranger.id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let response = follower_client
+        .post(
+            &votes_uri,
+            Some(mime::APPLICATION_JSON),
+            r#"{"choices":[1]}"#,
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let poll: Poll = response.json().await.unwrap();
+    assert_eq!(poll.voted, Some(true));
+    assert_eq!(poll.own_votes, Some(vec![1]));
+    assert_eq!(poll.votes_count, 1);
+    assert_eq!(poll.voters_count, Some(1));
+
+    // The poll allows only one choice, so the follower can't vote again
+    let response = follower_client
+        .post(
+            &votes_uri,
+            Some(mime::APPLICATION_JSON),
+            r#"{"choices":[0]}"#,
+        )
+        .await
+        .unwrap();
+    assert!(response.status().is_client_error());
+    assert_eq!(
+        PollVote::choices(&state.db_pool, db_object.id, follower.id)
+            .await
+            .unwrap(),
+        [1]
+    );
+}
+
+#[tokio::test]
+async fn receive_vote() {
+    let state: ArcState = test_state().await.into();
+    let domain = random_domain();
+
+    let author = insert_local_actor(&state, &domain, "author").await;
+    let voter = insert_actor(&state, &domain, "voter", true).await;
+    let voter_url = actor_url(&domain, "voter");
+
+    let db_object = insert_poll(
+        &state,
+        &author,
+        vec!["https://www.w3.org/ns/activitystreams#Public".into()],
+    )
+    .await;
+    let poll_url = db_object.data["id"].as_str().unwrap().to_string();
+
+    // Replies to the poll aren't votes
+    let mut reply = vote_object(&voter_url, &poll_url, "First");
+    reply.content = "I like the first one".into();
+    assert!(!poll::receive_vote(&state, &voter_url, &reply)
+        .await
+        .unwrap());
+
+    let vote = vote_object(&voter_url, &poll_url, "Second");
+    assert!(poll::receive_vote(&state, &voter_url, &vote).await.unwrap());
+
+    // The poll allows only one choice, so another vote of the same actor is rejected
+    let vote = vote_object(&voter_url, &poll_url, "First");
+    assert!(matches!(
+        poll::receive_vote(&state, &voter_url, &vote).await,
+        Err(Error::InvalidRequest)
+    ));
+
+    let choices = PollVote::choices(&state.db_pool, db_object.id, voter.id)
+        .await
+        .unwrap();
+    assert_eq!(choices, [1]);
+
+    // The tally stored in the poll only counts the first vote
+    let db_object = DbObject::get(&state.db_pool, db_object.id).await.unwrap();
+    let object: Object = serde_json::from_value(db_object.data).unwrap();
+    let vote_counts = object
+        .poll_options()
+        .unwrap()
+        .iter()
+        .map(|option| option.replies.total_items)
+        .collect::<Vec<_>>();
+    assert_eq!(vote_counts, [0, 1]);
+    assert_eq!(object.voters_count, Some(1));
+
+    // Actors who can't see the poll can't vote on it
+    let followers_url = author.actor["followers"].as_str().unwrap();
+    let db_object = insert_poll(&state, &author, vec![followers_url.into()]).await;
+    let poll_url = db_object.data["id"].as_str().unwrap();
+
+    let vote = vote_object(&voter_url, poll_url, "First");
+    assert!(matches!(
+        poll::receive_vote(&state, &voter_url, &vote).await,
+        Err(Error::Unauthorized)
+    ));
+    let choices = PollVote::choices(&state.db_pool, db_object.id, voter.id)
+        .await
+        .unwrap();
+    assert!(choices.is_empty());
+}
+
+#[tokio::test]
+async fn concurrent_votes() {
+    let state: ArcState = test_state().await.into();
+    let domain = random_domain();
+
+    let author = insert_local_actor(&state, &domain, "author").await;
+    let voter = insert_actor(&state, &domain, "voter", true).await;
+    let voter_url = actor_url(&domain, "voter");
+
+    let db_object = insert_poll(
+        &state,
+        &author,
+        vec!["https://www.w3.org/ns/activitystreams#Public".into()],
+    )
+    .await;
+    let poll_url = db_object.data["id"].as_str().unwrap();
+
+    // Only one of the votes arriving at the same time is counted on single-choice polls
+    let first_vote = vote_object(&voter_url, poll_url, "First");
+    let second_vote = vote_object(&voter_url, poll_url, "Second");
+    let (first_result, second_result) = tokio::join!(
+        poll::receive_vote(&state, &voter_url, &first_vote),
+        poll::receive_vote(&state, &voter_url, &second_vote),
+    );
+    assert_eq!(
+        [first_result.is_ok(), second_result.is_ok()]
+            .into_iter()
+            .filter(|is_ok| *is_ok)
+            .count(),
+        1
+    );
+
+    let choices = PollVote::choices(&state.db_pool, db_object.id, voter.id)
+        .await
+        .unwrap();
+    assert_eq!(choices.len(), 1);
+}
+
+#[tokio::test]
+async fn close_poll() {
+    let state: ArcState = test_state().await.into();
+    let domain = random_domain();
+
+    let author = insert_local_actor(&state, &domain, "author").await;
+    let voter = insert_local_actor(&state, &domain, "voter").await;
+
+    // Followers-only, so the Update activity doesn't need a Linked Data Signature
+    let followers_url = author.actor["followers"].as_str().unwrap();
+    let db_object = insert_poll(&state, &author, vec![followers_url.into()]).await;
+    PollVote::vote(&state.db_pool, db_object.id, voter.id, 0)
+        .await
+        .unwrap();
+
+    poll::close(&state, db_object.clone()).await.unwrap();
+
+    let db_object = DbObject::get(&state.db_pool, db_object.id).await.unwrap();
+    let object: Object = serde_json::from_value(db_object.data.clone()).unwrap();
+    assert!(object.closed.is_some());
+    assert!(!poll::is_open(&object));
+
+    // The Update activity carries the final results
+    let updates = DbObject::by_type_and_owner(&state.db_pool, "Update", &author.id, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(updates.len(), 1);
+
+    let update: Value = updates[0].data.clone();
+    assert_eq!(update["actor"], author.actor["id"]);
+    assert_eq!(update["object"]["id"], db_object.data["id"]);
+    assert!(update["object"]["closed"].is_string());
+    assert_eq!(update["object"]["oneOf"][0]["replies"]["totalItems"], 1);
+    assert_eq!(update["object"]["votersCount"], 1);
+    assert_eq!(update["to"], json!([followers_url]));
+
+    // Closed polls don't accept votes anymore
+    let open_polls = DbObject::open_local_polls(&state.db_pool).await.unwrap();
+    assert!(open_polls
+        .iter()
+        .all(|open_poll| open_poll.id != db_object.id));
+}
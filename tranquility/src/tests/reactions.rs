@@ -0,0 +1,255 @@
+use crate::{
+    activitypub::{handler, routes::inbox},
+    database::reaction::Reaction,
+    error::Error,
+    format_uuid,
+    state::ArcState,
+    tests::{
+        actor_url, inbox_payload, insert_actor, insert_local_actor, insert_object, random_domain,
+        start_test_server, test_state,
+    },
+};
+use itertools::Itertools;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tranquility_types::activitypub::{Activity, PUBLIC_IDENTIFIER};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn reactions() {
+    let state = test_state().await;
+    let domain = random_domain();
+
+    let first = insert_actor(&state, &domain, "first", true).await;
+    let second = insert_actor(&state, &domain, "second", true).await;
+
+    let note = json!({
+        "id": format!("https://{}/objects/1", domain),
+        "type": "Note",
+        "to": [PUBLIC_IDENTIFIER],
+    });
+    let db_object = insert_object(&state, first.id, note).await;
+
+    let blobcat = format!("blobcat@{}", domain);
+    let reactions = [
+        (second.id, "👍", None, "1"),
+        (
+            first.id,
+            blobcat.as_str(),
+            Some("https://example.com/blobcat.png"),
+            "2",
+        ),
+        (first.id, "👍", None, "3"),
+    ];
+    for (actor_id, name, emoji_url, activity_id) in reactions {
+        let activity_url = format!("https://{}/activities/{}", domain, activity_id);
+        assert!(Reaction::react(
+            &state.db_pool,
+            db_object.id,
+            actor_id,
+            name,
+            emoji_url,
+            &activity_url
+        )
+        .await
+        .unwrap());
+    }
+
+    // Actors can react with every emoji only once
+    let activity_url = format!("https://{}/activities/4", domain);
+    assert!(!Reaction::react(
+        &state.db_pool,
+        db_object.id,
+        second.id,
+        "👍",
+        None,
+        &activity_url
+    )
+    .await
+    .unwrap());
+
+    let all_reactions = Reaction::by_object(&state.db_pool, db_object.id, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        all_reactions
+            .iter()
+            .map(|reaction| reaction.name.as_str())
+            .collect_vec(),
+        ["👍", blobcat.as_str(), "👍"]
+    );
+
+    let thumbs_up = Reaction::by_object(&state.db_pool, db_object.id, Some("👍"))
+        .await
+        .unwrap();
+    assert_eq!(thumbs_up.len(), 2);
+    assert!(thumbs_up
+        .iter()
+        .all(|reaction| reaction.emoji_url.is_none()));
+
+    // Only the actor who reacted can undo the reaction
+    let activity_url = format!("https://{}/activities/2", domain);
+    let first_url = actor_url(&domain, "first");
+    let second_url = actor_url(&domain, "second");
+    assert!(
+        !Reaction::delete_by_activity_url(&state.db_pool, &activity_url, &second_url)
+            .await
+            .unwrap()
+    );
+    assert!(
+        Reaction::delete_by_activity_url(&state.db_pool, &activity_url, &first_url)
+            .await
+            .unwrap()
+    );
+
+    let removed_reaction = Reaction::unreact(&state.db_pool, db_object.id, second.id, "👍")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        removed_reaction.activity_url,
+        format!("https://{}/activities/1", domain)
+    );
+    assert!(
+        Reaction::unreact(&state.db_pool, db_object.id, second.id, "👍")
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let remaining_reactions = Reaction::by_object(&state.db_pool, db_object.id, None)
+        .await
+        .unwrap();
+    assert_eq!(remaining_reactions.len(), 1);
+    assert_eq!(remaining_reactions[0].actor_id, first.id);
+}
+
+#[tokio::test]
+async fn receive_reactions() {
+    let state: ArcState = test_state().await.into();
+    let domain = random_domain();
+
+    let author = insert_local_actor(&state, &domain, "author").await;
+    let reactor = insert_actor(&state, &domain, "reactor", true).await;
+    let author_url = author.actor["id"].as_str().unwrap();
+    let reactor_url = actor_url(&domain, "reactor");
+
+    let mut note_ids = Vec::new();
+    for to in [
+        PUBLIC_IDENTIFIER,
+        author.actor["followers"].as_str().unwrap(),
+    ] {
+        let note_url = format!("{}/notes/{}", author_url, Uuid::new_v4().as_simple());
+        let note = json!({
+            "id": note_url,
+            "type": "Note",
+            "attributedTo": author_url,
+            "to": [to],
+        });
+        let db_object = insert_object(&state, author.id, note).await;
+
+        note_ids.push((db_object.id, note_url));
+    }
+
+    let react_activity = |object_url: &str, content: &str| -> Activity {
+        serde_json::from_value(json!({
+            "id": format!("{}/activities/{}", reactor_url, Uuid::new_v4().as_simple()),
+            "type": "EmojiReact",
+            "actor": reactor_url,
+            "object": object_url,
+            "content": content,
+        }))
+        .unwrap()
+    };
+
+    // Text that isn't an emoji is rejected
+    let activity = react_activity(&note_ids[0].1, "nice");
+    assert!(matches!(
+        handler::emojireact::handle(&state, activity).await,
+        Err(Error::InvalidRequest)
+    ));
+
+    for (_, note_url) in &note_ids {
+        let activity = react_activity(note_url, "👍");
+        handler::emojireact::handle(&state, activity).await.unwrap();
+    }
+
+    let test_client = start_test_server(Arc::clone(&state));
+    let reactions: Value = test_client
+        .get(&format!(
+            "/api/v1/pleroma/statuses/{}/reactions",
+            note_ids[0].0
+        ))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(reactions.as_array().unwrap().len(), 1);
+    assert_eq!(reactions[0]["name"], "👍");
+    assert_eq!(reactions[0]["count"], 1);
+    assert_eq!(reactions[0]["me"], false);
+    assert_eq!(reactions[0]["accounts"][0]["id"], format_uuid!(reactor.id));
+
+    // The reactions to followers-only posts aren't exposed publicly
+    let response = test_client
+        .get(&format!(
+            "/api/v1/pleroma/statuses/{}/reactions",
+            note_ids[1].0
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn reactions_through_inbox() {
+    let state: ArcState = test_state().await.into();
+    let domain = random_domain();
+
+    let author = insert_local_actor(&state, &domain, "author").await;
+    insert_actor(&state, &domain, "reactor", true).await;
+    let author_url = author.actor["id"].as_str().unwrap();
+    let reactor_url = actor_url(&domain, "reactor");
+
+    let note_url = format!("{}/notes/{}", author_url, Uuid::new_v4().as_simple());
+    let note = json!({
+        "id": note_url,
+        "type": "Note",
+        "attributedTo": author_url,
+        "to": [PUBLIC_IDENTIFIER],
+    });
+    let db_object = insert_object(&state, author.id, note).await;
+
+    // Reactions to posts of someone else pass the ownership check, no matter which type carries them
+    let reactions = [
+        json!({ "type": "EmojiReact", "content": "👍" }),
+        json!({ "type": "Like", "_misskey_reaction": "🎉" }),
+    ];
+    for mut activity in reactions {
+        activity["id"] = json!(format!(
+            "{}/activities/{}",
+            reactor_url,
+            Uuid::new_v4().as_simple()
+        ));
+        activity["actor"] = json!(reactor_url);
+        activity["object"] = json!(note_url);
+
+        let status = inbox::process(Arc::clone(&state), inbox_payload(&activity, &reactor_url))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let reactions = Reaction::by_object(&state.db_pool, db_object.id, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        reactions
+            .iter()
+            .map(|reaction| reaction.name.as_str())
+            .collect_vec(),
+        ["👍", "🎉"]
+    );
+}
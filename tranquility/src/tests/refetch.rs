@@ -0,0 +1,245 @@
+use crate::{
+    activitypub::{fetcher, key_resolver::ActorKeyResolver},
+    database::{Actor as DbActor, InsertActor, InsertExt},
+    error::Error,
+    state::ArcState,
+    tests::{
+        insert_actor, insert_local_actor, insert_object, random_domain, test_state, RemoteServer,
+    },
+};
+use ormx::Table;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use time::{Duration, OffsetDateTime};
+use tranquility_http_signatures::{KeyResolver, ResolvedKey};
+use uuid::Uuid;
+
+/// Actor document of Alice on the remote server with the key
+fn alice(remote_server: &RemoteServer, name: &str, key_fragment: &str) -> Value {
+    let actor_url = remote_server.url("/users/alice");
+
+    json!({
+        "id": actor_url,
+        "type": "Person",
+        "name": name,
+        "preferredUsername": "alice",
+        "inbox": format!("{}/inbox", actor_url),
+        "outbox": format!("{}/outbox", actor_url),
+        "publicKey": {
+            "id": format!("{}#{}", actor_url, key_fragment),
+            "owner": actor_url,
+            "publicKeyPem": format!(
+                "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----",
+                key_fragment
+            ),
+        },
+    })
+}
+
+/// Insert the remote actor as if it was fetched the given time ago
+async fn insert_fetched_actor(state: &ArcState, actor: Value, fetched_ago: Duration) -> DbActor {
+    let mut db_actor = InsertActor {
+        id: Uuid::new_v4(),
+        username: actor["preferredUsername"].as_str().unwrap().into(),
+        email: None,
+        password_hash: None,
+        private_key: None,
+        is_confirmed: false,
+        confirmation_code: None,
+        actor,
+        remote: true,
+    }
+    .insert(&state.db_pool)
+    .await
+    .unwrap();
+
+    set_fetched_ago(state, &mut db_actor, fetched_ago).await;
+    db_actor
+}
+
+/// Pretend the actor was last fetched the given time ago
+async fn set_fetched_ago(state: &ArcState, db_actor: &mut DbActor, fetched_ago: Duration) {
+    db_actor.last_fetched_at = OffsetDateTime::now_utc() - fetched_ago;
+    db_actor.update(&state.db_pool).await.unwrap();
+}
+
+#[tokio::test]
+async fn stale_active_actors() {
+    let state: ArcState = test_state().await.into();
+    let domain = random_domain();
+
+    // Only recently active remote actors that weren't fetched within the TTL are stale
+    let mut stale_ids = Vec::new();
+    for (username, fetched_ago) in [("stalest", Duration::days(3)), ("stale", Duration::days(2))] {
+        let mut db_actor = insert_actor(&state, &domain, username, true).await;
+        set_fetched_ago(&state, &mut db_actor, fetched_ago).await;
+        insert_object(&state, db_actor.id, json!({ "type": "Note" })).await;
+
+        stale_ids.push(db_actor.id);
+    }
+
+    let mut inactive = insert_actor(&state, &domain, "inactive", true).await;
+    set_fetched_ago(&state, &mut inactive, Duration::days(2)).await;
+
+    let fresh = insert_actor(&state, &domain, "fresh", true).await;
+    insert_object(&state, fresh.id, json!({ "type": "Note" })).await;
+
+    let mut local = insert_local_actor(&state, &domain, "local").await;
+    set_fetched_ago(&state, &mut local, Duration::days(2)).await;
+    insert_object(&state, local.id, json!({ "type": "Note" })).await;
+
+    let other_ids = [inactive.id, fresh.id, local.id];
+    let actor_ttl = i64::try_from(state.config.federation.actor_ttl).unwrap();
+    let active_secs = Duration::days(7).whole_seconds();
+    let stale_actor_ids: Vec<Uuid> =
+        DbActor::stale_active(&state.db_pool, actor_ttl, active_secs, i64::MAX)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|db_actor| db_actor.id)
+            .filter(|id| stale_ids.contains(id) || other_ids.contains(id))
+            .collect();
+    assert_eq!(stale_actor_ids, stale_ids);
+
+    // Objects created before the activity window don't count
+    let stale_actor_ids: Vec<Uuid> = DbActor::stale_active(&state.db_pool, actor_ttl, 0, i64::MAX)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|db_actor| db_actor.id)
+        .filter(|id| stale_ids.contains(id))
+        .collect();
+    assert!(stale_actor_ids.is_empty());
+}
+
+#[tokio::test]
+async fn refetch_actor() {
+    let state: ArcState = test_state().await.into();
+    let remote_server = RemoteServer::start(&random_domain());
+
+    let db_actor = insert_fetched_actor(
+        &state,
+        alice(&remote_server, "Alice", "main-key"),
+        Duration::days(2),
+    )
+    .await;
+
+    remote_server.serve(alice(&remote_server, "Alice Renamed", "main-key"));
+    let (actor, refetched_db_actor) = fetcher::refetch_actor(&state, db_actor.clone())
+        .await
+        .unwrap();
+    assert_eq!(actor.name, "Alice Renamed");
+    assert_eq!(refetched_db_actor.id, db_actor.id);
+    assert_eq!(refetched_db_actor.actor["name"], "Alice Renamed");
+    assert!(refetched_db_actor.last_fetched_at > db_actor.last_fetched_at);
+
+    // The remote server can't swap the actor for a different entity.
+    // Failed fetches count as fetch attempts as well
+    let mut db_actor = refetched_db_actor;
+    set_fetched_ago(&state, &mut db_actor, Duration::days(2)).await;
+
+    remote_server.serve(json!({
+        "id": remote_server.url("/users/alice"),
+        "type": "Note",
+        "attributedTo": remote_server.url("/users/alice"),
+    }));
+    assert!(fetcher::refetch_actor(&state, db_actor.clone())
+        .await
+        .is_err());
+
+    let failed_db_actor = DbActor::by_id(&state.db_pool, db_actor.id).await.unwrap();
+    assert_eq!(failed_db_actor.actor["name"], "Alice Renamed");
+    assert!(failed_db_actor.last_fetched_at > db_actor.last_fetched_at);
+}
+
+#[tokio::test]
+async fn refetch_on_key_mismatch() {
+    let state: ArcState = test_state().await.into();
+    let remote_server = RemoteServer::start(&random_domain());
+    let key_resolver = ActorKeyResolver::new(Arc::clone(&state));
+
+    // The cached copy isn't stale yet, but it was fetched before the cooldown
+    let db_actor = insert_fetched_actor(
+        &state,
+        alice(&remote_server, "Alice", "main-key"),
+        Duration::minutes(5),
+    )
+    .await;
+
+    // Alice rotated her key
+    remote_server.serve(alice(&remote_server, "Alice", "rotated-key"));
+    let rotated_key_id = remote_server.url("/users/alice#rotated-key");
+    let key = key_resolver.resolve(&rotated_key_id).await.unwrap();
+    assert_eq!(key.owner, remote_server.url("/users/alice"));
+    assert!(key.public_key_pem.contains("rotated-key"));
+
+    let refetched_db_actor = DbActor::by_id(&state.db_pool, db_actor.id).await.unwrap();
+    assert_eq!(
+        refetched_db_actor.actor["publicKey"]["id"],
+        rotated_key_id.as_str()
+    );
+
+    // The old key isn't valid anymore
+    let main_key_id = remote_server.url("/users/alice#main-key");
+    assert!(matches!(
+        key_resolver.resolve(&main_key_id).await,
+        Err(Error::Unauthorized)
+    ));
+}
+
+#[tokio::test]
+async fn key_refetch_cooldown() {
+    let state: ArcState = test_state().await.into();
+    let remote_server = RemoteServer::start(&random_domain());
+    let key_resolver = ActorKeyResolver::new(Arc::clone(&state));
+
+    let mut db_actor = insert_fetched_actor(
+        &state,
+        alice(&remote_server, "Alice", "main-key"),
+        Duration::ZERO,
+    )
+    .await;
+    remote_server.serve(alice(&remote_server, "Alice", "rotated-key"));
+    let rotated_key_id = remote_server.url("/users/alice#rotated-key");
+
+    // The actor was fetched just now, so unknown key IDs don't cause another fetch
+    assert!(matches!(
+        key_resolver.resolve(&rotated_key_id).await,
+        Err(Error::Unauthorized)
+    ));
+    let cached_db_actor = DbActor::by_id(&state.db_pool, db_actor.id).await.unwrap();
+    assert_eq!(
+        cached_db_actor.actor["publicKey"]["id"],
+        remote_server.url("/users/alice#main-key")
+    );
+
+    let main_key_id = remote_server.url("/users/alice#main-key");
+    let main_key = key_resolver.resolve(&main_key_id).await.unwrap();
+    assert!(key_resolver
+        .refresh(&main_key_id, &main_key)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Once the cooldown is over, the actor is refetched
+    set_fetched_ago(&state, &mut db_actor, Duration::minutes(5)).await;
+    let key = key_resolver.resolve(&rotated_key_id).await.unwrap();
+    assert!(key.public_key_pem.contains("rotated-key"));
+
+    // The refetch started the cooldown again
+    remote_server.serve(alice(&remote_server, "Alice", "second-rotated-key"));
+    let second_rotated_key_id = remote_server.url("/users/alice#second-rotated-key");
+    assert!(matches!(
+        key_resolver.resolve(&second_rotated_key_id).await,
+        Err(Error::Unauthorized)
+    ));
+    let refresh_key = ResolvedKey {
+        owner: key.owner.clone(),
+        public_key_pem: key.public_key_pem.clone(),
+    };
+    assert!(key_resolver
+        .refresh(&rotated_key_id, &refresh_key)
+        .await
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,136 @@
+use crate::{
+    activitypub::handler,
+    database::{
+        relay::{InsertRelay, Relay, RelayState},
+        InsertExt, Object as DbObject,
+    },
+    error::Error,
+    state::ArcState,
+    tests::{actor_url, insert_actor, insert_object, random_domain, test_state},
+};
+use serde_json::json;
+use tranquility_types::activitypub::{Activity, PUBLIC_IDENTIFIER};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn relay_state() {
+    let state = test_state().await;
+    let domain = random_domain();
+    let actor_url = format!("https://{}/actor", domain);
+
+    let relay = InsertRelay {
+        id: Uuid::new_v4(),
+        inbox_url: format!("https://{}/inbox", domain),
+        follow_activity_url: format!(
+            "https://tranquility.example.com/objects/{}",
+            Uuid::new_v4().as_simple()
+        ),
+    }
+    .insert(&state.db_pool)
+    .await
+    .unwrap();
+    assert_eq!(relay.state(), RelayState::Pending);
+    assert!(!Relay::is_accepted_actor(&state.db_pool, &actor_url)
+        .await
+        .unwrap());
+
+    Relay::set_state(&state.db_pool, relay.id, RelayState::Accepted, &actor_url)
+        .await
+        .unwrap();
+    assert!(Relay::is_accepted_actor(&state.db_pool, &actor_url)
+        .await
+        .unwrap());
+    assert!(Relay::accepted_inbox_urls(&state.db_pool)
+        .await
+        .unwrap()
+        .contains(&relay.inbox_url));
+
+    let relay = Relay::by_follow_activity_url(&state.db_pool, &relay.follow_activity_url)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(relay.state(), RelayState::Accepted);
+    assert_eq!(relay.actor_url.as_deref(), Some(actor_url.as_str()));
+}
+
+#[tokio::test]
+async fn relay_subscription() {
+    let state: ArcState = test_state().await.into();
+    let domain = random_domain();
+    let other_domain = random_domain();
+
+    let relay_db_actor = insert_actor(&state, &domain, "relay", true).await;
+    insert_actor(&state, &other_domain, "impostor", true).await;
+    let relay_actor_url = actor_url(&domain, "relay");
+
+    let relay = InsertRelay {
+        id: Uuid::new_v4(),
+        inbox_url: format!("https://{}/inbox", domain),
+        follow_activity_url: format!(
+            "https://tranquility.example.com/objects/{}",
+            Uuid::new_v4().as_simple()
+        ),
+    }
+    .insert(&state.db_pool)
+    .await
+    .unwrap();
+
+    let accept_activity = |actor_url: &str| -> Activity {
+        serde_json::from_value(json!({
+            "id": format!("{}/activities/{}", actor_url, Uuid::new_v4().as_simple()),
+            "type": "Accept",
+            "actor": actor_url,
+            "object": relay.follow_activity_url,
+        }))
+        .unwrap()
+    };
+
+    // Only the relay itself can accept the subscription
+    let activity = accept_activity(&actor_url(&other_domain, "impostor"));
+    assert!(matches!(
+        handler::accept::handle(&state, activity).await,
+        Err(Error::Unauthorized)
+    ));
+    let relay = Relay::by_follow_activity_url(&state.db_pool, &relay.follow_activity_url)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(relay.state(), RelayState::Pending);
+
+    let activity = accept_activity(&relay_actor_url);
+    handler::accept::handle(&state, activity).await.unwrap();
+    let relay = Relay::by_follow_activity_url(&state.db_pool, &relay.follow_activity_url)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(relay.state(), RelayState::Accepted);
+
+    // Announces of the relay only bring in the object, they aren't stored as boosts
+    let author = insert_actor(&state, &other_domain, "author", true).await;
+    let author_url = actor_url(&other_domain, "author");
+    let note_url = format!("{}/notes/{}", author_url, Uuid::new_v4().as_simple());
+    let note = json!({
+        "id": note_url,
+        "type": "Note",
+        "attributedTo": author_url,
+        "to": [PUBLIC_IDENTIFIER],
+    });
+    insert_object(&state, author.id, note).await;
+
+    let announce_activity: Activity = serde_json::from_value(json!({
+        "id": format!("{}/activities/{}", relay_actor_url, Uuid::new_v4().as_simple()),
+        "type": "Announce",
+        "actor": relay_actor_url,
+        "object": note_url,
+    }))
+    .unwrap();
+    handler::announce::handle(&state, announce_activity)
+        .await
+        .unwrap();
+
+    let announces =
+        DbObject::by_type_and_owner(&state.db_pool, "Announce", &relay_db_actor.id, 10, 0)
+            .await
+            .unwrap();
+    assert!(announces.is_empty());
+}
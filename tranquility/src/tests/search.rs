@@ -0,0 +1,284 @@
+use crate::{
+    database::search,
+    state::ArcState,
+    tests::{
+        actor_url, insert_access_token, insert_actor, insert_local_actor, insert_object,
+        random_domain, start_test_server, test_state, RemoteServer,
+    },
+};
+use itertools::Itertools;
+use ormx::Table;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tranquility_types::activitypub::PUBLIC_IDENTIFIER;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn full_text_search() {
+    let state = test_state().await;
+    let domain = random_domain();
+    // Usernames are searched across all domains
+    let unique = Uuid::new_v4().as_simple().to_string();
+
+    let mut actor_ids = Vec::new();
+    for (username, name) in [("viewer", "Viewer"), ("author", "Searchable Author")] {
+        let mut db_actor =
+            insert_actor(&state, &domain, &format!("{}{}", username, unique), true).await;
+        db_actor.actor["name"] = json!(format!("{} {}", name, unique));
+        db_actor.update(&state.db_pool).await.unwrap();
+
+        actor_ids.push(db_actor.id);
+    }
+    let viewer_url = actor_url(&domain, &format!("viewer{}", unique));
+
+    // Usernames are matched by their prefix and display names anywhere
+    let accounts = search::accounts(&state.db_pool, &format!("AUTHOR{}", unique), None, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].id, actor_ids[1]);
+
+    let accounts = search::accounts(&state.db_pool, &format!("author {}", unique), None, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(accounts.len(), 1);
+
+    let accounts = search::accounts(&state.db_pool, "viewer", Some(&domain), 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].id, actor_ids[0]);
+
+    let accounts = search::accounts(&state.db_pool, "viewer", Some("example.com"), 10, 0)
+        .await
+        .unwrap();
+    assert!(accounts.iter().all(|account| account.id != actor_ids[0]));
+
+    let hashtag = format!("search{}", unique);
+    let notes = [
+        // Written by the viewer
+        (
+            actor_ids[0],
+            json!({ "content": format!("<p>{} own</p>", unique) }),
+        ),
+        // Mentioning the viewer
+        (
+            actor_ids[1],
+            json!({
+                "content": format!("<p>{} mention</p>", unique),
+                "tag": { "type": "Mention", "href": viewer_url, "name": "@viewer" },
+            }),
+        ),
+        // Without any interaction of the viewer
+        (
+            actor_ids[1],
+            json!({
+                "content": format!("<p>{} <a href=\"https://example.com\">#{}</a></p>", unique, hashtag),
+                "tag": [{ "type": "Hashtag", "name": format!("#{}", hashtag) }],
+            }),
+        ),
+    ];
+
+    let mut object_urls = Vec::new();
+    for (index, (owner_id, mut data)) in notes.into_iter().enumerate() {
+        let object_url = format!("https://{}/objects/{}", domain, index);
+        data["id"] = json!(object_url);
+        data["type"] = json!("Note");
+        data["to"] = json!([PUBLIC_IDENTIFIER]);
+
+        insert_object(&state, owner_id, data).await;
+        object_urls.push(object_url);
+    }
+
+    let statuses = search::statuses(&state.db_pool, actor_ids[0], &viewer_url, &unique, 10, 0)
+        .await
+        .unwrap();
+    let found_urls = statuses
+        .iter()
+        .map(|status| status.data["id"].as_str().unwrap())
+        .sorted()
+        .collect_vec();
+    assert_eq!(
+        found_urls,
+        [object_urls[0].as_str(), object_urls[1].as_str()]
+    );
+
+    // Announcing the post counts as an interaction
+    let announce = json!({
+        "id": format!("https://{}/activities/announce", domain),
+        "type": "Announce",
+        "object": object_urls[2],
+    });
+    insert_object(&state, actor_ids[0], announce).await;
+
+    let statuses = search::statuses(&state.db_pool, actor_ids[0], &viewer_url, "mention", 10, 0)
+        .await
+        .unwrap();
+    assert!(statuses
+        .iter()
+        .any(|status| status.data["id"] == object_urls[1].as_str()));
+    assert!(statuses
+        .iter()
+        .all(|status| status.owner_id == actor_ids[1]));
+
+    let statuses = search::statuses(&state.db_pool, actor_ids[0], &viewer_url, &hashtag, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].data["id"], object_urls[2].as_str());
+
+    // Liking the post counts as an interaction as well
+    let liked_url = format!("https://{}/objects/liked", domain);
+    let liked_note = json!({
+        "id": liked_url,
+        "type": "Note",
+        "content": format!("<p>{} liked</p>", unique),
+        "to": [PUBLIC_IDENTIFIER],
+    });
+    insert_object(&state, actor_ids[1], liked_note).await;
+
+    let liked_query = format!("{} liked", unique);
+    let statuses = search::statuses(
+        &state.db_pool,
+        actor_ids[0],
+        &viewer_url,
+        &liked_query,
+        10,
+        0,
+    )
+    .await
+    .unwrap();
+    assert!(statuses.is_empty());
+
+    let like = json!({
+        "id": format!("https://{}/activities/like", domain),
+        "type": "Like",
+        "object": liked_url,
+    });
+    insert_object(&state, actor_ids[0], like).await;
+
+    let statuses = search::statuses(
+        &state.db_pool,
+        actor_ids[0],
+        &viewer_url,
+        &liked_query,
+        10,
+        0,
+    )
+    .await
+    .unwrap();
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].data["id"], liked_url.as_str());
+
+    // Followers-only posts the viewer interacted with disappear once the viewer isn't a follower (anymore)
+    let followers_only_url = format!("https://{}/objects/followers-only", domain);
+    let followers_only_note = json!({
+        "id": followers_only_url,
+        "type": "Note",
+        "content": format!("<p>{} followers only</p>", unique),
+        "to": [format!("{}/followers", actor_url(&domain, &format!("author{}", unique)))],
+    });
+    insert_object(&state, actor_ids[1], followers_only_note).await;
+
+    let like = json!({
+        "id": format!("https://{}/activities/like-followers-only", domain),
+        "type": "Like",
+        "object": followers_only_url,
+    });
+    insert_object(&state, actor_ids[0], like).await;
+
+    let followers_only_query = format!("{} followers only", unique);
+    let statuses = search::statuses(
+        &state.db_pool,
+        actor_ids[0],
+        &viewer_url,
+        &followers_only_query,
+        10,
+        0,
+    )
+    .await
+    .unwrap();
+    assert!(statuses.is_empty());
+
+    let hashtags = search::hashtags(&state.db_pool, &format!("SEARCH{}", &unique[..8]), 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(hashtags, [hashtag]);
+}
+
+#[tokio::test]
+async fn remote_account_search() {
+    let state = test_state().await;
+    let domain = random_domain();
+    let unique = Uuid::new_v4().as_simple().to_string();
+
+    // Remote actors are inserted unconfirmed, just like the fetcher does
+    let remote_actor = insert_actor(&state, &domain, &format!("remote{}", unique), true).await;
+    assert!(!remote_actor.is_confirmed);
+
+    let accounts = search::accounts(&state.db_pool, &format!("remote{}", unique), None, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].id, remote_actor.id);
+
+    let accounts = search::accounts(&state.db_pool, "remote", Some(&domain), 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(accounts.len(), 1);
+
+    // Unconfirmed local actors stay hidden
+    let mut local_actor = insert_actor(&state, &domain, &format!("local{}", unique), false).await;
+    local_actor.is_confirmed = false;
+    local_actor.update(&state.db_pool).await.unwrap();
+
+    let accounts = search::accounts(&state.db_pool, &format!("local{}", unique), None, 10, 0)
+        .await
+        .unwrap();
+    assert!(accounts.is_empty());
+}
+
+#[tokio::test]
+async fn url_search() {
+    let state: ArcState = test_state().await.into();
+    let remote_server = RemoteServer::start(&random_domain());
+
+    let viewer = insert_local_actor(&state, &random_domain(), "viewer").await;
+    let test_client = start_test_server(Arc::clone(&state))
+        .with_access_token(insert_access_token(&state, viewer.id).await);
+
+    // Remote servers aren't necessarily served via HTTPS
+    let author_url = remote_server.url("/users/author");
+    let note_url = remote_server.url("/notes/1");
+    assert!(note_url.starts_with("http://"));
+
+    remote_server.serve(json!({
+        "id": author_url,
+        "type": "Person",
+        "preferredUsername": "author",
+        "inbox": format!("{}/inbox", author_url),
+        "outbox": format!("{}/outbox", author_url),
+        "publicKey": {
+            "id": format!("{}#main-key", author_url),
+            "owner": author_url,
+            "publicKeyPem": "",
+        },
+    }));
+    remote_server.serve(json!({
+        "id": note_url,
+        "type": "Note",
+        "attributedTo": author_url,
+        "content": "<p>Hello</p>",
+        "to": [PUBLIC_IDENTIFIER],
+    }));
+
+    let search: Value = test_client
+        .get(&format!("/api/v2/search?q={}&resolve=true", note_url))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(search["statuses"].as_array().unwrap().len(), 1);
+    assert_eq!(search["statuses"][0]["uri"], note_url.as_str());
+}
@@ -0,0 +1,252 @@
+use crate::{
+    database::{
+        statuses::{self, StatusFilter},
+        Cursor,
+    },
+    state::State,
+    tests::{actor_url, insert_actor, insert_object, random_domain, test_state},
+};
+use itertools::Itertools;
+use serde_json::json;
+use std::collections::HashMap;
+use tranquility_types::activitypub::PUBLIC_IDENTIFIER;
+use uuid::Uuid;
+
+/// Get the sorted names of the statuses of the account the viewer is allowed to see
+async fn status_names(
+    state: &State,
+    account_id: Uuid,
+    viewer_url: Option<&str>,
+    filter: StatusFilter<'_>,
+    post_ids: &HashMap<Uuid, &'static str>,
+) -> Vec<&'static str> {
+    let db_objects = statuses::by_account(
+        &state.db_pool,
+        account_id,
+        viewer_url,
+        filter,
+        Cursor::Before(None),
+        20,
+    )
+    .await
+    .unwrap();
+
+    db_objects
+        .into_iter()
+        .map(|db_object| post_ids[&db_object.id])
+        .sorted_unstable()
+        .collect()
+}
+
+#[tokio::test]
+async fn account_statuses() {
+    let state = test_state().await;
+    let domain = random_domain();
+    let author_url = actor_url(&domain, "author");
+    let followers_url = format!("{}/followers", author_url);
+    let follower_url = actor_url(&domain, "follower");
+    let stranger_url = actor_url(&domain, "stranger");
+
+    let author = insert_actor(&state, &domain, "author", true).await;
+    let follower = insert_actor(&state, &domain, "follower", true).await;
+    insert_actor(&state, &domain, "stranger", true).await;
+
+    let follow = json!({
+        "id": format!("https://{}/follows/1", domain),
+        "type": "Follow",
+        "actor": follower_url,
+        "object": author_url,
+        "approved": true,
+    });
+    insert_object(&state, follower.id, follow).await;
+
+    let public_url = format!("https://{}/notes/public", domain);
+    let posts = [
+        (
+            "public",
+            json!({
+                "id": public_url,
+                "type": "Note",
+                "to": [PUBLIC_IDENTIFIER],
+                "attachment": [{ "type": "Document", "url": "https://example.com/image.png" }],
+                "tag": [{ "type": "Hashtag", "name": "#Rust", "href": "https://example.com/tags/rust" }],
+            }),
+        ),
+        (
+            "followers",
+            json!({ "type": "Note", "to": [followers_url] }),
+        ),
+        ("direct", json!({ "type": "Note", "to": [stranger_url] })),
+        (
+            "reply",
+            json!({
+                "type": "Note",
+                "to": [PUBLIC_IDENTIFIER],
+                "inReplyTo": format!("https://{}/notes/unknown", domain),
+            }),
+        ),
+        (
+            "self_reply",
+            json!({ "type": "Note", "cc": [PUBLIC_IDENTIFIER], "inReplyTo": public_url }),
+        ),
+        (
+            "announce",
+            json!({ "type": "Announce", "to": [PUBLIC_IDENTIFIER], "object": public_url }),
+        ),
+    ];
+
+    let mut post_ids = HashMap::new();
+    for (name, mut data) in posts {
+        if data.get("id").is_none() {
+            data["id"] = json!(format!("https://{}/objects/{}", domain, name));
+        }
+
+        let db_object = insert_object(&state, author.id, data).await;
+        post_ids.insert(db_object.id, name);
+    }
+
+    assert_eq!(
+        status_names(&state, author.id, None, StatusFilter::default(), &post_ids).await,
+        ["announce", "public", "reply", "self_reply"]
+    );
+    assert_eq!(
+        status_names(
+            &state,
+            author.id,
+            Some(&follower_url),
+            StatusFilter::default(),
+            &post_ids
+        )
+        .await,
+        ["announce", "followers", "public", "reply", "self_reply"]
+    );
+    assert_eq!(
+        status_names(
+            &state,
+            author.id,
+            Some(&stranger_url),
+            StatusFilter::default(),
+            &post_ids
+        )
+        .await,
+        ["announce", "direct", "public", "reply", "self_reply"]
+    );
+    assert_eq!(
+        status_names(
+            &state,
+            author.id,
+            Some(&author_url),
+            StatusFilter::default(),
+            &post_ids
+        )
+        .await,
+        [
+            "announce",
+            "direct",
+            "followers",
+            "public",
+            "reply",
+            "self_reply"
+        ]
+    );
+
+    let filter = StatusFilter {
+        exclude_replies: true,
+        exclude_reblogs: true,
+        ..StatusFilter::default()
+    };
+    assert_eq!(
+        status_names(&state, author.id, None, filter, &post_ids).await,
+        ["public", "self_reply"]
+    );
+
+    let filter = StatusFilter {
+        only_media: true,
+        ..StatusFilter::default()
+    };
+    assert_eq!(
+        status_names(&state, author.id, None, filter, &post_ids).await,
+        ["public"]
+    );
+
+    let filter = StatusFilter {
+        tagged: Some("rust"),
+        ..StatusFilter::default()
+    };
+    assert_eq!(
+        status_names(&state, author.id, None, filter, &post_ids).await,
+        ["public"]
+    );
+
+    // Single posts follow the same rules
+    let post_id = |name: &str| {
+        post_ids
+            .iter()
+            .find(|(_, post_name)| **post_name == name)
+            .map(|(id, _)| *id)
+            .unwrap()
+    };
+    assert!(
+        statuses::is_visible(&state.db_pool, post_id("followers"), Some(&follower_url))
+            .await
+            .unwrap()
+    );
+    assert!(
+        !statuses::is_visible(&state.db_pool, post_id("followers"), Some(&stranger_url))
+            .await
+            .unwrap()
+    );
+    assert!(
+        !statuses::is_visible(&state.db_pool, post_id("direct"), None)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !statuses::is_visible(&state.db_pool, Uuid::new_v4(), Some(&author_url))
+            .await
+            .unwrap()
+    );
+
+    // Paginating through the statuses returns every status exactly once
+    let first_page = statuses::by_account(
+        &state.db_pool,
+        author.id,
+        None,
+        StatusFilter::default(),
+        Cursor::Before(None),
+        2,
+    )
+    .await
+    .unwrap();
+    let second_page = statuses::by_account(
+        &state.db_pool,
+        author.id,
+        None,
+        StatusFilter::default(),
+        Cursor::Before(first_page.last().map(|db_object| db_object.id)),
+        20,
+    )
+    .await
+    .unwrap();
+    let previous_page = statuses::by_account(
+        &state.db_pool,
+        author.id,
+        None,
+        StatusFilter::default(),
+        Cursor::After(second_page.first().map(|db_object| db_object.id)),
+        20,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(first_page.len() + second_page.len(), 4);
+    let first_page_ids = first_page
+        .iter()
+        .map(|db_object| db_object.id)
+        .collect::<Vec<_>>();
+    let previous_page_ids = previous_page
+        .iter()
+        .map(|db_object| db_object.id)
+        .collect::<Vec<_>>();
+    assert_eq!(first_page_ids, previous_page_ids);
+}
@@ -0,0 +1,168 @@
+use crate::{
+    config::Configuration,
+    consts::{
+        activitypub::MAX_REACTION_CHARS,
+        regex::{EMOJI, EMOJI_SHORTCODE},
+    },
+    database::custom_emoji::CustomEmoji,
+    error::Error,
+    format_uuid, regex,
+    state::ArcState,
+};
+use itertools::Itertools;
+use tranquility_types::activitypub::{tag::Emoji, Attachment, Tag};
+use uuid::Uuid;
+
+regex!(EMOJI_REGEX = EMOJI);
+regex!(EMOJI_SHORTCODE_REGEX = EMOJI_SHORTCODE);
+
+/// Check whether the shortcode (without the surrounding colons) is a valid emoji shortcode
+pub fn is_valid_shortcode(shortcode: &str) -> bool {
+    EMOJI_SHORTCODE_REGEX.is_match(shortcode)
+}
+
+/// Get the shortcode of the custom emoji used as a reaction (`None` if the reaction isn't a custom emoji)
+///
+/// Reactions carry the shortcode surrounded by colons (for example, ":blobcat:")
+pub fn reaction_shortcode(reaction: &str) -> Option<&str> {
+    let shortcode = reaction.strip_prefix(':')?.strip_suffix(':')?;
+
+    is_valid_shortcode(shortcode).then_some(shortcode)
+}
+
+/// Check whether the reaction could be a Unicode emoji
+///
+/// This doesn't check against the list of Unicode emojis, it only rules out text that can't be an emoji
+pub fn is_unicode_emoji(reaction: &str) -> bool {
+    !reaction.is_ascii()
+        && reaction.chars().count() <= MAX_REACTION_CHARS
+        && !reaction
+            .chars()
+            .any(|ch| ch.is_alphabetic() || ch.is_whitespace() || ch.is_control() || ch == ':')
+}
+
+/// Get the ActivityPub ID of the local emoji
+pub fn url(config: &Configuration, id: Uuid) -> String {
+    format!(
+        "https://{}/emojis/{}",
+        config.instance.domain,
+        format_uuid!(id)
+    )
+}
+
+/// Get the URL of the image of the emoji
+///
+/// Remote emojis whose image isn't cached (yet) respond with a 404
+pub fn image_url(config: &Configuration, id: Uuid) -> String {
+    format!(
+        "https://{}/media/emojis/{}",
+        config.instance.domain,
+        format_uuid!(id)
+    )
+}
+
+/// Construct the Emoji tag of the local emoji
+pub fn tag(config: &Configuration, custom_emoji: &CustomEmoji) -> Tag {
+    Tag::Emoji(Emoji {
+        id: url(config, custom_emoji.id),
+        name: format!(":{}:", custom_emoji.shortcode),
+        updated: Some(custom_emoji.updated_at),
+        icon: Attachment {
+            r#type: "Image".into(),
+            url: image_url(config, custom_emoji.id),
+        },
+    })
+}
+
+/// Trait for getting emoji shortcodes
+pub trait ExtractEmoji {
+    /// Get the unique shortcodes of the emojis used in the value (without the surrounding colons)
+    fn emoji_shortcodes(&self) -> Vec<&str>;
+}
+
+impl<T> ExtractEmoji for T
+where
+    T: AsRef<str>,
+{
+    fn emoji_shortcodes(&self) -> Vec<&str> {
+        EMOJI_REGEX
+            .captures_iter(self.as_ref())
+            .map(|capture| capture.name("shortcode").unwrap().as_str())
+            .unique()
+            .collect()
+    }
+}
+
+/// Get the Emoji tags of the local emojis used in the texts
+///
+/// Shortcodes that don't belong to any local emoji are left as they are
+pub async fn local_tags(state: &ArcState, texts: &[&str]) -> Result<Vec<Tag>, Error> {
+    let shortcodes = texts
+        .iter()
+        .flat_map(ExtractEmoji::emoji_shortcodes)
+        .unique()
+        .map(ToOwned::to_owned)
+        .collect_vec();
+    if shortcodes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let custom_emojis = CustomEmoji::local_by_shortcodes(&state.db_pool, &shortcodes).await?;
+    let tags = custom_emojis
+        .iter()
+        .map(|custom_emoji| tag(&state.config, custom_emoji))
+        .collect();
+
+    Ok(tags)
+}
+
+/// Replace the Emoji tags with the tags of the local emojis used in the texts
+pub async fn replace_tags(
+    state: &ArcState,
+    tags: &mut Vec<Tag>,
+    texts: &[&str],
+) -> Result<(), Error> {
+    let emoji_tags = local_tags(state, texts).await?;
+
+    tags.retain(|tag| tag.emoji().is_none());
+    tags.extend(emoji_tags);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{is_unicode_emoji, is_valid_shortcode, reaction_shortcode, ExtractEmoji};
+
+    const EMOJIS: &str = ":blobcat: says hi :blob_fox:! 12:30:00 :blobcat: :a: ::";
+
+    #[test]
+    fn emoji_shortcodes() {
+        assert_eq!(EMOJIS.emoji_shortcodes(), ["blobcat", "blob_fox", "30"]);
+    }
+
+    #[test]
+    fn valid_shortcodes() {
+        assert!(is_valid_shortcode("blobcat"));
+        assert!(is_valid_shortcode("blob_cat_2"));
+        assert!(!is_valid_shortcode("a"));
+        assert!(!is_valid_shortcode("blob-cat"));
+        assert!(!is_valid_shortcode(":blobcat:"));
+    }
+
+    #[test]
+    fn reactions() {
+        assert_eq!(reaction_shortcode(":blobcat:"), Some("blobcat"));
+        assert_eq!(reaction_shortcode("blobcat"), None);
+        assert_eq!(reaction_shortcode(":blob cat:"), None);
+
+        assert!(is_unicode_emoji("👍"));
+        assert!(is_unicode_emoji("👩‍👩‍👧"));
+        assert!(is_unicode_emoji("🇩🇪"));
+        assert!(!is_unicode_emoji(""));
+        assert!(!is_unicode_emoji("+1"));
+        assert!(!is_unicode_emoji("👍 nice"));
+        assert!(!is_unicode_emoji("ü"));
+        assert!(!is_unicode_emoji(":blobcat:"));
+    }
+}
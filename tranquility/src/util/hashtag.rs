@@ -0,0 +1,102 @@
+use crate::{config::Configuration, consts::regex::HASHTAG, regex};
+use regex::Captures;
+use tranquility_types::activitypub::{tag::Link, Object, Tag};
+
+regex!(HASHTAG_REGEX = HASHTAG);
+
+/// Normalise the name of a hashtag (lowercase and without the leading `#`)
+pub fn normalise(name: &str) -> String {
+    name.trim_start_matches('#').to_lowercase()
+}
+
+/// Check whether the name (without the leading `#`) is a valid hashtag name
+pub fn is_valid(name: &str) -> bool {
+    format!("#{}", name).hashtags() == [name]
+}
+
+/// Get the URL of the page listing the posts tagged with the hashtag
+pub fn url(config: &Configuration, name: &str) -> String {
+    format!(
+        "https://{}/tags/{}",
+        config.instance.domain,
+        normalise(name)
+    )
+}
+
+/// Trait for getting hashtags
+pub trait ExtractHashtag {
+    /// Get the names of the hashtags contained in the value (without the leading `#`)
+    fn hashtags(&self) -> Vec<&str>;
+}
+
+impl<T> ExtractHashtag for T
+where
+    T: AsRef<str>,
+{
+    fn hashtags(&self) -> Vec<&str> {
+        HASHTAG_REGEX
+            .captures_iter(self.as_ref())
+            .map(|capture| capture.name("name").unwrap().as_str())
+            .collect()
+    }
+}
+
+/// Trait for formatting hashtags
+pub trait FormatHashtag {
+    /// Format the hashtags to links and add them as `Hashtag` tags
+    fn format_hashtags(&mut self, config: &Configuration);
+}
+
+impl FormatHashtag for Object {
+    fn format_hashtags(&mut self, config: &Configuration) {
+        let mut names = Vec::new();
+
+        let content = HASHTAG_REGEX.replace_all(self.content.as_str(), |capture: &Captures<'_>| {
+            let prefix = capture.name("prefix").unwrap().as_str();
+            let name = capture.name("name").unwrap().as_str();
+
+            if !names
+                .iter()
+                .any(|known_name: &String| normalise(known_name) == normalise(name))
+            {
+                names.push(name.to_string());
+            }
+
+            format!(r#"{}<a href="{}">#{}</a>"#, prefix, url(config, name), name)
+        });
+        self.content = content.to_string();
+
+        let tags = names.into_iter().map(|name| {
+            Tag::Hashtag(Link {
+                href: url(config, &name),
+                name: format!("#{}", name),
+            })
+        });
+        self.tag.extend(tags);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{is_valid, ExtractHashtag};
+
+    const HASHTAGS: &str =
+        "#rust is #1 #Rust_Lang\n#über https://example.com/#anchor &#39;#tranquility";
+
+    #[test]
+    fn hashtags() {
+        assert_eq!(
+            HASHTAGS.hashtags(),
+            ["rust", "Rust_Lang", "über", "tranquility"]
+        );
+    }
+
+    #[test]
+    fn valid_names() {
+        assert!(is_valid("rust"));
+        assert!(is_valid("Rust_2021"));
+        assert!(!is_valid("2021"));
+        assert!(!is_valid("rust lang"));
+        assert!(!is_valid(""));
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
 use async_trait::async_trait;
 use regex::{Captures, Match};
 use tokio::runtime::Handle;
-use tranquility_types::activitypub::{Actor, Object, Tag};
+use tranquility_types::activitypub::{tag::Link, Actor, Object, Tag};
 
 regex!(MENTION_REGEX = MENTION);
 
@@ -106,11 +106,10 @@ impl FormatMention for String {
                 let mut mention = capture.get(0).unwrap().as_str().to_string();
                 if let Ok(actor) = actor_result {
                     // Create a new ActivityPub tag object
-                    tags.push(Tag {
-                        r#type: "Mention".into(),
+                    tags.push(Tag::Mention(Link {
                         name: mention.clone(),
                         href: actor.id.clone(),
-                    });
+                    }));
 
                     mention = format!(r#"<a href="{}">{}</a>"#, actor.id, mention);
                 }
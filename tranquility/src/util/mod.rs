@@ -1,21 +1,50 @@
-use crate::consts::USER_AGENT;
+use self::network::PublicResolver;
+use crate::{
+    consts::{
+        http_client::{CONNECT_TIMEOUT, TIMEOUT},
+        ALLOWED_IMAGE_TYPES, FORM_MAX_DEPTH, USER_AGENT,
+    },
+    error::Error,
+};
 use async_trait::async_trait;
 use axum::{
+    body::Bytes,
     body::HttpBody,
-    extract::{Form as AxumForm, FromRequest, RequestParts},
+    extract::{FromRequest, RequestParts},
     Json,
 };
 use futures_util::FutureExt;
+use mime::Mime;
 use once_cell::sync::Lazy;
 use reqwest::Client;
 use serde::de::DeserializeOwned;
-use std::{error::Error as StdError, future::Future};
+use std::{error::Error as StdError, future::Future, sync::Arc};
 use tokio::sync::oneshot;
 
-pub static HTTP_CLIENT: Lazy<Client> =
-    Lazy::new(|| Client::builder().user_agent(USER_AGENT).build().unwrap());
+/// HTTP client used for all outgoing requests to remote servers
+///
+/// - Only connects to publicly routable addresses
+/// - Limits the amount of redirects
+/// - Times out requests that take too long
+pub static HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
+    let client_builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .redirect(network::redirect_policy())
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(TIMEOUT);
+
+    #[cfg(not(test))]
+    let client_builder = client_builder.dns_resolver(Arc::new(PublicResolver));
+    // The tests route the domains of their stand-in remote servers to local addresses
+    #[cfg(test)]
+    let client_builder = client_builder.dns_resolver(Arc::new(crate::tests::TestResolver));
+
+    client_builder.build().unwrap()
+});
 
 /// Specialised form that deserialises both, JSON and URL-encoded form data
+///
+/// URL-encoded form data may contain nested fields and arrays (for example, `poll[options][]=Yes&poll[options][]=No`)
 pub struct Form<T>(pub T);
 
 #[async_trait]
@@ -26,20 +55,34 @@ where
     B::Error: StdError + Send + Sync + 'static,
     T: DeserializeOwned + Send,
 {
-    type Rejection = <AxumForm<T> as FromRequest<B>>::Rejection;
+    type Rejection = Error;
 
     async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
         match Json::from_request(req).await {
             Ok(Json(val)) => Ok(Self(val)),
             Err(err) => {
                 trace!(error = %err, "Form could not get deserialised as JSON. Attempting URL encoded");
-                let AxumForm(val) = AxumForm::from_request(req).await?;
+                let body = Bytes::from_request(req)
+                    .await
+                    .map_err(|_| Error::InvalidRequest)?;
+
+                // Non-strict mode, since clients usually percent-encode the brackets of the nested fields
+                let val = serde_qs::Config::new(FORM_MAX_DEPTH, false).deserialize_bytes(&body)?;
                 Ok(Self(val))
             }
         }
     }
 }
 
+/// Check whether the content type is one of the image types that are accepted for uploads and cached media
+///
+/// SVG images are rejected since they can contain scripts
+pub fn is_image(content_type: &str) -> bool {
+    content_type
+        .parse::<Mime>()
+        .is_ok_and(|mime| ALLOWED_IMAGE_TYPES.contains(&mime.essence_str()))
+}
+
 /// Run any CPU intensive tasks (RSA key generation, password hashing, etc.) via this function
 pub fn cpu_intensive_task<F, T>(func: F) -> impl Future<Output = T> + Send + Sync + 'static
 where
@@ -65,4 +108,7 @@ where
     receiver.map(Result::unwrap)
 }
 
+pub mod emoji;
+pub mod hashtag;
 pub mod mention;
+pub mod network;
@@ -0,0 +1,268 @@
+use crate::{consts::http_client::MAX_REDIRECTS, error::FetchError};
+use futures_util::FutureExt;
+use itertools::Itertools;
+use mime::Mime;
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    header::CONTENT_TYPE,
+    redirect::Policy,
+    Response,
+};
+use std::{
+    error::Error as StdError,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+use url::{Host, Url};
+
+/// Check whether an IPv4 address is publicly routable
+fn is_public_ipv4(addr: Ipv4Addr) -> bool {
+    let [first_octet, second_octet, third_octet, _] = addr.octets();
+
+    // 100.64.0.0/10 (Shared address space, RFC 6598)
+    let is_shared = first_octet == 100 && (second_octet & 0b1100_0000) == 64;
+    // 192.0.0.0/24 (IETF protocol assignments)
+    let is_protocol_assignment = first_octet == 192 && second_octet == 0 && third_octet == 0;
+    // 198.18.0.0/15 (Benchmarking, RFC 2544)
+    let is_benchmarking = first_octet == 198 && (second_octet & 0b1111_1110) == 18;
+    // 0.0.0.0/8 ("This network") and 240.0.0.0/4 (Reserved)
+    let is_reserved = first_octet == 0 || first_octet >= 240;
+
+    !(addr.is_private()
+        || addr.is_loopback()
+        || addr.is_link_local()
+        || addr.is_broadcast()
+        || addr.is_documentation()
+        || addr.is_multicast()
+        || addr.is_unspecified()
+        || is_shared
+        || is_protocol_assignment
+        || is_benchmarking
+        || is_reserved)
+}
+
+/// Get the IPv4 address embedded into an IPv6 address
+///
+/// These are IPv4-mapped (::ffff:0:0/96), IPv4-compatible (::/96), NAT64 (64:ff9b::/96) and 6to4 (2002::/16) addresses
+fn embedded_ipv4(addr: Ipv6Addr) -> Option<Ipv4Addr> {
+    let segments = addr.segments();
+    let octets = addr.octets();
+
+    match segments {
+        // Covers both IPv4-mapped and IPv4-compatible addresses
+        [0, 0, 0, 0, 0, 0 | 0xffff, ..] => addr.to_ipv4(),
+        [0x64, 0xff9b, 0, 0, 0, 0, ..] => Some(Ipv4Addr::new(
+            octets[12], octets[13], octets[14], octets[15],
+        )),
+        [0x2002, ..] => Some(Ipv4Addr::new(octets[2], octets[3], octets[4], octets[5])),
+        _ => None,
+    }
+}
+
+/// Check whether an IPv6 address is publicly routable
+fn is_public_ipv6(addr: Ipv6Addr) -> bool {
+    // Check addresses with an embedded IPv4 address with the IPv4 rules
+    if let Some(ipv4_addr) = embedded_ipv4(addr) {
+        return is_public_ipv4(ipv4_addr);
+    }
+
+    let first_segment = addr.segments()[0];
+
+    // fc00::/7 (Unique local addresses)
+    let is_unique_local = (first_segment & 0xfe00) == 0xfc00;
+    // fe80::/10 (Link-local addresses) and fec0::/10 (Deprecated site-local addresses)
+    let is_link_or_site_local =
+        (first_segment & 0xffc0) == 0xfe80 || (first_segment & 0xffc0) == 0xfec0;
+    // 2001:db8::/32 (Documentation)
+    let is_documentation = first_segment == 0x2001 && addr.segments()[1] == 0x0db8;
+    // 64:ff9b:1::/48 (Local-use NAT64, RFC 8215)
+    let is_local_nat64 = first_segment == 0x64 && addr.segments()[1..3] == [0xff9b, 1];
+
+    !(addr.is_loopback()
+        || addr.is_multicast()
+        || addr.is_unspecified()
+        || is_unique_local
+        || is_link_or_site_local
+        || is_documentation
+        || is_local_nat64)
+}
+
+/// Check whether an IP address is publicly routable
+///
+/// Requests to private, loopback, link-local, etc. addresses could be used to reach services that aren't supposed to be reachable from the outside
+pub fn is_public_address(addr: IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(addr) => is_public_ipv4(addr),
+        IpAddr::V6(addr) => is_public_ipv6(addr),
+    }
+}
+
+/// Check whether the URL is allowed to be requested
+///
+/// Only HTTP(S) URLs are allowed. If the host is an IP address, it has to be publicly routable.
+/// Domains are checked after the DNS resolution by the [`PublicResolver`]
+pub fn check_url(url: &Url) -> Result<(), FetchError> {
+    if url.scheme() != "https" && url.scheme() != "http" {
+        return Err(FetchError::UnsupportedScheme);
+    }
+
+    let is_allowed = match url.host() {
+        Some(Host::Domain(..)) => true,
+        Some(Host::Ipv4(addr)) => is_public_ipv4(addr),
+        Some(Host::Ipv6(addr)) => is_public_ipv6(addr),
+        None => false,
+    };
+
+    is_allowed.then_some(()).ok_or(FetchError::ForbiddenAddress)
+}
+
+/// Check whether the response has one of the allowed content types
+///
+/// Parameters of the content type (for example, the `profile` of JSON-LD) are ignored
+pub fn check_content_type(response: &Response, allowed_types: &[&str]) -> Result<(), FetchError> {
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|header_value| header_value.to_str().ok())
+        .unwrap_or_default();
+
+    match content_type.parse::<Mime>() {
+        Ok(mime) if allowed_types.contains(&mime.essence_str()) => Ok(()),
+        _ => Err(FetchError::UnexpectedContentType(content_type.into())),
+    }
+}
+
+/// DNS resolver that only returns publicly routable addresses
+///
+/// Because the check happens after the DNS resolution, a domain can't be used to sneak in a private address
+pub struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        async move {
+            let addrs = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_address(addr.ip()))
+                .collect_vec();
+
+            if addrs.is_empty() {
+                return Err(
+                    Box::new(FetchError::ForbiddenAddress) as Box<dyn StdError + Send + Sync>
+                );
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        }
+        .boxed()
+    }
+}
+
+/// Redirect policy that limits the amount of redirects and checks every redirect target via [`check_url`]
+pub fn redirect_policy() -> Policy {
+    Policy::custom(|attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            return attempt.error(FetchError::TooManyRedirects);
+        }
+
+        match check_url(attempt.url()) {
+            Ok(()) => attempt.follow(),
+            Err(err) => attempt.error(err),
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_content_type, check_url, is_public_address};
+    use crate::consts::http_client::{ACTIVITYPUB_CONTENT_TYPES, WEBFINGER_CONTENT_TYPES};
+    use reqwest::{header::CONTENT_TYPE, Response};
+    use std::net::IpAddr;
+    use url::Url;
+
+    const PRIVATE_ADDRESSES: &[&str] = &[
+        "0.0.0.0",
+        "10.0.0.1",
+        "100.64.0.1",
+        "127.0.0.1",
+        "169.254.169.254",
+        "172.16.0.1",
+        "192.168.1.1",
+        "255.255.255.255",
+        "::",
+        "::1",
+        "::ffff:127.0.0.1",
+        "::127.0.0.1",
+        "::10.0.0.1",
+        "64:ff9b::127.0.0.1",
+        "64:ff9b::a9fe:a9fe",
+        "64:ff9b:1::1.1.1.1",
+        "2002:7f00:1::",
+        "2002:c0a8:101::1",
+        "fc00::1",
+        "fd12:3456:789a::1",
+        "fe80::1",
+    ];
+    const PUBLIC_ADDRESSES: &[&str] = &[
+        "1.1.1.1",
+        "93.184.216.34",
+        "2606:4700:4700::1111",
+        "::ffff:1.1.1.1",
+        "64:ff9b::1.1.1.1",
+        "2002:101:101::1",
+    ];
+
+    #[test]
+    fn private_addresses() {
+        for addr in PRIVATE_ADDRESSES {
+            let addr: IpAddr = addr.parse().unwrap();
+            assert!(!is_public_address(addr), "{} is considered public", addr);
+        }
+    }
+
+    #[test]
+    fn public_addresses() {
+        for addr in PUBLIC_ADDRESSES {
+            let addr: IpAddr = addr.parse().unwrap();
+            assert!(is_public_address(addr), "{} is considered private", addr);
+        }
+    }
+
+    #[test]
+    fn url_check() {
+        let check = |url: &str| check_url(&Url::parse(url).unwrap()).is_ok();
+
+        assert!(check("https://example.com/users/test"));
+        assert!(check("https://1.1.1.1/users/test"));
+
+        assert!(!check("https://127.0.0.1/users/test"));
+        assert!(!check("http://[::1]:8080/users/test"));
+        assert!(!check("file:///etc/passwd"));
+    }
+
+    #[test]
+    fn content_type_check() {
+        let check = |content_type: &str, allowed_types: &[&str]| {
+            let response: Response = http::Response::builder()
+                .header(CONTENT_TYPE, content_type)
+                .body("")
+                .unwrap()
+                .into();
+
+            check_content_type(&response, allowed_types).is_ok()
+        };
+
+        assert!(check(
+            "application/activity+json",
+            ACTIVITYPUB_CONTENT_TYPES
+        ));
+        assert!(check(
+            "application/ld+json; profile=\"https://www.w3.org/ns/activitystreams\"",
+            ACTIVITYPUB_CONTENT_TYPES
+        ));
+        assert!(check("application/jrd+json", WEBFINGER_CONTENT_TYPES));
+
+        assert!(!check("application/jrd+json", ACTIVITYPUB_CONTENT_TYPES));
+        assert!(!check("application/activity+json", WEBFINGER_CONTENT_TYPES));
+        assert!(!check("text/html", ACTIVITYPUB_CONTENT_TYPES));
+        assert!(!check("", WEBFINGER_CONTENT_TYPES));
+    }
+}
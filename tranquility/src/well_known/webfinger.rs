@@ -1,6 +1,13 @@
 use crate::{
-    consts::cors::GENERAL_ALLOWED_METHODS, database::Actor as DbActor, error::Error,
-    state::ArcState, util::HTTP_CLIENT,
+    activitypub::fetcher,
+    consts::{
+        cors::GENERAL_ALLOWED_METHODS,
+        http_client::{MAX_RESPONSE_SIZE, WEBFINGER_CONTENT_TYPES},
+    },
+    database::Actor as DbActor,
+    error::{Error, FetchError},
+    state::ArcState,
+    util::{network, HTTP_CLIENT},
 };
 use axum::{
     extract::Query, http::StatusCode, response::IntoResponse, routing::get, Extension, Json, Router,
@@ -11,8 +18,9 @@ use tranquility_types::{
     activitypub::Actor,
     webfinger::{Link, Resource},
 };
+use url::Url;
 
-// Keeping this for future use
+/// Look up the actor via webfinger and fetch them
 pub async fn fetch_actor(
     state: &ArcState,
     username: &str,
@@ -24,11 +32,26 @@ pub async fn fetch_actor(
         domain, resource
     );
 
+    let url = Url::parse(&url)?;
+    network::check_url(&url)?;
+    if state.config.federation.is_blocked(&url) {
+        return Err(Error::BlockedDomain);
+    }
+
     let request = HTTP_CLIENT
-        .get(&url)
+        .get(url)
         .header("Accept", "application/jrd+json")
         .build()?;
-    let resource: Resource = HTTP_CLIENT.execute(request).await?.json().await?;
+
+    let response = HTTP_CLIENT.execute(request).await?;
+    let response_status = response.status();
+    if !response_status.is_success() {
+        return Err(FetchError::UnsuccessfulStatus(response_status).into());
+    }
+
+    network::check_content_type(&response, WEBFINGER_CONTENT_TYPES)?;
+    let body = fetcher::read_body(response, MAX_RESPONSE_SIZE).await?;
+    let resource: Resource = serde_json::from_slice(&body)?;
 
     let actor_url = resource
         .links
@@ -36,7 +59,7 @@ pub async fn fetch_actor(
         .find(|link| link.rel == "self")
         .ok_or(Error::UnexpectedWebfingerResource)?;
 
-    crate::activitypub::fetcher::fetch_actor(state, &actor_url.href).await
+    fetcher::fetch_actor(state, &actor_url.href).await
 }
 
 #[derive(Deserialize)]